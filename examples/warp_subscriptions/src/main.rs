@@ -7,8 +7,8 @@ use juniper::{
     graphql_object, graphql_subscription, DefaultScalarValue, EmptyMutation, FieldError,
     GraphQLEnum, RootNode,
 };
-use juniper_graphql_ws::ConnectionConfig;
-use juniper_warp::{playground_filter, subscriptions::serve_graphql_ws};
+use juniper_graphql_ws::{ConnectionConfig, Protocol};
+use juniper_warp::{playground_filter, subscriptions::serve};
 use warp::{http::Response, Filter};
 
 #[derive(Clone)]
@@ -160,22 +160,26 @@ async fn main() {
 
     let routes = (warp::path("subscriptions")
         .and(warp::ws())
-        .map(move |ws: warp::ws::Ws| {
+        .and(warp::header::optional::<String>("sec-websocket-protocol"))
+        .map(move |ws: warp::ws::Ws, protocol: Option<String>| {
+            let protocol = Protocol::from_header(protocol.as_deref());
             let root_node = root_node.clone();
-            ws.on_upgrade(move |websocket| async move {
-                serve_graphql_ws(websocket, root_node, ConnectionConfig::new(Context {}))
-                    .map(|r| {
-                        if let Err(e) = r {
-                            println!("Websocket error: {}", e);
-                        }
-                    })
-                    .await
-            })
+            let reply = ws.on_upgrade(move |websocket| async move {
+                serve(
+                    websocket,
+                    protocol,
+                    root_node,
+                    ConnectionConfig::new(Context {}),
+                )
+                .map(|r| {
+                    if let Err(e) = r {
+                        println!("Websocket error: {}", e);
+                    }
+                })
+                .await
+            });
+            warp::reply::with_header(reply, "Sec-WebSocket-Protocol", protocol.as_str())
         }))
-    .map(|reply| {
-        // TODO#584: remove this workaround
-        warp::reply::with_header(reply, "Sec-WebSocket-Protocol", "graphql-ws")
-    })
     .or(warp::post()
         .and(warp::path("graphql"))
         .and(qm_graphql_filter))
@@ -0,0 +1,7 @@
+#[derive(juniper::GraphQLObject)]
+#[graphql(directive = "@key(fields: $id)")]
+struct Object {
+    id: String,
+}
+
+fn main() {}
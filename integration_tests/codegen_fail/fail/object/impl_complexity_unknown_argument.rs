@@ -0,0 +1,11 @@
+struct Object;
+
+#[juniper::graphql_object]
+impl Object {
+    #[graphql(complexity(multiplier = "first"))]
+    fn items(limit: i32) -> Vec<i32> {
+        vec![limit]
+    }
+}
+
+fn main() {}
@@ -0,0 +1,139 @@
+use juniper::{
+    execute_sync, graphql_object, meta::MetaType, EmptyMutation, EmptySubscription, GraphQLError,
+    GraphQLObject, InputValue, RootNode, Variables,
+};
+
+#[derive(GraphQLObject)]
+struct Review {
+    body: String,
+    #[graphql(complexity = 5)]
+    sentiment: i32,
+}
+
+struct Query;
+
+#[graphql_object]
+impl Query {
+    #[graphql(complexity(cost = 2, multiplier = "first"))]
+    fn reviews(first: Option<i32>) -> Vec<Review> {
+        (0..first.unwrap_or(1))
+            .map(|i| Review {
+                body: format!("Review {}", i),
+                sentiment: i,
+            })
+            .collect()
+    }
+
+    #[graphql(complexity(multiplier = 10))]
+    fn featured() -> Vec<Review> {
+        vec![]
+    }
+}
+
+type Schema = RootNode<'static, Query, EmptyMutation, EmptySubscription>;
+
+fn schema() -> Schema {
+    Schema::new(Query, EmptyMutation::new(), EmptySubscription::new())
+}
+
+fn validation_error(schema: &Schema, query: &str, variables: &Variables) -> Option<String> {
+    match execute_sync(query, None, schema, variables, &()) {
+        Ok(_) => None,
+        Err(GraphQLError::ValidationError(errors)) => Some(errors[0].message().to_owned()),
+        Err(e) => panic!("unexpected error: {:?}", e),
+    }
+}
+
+#[test]
+fn stores_complexity_in_meta() {
+    let schema = schema();
+
+    match schema.schema.concrete_type_by_name("Query") {
+        Some(MetaType::Object(meta)) => {
+            let reviews = meta.fields.iter().find(|f| f.name == "reviews").unwrap();
+            assert_eq!(reviews.complexity.cost, 2);
+            assert_eq!(
+                reviews.complexity.multiplier_argument.as_deref(),
+                Some("first"),
+            );
+
+            let featured = meta.fields.iter().find(|f| f.name == "featured").unwrap();
+            assert_eq!(featured.complexity.cost, 1);
+            assert_eq!(featured.complexity.multiplier, 10);
+        }
+        _ => panic!("Query is not an object"),
+    }
+
+    match schema.schema.concrete_type_by_name("Review") {
+        Some(MetaType::Object(meta)) => {
+            let sentiment = meta.fields.iter().find(|f| f.name == "sentiment").unwrap();
+            assert_eq!(sentiment.complexity.cost, 5);
+            let body = meta.fields.iter().find(|f| f.name == "body").unwrap();
+            assert_eq!(body.complexity.cost, 1);
+        }
+        _ => panic!("Review is not an object"),
+    }
+}
+
+#[test]
+fn rejects_complex_queries() {
+    let schema = schema().with_max_complexity(50);
+    let query = "query($first: Int) { reviews(first: $first) { body sentiment } }";
+
+    // 2 + 5 * (1 + 5)
+    let variables = vec![("first".to_owned(), InputValue::scalar(5))]
+        .into_iter()
+        .collect();
+    assert_eq!(validation_error(&schema, query, &variables), None);
+
+    // 2 + 10 * (1 + 5)
+    let variables = vec![("first".to_owned(), InputValue::scalar(10))]
+        .into_iter()
+        .collect();
+    assert_eq!(
+        validation_error(&schema, query, &variables).as_deref(),
+        Some("Operation has a complexity of 62, which exceeds the maximum complexity of 50"),
+    );
+
+    // 1 + 10 * 1
+    assert_eq!(
+        validation_error(&schema, "{ featured { body } }", &Variables::new()),
+        None,
+    );
+    assert_eq!(
+        validation_error(
+            &schema,
+            "{ reviews(first: 100) { body } }",
+            &Variables::new(),
+        )
+        .as_deref(),
+        Some("Operation has a complexity of 102, which exceeds the maximum complexity of 50"),
+    );
+}
+
+#[test]
+fn rejects_deep_queries() {
+    let schema = schema().with_max_depth(1);
+
+    assert_eq!(
+        validation_error(
+            &schema,
+            "query Deep { featured { body } }",
+            &Variables::new()
+        )
+        .as_deref(),
+        Some(r#"Operation "Deep" has a depth of 2, which exceeds the maximum depth of 1"#),
+    );
+}
+
+#[test]
+fn has_no_limits_by_default() {
+    assert_eq!(
+        validation_error(
+            &schema(),
+            "{ reviews(first: 100) { body } }",
+            &Variables::new()
+        ),
+        None,
+    );
+}
@@ -52,6 +52,15 @@ struct OverrideDocComment {
     regular_field: bool,
 }
 
+#[derive(GraphQLInputObject, Debug, PartialEq)]
+#[graphql(scalar = DefaultScalarValue)]
+enum OneOfInput {
+    /// Variant comment.
+    ById(i32),
+    #[graphql(name = "name")]
+    ByName(String),
+}
+
 #[derive(Debug, PartialEq)]
 struct Fake;
 
@@ -83,6 +92,8 @@ impl<'a> GraphQLType<DefaultScalarValue> for &'a Fake {
                 name: "fake".to_string(),
                 description: None,
                 deprecation_status: juniper::meta::DeprecationStatus::Current,
+                directives: vec![],
+                visibility: None,
             }],
         );
         meta.into_meta()
@@ -192,3 +203,72 @@ fn test_doc_comment_override() {
     let meta = OverrideDocComment::meta(&(), &mut registry);
     assert_eq!(meta.description(), Some(&"obj override".to_string()));
 }
+
+#[test]
+fn test_derived_one_of_input_object() {
+    let mut registry: Registry = Registry::new(FnvHashMap::default());
+    let meta = OneOfInput::meta(&(), &mut registry);
+    assert!(meta.is_one_of());
+    let field = meta.input_field_by_name("byId").unwrap();
+    assert_eq!(field.arg_type.to_string(), "Int");
+    assert_eq!(field.description, Some("Variant comment.".to_string()));
+    assert!(meta.input_field_by_name("name").is_some());
+
+    let input: InputValue = ::serde_json::from_value(serde_json::json!({
+        "name": "a",
+    }))
+    .unwrap();
+    let output: OneOfInput = FromInputValue::from_input_value(&input).unwrap();
+    assert_eq!(output, OneOfInput::ByName("a".into()));
+    assert_eq!(output.to_input_value(), input);
+
+    for input in &[
+        serde_json::json!({}),
+        serde_json::json!({ "byId": null }),
+        serde_json::json!({ "byId": 1, "name": "a" }),
+    ] {
+        let input: InputValue = ::serde_json::from_value(input.clone()).unwrap();
+        assert_eq!(
+            <OneOfInput as FromInputValue>::from_input_value(&input),
+            None,
+        );
+    }
+}
+
+#[derive(GraphQLInputObject, Debug, PartialEq)]
+#[graphql(scalar = DefaultScalarValue)]
+struct Outer {
+    inputs: Vec<Input>,
+}
+
+#[test]
+fn test_derived_input_object_errors() {
+    let input: InputValue = ::serde_json::from_value(serde_json::json!({
+        "inputs": [{"regularField": "a"}, {"regularField": "b", "haha": "c"}],
+    }))
+    .unwrap();
+    let err = <Outer as FromInputValue>::try_from_input_value(&input).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "In field \"inputs\": In element #1: In field \"haha\": \
+         Int cannot represent non-integer value: \"c\"",
+    );
+
+    let input: InputValue = ::serde_json::from_value(serde_json::json!({
+        "inputs": [{"haha": 1}],
+    }))
+    .unwrap();
+    let err = <Outer as FromInputValue>::try_from_input_value(&input).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "In field \"inputs\": In element #0: Missing required field \"regularField\"",
+    );
+
+    let input: InputValue =
+        ::serde_json::from_value(serde_json::json!({ "byId": 1, "name": "a" })).unwrap();
+    let err = <OneOfInput as FromInputValue>::try_from_input_value(&input).unwrap_err();
+    assert_eq!(
+        err.message(),
+        "Exactly one field of OneOf input object \"OneOfInput\" must be given",
+    );
+}
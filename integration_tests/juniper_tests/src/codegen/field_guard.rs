@@ -0,0 +1,253 @@
+//! Tests for `guard` attribute argument of fields.
+
+use juniper::{
+    execute, graphql_interface, graphql_object, graphql_value, Arguments, DefaultScalarValue,
+    EmptyMutation, EmptySubscription, ExecutionError, FieldError, FieldResult, GraphQLObject,
+    Guard, Object, PathSegment, RootNode, ScalarValue, Value, Variables,
+};
+
+struct Context {
+    roles: Vec<&'static str>,
+}
+
+impl juniper::Context for Context {}
+
+struct HasRole(&'static str);
+
+impl<S: ScalarValue> Guard<Context, S> for HasRole {
+    fn check(&self, context: &Context, _: &Arguments<S>) -> FieldResult<(), S> {
+        if context.roles.contains(&self.0) {
+            Ok(())
+        } else {
+            Err(format!("Missing role \"{}\"", self.0).into())
+        }
+    }
+}
+
+fn limit_is_small<S: ScalarValue>(_: &Context, args: &Arguments<S>) -> FieldResult<(), S> {
+    match args.get::<i32>("limit") {
+        Some(limit) if limit > 10 => Err("Limit is too large".into()),
+        _ => Ok(()),
+    }
+}
+
+#[derive(GraphQLObject)]
+#[graphql(context = Context)]
+struct User {
+    name: String,
+    #[graphql(guard = r#"HasRole("admin")"#)]
+    email: String,
+}
+
+#[graphql_interface(for = Human, context = Context)]
+trait Character {
+    #[graphql_interface(guard = r#"HasRole("admin")"#)]
+    fn secret(&self) -> &str;
+}
+
+#[derive(GraphQLObject)]
+#[graphql(impl = CharacterValue, context = Context)]
+struct Human {
+    name: String,
+    secret: String,
+}
+
+#[graphql_interface]
+impl Character for Human {
+    fn secret(&self) -> &str {
+        &self.secret
+    }
+}
+
+struct Query;
+
+#[graphql_object(context = Context)]
+impl Query {
+    #[graphql(guard = r#"HasRole("admin")"#)]
+    fn users_count() -> i32 {
+        42
+    }
+
+    #[graphql(guard = r#"HasRole("user")"#)]
+    async fn user() -> Option<User> {
+        Some(User {
+            name: "Luke".into(),
+            email: "luke@example.com".into(),
+        })
+    }
+
+    #[graphql(guard = r#"HasRole("user")"#, guard = "limit_is_small")]
+    fn numbers(limit: i32) -> Vec<i32> {
+        (0..limit).collect()
+    }
+
+    fn character() -> Option<CharacterValue> {
+        Some(
+            Human {
+                name: "Han".into(),
+                secret: "Likes pineapple pizza".into(),
+            }
+            .into(),
+        )
+    }
+}
+
+async fn run(
+    doc: &str,
+    roles: Vec<&'static str>,
+) -> (
+    Value<DefaultScalarValue>,
+    Vec<ExecutionError<DefaultScalarValue>>,
+) {
+    let schema = RootNode::new(
+        Query,
+        EmptyMutation::<Context>::new(),
+        EmptySubscription::<Context>::new(),
+    );
+
+    execute(doc, None, &schema, &Variables::new(), &Context { roles })
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn resolves_allowed_fields() {
+    const DOC: &str = r#"{
+        usersCount
+        user { name email }
+        numbers(limit: 3)
+        character { secret }
+    }"#;
+
+    assert_eq!(
+        run(DOC, vec!["admin", "user"]).await,
+        (
+            graphql_value!({
+                "usersCount": 42,
+                "user": {"name": "Luke", "email": "luke@example.com"},
+                "numbers": [0, 1, 2],
+                "character": {"secret": "Likes pineapple pizza"},
+            }),
+            vec![],
+        ),
+    );
+}
+
+#[tokio::test]
+async fn rejects_fields_of_objects() {
+    const DOC: &str = r#"{ usersCount }"#;
+
+    let (res, errors) = run(DOC, vec!["user"]).await;
+
+    assert_eq!(res, graphql_value!(None));
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].path(), &[PathSegment::Field("usersCount".into())]);
+    assert_eq!(
+        errors[0].error(),
+        &FieldError::from("Missing role \"admin\""),
+    );
+}
+
+#[tokio::test]
+async fn rejects_async_fields() {
+    const DOC: &str = r#"{ user { name } }"#;
+
+    let (res, errors) = run(DOC, vec![]).await;
+
+    assert_eq!(res, graphql_value!({"user": None}));
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        errors[0].error(),
+        &FieldError::from("Missing role \"user\""),
+    );
+}
+
+#[tokio::test]
+async fn rejects_fields_of_derived_objects() {
+    const DOC: &str = r#"{ user { name email } }"#;
+
+    let (res, errors) = run(DOC, vec!["user"]).await;
+
+    assert_eq!(res, graphql_value!({"user": None}));
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        errors[0].path(),
+        &[
+            PathSegment::Field("user".into()),
+            PathSegment::Field("email".into()),
+        ],
+    );
+    assert_eq!(
+        errors[0].error(),
+        &FieldError::from("Missing role \"admin\""),
+    );
+}
+
+#[tokio::test]
+async fn checks_guards_in_order_with_arguments() {
+    const DOC: &str = r#"{ numbers(limit: 20) }"#;
+
+    let (_, errors) = run(DOC, vec![]).await;
+    assert_eq!(
+        errors[0].error(),
+        &FieldError::from("Missing role \"user\""),
+    );
+
+    let (_, errors) = run(DOC, vec!["user"]).await;
+    assert_eq!(errors[0].error(), &FieldError::from("Limit is too large"));
+}
+
+#[tokio::test]
+async fn rejects_fields_of_interfaces() {
+    const DOC: &str = r#"{ character { secret } }"#;
+
+    let (res, errors) = run(DOC, vec!["user"]).await;
+
+    assert_eq!(res, graphql_value!({"character": None}));
+    assert_eq!(
+        errors[0].path(),
+        &[
+            PathSegment::Field("character".into()),
+            PathSegment::Field("secret".into()),
+        ],
+    );
+    assert_eq!(
+        errors[0].error(),
+        &FieldError::from("Missing role \"admin\""),
+    );
+}
+
+#[tokio::test]
+async fn rejects_fields_of_interfaces_resolved_on_implementers() {
+    const DOC: &str = r#"{ character { ... on Human { name secret } } }"#;
+
+    let (res, errors) = run(DOC, vec!["user"]).await;
+
+    // The fragment resolved on `Human` is left out of the response once it errors.
+    assert_eq!(
+        res,
+        graphql_value!({"character": (Value::object(Object::with_capacity(0)))}),
+    );
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        errors[0].path(),
+        &[
+            PathSegment::Field("character".into()),
+            PathSegment::Field("secret".into()),
+        ],
+    );
+    assert_eq!(
+        errors[0].error(),
+        &FieldError::from("Missing role \"admin\""),
+    );
+
+    let (res, errors) = run(DOC, vec!["admin"]).await;
+
+    assert_eq!(
+        res,
+        graphql_value!({
+            "character": {"name": "Han", "secret": "Likes pineapple pizza"},
+        }),
+    );
+    assert_eq!(errors, vec![]);
+}
@@ -1,6 +1,7 @@
 use juniper::{
-    execute, graphql_object, graphql_scalar, graphql_value, DefaultScalarValue, EmptyMutation,
-    EmptySubscription, Object, ParseScalarResult, ParseScalarValue, RootNode, Value, Variables,
+    execute, graphql_object, graphql_scalar, graphql_value, parser::SourcePosition,
+    DefaultScalarValue, EmptyMutation, EmptySubscription, GraphQLError, InputValue, Object,
+    ParseScalarResult, ParseScalarValue, RootNode, RuleError, Value, Variables,
 };
 
 use crate::custom_scalar::MyScalarValue;
@@ -129,6 +130,39 @@ impl RootWithCustomScalarValue {
     }
 }
 
+struct Even(i32);
+
+#[graphql_scalar]
+impl GraphQLScalar for Even {
+    fn resolve(&self) -> Value {
+        Value::scalar(self.0)
+    }
+
+    fn from_input_value(v: &InputValue) -> Result<Even, String> {
+        let i = v
+            .as_scalar_value::<i32>()
+            .ok_or_else(|| format!("Expected an integer, found: {}", v))?;
+        if i % 2 == 0 {
+            Ok(Even(*i))
+        } else {
+            Err(format!("{} is not even", i))
+        }
+    }
+
+    fn from_str<'a>(value: ScalarToken<'a>) -> ParseScalarResult<'a, DefaultScalarValue> {
+        <i32 as ParseScalarValue>::from_str(value)
+    }
+}
+
+struct RootWithEven;
+
+#[graphql_object(scalar = DefaultScalarValue)]
+impl RootWithEven {
+    fn half(n: Even) -> i32 {
+        n.0 / 2
+    }
+}
+
 async fn run_type_info_query<F>(doc: &str, f: F)
 where
     F: Fn(&Object<DefaultScalarValue>) -> (),
@@ -289,3 +323,30 @@ async fn resolves_with_custom_scalar_value() {
         Ok((graphql_value!({"withCustomScalarValue": 0}), vec![])),
     );
 }
+
+#[tokio::test]
+async fn reports_errors_of_fallible_from_input_value() {
+    const DOC: &str = r#"query q($n: Even!) { half(n: $n) }"#;
+
+    let schema = RootNode::new(
+        RootWithEven,
+        EmptyMutation::<()>::new(),
+        EmptySubscription::<()>::new(),
+    );
+
+    let mut vars = Variables::new();
+    vars.insert("n".to_owned(), InputValue::scalar(4));
+    assert_eq!(
+        execute(DOC, None, &schema, &vars, &()).await,
+        Ok((graphql_value!({"half": 2}), vec![])),
+    );
+
+    vars.insert("n".to_owned(), InputValue::scalar(3));
+    assert_eq!(
+        execute(DOC, None, &schema, &vars, &()).await,
+        Err(GraphQLError::ValidationError(vec![RuleError::new(
+            r#"Variable "$n" got invalid value. Expected "Even": 3 is not even."#,
+            &[SourcePosition::new(8, 0, 8)],
+        )])),
+    );
+}
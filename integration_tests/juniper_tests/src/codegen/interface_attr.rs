@@ -5270,3 +5270,116 @@ mod external_downcast {
         }
     }
 }
+
+mod implements_interface {
+    use super::*;
+
+    #[graphql_interface(for = File)]
+    trait Node {
+        fn id(&self) -> &str;
+    }
+
+    #[graphql_interface(impl = NodeValue, for = File)]
+    trait Resource {
+        fn id(&self) -> &str;
+
+        fn url(&self) -> &str;
+    }
+
+    #[derive(GraphQLObject)]
+    #[graphql(impl = [NodeValue, ResourceValue])]
+    struct File {
+        id: String,
+        url: String,
+    }
+
+    #[graphql_interface]
+    impl Node for File {
+        fn id(&self) -> &str {
+            &self.id
+        }
+    }
+
+    #[graphql_interface]
+    impl Resource for File {
+        fn id(&self) -> &str {
+            &self.id
+        }
+
+        fn url(&self) -> &str {
+            &self.url
+        }
+    }
+
+    struct QueryRoot;
+
+    #[graphql_object]
+    impl QueryRoot {
+        fn resource(&self) -> ResourceValue {
+            File {
+                id: "file-1".to_string(),
+                url: "/files/1".to_string(),
+            }
+            .into()
+        }
+    }
+
+    #[tokio::test]
+    async fn resolves_fragment_on_implemented_interface() {
+        const DOC: &str = r#"{
+            resource {
+                url
+                ... on Node {
+                    nodeId: id
+                }
+            }
+        }"#;
+
+        let schema = schema(QueryRoot);
+
+        assert_eq!(
+            execute(DOC, None, &schema, &Variables::new(), &()).await,
+            Ok((
+                graphql_value!({"resource": {"url": "/files/1", "nodeId": "file-1"}}),
+                vec![],
+            )),
+        );
+    }
+
+    #[tokio::test]
+    async fn registers_implemented_interfaces() {
+        const DOC: &str = r#"{
+            __type(name: "Resource") {
+                interfaces {
+                    kind
+                    name
+                }
+            }
+        }"#;
+
+        let schema = schema(QueryRoot);
+
+        assert_eq!(
+            execute(DOC, None, &schema, &Variables::new(), &()).await,
+            Ok((
+                graphql_value!({"__type": {"interfaces": [
+                    {"kind": "INTERFACE", "name": "Node"},
+                ]}}),
+                vec![],
+            )),
+        );
+    }
+
+    #[test]
+    fn is_possible_type_of_implemented_interface() {
+        let schema = schema(QueryRoot);
+        let node = schema.schema.concrete_type_by_name("Node").unwrap();
+        let resource = schema.schema.concrete_type_by_name("Resource").unwrap();
+        let file = schema.schema.concrete_type_by_name("File").unwrap();
+
+        assert!(schema.schema.is_possible_type(node, file));
+        assert!(schema.schema.is_named_subtype("Resource", "Node"));
+        assert!(!schema.schema.is_named_subtype("Node", "Resource"));
+        assert!(schema.schema.implements_interface(resource, "Node"));
+    }
+}
@@ -0,0 +1,159 @@
+//! Tests for `#[derive(IntoFieldError)]` macro.
+
+use std::fmt;
+
+use juniper::{
+    execute, graphql_object, graphql_value, DefaultScalarValue, EmptyMutation, EmptySubscription,
+    FieldError, IntoFieldError, PathSegment, RootNode, Variables,
+};
+
+#[derive(Debug, IntoFieldError)]
+#[graphql(code = "BAD_USER_INPUT")]
+enum UserError {
+    #[graphql(message = "User not found", code = "NOT_FOUND", extensions(id = self.0))]
+    NotFound(i32),
+    #[graphql(
+        message = "Name is too long",
+        extensions(name = self.name, max = self.max as i32, exceeded = self.exceeded()),
+    )]
+    NameTooLong {
+        name: String,
+        max: usize,
+    },
+    Banned,
+    #[graphql(internal)]
+    Database(String),
+}
+
+impl UserError {
+    fn exceeded(&self) -> bool {
+        true
+    }
+}
+
+impl fmt::Display for UserError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Database(e) => write!(f, "Database error: {}", e),
+            e => write!(f, "{:?}", e),
+        }
+    }
+}
+
+#[derive(IntoFieldError)]
+#[graphql(
+    message = "Too many requests",
+    code = "RATE_LIMITED",
+    extensions(retry_after = self.retry_after),
+)]
+struct RateLimited {
+    retry_after: Option<i32>,
+}
+
+#[test]
+fn uses_message_and_extensions_of_variant() {
+    let error: FieldError = UserError::NotFound(42).into_field_error();
+
+    assert_eq!(error.message(), "User not found");
+    assert_eq!(
+        error.extensions(),
+        &graphql_value!({"code": "NOT_FOUND", "id": 42}),
+    );
+    assert!(error.is_user_facing());
+}
+
+#[test]
+fn interpolates_named_fields_and_methods() {
+    let error: FieldError = UserError::NameTooLong {
+        name: "Luke".into(),
+        max: 3,
+    }
+    .into_field_error();
+
+    assert_eq!(error.message(), "Name is too long");
+    assert_eq!(
+        error.extensions(),
+        &graphql_value!({
+            "code": "BAD_USER_INPUT",
+            "name": "Luke",
+            "max": 3,
+            "exceeded": true,
+        }),
+    );
+}
+
+#[test]
+fn falls_back_to_display_and_default_code() {
+    let error: FieldError = UserError::Banned.into_field_error();
+
+    assert_eq!(error.message(), "Banned");
+    assert_eq!(
+        error.extensions(),
+        &graphql_value!({"code": "BAD_USER_INPUT"})
+    );
+    assert!(error.is_user_facing());
+}
+
+#[test]
+fn marks_internal_variants() {
+    let error: FieldError = UserError::Database("Connection refused".into()).into_field_error();
+
+    assert_eq!(error.message(), "Database error: Connection refused");
+    assert!(!error.is_user_facing());
+}
+
+#[test]
+fn derives_for_structs() {
+    let error: FieldError = RateLimited {
+        retry_after: Some(30),
+    }
+    .into_field_error();
+
+    assert_eq!(error.message(), "Too many requests");
+    assert_eq!(
+        error.extensions(),
+        &graphql_value!({"code": "RATE_LIMITED", "retry_after": 30}),
+    );
+
+    let error: FieldError = RateLimited { retry_after: None }.into_field_error();
+    assert_eq!(
+        error.extensions(),
+        &graphql_value!({"code": "RATE_LIMITED", "retry_after": None}),
+    );
+}
+
+struct Query;
+
+#[graphql_object]
+impl Query {
+    fn user_name(id: i32) -> Result<String, UserError> {
+        Err(UserError::NotFound(id))
+    }
+}
+
+#[tokio::test]
+async fn is_returned_from_resolvers() {
+    const DOC: &str = r#"{ userName(id: 7) }"#;
+
+    let schema = RootNode::new(
+        Query,
+        EmptyMutation::<()>::new(),
+        EmptySubscription::<()>::new(),
+    );
+
+    let (res, errors) = execute(DOC, None, &schema, &Variables::new(), &())
+        .await
+        .unwrap();
+
+    assert_eq!(res, graphql_value!(None));
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].path(), &[PathSegment::Field("userName".into())]);
+    assert_eq!(
+        errors[0].error(),
+        &FieldError::<DefaultScalarValue>::new(
+            "User not found",
+            graphql_value!({"code": "NOT_FOUND", "id": 7}),
+        )
+        .user_facing(),
+    );
+}
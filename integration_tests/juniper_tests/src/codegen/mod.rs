@@ -1,10 +1,15 @@
+mod complexity;
 mod derive_enum;
 mod derive_input_object;
 mod derive_object;
 mod derive_object_with_raw_idents;
+mod field_guard;
 mod impl_object;
 mod impl_scalar;
 mod interface_attr;
+mod into_field_error;
 mod scalar_value_transparent;
+mod type_system_directives;
 mod union_attr;
 mod union_derive;
+mod visibility;
@@ -0,0 +1,158 @@
+use juniper::{
+    graphql_object, meta::MetaType, DefaultScalarValue, EmptyMutation, EmptySubscription,
+    GraphQLEnum, GraphQLInputObject, GraphQLObject, InputValue, RootNode,
+};
+
+#[derive(GraphQLObject)]
+#[graphql(directive = r#"@key(fields: "id") @shareable"#)]
+struct Product {
+    #[graphql(directive = "@external")]
+    id: String,
+    #[graphql(
+        directive = r#"@requires(fields: "id")"#,
+        directive = "@tag(names: [\"a\", \"b\"], weight: 1.5)"
+    )]
+    price: i32,
+}
+
+#[derive(GraphQLEnum)]
+#[graphql(directive = "@inaccessible")]
+enum Color {
+    #[graphql(directive = r#"@tag(name: "warm")"#)]
+    Red,
+    Blue,
+}
+
+#[derive(GraphQLInputObject)]
+#[graphql(directive = "@auth(requires: ADMIN, scopes: {read: true, level: -2})")]
+struct Filter {
+    #[graphql(directive = "@length(max: 10)")]
+    name: Option<String>,
+}
+
+struct Query;
+
+#[graphql_object(directive = "@auth(requires: null)")]
+impl Query {
+    #[graphql(
+        directive = "@deprecatedByPolicy",
+        arguments(filter(directive = "@sensitive"))
+    )]
+    fn products(filter: Option<Filter>) -> Vec<Product> {
+        let _ = filter;
+        vec![]
+    }
+
+    fn color() -> Color {
+        Color::Red
+    }
+}
+
+type Schema = RootNode<'static, Query, EmptyMutation, EmptySubscription>;
+
+fn schema() -> Schema {
+    Schema::new(Query, EmptyMutation::new(), EmptySubscription::new())
+}
+
+#[test]
+fn stores_directives_in_meta() {
+    let schema = schema();
+
+    match schema.schema.concrete_type_by_name("Product") {
+        Some(MetaType::Object(meta)) => {
+            let names = meta.directives.iter().map(|d| &d.name).collect::<Vec<_>>();
+            assert_eq!(names, ["key", "shareable"]);
+            assert_eq!(
+                meta.directives[0].arguments,
+                [("fields".to_owned(), InputValue::scalar("id"))],
+            );
+
+            let price = meta.fields.iter().find(|f| f.name == "price").unwrap();
+            assert_eq!(price.directives.len(), 2);
+            assert_eq!(
+                price.directives[1].arguments,
+                [
+                    (
+                        "names".to_owned(),
+                        InputValue::list(vec![InputValue::scalar("a"), InputValue::scalar("b")]),
+                    ),
+                    ("weight".to_owned(), InputValue::scalar(1.5)),
+                ],
+            );
+        }
+        _ => panic!("Product is not an object"),
+    }
+
+    match schema.schema.concrete_type_by_name("Color") {
+        Some(MetaType::Enum(meta)) => {
+            assert_eq!(meta.directives[0].name, "inaccessible");
+            assert_eq!(meta.values[0].directives[0].name, "tag");
+            assert!(meta.values[1].directives.is_empty());
+        }
+        _ => panic!("Color is not an enum"),
+    }
+
+    match schema.schema.concrete_type_by_name("Filter") {
+        Some(MetaType::InputObject(meta)) => {
+            let auth = &meta.directives[0];
+            assert_eq!(
+                auth.arguments[0].1,
+                InputValue::<DefaultScalarValue>::enum_value("ADMIN")
+            );
+            assert_eq!(
+                auth.arguments[1].1,
+                InputValue::object(
+                    vec![
+                        ("read", InputValue::scalar(true)),
+                        ("level", InputValue::scalar(-2)),
+                    ]
+                    .into_iter()
+                    .collect(),
+                ),
+            );
+            assert_eq!(meta.input_fields[0].directives[0].name, "length");
+        }
+        _ => panic!("Filter is not an input object"),
+    }
+
+    match schema.schema.concrete_type_by_name("Query") {
+        Some(MetaType::Object(meta)) => {
+            assert_eq!(
+                meta.directives[0].arguments,
+                [("requires".to_owned(), InputValue::null())],
+            );
+
+            let products = meta.fields.iter().find(|f| f.name == "products").unwrap();
+            assert_eq!(products.directives[0].name, "deprecatedByPolicy");
+            assert_eq!(
+                products.arguments.as_ref().unwrap()[0].directives[0].name,
+                "sensitive",
+            );
+        }
+        _ => panic!("Query is not an object"),
+    }
+}
+
+#[test]
+fn emits_directives_in_schema_language() {
+    let sdl = schema().as_schema_language();
+
+    for expected in &[
+        r#"type Product @key(fields: "id") @shareable {"#,
+        "id: String! @external",
+        r#"price: Int! @requires(fields: "id") @tag(names: ["a", "b"], weight: 1.5)"#,
+        "enum Color @inaccessible {",
+        r#"RED @tag(name: "warm")"#,
+        "input Filter @auth(requires: ADMIN, scopes: {level: -2, read: true}) {",
+        "name: String @length(max: 10)",
+        "type Query @auth(requires: null) {",
+        "products(filter: Filter @sensitive): [Product!]! @deprecatedByPolicy",
+    ] {
+        assert!(
+            sdl.contains(expected),
+            "{:?} not found in:\n{}",
+            expected,
+            sdl
+        );
+    }
+}
@@ -0,0 +1,342 @@
+//! Tests for `visibility` attribute argument of types, fields and enum values.
+
+use juniper::{
+    execute, graphql_object, graphql_value, DefaultScalarValue, EmptyMutation, EmptySubscription,
+    ExecutionError, GraphQLEnum, GraphQLError, GraphQLInputObject, GraphQLObject, InputValue,
+    RootNode, Value, Variables,
+};
+
+struct Context {
+    flags: Vec<&'static str>,
+}
+
+impl juniper::Context for Context {}
+
+#[derive(GraphQLEnum)]
+enum Role {
+    User,
+    #[graphql(visibility = "internal")]
+    Admin,
+}
+
+#[derive(GraphQLObject)]
+#[graphql(context = Context, visibility = "internal")]
+struct AuditLog {
+    message: String,
+}
+
+#[derive(GraphQLObject)]
+#[graphql(context = Context)]
+struct User {
+    name: String,
+    #[graphql(visibility = "internal")]
+    email: String,
+    role: Role,
+}
+
+#[derive(GraphQLInputObject)]
+#[graphql(visibility = "beta")]
+struct Filter {
+    name: String,
+}
+
+struct Query;
+
+#[graphql_object(context = Context)]
+impl Query {
+    fn user() -> User {
+        User {
+            name: "Luke".into(),
+            email: "luke@example.com".into(),
+            role: Role::Admin,
+        }
+    }
+
+    fn audit_log() -> Vec<AuditLog> {
+        vec![AuditLog {
+            message: "Luke signed in".into(),
+        }]
+    }
+
+    #[graphql(visibility = "beta")]
+    fn search(filter: Filter) -> Vec<String> {
+        vec![filter.name]
+    }
+
+    #[graphql(visibility = "unregistered")]
+    fn legacy() -> bool {
+        true
+    }
+
+    fn users_with_role(role: Role) -> Vec<String> {
+        match role {
+            Role::User => vec!["Luke".into()],
+            Role::Admin => vec![],
+        }
+    }
+}
+
+type Schema = RootNode<'static, Query, EmptyMutation<Context>, EmptySubscription<Context>>;
+
+fn schema() -> Schema {
+    Schema::new(Query, EmptyMutation::new(), EmptySubscription::new())
+        .with_visibility("internal", |ctx: &Context| ctx.flags.contains(&"internal"))
+        .with_visibility("beta", |ctx: &Context| ctx.flags.contains(&"beta"))
+}
+
+type ExecutionOutput = (Value, Vec<ExecutionError<DefaultScalarValue>>);
+
+async fn run<'a>(
+    schema: &'a Schema,
+    doc: &'a str,
+    flags: Vec<&'static str>,
+) -> Result<ExecutionOutput, GraphQLError<'a>> {
+    run_with_variables(schema, doc, &Variables::new(), flags).await
+}
+
+async fn run_with_variables<'a>(
+    schema: &'a Schema,
+    doc: &'a str,
+    variables: &Variables,
+    flags: Vec<&'static str>,
+) -> Result<ExecutionOutput, GraphQLError<'a>> {
+    execute(doc, None, schema, variables, &Context { flags }).await
+}
+
+fn validation_errors(res: Result<ExecutionOutput, GraphQLError>) -> Vec<String> {
+    match res {
+        Err(GraphQLError::ValidationError(errors)) => {
+            errors.iter().map(|e| e.message().to_owned()).collect()
+        }
+        res => panic!("expected validation errors, got {:?}", res),
+    }
+}
+
+const INTROSPECTION: &str = r#"{
+    query: __type(name: "Query") { fields { name } }
+    user: __type(name: "User") { fields { name } }
+    role: __type(name: "Role") { enumValues { name } }
+    auditLog: __type(name: "AuditLog") { name }
+    filter: __type(name: "Filter") { name }
+}"#;
+
+#[tokio::test]
+async fn hides_items_from_introspection() {
+    let schema = schema();
+
+    assert_eq!(
+        run(&schema, INTROSPECTION, vec![]).await,
+        Ok((
+            graphql_value!({
+                "query": {"fields": [{"name": "user"}, {"name": "usersWithRole"}]},
+                "user": {"fields": [{"name": "name"}, {"name": "role"}]},
+                "role": {"enumValues": [{"name": "USER"}]},
+                "auditLog": None,
+                "filter": None,
+            }),
+            vec![],
+        )),
+    );
+}
+
+#[tokio::test]
+async fn shows_items_to_allowed_requests() {
+    let schema = schema();
+
+    assert_eq!(
+        run(&schema, INTROSPECTION, vec!["internal", "beta"]).await,
+        Ok((
+            graphql_value!({
+                "query": {"fields": [
+                    {"name": "user"},
+                    {"name": "auditLog"},
+                    {"name": "search"},
+                    {"name": "usersWithRole"},
+                ]},
+                "user": {"fields": [{"name": "name"}, {"name": "email"}, {"name": "role"}]},
+                "role": {"enumValues": [{"name": "USER"}, {"name": "ADMIN"}]},
+                "auditLog": {"name": "AuditLog"},
+                "filter": {"name": "Filter"},
+            }),
+            vec![],
+        )),
+    );
+}
+
+#[tokio::test]
+async fn hides_types_from_schema_introspection() {
+    const DOC: &str = r#"{ __schema { types { name } } }"#;
+
+    let schema = schema();
+
+    let (res, _) = run(&schema, DOC, vec![]).await.unwrap();
+    let names = res
+        .as_object_value()
+        .and_then(|o| o.get_field_value("__schema"))
+        .and_then(|s| s.as_object_value())
+        .and_then(|s| s.get_field_value("types"))
+        .and_then(|t| t.as_list_value())
+        .unwrap()
+        .iter()
+        .filter_map(|t| {
+            t.as_object_value()?
+                .get_field_value("name")?
+                .as_string_value()
+        })
+        .collect::<Vec<_>>();
+
+    assert!(names.contains(&"User"));
+    assert!(!names.contains(&"AuditLog"));
+    assert!(!names.contains(&"Filter"));
+}
+
+#[tokio::test]
+async fn rejects_hidden_fields() {
+    const DOC: &str = r#"{ user { name email } }"#;
+
+    let schema = schema();
+
+    assert_eq!(
+        validation_errors(run(&schema, DOC, vec![]).await),
+        vec![r#"Unknown field "email" on type "User""#],
+    );
+    assert_eq!(
+        run(&schema, DOC, vec!["internal"]).await,
+        Ok((
+            graphql_value!({"user": {"name": "Luke", "email": "luke@example.com"}}),
+            vec![],
+        )),
+    );
+}
+
+#[tokio::test]
+async fn rejects_fields_of_hidden_types() {
+    const DOC: &str = r#"{ auditLog { message } }"#;
+
+    let schema = schema();
+
+    assert_eq!(
+        validation_errors(run(&schema, DOC, vec![]).await),
+        vec![r#"Unknown field "auditLog" on type "Query""#],
+    );
+    assert!(run(&schema, DOC, vec!["internal"]).await.is_ok());
+}
+
+#[tokio::test]
+async fn resolves_hidden_enum_values() {
+    const DOC: &str = r#"{ user { role } }"#;
+
+    let schema = schema();
+
+    assert_eq!(
+        run(&schema, DOC, vec![]).await,
+        Ok((graphql_value!({"user": {"role": "ADMIN"}}), vec![])),
+    );
+}
+
+#[tokio::test]
+async fn rejects_hidden_enum_values_in_arguments() {
+    const DOC: &str = r#"{ usersWithRole(role: ADMIN) }"#;
+
+    let schema = schema();
+
+    assert_eq!(
+        validation_errors(run(&schema, DOC, vec![]).await),
+        vec![r#"Invalid value for argument "role", expected type "Role!""#],
+    );
+    assert_eq!(
+        run(&schema, DOC, vec!["internal"]).await,
+        Ok((graphql_value!({"usersWithRole": []}), vec![])),
+    );
+    assert!(run(&schema, r#"{ usersWithRole(role: USER) }"#, vec![])
+        .await
+        .is_ok());
+}
+
+#[tokio::test]
+async fn rejects_hidden_enum_values_in_variables() {
+    const DOC: &str = r#"query ($role: Role!) { usersWithRole(role: $role) }"#;
+
+    let schema = schema();
+    let variables = vec![("role".to_owned(), InputValue::scalar("ADMIN"))]
+        .into_iter()
+        .collect();
+
+    assert_eq!(
+        validation_errors(run_with_variables(&schema, DOC, &variables, vec![]).await),
+        vec![r#"Variable "$role" got invalid value. Invalid value for enum "Role"."#],
+    );
+    assert_eq!(
+        run_with_variables(&schema, DOC, &variables, vec!["internal"]).await,
+        Ok((graphql_value!({"usersWithRole": []}), vec![])),
+    );
+}
+
+#[tokio::test]
+async fn rejects_hidden_enum_values_in_default_values() {
+    const DOC: &str = r#"query ($role: Role = ADMIN) { usersWithRole(role: $role) }"#;
+
+    let schema = schema();
+
+    assert_eq!(
+        validation_errors(run(&schema, DOC, vec![]).await),
+        vec![r#"Invalid default value for argument "role", expected type "Role""#],
+    );
+}
+
+#[tokio::test]
+async fn rejects_hidden_types() {
+    const DOC: &str = r#"query ($filter: Filter!) { search(filter: $filter) }"#;
+
+    let schema = schema();
+
+    let errors = validation_errors(run(&schema, DOC, vec!["internal"]).await);
+    assert!(errors.contains(&r#"Unknown type "Filter""#.to_owned()));
+    assert!(errors.contains(&r#"Unknown field "search" on type "Query""#.to_owned()));
+}
+
+#[tokio::test]
+async fn hides_items_with_unregistered_visibility() {
+    const DOC: &str = r#"{ legacy }"#;
+
+    let schema = schema();
+
+    assert_eq!(
+        validation_errors(run(&schema, DOC, vec!["internal", "beta"]).await),
+        vec![r#"Unknown field "legacy" on type "Query""#],
+    );
+}
+
+#[tokio::test]
+async fn validates_cached_documents_again() {
+    const DOC: &str = r#"{ user { email } }"#;
+
+    let schema = schema().with_document_cache(10);
+
+    assert!(run(&schema, DOC, vec!["internal"]).await.is_ok());
+    assert_eq!(
+        validation_errors(run(&schema, DOC, vec![]).await),
+        vec![r#"Unknown field "email" on type "User""#],
+    );
+}
+
+#[test]
+fn hides_items_from_schema_language() {
+    let schema = schema();
+
+    let sdl = schema.as_schema_language_for(&Context { flags: vec![] });
+    assert!(sdl.contains("type User {\n  name: String!\n  role: Role!\n}"));
+    assert!(sdl.contains("enum Role {\n  USER\n}"));
+    assert!(!sdl.contains("AuditLog"));
+    assert!(!sdl.contains("Filter"));
+    assert!(!sdl.contains("legacy"));
+
+    let sdl = schema.as_schema_language_for(&Context {
+        flags: vec!["internal"],
+    });
+    assert!(sdl.contains("email: String!"));
+    assert!(sdl.contains("type AuditLog {"));
+    assert!(!sdl.contains("Filter"));
+
+    assert!(schema.as_schema_language().contains("legacy: Boolean!"));
+}
@@ -1,10 +1,13 @@
-use std::{borrow::Cow, fmt, hash::Hash, slice, vec};
+use std::{borrow::Cow, fmt, hash::Hash, slice, sync::Arc, vec};
 
 use indexmap::IndexMap;
 
 use crate::{
-    executor::Variables,
-    parser::Spanning,
+    executor::{PathSegment, Variables},
+    parser::{
+        parse_document_source, ParseError, ScalarToken, Spanning, Token, UnlocatedParseResult,
+    },
+    schema::model::SchemaType,
     value::{DefaultScalarValue, ScalarValue},
 };
 
@@ -144,6 +147,102 @@ pub enum Definition<'a, S> {
 
 pub type Document<'a, S> = Vec<Definition<'a, S>>;
 
+/// A [`Document`] owning its source
+///
+/// Parsed documents borrow their names from the source they are parsed from, so this keeps
+/// both of them together, allowing a document to outlive the request it was sent with.
+pub struct OwnedDocument<S> {
+    // Borrows from `source`, so it's declared first to be dropped first. It's only handed out
+    // through `document()`, which shortens its lifetime to the one of `self`.
+    document: Document<'static, S>,
+    // Behind an `Arc` rather than a `Box`, as moving a `Box` asserts unique ownership of the
+    // string the document borrows from.
+    source: Arc<str>,
+}
+
+impl<S> OwnedDocument<S>
+where
+    S: ScalarValue,
+{
+    /// Parses a copy of `source` into a document owning it
+    pub fn parse<'a>(
+        source: &'a str,
+        schema: &SchemaType<S>,
+    ) -> UnlocatedParseResult<'a, OwnedDocument<S>> {
+        let owned_source: Arc<str> = source.into();
+
+        // SAFETY: The copy lives on the heap, so it doesn't move along with this struct, and
+        //         it's never mutated. The document is private and never outlives it, as it's
+        //         only borrowed for the lifetime of `self` and dropped before the copy. A parse
+        //         error is moved to the original source before the copy is dropped.
+        let borrowed_source: &'static str = unsafe { &*(&*owned_source as *const str) };
+
+        match parse_document_source(borrowed_source, schema) {
+            Ok(document) => Ok(OwnedDocument {
+                document,
+                source: owned_source,
+            }),
+            Err(err) => Err(err.map(|err| rebase_parse_error(err, borrowed_source, source))),
+        }
+    }
+}
+
+/// Makes a parse error of a copy of `source` borrow from `source` itself
+fn rebase_parse_error<'a>(error: ParseError<'_>, copy: &str, source: &'a str) -> ParseError<'a> {
+    let rebase = |s: &str| {
+        let offset = s.as_ptr() as usize - copy.as_ptr() as usize;
+        &source[offset..offset + s.len()]
+    };
+    match error {
+        ParseError::UnexpectedToken(token) => ParseError::UnexpectedToken(match token {
+            Token::Name(name) => Token::Name(rebase(name)),
+            Token::Scalar(ScalarToken::String(s)) => Token::Scalar(ScalarToken::String(rebase(s))),
+            Token::Scalar(ScalarToken::Float(s)) => Token::Scalar(ScalarToken::Float(rebase(s))),
+            Token::Scalar(ScalarToken::Int(s)) => Token::Scalar(ScalarToken::Int(rebase(s))),
+            Token::ExclamationMark => Token::ExclamationMark,
+            Token::Dollar => Token::Dollar,
+            Token::ParenOpen => Token::ParenOpen,
+            Token::ParenClose => Token::ParenClose,
+            Token::BracketOpen => Token::BracketOpen,
+            Token::BracketClose => Token::BracketClose,
+            Token::CurlyOpen => Token::CurlyOpen,
+            Token::CurlyClose => Token::CurlyClose,
+            Token::Ellipsis => Token::Ellipsis,
+            Token::Colon => Token::Colon,
+            Token::Equals => Token::Equals,
+            Token::At => Token::At,
+            Token::Pipe => Token::Pipe,
+            Token::EndOfFile => Token::EndOfFile,
+        }),
+        ParseError::UnexpectedEndOfFile => ParseError::UnexpectedEndOfFile,
+        ParseError::LexerError(err) => ParseError::LexerError(err),
+        ParseError::ExpectedScalarError(err) => ParseError::ExpectedScalarError(err),
+    }
+}
+
+impl<S> OwnedDocument<S> {
+    /// Returns the parsed document
+    pub fn document(&self) -> &Document<'_, S> {
+        &self.document
+    }
+
+    /// Returns the source the document was parsed from
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+impl<S> fmt::Debug for OwnedDocument<S>
+where
+    S: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("OwnedDocument")
+            .field("document", &self.document)
+            .finish()
+    }
+}
+
 /// Parse an unstructured input value into a Rust data type.
 ///
 /// The conversion _can_ fail, and must in that case return None. Implemented
@@ -154,6 +253,16 @@ pub trait FromInputValue<S = DefaultScalarValue>: Sized {
     /// Performs the conversion.
     fn from_input_value(v: &InputValue<S>) -> Option<Self>;
 
+    /// Performs the conversion, reporting why it failed. The default implementation uses
+    /// `from_input_value`, so it can only tell that the value is invalid.
+    fn try_from_input_value(v: &InputValue<S>) -> Result<Self, InputValueError>
+    where
+        S: ScalarValue,
+    {
+        Self::from_input_value(v)
+            .ok_or_else(|| InputValueError::new(format!("Invalid value: {}", v)))
+    }
+
     /// Performs the conversion from an absent value (e.g. to distinguish between implicit and
     /// explicit null). The default implementation just uses `from_input_value` as if an explicit
     /// null were provided. This conversion must not fail.
@@ -163,6 +272,68 @@ pub trait FromInputValue<S = DefaultScalarValue>: Sized {
     }
 }
 
+/// An error of converting an [`InputValue`] into a Rust data type with
+/// [`FromInputValue::try_from_input_value`]
+///
+/// The error tells why the conversion failed, and the path to the nested input field or list
+/// item which failed to convert, if any.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InputValueError {
+    path: Vec<PathSegment>,
+    message: String,
+}
+
+impl InputValueError {
+    /// Constructs a new error with the given message
+    pub fn new<T: fmt::Display>(message: T) -> Self {
+        InputValueError {
+            path: vec![],
+            message: message.to_string(),
+        }
+    }
+
+    /// Constructs a new error for a required field missing from an input object
+    pub fn missing_field(name: &str) -> Self {
+        Self::new(format!(r#"Missing required field "{}""#, name))
+    }
+
+    /// Nests the error in the field `name` of an input object.
+    pub fn in_field(mut self, name: &str) -> Self {
+        self.path.insert(0, PathSegment::Field(name.to_owned()));
+        self
+    }
+
+    /// Nests the error in the item at `index` of a list.
+    pub fn in_index(mut self, index: usize) -> Self {
+        self.path.insert(0, PathSegment::Index(index));
+        self
+    }
+
+    /// The path to the nested input field or list item which failed to convert
+    pub fn path(&self) -> &[PathSegment] {
+        &self.path
+    }
+
+    /// Why the conversion failed
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for InputValueError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for segment in &self.path {
+            match segment {
+                PathSegment::Field(name) => write!(f, r#"In field "{}": "#, name)?,
+                PathSegment::Index(index) => write!(f, "In element #{}: ", index)?,
+            }
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for InputValueError {}
+
 /// Losslessly clones a Rust data type into an InputValue.
 pub trait ToInputValue<S = DefaultScalarValue>: Sized {
     /// Performs the conversion.
@@ -326,6 +497,14 @@ where
         <T as FromInputValue<S>>::from_input_value(self)
     }
 
+    /// Shorthand form of invoking `FromInputValue::try_from_input_value()`.
+    pub fn try_convert<T>(&self) -> Result<T, InputValueError>
+    where
+        T: FromInputValue<S>,
+    {
+        <T as FromInputValue<S>>::try_from_input_value(self)
+    }
+
     /// Does the value represent null?
     pub fn is_null(&self) -> bool {
         match *self {
@@ -623,8 +802,50 @@ impl<'a, S> std::fmt::Display for Arguments<'a, S> where S: ScalarValue {
 
 #[cfg(test)]
 mod tests {
-    use super::InputValue;
-    use crate::parser::Spanning;
+    use super::{InputValue, InputValueError, OwnedDocument};
+    use crate::{
+        executor::PathSegment,
+        parser::{parse_document_source, Spanning},
+        tests::fixtures::starwars::schema::{Database, Query},
+        value::DefaultScalarValue,
+        EmptyMutation, EmptySubscription, RootNode,
+    };
+
+    fn schema() -> RootNode<'static, Query, EmptyMutation<Database>, EmptySubscription<Database>> {
+        RootNode::new(Query, EmptyMutation::new(), EmptySubscription::new())
+    }
+
+    #[test]
+    fn owned_document_outlives_its_source() {
+        let schema = schema();
+        let source = String::from("query Hero { hero { name } }");
+        let document = OwnedDocument::<DefaultScalarValue>::parse(&source, &schema.schema).unwrap();
+        drop(source);
+        let expected =
+            parse_document_source("query Hero { hero { name } }", &schema.schema).unwrap();
+
+        let moved = [document];
+        assert_eq!(moved[0].document(), &expected);
+        assert_eq!(moved[0].source(), "query Hero { hero { name } }");
+    }
+
+    #[test]
+    fn owned_document_reports_errors_from_its_source() {
+        let schema = schema();
+        for source in &[
+            "{ hero { name }",
+            "{ hero(episode: 1.5.) }",
+            "{ hero } name",
+        ] {
+            let error =
+                OwnedDocument::<DefaultScalarValue>::parse(source, &schema.schema).unwrap_err();
+
+            assert_eq!(
+                error,
+                parse_document_source::<DefaultScalarValue>(source, &schema.schema).unwrap_err(),
+            );
+        }
+    }
 
     #[test]
     fn test_input_value_fmt() {
@@ -666,4 +887,31 @@ mod tests {
         let value: InputValue = InputValue::parsed_object(object);
         assert_eq!(format!("{}", value), "{foo: 1, bar: 2}");
     }
+
+    #[test]
+    fn test_input_value_error_path() {
+        let value: InputValue = InputValue::list(vec![
+            InputValue::list(vec![InputValue::scalar(1)]),
+            InputValue::list(vec![InputValue::scalar(2), InputValue::scalar("x")]),
+        ]);
+
+        let err = value.try_convert::<Vec<Vec<i32>>>().unwrap_err();
+
+        assert_eq!(err.path(), &[PathSegment::Index(1), PathSegment::Index(1)]);
+        assert_eq!(
+            err.message(),
+            "Int cannot represent non-integer value: \"x\""
+        );
+        assert_eq!(
+            err.to_string(),
+            "In element #1: In element #1: Int cannot represent non-integer value: \"x\"",
+        );
+
+        let err = InputValueError::missing_field("id").in_field("user");
+        assert_eq!(err.path(), &[PathSegment::Field("user".into())]);
+        assert_eq!(
+            err.to_string(),
+            "In field \"user\": Missing required field \"id\"",
+        );
+    }
 }
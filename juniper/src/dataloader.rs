@@ -0,0 +1,464 @@
+//! Batching and caching of data loads made while executing a request
+//!
+//! Resolving a list of objects whose fields load data one by one leads to the N+1 problem: one
+//! query for the list, then one query per object. A [`Loader`] gathers the keys requested by the
+//! resolvers executed concurrently by [`crate::execute`], and loads all of them with a single
+//! call to its [`BatchFn`].
+//!
+//! ```rust
+//! # use std::collections::HashMap;
+//! # use juniper::{async_trait, dataloader::{BatchFn, Loader}};
+//! struct CultBatcher;
+//!
+//! #[async_trait]
+//! impl BatchFn<i32, String> for CultBatcher {
+//!     async fn load(&self, keys: &[i32]) -> HashMap<i32, String> {
+//!         // e.g. `SELECT id, name FROM cults WHERE id = ANY($1)`
+//!         keys.iter().map(|&id| (id, format!("Cult #{}", id))).collect()
+//!     }
+//! }
+//!
+//! struct Context {
+//!     cult_loader: Loader<i32, String, CultBatcher>,
+//! }
+//!
+//! impl juniper::Context for Context {}
+//!
+//! struct Person {
+//!     cult_id: i32,
+//! }
+//!
+//! #[juniper::graphql_object(Context = Context)]
+//! impl Person {
+//!     async fn cult(&self, context: &Context) -> Option<String> {
+//!         context.cult_loader.load(self.cult_id).await
+//!     }
+//! }
+//! ```
+//!
+//! Loaders memoize the values they load, so they should be created for every request, usually
+//! along with its context. Otherwise, values would be shared across requests, and possibly users.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    future::Future,
+    hash::Hash,
+    mem,
+    pin::Pin,
+    sync::Mutex,
+    task::{Context, Poll, Waker},
+};
+
+use futures::future;
+
+/// Loads a batch of values at once, e.g. with a single database query
+#[crate::async_trait]
+pub trait BatchFn<K, V>: Send + Sync {
+    /// Loads the values of the given keys.
+    ///
+    /// Keys missing from the returned map have no value, and are loaded as `None`.
+    async fn load(&self, keys: &[K]) -> HashMap<K, V>;
+}
+
+/// Coalesces the loads of values requested concurrently into batches, memoizing their values
+///
+/// Every [`Loader::load`] waits for the other futures executed along with it to request their
+/// keys, before all the keys not loaded yet are passed to the [`BatchFn`] at once. Loaded values
+/// are cached, so every key is loaded at most once.
+pub struct Loader<K, V, F> {
+    batch_fn: F,
+    yield_count: usize,
+    state: Mutex<State<K, V>>,
+}
+
+struct State<K, V> {
+    /// Loaded values, `None` for keys the batch function didn't return a value for
+    cache: HashMap<K, Option<V>>,
+    /// Keys waiting for the next batch
+    pending: Vec<K>,
+    /// Keys of the batches being loaded
+    loading: HashSet<K>,
+    /// Loads waiting for a batch loaded by another one
+    waiters: Vec<Waker>,
+}
+
+impl<K, V, F> Loader<K, V, F>
+where
+    K: Eq + Hash + Clone + Send + Sync,
+    V: Clone + Send + Sync,
+    F: BatchFn<K, V>,
+{
+    /// Constructs a loader with an empty cache
+    pub fn new(batch_fn: F) -> Self {
+        Loader {
+            batch_fn,
+            yield_count: 10,
+            state: Mutex::new(State {
+                cache: HashMap::new(),
+                pending: vec![],
+                loading: HashSet::new(),
+                waiters: vec![],
+            }),
+        }
+    }
+
+    /// Sets how many times a load yields to the other futures before loading its batch
+    ///
+    /// Futures executed concurrently usually request their keys on the first yield, but more
+    /// yields allow keys requested after other asynchronous work to join the batch, at the cost of
+    /// a delay. Defaults to `10`.
+    pub fn with_yield_count(mut self, yield_count: usize) -> Self {
+        self.yield_count = yield_count;
+        self
+    }
+
+    /// Loads the value of a key, batched with the other keys requested concurrently
+    ///
+    /// Returns `None` if the batch function didn't return a value for the key.
+    pub async fn load(&self, key: K) -> Option<V> {
+        if let Some(value) = self.enqueue(&key) {
+            return value;
+        }
+
+        for _ in 0..self.yield_count {
+            yield_now().await;
+        }
+
+        loop {
+            self.dispatch(&key).await;
+            if let Some(value) = self.wait(&key).await {
+                return value;
+            }
+        }
+    }
+
+    /// Loads the values of several keys, batched with the other keys requested concurrently
+    ///
+    /// Keys the batch function didn't return a value for are missing from the returned map.
+    pub async fn load_many(&self, keys: Vec<K>) -> HashMap<K, V> {
+        let values = future::join_all(keys.iter().cloned().map(|key| self.load(key))).await;
+        keys.into_iter()
+            .zip(values)
+            .filter_map(|(key, value)| value.map(|value| (key, value)))
+            .collect()
+    }
+
+    /// Caches the value of a key, unless it's already loaded
+    pub fn prime(&self, key: K, value: V) {
+        let mut state = self.state.lock().unwrap();
+        if !state.loading.contains(&key) {
+            state.cache.entry(key).or_insert(Some(value));
+        }
+    }
+
+    /// Removes the cached value of a key, so it's loaded again by the next [`Loader::load`]
+    pub fn clear(&self, key: &K) {
+        self.state.lock().unwrap().cache.remove(key);
+    }
+
+    /// Returns the cached value of a key, or queues it for the next batch
+    fn enqueue(&self, key: &K) -> Option<Option<V>> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(value) = state.cache.get(key) {
+            return Some(value.clone());
+        }
+        if !state.loading.contains(key) && !state.pending.contains(key) {
+            state.pending.push(key.clone());
+        }
+        None
+    }
+
+    /// Waits for the batch loading a key, returning `None` if the key has to be dispatched
+    /// again, as its batch was cancelled or its value was cleared
+    async fn wait(&self, key: &K) -> Option<Option<V>> {
+        future::poll_fn(|cx| {
+            let mut state = self.state.lock().unwrap();
+            if let Some(value) = state.cache.get(key) {
+                return Poll::Ready(Some(value.clone()));
+            }
+            if !state.loading.contains(key) {
+                if !state.pending.contains(key) {
+                    state.pending.push(key.clone());
+                }
+                return Poll::Ready(None);
+            }
+            state.waiters.push(cx.waker().clone());
+            Poll::Pending
+        })
+        .await
+    }
+
+    /// Loads the pending batch, unless another load already took the key
+    async fn dispatch(&self, key: &K) {
+        let mut batch = {
+            let mut state = self.state.lock().unwrap();
+            if !state.pending.contains(key) {
+                return;
+            }
+            let keys = mem::take(&mut state.pending);
+            state.loading.extend(keys.iter().cloned());
+            Batch {
+                state: &self.state,
+                keys,
+            }
+        };
+
+        let mut values = self.batch_fn.load(&batch.keys).await;
+
+        let keys = mem::take(&mut batch.keys);
+        let mut state = self.state.lock().unwrap();
+        for key in keys {
+            state.loading.remove(&key);
+            let value = values.remove(&key);
+            state.cache.insert(key, value);
+        }
+        for waker in state.waiters.drain(..) {
+            waker.wake();
+        }
+    }
+}
+
+/// The keys of a batch being loaded
+///
+/// If the load is cancelled, e.g. because the future running it is dropped on a timeout, the keys
+/// are moved back to the pending ones, and the loads waiting for them are woken up to load them
+/// again.
+struct Batch<'a, K, V>
+where
+    K: Eq + Hash,
+{
+    state: &'a Mutex<State<K, V>>,
+    keys: Vec<K>,
+}
+
+impl<'a, K, V> Drop for Batch<'a, K, V>
+where
+    K: Eq + Hash,
+{
+    fn drop(&mut self) {
+        if self.keys.is_empty() {
+            return;
+        }
+        // The lock is never held across a panic, but this may run while unwinding.
+        let mut state = match self.state.lock() {
+            Ok(state) => state,
+            Err(_) => return,
+        };
+        for key in self.keys.drain(..) {
+            state.loading.remove(&key);
+            state.pending.push(key);
+        }
+        for waker in state.waiters.drain(..) {
+            waker.wake();
+        }
+    }
+}
+
+impl<K, V, F> fmt::Debug for Loader<K, V, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Loader")
+            .field("yield_count", &self.yield_count)
+            .finish()
+    }
+}
+
+/// Yields to the other futures polled along with the current one
+fn yield_now() -> YieldNow {
+    YieldNow { yielded: false }
+}
+
+struct YieldNow {
+    yielded: bool,
+}
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+        if self.yielded {
+            Poll::Ready(())
+        } else {
+            self.yielded = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Mutex,
+        },
+        time::Duration,
+    };
+
+    use futures::{future, FutureExt as _};
+
+    use super::{BatchFn, Loader};
+    use crate::{EmptyMutation, EmptySubscription, RootNode, Variables};
+
+    #[derive(Default)]
+    struct Squares {
+        batches: Mutex<Vec<Vec<i32>>>,
+    }
+
+    #[crate::async_trait]
+    impl BatchFn<i32, i32> for Squares {
+        async fn load(&self, keys: &[i32]) -> HashMap<i32, i32> {
+            self.batches.lock().unwrap().push(keys.to_vec());
+            keys.iter()
+                .filter(|&&k| k >= 0)
+                .map(|&k| (k, k * k))
+                .collect()
+        }
+    }
+
+    fn batches(loader: &Loader<i32, i32, Squares>) -> Vec<Vec<i32>> {
+        loader.batch_fn.batches.lock().unwrap().clone()
+    }
+
+    #[tokio::test]
+    async fn batches_concurrent_loads() {
+        let loader = Loader::new(Squares::default());
+
+        let values = future::join_all(vec![loader.load(1), loader.load(2), loader.load(1)]).await;
+
+        assert_eq!(values, vec![Some(1), Some(4), Some(1)]);
+        assert_eq!(batches(&loader), vec![vec![1, 2]]);
+    }
+
+    #[tokio::test]
+    async fn caches_loaded_values() {
+        let loader = Loader::new(Squares::default());
+
+        assert_eq!(loader.load(3).await, Some(9));
+        assert_eq!(loader.load(3).await, Some(9));
+        assert_eq!(loader.load(-1).await, None);
+        assert_eq!(loader.load(-1).await, None);
+
+        assert_eq!(batches(&loader), vec![vec![3], vec![-1]]);
+    }
+
+    #[tokio::test]
+    async fn loads_many() {
+        let loader = Loader::new(Squares::default());
+        loader.prime(2, 5);
+
+        let values = loader.load_many(vec![1, 2, -3]).await;
+
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[&1], 1);
+        assert_eq!(values[&2], 5);
+        assert_eq!(batches(&loader), vec![vec![1, -3]]);
+    }
+
+    #[tokio::test]
+    async fn reloads_cleared_keys() {
+        let loader = Loader::new(Squares::default());
+
+        assert_eq!(loader.load(2).await, Some(4));
+        loader.clear(&2);
+        assert_eq!(loader.load(2).await, Some(4));
+
+        assert_eq!(batches(&loader), vec![vec![2], vec![2]]);
+    }
+
+    /// Never finishes loading its first batch
+    #[derive(Default)]
+    struct StallingSquares {
+        stalled: AtomicBool,
+        squares: Squares,
+    }
+
+    #[crate::async_trait]
+    impl BatchFn<i32, i32> for StallingSquares {
+        async fn load(&self, keys: &[i32]) -> HashMap<i32, i32> {
+            if !self.stalled.swap(true, Ordering::SeqCst) {
+                future::pending::<()>().await;
+            }
+            self.squares.load(keys).await
+        }
+    }
+
+    #[tokio::test]
+    async fn reloads_keys_of_cancelled_batches() {
+        let loader = Loader::new(StallingSquares::default()).with_yield_count(0);
+
+        let mut dispatching = Box::pin(loader.load(2));
+        let mut waiting = Box::pin(loader.load(2));
+        assert_eq!((&mut dispatching).now_or_never(), None);
+        assert_eq!((&mut waiting).now_or_never(), None);
+
+        drop(dispatching);
+
+        let value = tokio::time::timeout(Duration::from_secs(5), waiting)
+            .await
+            .expect("Load of a cancelled batch never finished");
+        assert_eq!(value, Some(4));
+        assert_eq!(loader.load(3).await, Some(9));
+        assert_eq!(
+            loader.batch_fn.squares.batches.lock().unwrap().clone(),
+            vec![vec![2], vec![3]],
+        );
+    }
+
+    struct Context {
+        squares: Loader<i32, i32, Squares>,
+    }
+
+    impl crate::Context for Context {}
+
+    struct Number(i32);
+
+    #[crate::graphql_object(Context = Context)]
+    impl Number {
+        async fn square(&self, context: &Context) -> Option<i32> {
+            context.squares.load(self.0).await
+        }
+    }
+
+    struct Query;
+
+    #[crate::graphql_object(Context = Context)]
+    impl Query {
+        fn numbers() -> Vec<Number> {
+            vec![Number(1), Number(2), Number(3), Number(2)]
+        }
+    }
+
+    #[tokio::test]
+    async fn batches_loads_of_executed_fields() {
+        let schema = RootNode::new(
+            Query,
+            EmptyMutation::<Context>::new(),
+            EmptySubscription::<Context>::new(),
+        );
+        let context = Context {
+            squares: Loader::new(Squares::default()),
+        };
+
+        let (result, errs) = crate::execute(
+            "{ numbers { square } }",
+            None,
+            &schema,
+            &Variables::new(),
+            &context,
+        )
+        .await
+        .expect("Execution failed");
+
+        assert_eq!(errs, []);
+        assert_eq!(
+            result,
+            crate::graphql_value!({
+                "numbers": [{"square": 1}, {"square": 4}, {"square": 9}, {"square": 4}],
+            }),
+        );
+        assert_eq!(batches(&context.squares), vec![vec![1, 2, 3]]);
+    }
+}
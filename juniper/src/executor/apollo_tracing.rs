@@ -0,0 +1,263 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    executor::{Extension, PathSegment, ResolvedField},
+    validation::RuleError,
+    value::{Object, ScalarValue, Value},
+};
+
+/// Traces the execution of requests in the [Apollo Tracing][1] format
+///
+/// The start offset and duration of the parsing and validation of the document, and of every
+/// resolved field, are added to the response under `extensions.tracing`, where tools like GraphQL
+/// Playground display them. Tracing is enabled by registering the extension in the schema:
+///
+/// ```rust
+/// # use juniper::{ApolloTracing, EmptyMutation, EmptySubscription, RootNode};
+/// # struct Query;
+/// # #[juniper::graphql_object]
+/// # impl Query {
+/// #     fn hello() -> &str {
+/// #         "world"
+/// #     }
+/// # }
+/// let schema = RootNode::new(Query, EmptyMutation::<()>::new(), EmptySubscription::<()>::new())
+///     .with_extension(ApolloTracing::new);
+/// ```
+///
+/// Offsets and durations are measured in nanoseconds, as the format requires. Those not fitting
+/// in a 32-bit integer are emitted as floats.
+///
+/// [1]: https://github.com/apollographql/apollo-tracing
+#[derive(Debug)]
+pub struct ApolloTracing {
+    start_time: SystemTime,
+    start: Instant,
+    trace: Mutex<Trace>,
+}
+
+#[derive(Debug, Default)]
+struct Trace {
+    parsing: Option<Phase>,
+    validation: Option<Phase>,
+    end: Option<Instant>,
+    resolvers: Vec<Resolver>,
+}
+
+#[derive(Debug)]
+struct Phase {
+    start: Instant,
+    end: Option<Instant>,
+}
+
+#[derive(Debug)]
+struct Resolver {
+    path: Vec<PathSegment>,
+    parent_type: String,
+    field_name: String,
+    return_type: String,
+    start: Instant,
+    duration: Duration,
+}
+
+impl ApolloTracing {
+    /// Starts tracing a new request
+    pub fn new() -> Self {
+        ApolloTracing {
+            start_time: SystemTime::now(),
+            start: Instant::now(),
+            trace: Mutex::new(Trace::default()),
+        }
+    }
+
+    fn offset(&self, instant: Instant) -> Duration {
+        instant.saturating_duration_since(self.start)
+    }
+
+    fn phase<S: ScalarValue>(&self, phase: &Option<Phase>, end: Instant) -> Value<S> {
+        match phase {
+            Some(phase) => {
+                let mut obj = Object::with_capacity(2);
+                obj.add_field("startOffset", nanos(self.offset(phase.start)));
+                obj.add_field(
+                    "duration",
+                    nanos(
+                        phase
+                            .end
+                            .unwrap_or(end)
+                            .saturating_duration_since(phase.start),
+                    ),
+                );
+                Value::object(obj)
+            }
+            None => Value::null(),
+        }
+    }
+}
+
+impl Default for ApolloTracing {
+    fn default() -> Self {
+        ApolloTracing::new()
+    }
+}
+
+impl<S: ScalarValue> Extension<S> for ApolloTracing {
+    fn parse_start(&self, _: &str) {
+        self.trace.lock().unwrap().parsing = Some(Phase::start());
+    }
+
+    fn parse_end(&self) {
+        Phase::end(&mut self.trace.lock().unwrap().parsing);
+    }
+
+    fn validation_start(&self) {
+        self.trace.lock().unwrap().validation = Some(Phase::start());
+    }
+
+    fn validation_end(&self, _: &[RuleError]) {
+        Phase::end(&mut self.trace.lock().unwrap().validation);
+    }
+
+    fn execution_end(&self) {
+        self.trace.lock().unwrap().end = Some(Instant::now());
+    }
+
+    fn field_resolved(&self, field: &ResolvedField) {
+        self.trace.lock().unwrap().resolvers.push(Resolver {
+            path: field.path().to_vec(),
+            parent_type: field.parent_type().to_owned(),
+            field_name: field.field_name().to_owned(),
+            return_type: field.return_type().to_string(),
+            start: field.started_at(),
+            duration: field.duration(),
+        });
+    }
+
+    fn result(&self) -> Option<(String, Value<S>)> {
+        let trace = self.trace.lock().unwrap();
+        let end = trace.end.unwrap_or_else(Instant::now);
+        let duration = self.offset(end);
+
+        let mut resolvers = trace.resolvers.iter().collect::<Vec<_>>();
+        resolvers.sort_by_key(|resolver| resolver.start);
+        let resolvers = resolvers
+            .into_iter()
+            .map(|resolver| {
+                let mut obj = Object::with_capacity(6);
+                obj.add_field(
+                    "path",
+                    Value::list(resolver.path.iter().map(path_segment).collect()),
+                );
+                obj.add_field("parentType", Value::scalar(resolver.parent_type.clone()));
+                obj.add_field("fieldName", Value::scalar(resolver.field_name.clone()));
+                obj.add_field("returnType", Value::scalar(resolver.return_type.clone()));
+                obj.add_field("startOffset", nanos(self.offset(resolver.start)));
+                obj.add_field("duration", nanos(resolver.duration));
+                Value::object(obj)
+            })
+            .collect();
+
+        let mut execution = Object::with_capacity(1);
+        execution.add_field("resolvers", Value::list(resolvers));
+
+        let mut tracing = Object::with_capacity(7);
+        tracing.add_field("version", Value::scalar(1));
+        tracing.add_field("startTime", Value::scalar(rfc3339(self.start_time)));
+        tracing.add_field(
+            "endTime",
+            Value::scalar(rfc3339(self.start_time + duration)),
+        );
+        tracing.add_field("duration", nanos(duration));
+        tracing.add_field("parsing", self.phase(&trace.parsing, end));
+        tracing.add_field("validation", self.phase(&trace.validation, end));
+        tracing.add_field("execution", Value::object(execution));
+
+        Some(("tracing".into(), Value::object(tracing)))
+    }
+}
+
+impl Phase {
+    fn start() -> Self {
+        Phase {
+            start: Instant::now(),
+            end: None,
+        }
+    }
+
+    fn end(phase: &mut Option<Phase>) {
+        if let Some(phase) = phase {
+            phase.end = Some(Instant::now());
+        }
+    }
+}
+
+/// Converts a segment of the path of a resolver, list indices being integers
+fn path_segment<S: ScalarValue>(segment: &PathSegment) -> Value<S> {
+    match segment {
+        PathSegment::Field(name) => Value::scalar(name.clone()),
+        PathSegment::Index(index) => Value::scalar(*index as i32),
+    }
+}
+
+/// Converts a duration to nanoseconds, falling back to a float when they overflow an `i32`
+fn nanos<S: ScalarValue>(duration: Duration) -> Value<S> {
+    let nanos = duration.as_nanos();
+    if nanos <= i32::MAX as u128 {
+        Value::scalar(nanos as i32)
+    } else {
+        Value::scalar(nanos as f64)
+    }
+}
+
+/// Formats a time as an RFC 3339 timestamp in UTC, with millisecond precision
+fn rfc3339(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs();
+    let (days, secs_of_day) = ((secs / 86_400) as i64, secs % 86_400);
+
+    // Converts days since the epoch to a civil date, see
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        secs_of_day / 3_600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60,
+        since_epoch.subsec_millis(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::rfc3339;
+
+    #[test]
+    fn formats_timestamps() {
+        assert_eq!(rfc3339(UNIX_EPOCH), "1970-01-01T00:00:00.000Z");
+        assert_eq!(
+            rfc3339(UNIX_EPOCH + Duration::from_millis(951_782_400_123)),
+            "2000-02-29T00:00:00.123Z",
+        );
+        assert_eq!(
+            rfc3339(UNIX_EPOCH + Duration::from_secs(1_609_459_199)),
+            "2020-12-31T23:59:59.000Z",
+        );
+    }
+}
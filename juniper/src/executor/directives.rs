@@ -0,0 +1,152 @@
+use indexmap::IndexMap;
+
+use crate::{
+    ast::Directive,
+    executor::{ExecutionResult, Variables},
+    parser::Spanning,
+    schema::model::SchemaType,
+    types::base::Arguments,
+    value::{DefaultScalarValue, ScalarValue},
+};
+
+/// Runtime behavior of a custom executable directive
+///
+/// A resolver is attached to a directive with [`DirectiveType::resolver`], and is invoked
+/// around the resolution of every field the directive is applied to in a query. When several
+/// directives with resolvers are applied to the same field, the first one wraps all the others.
+///
+/// [`DirectiveType::resolver`]: crate::DirectiveType::resolver
+pub trait DirectiveResolver<S = DefaultScalarValue>: Send + Sync {
+    /// Called before the field is resolved.
+    ///
+    /// Returning a result skips the resolution of the field, and the resolvers of any
+    /// directives applied after this one. The `after_resolve` hook of this resolver is still
+    /// called with the returned result.
+    fn before_resolve(&self, directive: &DirectiveContext<S>) -> Option<ExecutionResult<S>> {
+        let _ = directive;
+        None
+    }
+
+    /// Called with the result of the field resolution, which may be transformed.
+    fn after_resolve(
+        &self,
+        directive: &DirectiveContext<S>,
+        result: ExecutionResult<S>,
+    ) -> ExecutionResult<S> {
+        let _ = directive;
+        result
+    }
+}
+
+/// A directive applied to a field, as seen by its [`DirectiveResolver`]
+#[derive(Debug)]
+pub struct DirectiveContext<'a, S = DefaultScalarValue> {
+    name: &'a str,
+    field_name: &'a str,
+    arguments: Arguments<'a, S>,
+}
+
+impl<'a, S> DirectiveContext<'a, S> {
+    /// Name of the directive, without the leading `@`.
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    /// Name of the field the directive is applied to.
+    pub fn field_name(&self) -> &'a str {
+        self.field_name
+    }
+
+    /// Arguments of the directive, with default values applied.
+    pub fn arguments(&self) -> &Arguments<'a, S> {
+        &self.arguments
+    }
+}
+
+/// The directives with resolvers applied to a single field.
+pub(crate) struct FieldDirectives<'a, S> {
+    resolvers: Vec<(&'a dyn DirectiveResolver<S>, DirectiveContext<'a, S>)>,
+}
+
+impl<'a, S> FieldDirectives<'a, S>
+where
+    S: ScalarValue,
+{
+    pub(crate) fn new(
+        schema: &'a SchemaType<'a, S>,
+        field_name: &'a str,
+        directives: &'a Option<Vec<Spanning<Directive<'a, S>>>>,
+        vars: &Variables<S>,
+    ) -> Self {
+        let mut resolvers = vec![];
+
+        for directive in directives.iter().flatten().map(|d| &d.item) {
+            let meta = match schema.directive_by_name(directive.name.item) {
+                Some(meta) => meta,
+                None => continue,
+            };
+            let resolver = match meta.resolver {
+                Some(ref resolver) => &**resolver,
+                None => continue,
+            };
+
+            let mut arguments = directive
+                .arguments
+                .iter()
+                .flat_map(|a| a.item.items.iter())
+                .map(|(k, v)| (k.item, v.item.clone().into_const(vars)))
+                .collect::<IndexMap<_, _>>();
+            for arg in &meta.arguments {
+                if arguments.get(arg.name.as_str()).is_none_or(|v| v.is_null()) {
+                    if let Some(ref default_value) = arg.default_value {
+                        arguments.insert(arg.name.as_str(), default_value.clone());
+                    }
+                }
+            }
+
+            resolvers.push((
+                resolver,
+                DirectiveContext {
+                    name: directive.name.item,
+                    field_name,
+                    arguments: Arguments::new(Some(arguments), &None),
+                },
+            ));
+        }
+
+        FieldDirectives { resolvers }
+    }
+
+    /// Runs the `before_resolve` hooks, returning the number of directives that were entered,
+    /// and the result to use instead of resolving the field, if any.
+    pub(crate) fn before_resolve(&self) -> (usize, Option<ExecutionResult<S>>) {
+        for (i, (resolver, directive)) in self.resolvers.iter().enumerate() {
+            if let Some(result) = resolver.before_resolve(directive) {
+                return (i + 1, Some(result));
+            }
+        }
+        (self.resolvers.len(), None)
+    }
+
+    /// Runs the `after_resolve` hooks of the `entered` directives, innermost first.
+    pub(crate) fn after_resolve(
+        &self,
+        entered: usize,
+        mut result: ExecutionResult<S>,
+    ) -> ExecutionResult<S> {
+        for (resolver, directive) in self.resolvers[..entered].iter().rev() {
+            result = resolver.after_resolve(directive, result);
+        }
+        result
+    }
+
+    /// Resolves a field with the given function, wrapped by the directive resolvers.
+    pub(crate) fn resolve<F>(&self, resolve: F) -> ExecutionResult<S>
+    where
+        F: FnOnce() -> ExecutionResult<S>,
+    {
+        let (entered, result) = self.before_resolve();
+        let result = result.unwrap_or_else(resolve);
+        self.after_resolve(entered, result)
+    }
+}
@@ -0,0 +1,256 @@
+use std::{
+    fmt,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    ast::{OperationType, Type},
+    executor::PathSegment,
+    validation::RuleError,
+    value::{DefaultScalarValue, Object, Value},
+};
+
+/// Hooks into the execution of a request, e.g. for tracing, logging or metrics
+///
+/// A new extension is created for every request by the factories registered with
+/// [`RootNode::with_extension`], or passed to [`crate::execute_with_extensions`] and
+/// [`crate::execute_sync_with_extensions`]. All the hooks do nothing by default.
+///
+/// ```rust
+/// # use std::sync::atomic::{AtomicUsize, Ordering};
+/// # use juniper::{Extension, ResolvedField, Value};
+/// #[derive(Default)]
+/// struct CountFields(AtomicUsize);
+///
+/// impl Extension for CountFields {
+///     fn field_resolved(&self, _: &ResolvedField) {
+///         self.0.fetch_add(1, Ordering::Relaxed);
+///     }
+///
+///     fn result(&self) -> Option<(String, Value)> {
+///         let count = self.0.load(Ordering::Relaxed) as i32;
+///         Some(("resolvedFields".into(), Value::scalar(count)))
+///     }
+/// }
+/// ```
+///
+/// [`RootNode::with_extension`]: crate::RootNode::with_extension
+pub trait Extension<S = DefaultScalarValue>: Send + Sync {
+    /// Called before the document is parsed.
+    fn parse_start(&self, document_source: &str) {
+        let _ = document_source;
+    }
+
+    /// Called after the document is parsed, or found in the document cache of the schema.
+    fn parse_end(&self) {}
+
+    /// Called before the document and the variables are validated.
+    fn validation_start(&self) {}
+
+    /// Called after the document and the variables are validated, with the errors found.
+    fn validation_end(&self, errors: &[RuleError]) {
+        let _ = errors;
+    }
+
+    /// Called before the operation is executed.
+    fn execution_start(&self, operation_name: Option<&str>, operation_type: &OperationType) {
+        let _ = (operation_name, operation_type);
+    }
+
+    /// Called after the operation is executed.
+    fn execution_end(&self) {}
+
+    /// Called after each field of a query or mutation is resolved.
+    fn field_resolved(&self, field: &ResolvedField) {
+        let _ = field;
+    }
+
+    /// The entry added by this extension to the `extensions` map of the response, if any.
+    ///
+    /// Called after the request is executed, including when it fails before execution.
+    fn result(&self) -> Option<(String, Value<S>)> {
+        None
+    }
+}
+
+/// Creates the [`Extension`]s of the requests executed in a schema
+///
+/// Implemented for closures returning an extension.
+pub trait ExtensionFactory<S = DefaultScalarValue>: Send + Sync {
+    /// Creates the extension of a new request.
+    fn create(&self) -> Arc<dyn Extension<S>>;
+}
+
+impl<S, F, E> ExtensionFactory<S> for F
+where
+    F: Fn() -> E + Send + Sync,
+    E: Extension<S> + 'static,
+{
+    fn create(&self) -> Arc<dyn Extension<S>> {
+        Arc::new(self())
+    }
+}
+
+impl<S> fmt::Debug for dyn ExtensionFactory<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("ExtensionFactory")
+    }
+}
+
+/// A field resolved during the execution of a request, as seen by [`Extension::field_resolved`]
+#[derive(Debug)]
+pub struct ResolvedField<'a> {
+    pub(crate) path: &'a [PathSegment],
+    pub(crate) parent_type: &'a str,
+    pub(crate) field_name: &'a str,
+    pub(crate) return_type: &'a Type<'a>,
+    pub(crate) started_at: Instant,
+    pub(crate) duration: Duration,
+}
+
+impl<'a> ResolvedField<'a> {
+    /// Path of the field in the response, made of the response names of its ancestors and the
+    /// indices of the list items containing it.
+    pub fn path(&self) -> &'a [PathSegment] {
+        self.path
+    }
+
+    /// Name of the type the field belongs to.
+    pub fn parent_type(&self) -> &'a str {
+        self.parent_type
+    }
+
+    /// Name of the field in the schema.
+    pub fn field_name(&self) -> &'a str {
+        self.field_name
+    }
+
+    /// Type of the field in the schema.
+    pub fn return_type(&self) -> &'a Type<'a> {
+        self.return_type
+    }
+
+    /// When the resolution of the field started.
+    pub fn started_at(&self) -> Instant {
+        self.started_at
+    }
+
+    /// How long the field took to resolve, including the fields selected on its value.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+}
+
+/// The [`Extension`]s of a single request
+///
+/// [`RootNode::create_extensions`] creates the extensions registered in a schema, to which more
+/// can be added for the request.
+///
+/// [`RootNode::create_extensions`]: crate::RootNode::create_extensions
+pub struct Extensions<S = DefaultScalarValue> {
+    extensions: Vec<Arc<dyn Extension<S>>>,
+}
+
+impl<S> Extensions<S> {
+    /// Constructs an empty list of extensions
+    pub fn new() -> Self {
+        Extensions { extensions: vec![] }
+    }
+
+    /// Adds an extension to the list
+    pub fn with<E>(mut self, extension: E) -> Self
+    where
+        E: Extension<S> + 'static,
+    {
+        self.extensions.push(Arc::new(extension));
+        self
+    }
+
+    /// Adds an extension created by a factory to the list
+    pub fn push(&mut self, extension: Arc<dyn Extension<S>>) {
+        self.extensions.push(extension);
+    }
+
+    /// Whether there are no extensions
+    pub fn is_empty(&self) -> bool {
+        self.extensions.is_empty()
+    }
+
+    /// Collects the entries added by the extensions to the `extensions` map of the response
+    pub fn results(&self) -> Object<S> {
+        let mut results = Object::with_capacity(0);
+        for (name, value) in self.extensions.iter().filter_map(|ext| ext.result()) {
+            results.add_field(name, value);
+        }
+        results
+    }
+
+    pub(crate) fn parse_start(&self, document_source: &str) {
+        for ext in &self.extensions {
+            ext.parse_start(document_source);
+        }
+    }
+
+    pub(crate) fn parse_end(&self) {
+        for ext in &self.extensions {
+            ext.parse_end();
+        }
+    }
+
+    pub(crate) fn validation_start(&self) {
+        for ext in &self.extensions {
+            ext.validation_start();
+        }
+    }
+
+    pub(crate) fn validation_end(&self, errors: &[RuleError]) {
+        for ext in &self.extensions {
+            ext.validation_end(errors);
+        }
+    }
+
+    pub(crate) fn execution_start(
+        &self,
+        operation_name: Option<&str>,
+        operation_type: &OperationType,
+    ) {
+        for ext in &self.extensions {
+            ext.execution_start(operation_name, operation_type);
+        }
+    }
+
+    pub(crate) fn execution_end(&self) {
+        for ext in &self.extensions {
+            ext.execution_end();
+        }
+    }
+
+    pub(crate) fn field_resolved(&self, field: &ResolvedField) {
+        for ext in &self.extensions {
+            ext.field_resolved(field);
+        }
+    }
+}
+
+impl<S> Default for Extensions<S> {
+    fn default() -> Self {
+        Extensions::new()
+    }
+}
+
+impl<S> Clone for Extensions<S> {
+    fn clone(&self) -> Self {
+        Extensions {
+            extensions: self.extensions.clone(),
+        }
+    }
+}
+
+impl<S> fmt::Debug for Extensions<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Extensions")
+            .field("len", &self.extensions.len())
+            .finish()
+    }
+}
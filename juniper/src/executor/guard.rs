@@ -0,0 +1,55 @@
+use crate::{executor::FieldResult, types::base::Arguments, value::DefaultScalarValue};
+
+/// A check run before a field is resolved, e.g. to authorize the access to it
+///
+/// Guards are attached to the fields of objects and interfaces with the `guard = "..."`
+/// attribute argument, taking an expression of a type implementing this trait. When a guard
+/// rejects a field, its error is reported for the field and the resolver isn't called.
+///
+/// ```rust
+/// use juniper::{graphql_object, Arguments, FieldError, FieldResult, Guard, ScalarValue};
+///
+/// struct Context {
+///     roles: Vec<String>,
+/// }
+///
+/// impl juniper::Context for Context {}
+///
+/// struct HasRole(&'static str);
+///
+/// impl<S: ScalarValue> Guard<Context, S> for HasRole {
+///     fn check(&self, context: &Context, _: &Arguments<S>) -> FieldResult<(), S> {
+///         if context.roles.iter().any(|role| role == self.0) {
+///             Ok(())
+///         } else {
+///             Err(FieldError::from(format!("Missing role \"{}\"", self.0)).user_facing())
+///         }
+///     }
+/// }
+///
+/// struct Query;
+///
+/// #[graphql_object(context = Context)]
+/// impl Query {
+///     #[graphql(guard = r#"HasRole("admin")"#)]
+///     fn secret() -> i32 {
+///         42
+///     }
+/// }
+/// ```
+///
+/// Functions taking the context and the arguments of the field are guards as well.
+pub trait Guard<CtxT, S = DefaultScalarValue> {
+    /// Checks whether the field may be resolved with the given `args`, rejecting it with an
+    /// error otherwise.
+    fn check(&self, context: &CtxT, args: &Arguments<S>) -> FieldResult<(), S>;
+}
+
+impl<CtxT, S, F> Guard<CtxT, S> for F
+where
+    F: Fn(&CtxT, &Arguments<S>) -> FieldResult<(), S>,
+{
+    fn check(&self, context: &CtxT, args: &Arguments<S>) -> FieldResult<(), S> {
+        self(context, args)
+    }
+}
@@ -0,0 +1,631 @@
+use std::{
+    future::Future,
+    mem,
+    pin::Pin,
+    sync::{Arc, Mutex, RwLock},
+    task::{Context, Poll},
+};
+
+use futures::{
+    channel::{mpsc, oneshot},
+    future::{self, Either, FutureExt as _, Shared},
+    stream::{self, FuturesUnordered, Stream, StreamExt as _},
+};
+
+use crate::{
+    ast::{Directive, Document, FromInputValue, OperationType},
+    executor::{
+        execute_validated_operation_async, get_operation, ExecutionError, Extensions, Variables,
+    },
+    parser::Spanning,
+    schema::model::RootNode,
+    types::{async_await::GraphQLTypeAsync, base::GraphQLType},
+    value::{DefaultScalarValue, ScalarValue, Value},
+    GraphQLError,
+};
+
+/// Boxed `Stream` yielding the payloads delivered after the initial response of a query
+/// executed with [`crate::execute_incremental`]
+pub type IncrementalStream<'a, S = DefaultScalarValue> =
+    Pin<Box<dyn Stream<Item = IncrementalPayload<S>> + Send + 'a>>;
+
+/// A segment of the path to a value in a response
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PathSegment {
+    /// The response name of a field, i.e. its alias or its name
+    Field(String),
+    /// The index of an item in a list
+    Index(usize),
+}
+
+/// A payload delivered after the initial response, with the fields of a deferred fragment or
+/// the remaining items of a streamed list
+#[derive(Debug, PartialEq)]
+pub struct IncrementalPayload<S = DefaultScalarValue> {
+    pub(crate) label: Option<String>,
+    pub(crate) path: Vec<PathSegment>,
+    pub(crate) data: IncrementalData<S>,
+    pub(crate) errors: Vec<ExecutionError<S>>,
+    pub(crate) has_next: bool,
+}
+
+/// The values delivered by an [`IncrementalPayload`]
+#[derive(Debug, PartialEq)]
+pub enum IncrementalData<S = DefaultScalarValue> {
+    /// The object with the fields of a deferred fragment, or `null` if an error propagated to it
+    Data(Value<S>),
+    /// The list of the items of a streamed list not delivered yet, or `null` if an error
+    /// propagated to the list
+    Items(Value<S>),
+}
+
+impl<S> IncrementalPayload<S> {
+    /// The label of the `@defer` or `@stream` directive delivered by this payload
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    /// The path to the object the fragment was deferred in, or to the first item delivered
+    /// of a streamed list
+    pub fn path(&self) -> &[PathSegment] {
+        &self.path
+    }
+
+    /// The values delivered by this payload
+    pub fn data(&self) -> &IncrementalData<S> {
+        &self.data
+    }
+
+    /// The errors raised while resolving the delivered values
+    pub fn errors(&self) -> &[ExecutionError<S>] {
+        &self.errors
+    }
+
+    /// Whether more payloads follow this one
+    pub fn has_next(&self) -> bool {
+        self.has_next
+    }
+}
+
+/// Where an executor publishes the value it resolves, once only the parts it defers or streams
+/// are left to resolve
+pub(crate) type EarlyValue<S> = Arc<Mutex<Option<oneshot::Sender<Value<S>>>>>;
+
+/// Constructs the channel through which an executor publishes its value early
+pub(crate) fn early_channel<S>() -> (EarlyValue<S>, oneshot::Receiver<Value<S>>) {
+    let (tx, rx) = oneshot::channel();
+    (Arc::new(Mutex::new(Some(tx))), rx)
+}
+
+/// The state of an incremental execution, shared by all its executors
+pub(crate) struct IncrementalState<S> {
+    /// The number of parts found and not delivered yet, and where their payloads are sent
+    payloads: Mutex<(usize, mpsc::UnboundedSender<IncrementalPayload<S>>)>,
+    /// The extensions of the executors of the parts, as only the initial execution is observed
+    no_extensions: Extensions<S>,
+}
+
+impl<S> IncrementalState<S> {
+    fn new(payloads: mpsc::UnboundedSender<IncrementalPayload<S>>) -> Self {
+        IncrementalState {
+            payloads: Mutex::new((0, payloads)),
+            no_extensions: Extensions::new(),
+        }
+    }
+
+    fn send(&self, mut payload: IncrementalPayload<S>) {
+        let mut payloads = self.payloads.lock().unwrap();
+        payloads.0 -= 1;
+        payload.has_next = payloads.0 > 0;
+        let _ = payloads.1.unbounded_send(payload);
+    }
+}
+
+/// The state of the incremental delivery of the value resolved by an executor
+pub(crate) struct Incremental<'r, S> {
+    state: &'r IncrementalState<S>,
+    /// Resolves once the payload holding the value is delivered
+    delivered: Shared<oneshot::Receiver<()>>,
+    /// Where the value is published early, if the caller of the executor waits for it
+    early: Option<EarlyValue<S>>,
+    /// The `@stream` directive of the list resolved by the executor
+    stream: Option<StreamDirective>,
+}
+
+impl<'r, S> Clone for Incremental<'r, S> {
+    fn clone(&self) -> Self {
+        Incremental {
+            state: self.state,
+            delivered: self.delivered.clone(),
+            early: self.early.clone(),
+            stream: self.stream.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct StreamDirective {
+    initial_count: usize,
+    label: Option<String>,
+}
+
+impl<'r, S> Incremental<'r, S> {
+    fn new(
+        state: &'r IncrementalState<S>,
+        delivered: Shared<oneshot::Receiver<()>>,
+        early: EarlyValue<S>,
+    ) -> Self {
+        Incremental {
+            state,
+            delivered,
+            early: Some(early),
+            stream: None,
+        }
+    }
+
+    /// The state of the executor of a field, publishing its value through `early`
+    pub(crate) fn field(
+        &self,
+        directives: &Option<Vec<Spanning<Directive<S>>>>,
+        vars: &Variables<S>,
+        early: EarlyValue<S>,
+    ) -> Self
+    where
+        S: ScalarValue,
+    {
+        let stream = active_directive(directives, "stream", vars).map(|stream| StreamDirective {
+            initial_count: argument::<i32, _>(stream, "initialCount", vars)
+                .unwrap_or(0)
+                .max(0) as usize,
+            label: argument(stream, "label", vars),
+        });
+        Incremental {
+            early: Some(early),
+            stream,
+            ..self.item()
+        }
+    }
+
+    /// The state of the executor of a fragment or of an item of a list, publishing its value
+    /// through `early`
+    pub(crate) fn nested(&self, early: EarlyValue<S>) -> Self {
+        Incremental {
+            early: Some(early),
+            ..self.item()
+        }
+    }
+
+    /// The state of the executors of the fields of an object or of the items of a list, whose
+    /// values aren't waited for
+    pub(crate) fn item(&self) -> Self {
+        Incremental {
+            state: self.state,
+            delivered: self.delivered.clone(),
+            early: None,
+            stream: None,
+        }
+    }
+
+    /// The number of items delivered with the list resolved by the executor, if it's streamed
+    pub(crate) fn streamed_count(&self) -> Option<usize> {
+        self.stream.as_ref().map(|stream| stream.initial_count)
+    }
+
+    /// The extensions of the executors of parts
+    pub(crate) fn no_extensions(&self) -> &'r Extensions<S> {
+        &self.state.no_extensions
+    }
+
+    /// Publishes the value resolved by the executor, if its caller waits for it
+    pub(crate) fn publish(&self, value: &Value<S>)
+    where
+        S: Clone,
+    {
+        let sender = self
+            .early
+            .as_ref()
+            .and_then(|early| early.lock().unwrap().take());
+        if let Some(sender) = sender {
+            let _ = sender.send(value.clone());
+        }
+    }
+
+    /// The fragment at `path` deferred by `directives`, if it's deferred
+    pub(crate) fn defer(
+        &self,
+        path: &[PathSegment],
+        directives: &Option<Vec<Spanning<Directive<S>>>>,
+        vars: &Variables<S>,
+    ) -> Option<Part<'r, S>>
+    where
+        S: ScalarValue,
+    {
+        active_directive(directives, "defer", vars).map(|defer| {
+            self.part(
+                PartKind::Defer,
+                argument(defer, "label", vars),
+                path.to_vec(),
+            )
+        })
+    }
+
+    /// The items of a streamed list not delivered with it, from the one at `path`
+    pub(crate) fn stream_rest(&self, path: Vec<PathSegment>) -> Option<Part<'r, S>> {
+        let label = self.stream.as_ref()?.label.clone();
+        Some(self.part(PartKind::Stream, label, path))
+    }
+
+    fn part(&self, kind: PartKind, label: Option<String>, path: Vec<PathSegment>) -> Part<'r, S> {
+        self.state.payloads.lock().unwrap().0 += 1;
+        Part {
+            state: self.state,
+            kind,
+            label,
+            path,
+            enclosing: self.delivered.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum PartKind {
+    Defer,
+    Stream,
+}
+
+/// A deferred fragment or the remaining items of a streamed list, delivered after the payload
+/// they were found in
+pub(crate) struct Part<'r, S> {
+    state: &'r IncrementalState<S>,
+    kind: PartKind,
+    label: Option<String>,
+    /// Path to the object the fragment is deferred in, or to the first item not delivered yet
+    path: Vec<PathSegment>,
+    /// Resolves once the payload the part was found in is delivered
+    enclosing: Shared<oneshot::Receiver<()>>,
+}
+
+/// The channels of the executor of a [`Part`]
+pub(crate) struct PartChannels<S> {
+    early: oneshot::Receiver<Value<S>>,
+    delivered: oneshot::Sender<()>,
+}
+
+impl<'r, S> Part<'r, S>
+where
+    S: ScalarValue,
+{
+    /// The state of the executor resolving the part
+    pub(crate) fn executor_state(&self) -> (Incremental<'r, S>, PartChannels<S>) {
+        let (early, early_rx) = early_channel();
+        let (delivered_tx, delivered) = oneshot::channel();
+        let incremental = Incremental::new(self.state, delivered.shared(), early);
+        let channels = PartChannels {
+            early: early_rx,
+            delivered: delivered_tx,
+        };
+        (incremental, channels)
+    }
+
+    /// Delivers the value resolved by `resolving` along with the `errors` raised by it, once the
+    /// payload the part was found in is delivered, and drives `resolving` to completion
+    pub(crate) async fn deliver<F>(
+        self,
+        resolving: F,
+        channels: PartChannels<S>,
+        errors: &RwLock<Vec<ExecutionError<S>>>,
+    ) where
+        F: Future<Output = Value<S>>,
+    {
+        let PartChannels { early, delivered } = channels;
+        let (value_tx, value_rx) = oneshot::channel();
+        let resolve = drive_early(resolving, Some(early), |value| {
+            let _ = value_tx.send(value);
+        });
+        let send = async {
+            let value = value_rx.await.unwrap_or(Value::Null);
+            let _ = self.enclosing.clone().await;
+            let data = match self.kind {
+                PartKind::Defer => IncrementalData::Data(value),
+                PartKind::Stream => IncrementalData::Items(value),
+            };
+            self.state.send(IncrementalPayload {
+                label: self.label.clone(),
+                path: self.path.clone(),
+                data,
+                errors: take_errors(errors),
+                has_next: false,
+            });
+            let _ = delivered.send(());
+        };
+        future::join(resolve, send).await;
+    }
+}
+
+/// Takes the errors raised so far, sorted
+fn take_errors<S>(errors: &RwLock<Vec<ExecutionError<S>>>) -> Vec<ExecutionError<S>>
+where
+    S: PartialEq,
+{
+    let mut errors = mem::take(&mut *errors.write().unwrap());
+    errors.sort();
+    errors
+}
+
+/// Drives `future` to completion, calling `on_value` as soon as its value is known: once it's
+/// published `early`, or once `future` resolves
+pub(crate) async fn drive_early<F, E, T, R>(
+    future: F,
+    early: Option<E>,
+    on_value: impl FnOnce(T) -> R,
+) -> R
+where
+    F: Future<Output = T>,
+    E: Future<Output = Result<T, oneshot::Canceled>> + Unpin,
+{
+    futures::pin_mut!(future);
+    let early = match early {
+        Some(early) => early,
+        None => return on_value(future.await),
+    };
+    match future::select(future, early).await {
+        Either::Left((value, _)) => on_value(value),
+        Either::Right((Ok(value), future)) => {
+            let res = on_value(value);
+            future.await;
+            res
+        }
+        Either::Right((Err(oneshot::Canceled), future)) => on_value(future.await),
+    }
+}
+
+/// The values of the fields of an object or of the items of a list, resolved concurrently
+///
+/// A value is known once its future resolves, or once it's published early by a future still
+/// resolving the parts its value defers or streams. The futures resolving parts have no value.
+pub(crate) struct Resolving<F, T> {
+    futures: FuturesUnordered<F>,
+    values: Vec<Slot<T>>,
+    next: usize,
+}
+
+enum Slot<T> {
+    Pending(Option<oneshot::Receiver<T>>),
+    Known(T),
+    Taken,
+}
+
+/// Where the future of a value of [`Resolving`] publishes it
+pub(crate) struct ValueSlot<T> {
+    index: usize,
+    early: Option<oneshot::Sender<T>>,
+}
+
+impl<T> ValueSlot<T> {
+    /// Publishes the value, returning it as the output of its future if it isn't published early
+    pub(crate) fn publish(self, value: T) -> Option<(usize, T)> {
+        match self.early {
+            Some(early) => {
+                let _ = early.send(value);
+                None
+            }
+            None => Some((self.index, value)),
+        }
+    }
+}
+
+impl<F, T> Resolving<F, T>
+where
+    F: Future<Output = Option<(usize, T)>>,
+{
+    pub(crate) fn new() -> Self {
+        Resolving {
+            futures: FuturesUnordered::new(),
+            values: Vec::new(),
+            next: 0,
+        }
+    }
+
+    /// Reserves the next value, to be published by the next future pushed, early if `early` is set
+    pub(crate) fn reserve(&mut self, early: bool) -> ValueSlot<T> {
+        let (early, slot) = if early {
+            let (tx, rx) = oneshot::channel();
+            (Some(tx), Slot::Pending(Some(rx)))
+        } else {
+            (None, Slot::Pending(None))
+        };
+        self.values.push(slot);
+        ValueSlot {
+            index: self.values.len() - 1,
+            early,
+        }
+    }
+
+    /// Adds the future of a reserved value, or of a part
+    pub(crate) fn push(&mut self, future: F) {
+        self.futures.push(future);
+    }
+
+    /// The number of values
+    pub(crate) fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Resolves the next value, in the order they were reserved
+    pub(crate) async fn next(&mut self) -> Option<T> {
+        future::poll_fn(|cx| self.poll_next_value(cx)).await
+    }
+
+    fn poll_next_value(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        loop {
+            let index = self.next;
+            match self.values.get_mut(index) {
+                None => return Poll::Ready(None),
+                Some(Slot::Known(_)) => {
+                    self.next += 1;
+                    match mem::replace(&mut self.values[index], Slot::Taken) {
+                        Slot::Known(value) => return Poll::Ready(Some(value)),
+                        _ => unreachable!(),
+                    }
+                }
+                Some(Slot::Pending(Some(early))) => {
+                    if let Poll::Ready(value) = Pin::new(early).poll(cx) {
+                        self.values[index] = match value {
+                            Ok(value) => Slot::Known(value),
+                            Err(oneshot::Canceled) => Slot::Pending(None),
+                        };
+                        continue;
+                    }
+                }
+                Some(_) => {}
+            }
+
+            match self.futures.poll_next_unpin(cx) {
+                Poll::Ready(Some(Some((index, value)))) => {
+                    if let Slot::Pending(_) = self.values[index] {
+                        self.values[index] = Slot::Known(value);
+                    }
+                }
+                Poll::Ready(Some(None)) => {}
+                // Only a future which panicked could leave its value unknown.
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+
+    /// Returns the value resolved from the known values, after publishing it and driving the
+    /// futures still resolving parts to completion when executing incrementally
+    pub(crate) async fn finish<S>(
+        self,
+        incremental: Option<&Incremental<'_, S>>,
+        value: Value<S>,
+    ) -> Value<S>
+    where
+        S: Clone,
+    {
+        if let Some(incremental) = incremental {
+            if !self.futures.is_empty() {
+                incremental.publish(&value);
+                self.futures.for_each(|_| future::ready(())).await;
+            }
+        }
+        value
+    }
+}
+
+/// Finds the directive called `name` in `directives`, unless its `if` argument is `false`
+fn active_directive<'d, 'a, S>(
+    directives: &'d Option<Vec<Spanning<Directive<'a, S>>>>,
+    name: &str,
+    vars: &Variables<S>,
+) -> Option<&'d Directive<'a, S>>
+where
+    S: ScalarValue,
+{
+    directives
+        .iter()
+        .flatten()
+        .map(|d| &d.item)
+        .find(|d| d.name.item == name)
+        .filter(|d| argument(d, "if", vars).unwrap_or(true))
+}
+
+fn argument<T, S>(directive: &Directive<S>, name: &str, vars: &Variables<S>) -> Option<T>
+where
+    T: FromInputValue<S>,
+    S: ScalarValue,
+{
+    directive
+        .arguments
+        .iter()
+        .flat_map(|m| m.item.get(name))
+        .flat_map(|v| v.item.clone().into_const(vars).convert())
+        .next()
+}
+
+/// Executes a validated query, returning its initial result once it's resolved, along with the
+/// stream of the deferred fragments and streamed lists resolved afterwards, if there are any
+pub(crate) async fn execute_validated_query_incremental<'a, QueryT, MutationT, SubscriptionT, S>(
+    document: Document<'a, S>,
+    operation_name: Option<&'a str>,
+    root_node: &'a RootNode<'a, QueryT, MutationT, SubscriptionT, S>,
+    variables: Variables<S>,
+    context: &'a QueryT::Context,
+    extensions: Extensions<S>,
+) -> Result<
+    (
+        Value<S>,
+        Vec<ExecutionError<S>>,
+        Option<IncrementalStream<'a, S>>,
+    ),
+    GraphQLError<'a>,
+>
+where
+    QueryT: GraphQLTypeAsync<S>,
+    QueryT::TypeInfo: Sync,
+    QueryT::Context: Sync,
+    MutationT: GraphQLTypeAsync<S, Context = QueryT::Context>,
+    MutationT::TypeInfo: Sync,
+    SubscriptionT: GraphQLType<S, Context = QueryT::Context> + Sync,
+    SubscriptionT::TypeInfo: Sync,
+    S: ScalarValue + Send + Sync + 'a,
+{
+    let (initial_tx, initial_rx) = oneshot::channel();
+    let (payloads_tx, payloads_rx) = mpsc::unbounded();
+
+    // The execution goes on after the initial result is sent, to resolve the parts it found
+    // with the values it resolved, so it owns what it borrows.
+    let mut execution = async move {
+        let operation =
+            get_operation(&document, operation_name).expect("Operation was validated already");
+        let state = IncrementalState::new(payloads_tx);
+        let (delivered_tx, delivered) = oneshot::channel();
+        let (early, early_rx) = early_channel();
+        // Mutations are executed at once, as their fields have side effects.
+        let incremental = match operation.item.operation_type {
+            OperationType::Query => Some(Incremental::new(&state, delivered.shared(), early)),
+            _ => None,
+        };
+        let errors = RwLock::new(Vec::new());
+
+        let executing = execute_validated_operation_async(
+            &document,
+            operation,
+            root_node,
+            &variables,
+            context,
+            &extensions,
+            incremental,
+            &errors,
+        );
+        drive_early(executing, Some(early_rx.map(|r| r.map(Ok))), |result| {
+            let _ = initial_tx.send(result.map(|value| (value, take_errors(&errors))));
+            let _ = delivered_tx.send(());
+        })
+        .await;
+    }
+    .boxed();
+
+    let (initial, finished) = match future::select(&mut execution, initial_rx).await {
+        Either::Left(((), initial_rx)) => (initial_rx.await, true),
+        Either::Right((initial, _)) => (initial, false),
+    };
+    let (value, errors) = initial.expect("Initial result is sent before the execution ends")?;
+
+    let subsequent = if finished {
+        let payloads = payloads_rx.collect::<Vec<_>>().await;
+        if payloads.is_empty() {
+            None
+        } else {
+            Some(stream::iter(payloads).boxed())
+        }
+    } else {
+        let execution = execution
+            .into_stream()
+            .filter_map(|()| future::ready(None::<IncrementalPayload<S>>));
+        Some(stream::select(execution, payloads_rx).boxed())
+    };
+
+    Ok((value, errors, subsequent))
+}
@@ -175,7 +175,8 @@ where
                             .unwrap_or(false),
                         ("skip", &None) => false,
                         ("include", &None) => true,
-                        (_, _) => unreachable!(),
+                        // Custom directives don't affect whether a selection is included.
+                        (_, _) => true,
                     }
                 })
             })
@@ -954,6 +955,48 @@ query Hero {
         }
     }
 
+    #[test]
+    fn check_query_with_custom_directives() {
+        let docs = parse_document_source::<DefaultScalarValue>(
+            "
+query Hero {
+    hero {
+        id @uppercase
+        name @mask(with: \"***\") @include(if: false)
+    }
+}",
+        )
+        .unwrap();
+        let fragments = extract_fragments(&docs);
+
+        if let crate::ast::Definition::Operation(ref op) = docs[0] {
+            let vars = Variables::default();
+            let look_ahead = LookAheadSelection::build_from_selection(
+                &op.item.selection_set[0],
+                &vars,
+                &fragments,
+            )
+            .unwrap();
+            let expected = LookAheadSelection {
+                name: "hero",
+                alias: None,
+                arguments: Vec::new(),
+                children: vec![ChildSelection {
+                    inner: LookAheadSelection {
+                        name: "id",
+                        alias: None,
+                        arguments: Vec::new(),
+                        children: Vec::new(),
+                    },
+                    applies_for: Applies::All,
+                }],
+            };
+            assert_eq!(look_ahead, expected);
+        } else {
+            panic!("No Operation found");
+        }
+    }
+
     #[test]
     fn check_query_with_inline_fragments() {
         let docs = parse_document_source::<DefaultScalarValue>(
@@ -4,6 +4,7 @@ use std::{
     collections::HashMap,
     fmt::{Debug, Display},
     sync::{Arc, RwLock},
+    time::Instant,
 };
 
 use fnv::FnvHashMap;
@@ -14,10 +15,11 @@ use crate::{
         Definition, Document, Fragment, FromInputValue, InputValue, Operation, OperationType,
         Selection, ToInputValue, Type,
     },
+    http::error_policy::ErrorPolicy,
     parser::{SourcePosition, Spanning},
     schema::{
         meta::{
-            Argument, DeprecationStatus, EnumMeta, EnumValue, Field, InputObjectMeta,
+            Argument, Complexity, DeprecationStatus, EnumMeta, EnumValue, Field, InputObjectMeta,
             InterfaceMeta, ListMeta, MetaType, NullableMeta, ObjectMeta, PlaceholderMeta,
             ScalarMeta, UnionMeta,
         },
@@ -34,6 +36,11 @@ use crate::{
 };
 
 pub use self::{
+    apollo_tracing::ApolloTracing,
+    directives::{DirectiveContext, DirectiveResolver},
+    extensions::{Extension, ExtensionFactory, Extensions, ResolvedField},
+    guard::Guard,
+    incremental::{IncrementalData, IncrementalPayload, IncrementalStream, PathSegment},
     look_ahead::{
         Applies, ChildSelection, ConcreteLookAheadSelection, LookAheadArgument, LookAheadMethods,
         LookAheadSelection, LookAheadValue,
@@ -41,6 +48,18 @@ pub use self::{
     owned_executor::OwnedExecutor,
 };
 
+pub(crate) use self::{
+    directives::FieldDirectives,
+    incremental::{
+        drive_early, early_channel, execute_validated_query_incremental, Incremental, Resolving,
+    },
+};
+
+mod apollo_tracing;
+mod directives;
+mod extensions;
+mod guard;
+mod incremental;
 mod look_ahead;
 mod owned_executor;
 
@@ -58,6 +77,7 @@ pub struct Registry<'r, S = DefaultScalarValue> {
 pub enum FieldPath<'a> {
     Root(SourcePosition),
     Field(&'a str, SourcePosition, Arc<FieldPath<'a>>),
+    Index(usize, Arc<FieldPath<'a>>),
 }
 
 /// Query execution engine
@@ -78,6 +98,9 @@ where
     context: &'a CtxT,
     errors: &'r RwLock<Vec<ExecutionError<S>>>,
     field_path: Arc<FieldPath<'a>>,
+    extensions: &'r Extensions<S>,
+    error_policy: &'r ErrorPolicy,
+    incremental: Option<Incremental<'r, S>>,
 }
 
 /// Error type for errors that occur during query execution
@@ -87,7 +110,7 @@ where
 #[derive(Debug, PartialEq)]
 pub struct ExecutionError<S> {
     location: SourcePosition,
-    path: Vec<String>,
+    path: Vec<PathSegment>,
     error: FieldError<S>,
 }
 
@@ -150,6 +173,7 @@ where
 pub struct FieldError<S = DefaultScalarValue> {
     message: String,
     extensions: Value<S>,
+    user_facing: bool,
 }
 
 impl<T: Display, S> From<T> for FieldError<S>
@@ -160,6 +184,7 @@ where
         FieldError {
             message: format!("{}", e),
             extensions: Value::null(),
+            user_facing: false,
         }
     }
 }
@@ -204,9 +229,32 @@ impl<S> FieldError<S> {
         FieldError {
             message: format!("{}", e),
             extensions,
+            user_facing: false,
         }
     }
 
+    /// Marks the error as meant for the clients, so it's exposed to them even when an
+    /// [`ErrorPolicy`](crate::http::error_policy::ErrorPolicy) masks unexpected errors
+    ///
+    /// ```rust
+    /// # use juniper::{FieldError, FieldResult};
+    /// fn check_age(age: i32) -> FieldResult<i32> {
+    ///     if age < 0 {
+    ///         return Err(FieldError::from("Age can't be negative").user_facing());
+    ///     }
+    ///     Ok(age)
+    /// }
+    /// ```
+    pub fn user_facing(mut self) -> Self {
+        self.user_facing = true;
+        self
+    }
+
+    /// Whether the error was marked as meant for the clients with [`FieldError::user_facing`]
+    pub fn is_user_facing(&self) -> bool {
+        self.user_facing
+    }
+
     #[doc(hidden)]
     pub fn message(&self) -> &str {
         &self.message
@@ -226,6 +274,7 @@ impl<S> FieldError<S> {
         FieldError {
             message: self.message,
             extensions: self.extensions.map_scalar_value(),
+            user_facing: self.user_facing,
         }
     }
 }
@@ -533,6 +582,9 @@ where
             context: ctx,
             errors: self.errors,
             field_path: self.field_path.clone(),
+            extensions: self.extensions,
+            error_policy: self.error_policy,
+            incremental: self.incremental.clone(),
         }
     }
 
@@ -565,6 +617,27 @@ where
                 location,
                 Arc::clone(&self.field_path),
             )),
+            extensions: self.extensions,
+            error_policy: self.error_policy,
+            incremental: self.incremental.as_ref().map(Incremental::item),
+        }
+    }
+
+    /// Derive a new executor for the item at `index` of the list resolved by this one
+    pub(crate) fn index_sub_executor(&self, index: usize) -> Executor<'_, 'a, CtxT, S> {
+        Executor {
+            fragments: self.fragments,
+            variables: self.variables,
+            current_selection_set: self.current_selection_set,
+            parent_selection_set: self.parent_selection_set,
+            current_type: self.current_type.clone(),
+            schema: self.schema,
+            context: self.context,
+            errors: self.errors,
+            field_path: Arc::new(FieldPath::Index(index, Arc::clone(&self.field_path))),
+            extensions: self.extensions,
+            error_policy: self.error_policy,
+            incremental: self.incremental.as_ref().map(Incremental::item),
         }
     }
 
@@ -587,9 +660,46 @@ where
             context: self.context,
             errors: self.errors,
             field_path: self.field_path.clone(),
+            extensions: self.extensions,
+            error_policy: self.error_policy,
+            incremental: self.incremental.clone(),
         }
     }
 
+    /// Derive an executor resolving a part delivered incrementally, raising its own errors
+    pub(crate) fn part_sub_executor<'s>(
+        &'s self,
+        errors: &'s RwLock<Vec<ExecutionError<S>>>,
+        incremental: Incremental<'s, S>,
+    ) -> Executor<'s, 'a, CtxT, S> {
+        Executor {
+            fragments: self.fragments,
+            variables: self.variables,
+            current_selection_set: self.current_selection_set,
+            parent_selection_set: self.parent_selection_set,
+            current_type: self.current_type.clone(),
+            schema: self.schema,
+            context: self.context,
+            errors,
+            field_path: self.field_path.clone(),
+            extensions: incremental.no_extensions(),
+            error_policy: self.error_policy,
+            incremental: Some(incremental),
+        }
+    }
+
+    /// Replaces the state of the incremental delivery of the value resolved by this executor
+    pub(crate) fn with_incremental(mut self, incremental: Option<Incremental<'r, S>>) -> Self {
+        self.incremental = incremental;
+        self
+    }
+
+    /// The state of the incremental delivery of the value resolved by this executor, if the query
+    /// is executed incrementally
+    pub(crate) fn incremental(&self) -> Option<&Incremental<'r, S>> {
+        self.incremental.as_ref()
+    }
+
     /// `Executor`'s current selection set
     pub fn current_selection_set(&self) -> Option<&[Selection<'a, S>]> {
         self.current_selection_set
@@ -628,6 +738,43 @@ where
         self.field_path.location()
     }
 
+    /// The path of the value resolved by the executor in the response
+    pub fn response_path(&self) -> Vec<PathSegment> {
+        let mut path = Vec::new();
+        self.field_path.construct_response_path(&mut path);
+        path
+    }
+
+    /// Returns when the resolution of a field starts, if any extension needs to know it
+    pub(crate) fn field_start(&self) -> Option<Instant> {
+        if self.extensions.is_empty() {
+            None
+        } else {
+            Some(Instant::now())
+        }
+    }
+
+    /// Reports the field resolved by this sub executor to the extensions
+    pub(crate) fn field_resolved(
+        &self,
+        parent_type: &str,
+        field_name: &str,
+        return_type: &Type,
+        started_at: Instant,
+    ) {
+        let duration = started_at.elapsed();
+        let path = self.response_path();
+
+        self.extensions.field_resolved(&ResolvedField {
+            path: &path,
+            parent_type,
+            field_name,
+            return_type,
+            started_at,
+            duration,
+        });
+    }
+
     /// Add an error to the execution engine at the current executor location
     pub fn push_error(&self, error: FieldError<S>) {
         self.push_error_at(error, *self.location());
@@ -635,27 +782,23 @@ where
 
     /// Add an error to the execution engine at a specific location
     pub fn push_error_at(&self, error: FieldError<S>, location: SourcePosition) {
-        let mut path = Vec::new();
-        self.field_path.construct_path(&mut path);
+        let path = self.response_path();
 
         let mut errors = self.errors.write().unwrap();
 
         errors.push(ExecutionError {
             location,
             path,
-            error,
+            error: self.error_policy.apply_to_field_error(error),
         });
     }
 
     /// Returns new [`ExecutionError`] at current location
     pub fn new_error(&self, error: FieldError<S>) -> ExecutionError<S> {
-        let mut path = Vec::new();
-        self.field_path.construct_path(&mut path);
-
         ExecutionError {
             location: *self.location(),
-            path,
-            error,
+            path: self.response_path(),
+            error: self.error_policy.apply_to_field_error(error),
         }
     }
 
@@ -664,10 +807,7 @@ where
     /// This allows seeing the whole selection and perform operations
     /// affecting the children.
     pub fn look_ahead(&'a self) -> LookAheadSelection<'a, S> {
-        let field_name = match *self.field_path {
-            FieldPath::Field(x, ..) => x,
-            FieldPath::Root(_) => unreachable!(),
-        };
+        let field_name = self.field_path.field_name();
         self.parent_selection_set
             .map(|p| {
                 // Search the parent's fields to find this field within the set
@@ -733,17 +873,23 @@ where
             context: self.context,
             errors: RwLock::new(vec![]),
             field_path: Arc::clone(&self.field_path),
+            extensions: self.extensions.clone(),
+            error_policy: self.error_policy.clone(),
         }
     }
 }
 
 impl<'a> FieldPath<'a> {
-    fn construct_path(&self, acc: &mut Vec<String>) {
+    fn construct_response_path(&self, acc: &mut Vec<PathSegment>) {
         match self {
             FieldPath::Root(_) => (),
             FieldPath::Field(name, _, parent) => {
-                parent.construct_path(acc);
-                acc.push((*name).to_owned());
+                parent.construct_response_path(acc);
+                acc.push(PathSegment::Field((*name).to_owned()));
+            }
+            FieldPath::Index(index, parent) => {
+                parent.construct_response_path(acc);
+                acc.push(PathSegment::Index(*index));
             }
         }
     }
@@ -751,6 +897,16 @@ impl<'a> FieldPath<'a> {
     fn location(&self) -> &SourcePosition {
         match *self {
             FieldPath::Root(ref pos) | FieldPath::Field(_, ref pos, _) => pos,
+            FieldPath::Index(_, ref parent) => parent.location(),
+        }
+    }
+
+    /// The name of the field of the innermost list, if the path points to an item
+    fn field_name(&self) -> &'a str {
+        match *self {
+            FieldPath::Field(name, ..) => name,
+            FieldPath::Index(_, ref parent) => parent.field_name(),
+            FieldPath::Root(_) => unreachable!(),
         }
     }
 }
@@ -760,7 +916,35 @@ impl<S> ExecutionError<S> {
     pub fn new(location: SourcePosition, path: &[&str], error: FieldError<S>) -> ExecutionError<S> {
         ExecutionError {
             location,
-            path: path.iter().map(|s| (*s).to_owned()).collect(),
+            path: path
+                .iter()
+                .map(|s| PathSegment::Field((*s).to_owned()))
+                .collect(),
+            error,
+        }
+    }
+
+    /// Maps the [`FieldError`] of this error, keeping its location and path.
+    pub(crate) fn map_error<F>(self, f: F) -> ExecutionError<S>
+    where
+        F: FnOnce(FieldError<S>) -> FieldError<S>,
+    {
+        ExecutionError {
+            location: self.location,
+            path: self.path,
+            error: f(self.error),
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn at_path(
+        location: SourcePosition,
+        path: Vec<PathSegment>,
+        error: FieldError<S>,
+    ) -> ExecutionError<S> {
+        ExecutionError {
+            location,
+            path,
             error,
         }
     }
@@ -775,8 +959,8 @@ impl<S> ExecutionError<S> {
         &self.location
     }
 
-    /// The path of fields leading to the field that generated this error
-    pub fn path(&self) -> &[String] {
+    /// The path of fields and list indices leading to the field that generated this error
+    pub fn path(&self) -> &[PathSegment] {
         &self.path
     }
 }
@@ -789,6 +973,7 @@ pub fn execute_validated_query<'a, 'b, QueryT, MutationT, SubscriptionT, S>(
     root_node: &RootNode<QueryT, MutationT, SubscriptionT, S>,
     variables: &Variables<S>,
     context: &QueryT::Context,
+    extensions: &Extensions<S>,
 ) -> Result<(Value<S>, Vec<ExecutionError<S>>), GraphQLError<'a>>
 where
     S: ScalarValue,
@@ -858,6 +1043,9 @@ where
             context,
             errors: &errors,
             field_path: Arc::new(FieldPath::Root(operation.start)),
+            extensions,
+            error_policy: &root_node.error_policy,
+            incremental: None,
         };
 
         value = match operation.item.operation_type {
@@ -878,12 +1066,48 @@ where
 /// Create new `Executor` and start asynchronous query execution.
 /// Returns `IsSubscription` error if subscription is passed.
 pub async fn execute_validated_query_async<'a, 'b, QueryT, MutationT, SubscriptionT, S>(
-    document: &'b Document<'a, S>,
+    document: &'b Document<'_, S>,
     operation: &'b Spanning<Operation<'_, S>>,
     root_node: &RootNode<'a, QueryT, MutationT, SubscriptionT, S>,
     variables: &Variables<S>,
     context: &QueryT::Context,
+    extensions: &Extensions<S>,
 ) -> Result<(Value<S>, Vec<ExecutionError<S>>), GraphQLError<'a>>
+where
+    QueryT: GraphQLTypeAsync<S>,
+    QueryT::TypeInfo: Sync,
+    QueryT::Context: Sync,
+    MutationT: GraphQLTypeAsync<S, Context = QueryT::Context>,
+    MutationT::TypeInfo: Sync,
+    SubscriptionT: GraphQLType<S, Context = QueryT::Context> + Sync,
+    SubscriptionT::TypeInfo: Sync,
+    S: ScalarValue + Send + Sync,
+{
+    let errors = RwLock::new(Vec::new());
+    let value = execute_validated_operation_async(
+        document, operation, root_node, variables, context, extensions, None, &errors,
+    )
+    .await?;
+
+    let mut errors = errors.into_inner().unwrap();
+    errors.sort();
+
+    Ok((value, errors))
+}
+
+/// Executes a query or a mutation asynchronously, raising `errors`, and delivering it
+/// incrementally if `incremental` is set
+#[allow(clippy::too_many_arguments)]
+async fn execute_validated_operation_async<'a, 'b, QueryT, MutationT, SubscriptionT, S>(
+    document: &'b Document<'_, S>,
+    operation: &'b Spanning<Operation<'_, S>>,
+    root_node: &RootNode<'a, QueryT, MutationT, SubscriptionT, S>,
+    variables: &Variables<S>,
+    context: &QueryT::Context,
+    extensions: &Extensions<S>,
+    incremental: Option<Incremental<'_, S>>,
+    errors: &RwLock<Vec<ExecutionError<S>>>,
+) -> Result<Value<S>, GraphQLError<'a>>
 where
     QueryT: GraphQLTypeAsync<S>,
     QueryT::TypeInfo: Sync,
@@ -917,7 +1141,6 @@ where
             .collect::<HashMap<String, InputValue<S>>>()
     });
 
-    let errors = RwLock::new(Vec::new());
     let value;
 
     {
@@ -954,8 +1177,11 @@ where
             current_type: root_type,
             schema: &root_node.schema,
             context,
-            errors: &errors,
+            errors,
             field_path: Arc::new(FieldPath::Root(operation.start)),
+            extensions,
+            error_policy: &root_node.error_policy,
+            incremental,
         };
 
         value = match operation.item.operation_type {
@@ -973,10 +1199,7 @@ where
         };
     }
 
-    let mut errors = errors.into_inner().unwrap();
-    errors.sort();
-
-    Ok((value, errors))
+    Ok(value)
 }
 
 pub fn get_operation<'b, 'd, 'e, S>(
@@ -1088,6 +1311,8 @@ where
             _ => unreachable!(),
         };
 
+        // Extensions only observe queries and mutations.
+        let extensions = Extensions::new();
         let executor: Executor<'_, 'r, _, _> = Executor {
             fragments: &fragments
                 .iter()
@@ -1101,6 +1326,9 @@ where
             context,
             errors: &errors,
             field_path: Arc::new(FieldPath::Root(operation.start)),
+            extensions: &extensions,
+            error_policy: &root_node.error_policy,
+            incremental: None,
         };
 
         value = match operation.item.operation_type {
@@ -1163,6 +1391,9 @@ where
             arguments: None,
             field_type: self.get_type::<T>(info),
             deprecation_status: DeprecationStatus::Current,
+            directives: vec![],
+            visibility: None,
+            complexity: Complexity::default(),
         }
     }
 
@@ -1181,6 +1412,9 @@ where
             arguments: None,
             field_type: self.get_type::<I>(info),
             deprecation_status: DeprecationStatus::Current,
+            directives: vec![],
+            visibility: None,
+            complexity: Complexity::default(),
         }
     }
 
@@ -5,7 +5,8 @@ use std::{
 
 use crate::{
     ast::Fragment,
-    executor::FieldPath,
+    executor::{Extensions, FieldPath},
+    http::error_policy::ErrorPolicy,
     parser::SourcePosition,
     schema::model::{SchemaType, TypeType},
     ExecutionError, Executor, Selection, Variables,
@@ -23,6 +24,8 @@ pub struct OwnedExecutor<'a, CtxT, S> {
     pub(super) context: &'a CtxT,
     pub(super) errors: RwLock<Vec<ExecutionError<S>>>,
     pub(super) field_path: Arc<FieldPath<'a>>,
+    pub(super) extensions: Extensions<S>,
+    pub(super) error_policy: ErrorPolicy,
 }
 
 impl<'a, CtxT, S> Clone for OwnedExecutor<'a, CtxT, S>
@@ -40,6 +43,8 @@ where
             context: self.context,
             errors: RwLock::new(vec![]),
             field_path: self.field_path.clone(),
+            extensions: self.extensions.clone(),
+            error_policy: self.error_policy.clone(),
         }
     }
 }
@@ -67,6 +72,8 @@ where
             context: self.context,
             errors: RwLock::new(vec![]),
             field_path: self.field_path.clone(),
+            extensions: self.extensions.clone(),
+            error_policy: self.error_policy.clone(),
         }
     }
 
@@ -104,6 +111,8 @@ where
                 location,
                 Arc::clone(&self.field_path),
             )),
+            extensions: self.extensions.clone(),
+            error_policy: self.error_policy.clone(),
         }
     }
 
@@ -127,6 +136,9 @@ where
             context: self.context,
             errors: &self.errors,
             field_path: Arc::clone(&self.field_path),
+            extensions: &self.extensions,
+            error_policy: &self.error_policy,
+            incremental: None,
         }
     }
 }
@@ -0,0 +1,164 @@
+use crate::{
+    executor::{DirectiveContext, DirectiveResolver, ExecutionResult, Variables},
+    schema::model::{DirectiveLocation, DirectiveType, RootNode},
+    types::scalars::{EmptyMutation, EmptySubscription},
+    value::{DefaultScalarValue, Value},
+    GraphQLError,
+};
+
+struct TestType;
+
+#[crate::graphql_object]
+impl TestType {
+    fn a() -> &str {
+        "a"
+    }
+
+    fn b() -> &str {
+        "b"
+    }
+}
+
+struct Uppercase;
+
+impl DirectiveResolver for Uppercase {
+    fn after_resolve(&self, _: &DirectiveContext, result: ExecutionResult) -> ExecutionResult {
+        result.map(|v| match v.as_scalar_value::<String>() {
+            Some(s) => Value::scalar(s.to_uppercase()),
+            None => v,
+        })
+    }
+}
+
+struct Mask;
+
+impl DirectiveResolver for Mask {
+    fn before_resolve(&self, directive: &DirectiveContext) -> Option<ExecutionResult> {
+        let with = directive.arguments().get::<String>("with").unwrap();
+        Some(Ok(Value::scalar(with)))
+    }
+}
+
+type Schema = RootNode<'static, TestType, EmptyMutation<()>, EmptySubscription<()>>;
+
+fn schema() -> Schema {
+    RootNode::new(
+        TestType,
+        EmptyMutation::<()>::new(),
+        EmptySubscription::<()>::new(),
+    )
+    .with_directive(|_| {
+        DirectiveType::new("uppercase", &[DirectiveLocation::Field], &[]).resolver(Uppercase)
+    })
+    .with_directive(|registry| {
+        DirectiveType::new(
+            "mask",
+            &[DirectiveLocation::Field],
+            &[registry
+                .arg_with_default::<String>("with", &"***".to_owned(), &())
+                .description("Replacement for the value")],
+        )
+        .resolver(Mask)
+    })
+}
+
+async fn run_query(query: &str) -> Value<DefaultScalarValue> {
+    let schema = schema();
+
+    let (result, errs) = crate::execute(query, None, &schema, &Variables::new(), &())
+        .await
+        .expect("Execution failed");
+    assert_eq!(errs, []);
+
+    let (sync_result, sync_errs) =
+        crate::execute_sync(query, None, &schema, &Variables::new(), &())
+            .expect("Execution failed");
+    assert_eq!(sync_errs, []);
+    assert_eq!(result, sync_result);
+
+    result
+}
+
+#[tokio::test]
+async fn transforms_resolved_value() {
+    assert_eq!(
+        run_query("{ a @uppercase, b }").await,
+        Value::object(
+            vec![("a", Value::scalar("A")), ("b", Value::scalar("b"))]
+                .into_iter()
+                .collect(),
+        ),
+    );
+}
+
+#[tokio::test]
+async fn replaces_resolution() {
+    assert_eq!(
+        run_query(r#"{ a @mask, b @mask(with: "-") }"#).await,
+        Value::object(
+            vec![("a", Value::scalar("***")), ("b", Value::scalar("-"))]
+                .into_iter()
+                .collect(),
+        ),
+    );
+}
+
+#[tokio::test]
+async fn outer_directive_wraps_inner() {
+    assert_eq!(
+        run_query(r#"{ a @uppercase @mask(with: "x"), b @mask(with: "y") @uppercase }"#).await,
+        Value::object(
+            vec![("a", Value::scalar("X")), ("b", Value::scalar("y"))]
+                .into_iter()
+                .collect(),
+        ),
+    );
+}
+
+#[tokio::test]
+async fn combines_with_builtin_directives() {
+    assert_eq!(
+        run_query("{ a @uppercase @include(if: false), b @skip(if: false) @uppercase }").await,
+        Value::object(vec![("b", Value::scalar("B"))].into_iter().collect()),
+    );
+}
+
+#[tokio::test]
+async fn is_introspected() {
+    let result =
+        run_query(r#"{ __schema { directives { name args { name defaultValue } } } }"#).await;
+    let directives = result
+        .as_object_value()
+        .and_then(|o| o.get_field_value("__schema"))
+        .and_then(|s| s.as_object_value())
+        .and_then(|s| s.get_field_value("directives"))
+        .and_then(|d| d.as_list_value())
+        .unwrap();
+
+    assert!(directives.contains(&Value::object(
+        vec![
+            ("name", Value::scalar("mask")),
+            (
+                "args",
+                Value::list(vec![Value::object(
+                    vec![
+                        ("name", Value::scalar("with")),
+                        ("defaultValue", Value::scalar(r#""***""#)),
+                    ]
+                    .into_iter()
+                    .collect(),
+                )]),
+            ),
+        ]
+        .into_iter()
+        .collect(),
+    )));
+}
+
+#[test]
+fn unknown_directive_is_rejected() {
+    assert!(matches!(
+        crate::execute_sync("{ a @unknown }", None, &schema(), &Variables::new(), &()),
+        Err(GraphQLError::ValidationError(_)),
+    ));
+}
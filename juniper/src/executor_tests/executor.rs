@@ -828,7 +828,7 @@ mod dynamic_context_switching {
 
 mod propagates_errors_to_nullable_fields {
     use crate::{
-        executor::{ExecutionError, FieldError, FieldResult, IntoFieldError},
+        executor::{ExecutionError, FieldError, FieldResult, IntoFieldError, PathSegment},
         graphql_object,
         parser::SourcePosition,
         schema::model::RootNode,
@@ -1095,9 +1095,13 @@ mod propagates_errors_to_nullable_fields {
 
         assert_eq!(
             errs,
-            vec![ExecutionError::new(
+            vec![ExecutionError::at_path(
                 SourcePosition::new(11, 0, 11),
-                &["inners", "nonNullableErrorField"],
+                vec![
+                    PathSegment::Field("inners".into()),
+                    PathSegment::Index(0),
+                    PathSegment::Field("nonNullableErrorField".into()),
+                ],
                 FieldError::new("Error for nonNullableErrorField", Value::null()),
             )]
         );
@@ -1127,33 +1131,47 @@ mod propagates_errors_to_nullable_fields {
 
         assert_eq!(
             errs,
-            vec![
-                ExecutionError::new(
-                    SourcePosition::new(19, 0, 19),
-                    &["nullableInners", "nonNullableErrorField"],
-                    FieldError::new("Error for nonNullableErrorField", Value::null()),
-                ),
-                ExecutionError::new(
-                    SourcePosition::new(19, 0, 19),
-                    &["nullableInners", "nonNullableErrorField"],
-                    FieldError::new("Error for nonNullableErrorField", Value::null()),
-                ),
-                ExecutionError::new(
-                    SourcePosition::new(19, 0, 19),
-                    &["nullableInners", "nonNullableErrorField"],
-                    FieldError::new("Error for nonNullableErrorField", Value::null()),
-                ),
-                ExecutionError::new(
-                    SourcePosition::new(19, 0, 19),
-                    &["nullableInners", "nonNullableErrorField"],
-                    FieldError::new("Error for nonNullableErrorField", Value::null()),
-                ),
-                ExecutionError::new(
+            (0..5)
+                .map(|i| ExecutionError::at_path(
                     SourcePosition::new(19, 0, 19),
-                    &["nullableInners", "nonNullableErrorField"],
+                    vec![
+                        PathSegment::Field("nullableInners".into()),
+                        PathSegment::Index(i),
+                        PathSegment::Field("nonNullableErrorField".into()),
+                    ],
                     FieldError::new("Error for nonNullableErrorField", Value::null()),
-                ),
-            ]
+                ))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn non_null_list_of_nullable_sync() {
+        let schema = RootNode::new(
+            Schema,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        );
+        let doc = r"{ nullableInners { nonNullableErrorField } }";
+
+        let vars = vec![].into_iter().collect();
+
+        let (result, errs) =
+            crate::execute_sync(doc, None, &schema, &vars, &()).expect("Execution failed");
+
+        assert_eq!(
+            result,
+            graphql_value!({ "nullableInners": [None, None, None, None, None] })
+        );
+
+        assert_eq!(errs.len(), 5);
+        assert_eq!(
+            errs[3].path(),
+            &[
+                PathSegment::Field("nullableInners".into()),
+                PathSegment::Index(3),
+                PathSegment::Field("nonNullableErrorField".into()),
+            ],
         );
     }
 }
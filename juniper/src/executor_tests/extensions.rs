@@ -0,0 +1,237 @@
+use std::sync::{Arc, Mutex};
+
+use crate::{
+    ast::OperationType,
+    executor::{ApolloTracing, Extension, Extensions, PathSegment, ResolvedField, Variables},
+    http::GraphQLRequest,
+    schema::model::RootNode,
+    types::scalars::{EmptyMutation, EmptySubscription},
+    validation::RuleError,
+    value::{DefaultScalarValue, Value},
+};
+
+struct Inner;
+
+#[crate::graphql_object]
+impl Inner {
+    fn c() -> &str {
+        "c"
+    }
+}
+
+struct TestType;
+
+#[crate::graphql_object]
+impl TestType {
+    fn a() -> &str {
+        "a"
+    }
+
+    fn b() -> Inner {
+        Inner
+    }
+
+    fn bs() -> Vec<Inner> {
+        vec![Inner]
+    }
+}
+
+type Schema = RootNode<'static, TestType, EmptyMutation<()>, EmptySubscription<()>>;
+
+fn schema() -> Schema {
+    RootNode::new(
+        TestType,
+        EmptyMutation::<()>::new(),
+        EmptySubscription::<()>::new(),
+    )
+}
+
+#[derive(Default)]
+struct Recorder(Mutex<Vec<String>>);
+
+impl Recorder {
+    fn record(&self, event: String) {
+        self.0.lock().unwrap().push(event);
+    }
+}
+
+impl Extension for Arc<Recorder> {
+    fn parse_start(&self, _: &str) {
+        self.record("parse_start".into());
+    }
+
+    fn parse_end(&self) {
+        self.record("parse_end".into());
+    }
+
+    fn validation_start(&self) {
+        self.record("validation_start".into());
+    }
+
+    fn validation_end(&self, errors: &[RuleError]) {
+        self.record(format!("validation_end {}", errors.len()));
+    }
+
+    fn execution_start(&self, operation_name: Option<&str>, operation_type: &OperationType) {
+        self.record(format!(
+            "execution_start {:?} {:?}",
+            operation_name, operation_type,
+        ));
+    }
+
+    fn execution_end(&self) {
+        self.record("execution_end".into());
+    }
+
+    fn field_resolved(&self, field: &ResolvedField) {
+        self.record(format!(
+            "field {} {}.{}: {}",
+            field
+                .path()
+                .iter()
+                .map(|segment| match segment {
+                    PathSegment::Field(name) => name.clone(),
+                    PathSegment::Index(index) => index.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join("."),
+            field.parent_type(),
+            field.field_name(),
+            field.return_type(),
+        ));
+    }
+
+    fn result(&self) -> Option<(String, Value)> {
+        let count = self.0.lock().unwrap().len() as i32;
+        Some(("events".into(), Value::scalar(count)))
+    }
+}
+
+fn events(recorder: &Recorder) -> Vec<String> {
+    recorder.0.lock().unwrap().clone()
+}
+
+#[tokio::test]
+async fn notifies_extensions() {
+    let schema = schema();
+    let query = "query Q { a b { c } bs { c } }";
+    let expected = vec![
+        "parse_start",
+        "parse_end",
+        "validation_start",
+        "validation_end 0",
+        "execution_start Some(\"Q\") Query",
+        "field a TestType.a: String!",
+        "field b.c Inner.c: String!",
+        "field b TestType.b: Inner!",
+        "field bs.0.c Inner.c: String!",
+        "field bs TestType.bs: [Inner!]!",
+        "execution_end",
+    ];
+
+    let recorder = Arc::new(Recorder::default());
+    let extensions = Extensions::new().with(recorder.clone());
+    crate::execute_with_extensions(query, None, &schema, &Variables::new(), &(), &extensions)
+        .await
+        .expect("Execution failed");
+    assert_eq!(events(&recorder), expected);
+
+    let recorder = Arc::new(Recorder::default());
+    let extensions = Extensions::new().with(recorder.clone());
+    crate::execute_sync_with_extensions(query, None, &schema, &Variables::new(), &(), &extensions)
+        .expect("Execution failed");
+    assert_eq!(events(&recorder), expected);
+}
+
+#[test]
+fn reports_validation_errors() {
+    let schema = schema();
+    let recorder = Arc::new(Recorder::default());
+    let extensions = Extensions::new().with(recorder.clone());
+
+    assert!(crate::execute_sync_with_extensions(
+        "{ unknown }",
+        None,
+        &schema,
+        &Variables::new(),
+        &(),
+        &extensions,
+    )
+    .is_err());
+    assert_eq!(
+        events(&recorder),
+        vec![
+            "parse_start",
+            "parse_end",
+            "validation_start",
+            "validation_end 1",
+        ],
+    );
+}
+
+#[test]
+fn adds_results_to_response() {
+    let recorder = Arc::new(Recorder::default());
+    let schema = schema().with_extension({
+        let recorder = recorder.clone();
+        move || recorder.clone()
+    });
+
+    let request = GraphQLRequest::<DefaultScalarValue>::new("{ a }".into(), None, None);
+    let response = request.execute_sync(&schema, &());
+
+    assert_eq!(
+        response.extensions().get_field_value("events"),
+        Some(&Value::scalar(7)),
+    );
+    assert_eq!(
+        serde_json::to_value(&response).unwrap(),
+        serde_json::json!({"data": {"a": "a"}, "extensions": {"events": 7}}),
+    );
+}
+
+#[test]
+fn traces_requests() {
+    let schema = schema().with_extension(ApolloTracing::new);
+
+    let request =
+        GraphQLRequest::<DefaultScalarValue>::new("{ a b { c } bs { c } }".into(), None, None);
+    let response = request.execute_sync(&schema, &());
+    let json = serde_json::to_value(&response).unwrap();
+    let tracing = &json["extensions"]["tracing"];
+
+    assert_eq!(tracing["version"], 1);
+    assert!(tracing["startTime"].as_str().unwrap().ends_with('Z'));
+    assert!(tracing["endTime"].as_str().unwrap() >= tracing["startTime"].as_str().unwrap());
+    for phase in &["parsing", "validation"] {
+        assert!(tracing[phase]["startOffset"].is_u64());
+        assert!(tracing[phase]["duration"].is_u64());
+    }
+
+    let mut resolvers = tracing["execution"]["resolvers"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|resolver| {
+            assert!(resolver["startOffset"].is_u64());
+            assert!(resolver["duration"].is_u64());
+            serde_json::json!([
+                resolver["path"],
+                resolver["parentType"],
+                resolver["fieldName"],
+                resolver["returnType"],
+            ])
+        })
+        .collect::<Vec<_>>();
+    resolvers.sort_by_key(|resolver| resolver.to_string());
+    assert_eq!(
+        resolvers,
+        vec![
+            serde_json::json!([["a"], "TestType", "a", "String!"]),
+            serde_json::json!([["b", "c"], "Inner", "c", "String!"]),
+            serde_json::json!([["b"], "TestType", "b", "Inner!"]),
+            serde_json::json!([["bs", 0, "c"], "Inner", "c", "String!"]),
+            serde_json::json!([["bs"], "TestType", "bs", "[Inner!]!"]),
+        ],
+    );
+}
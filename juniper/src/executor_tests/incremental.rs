@@ -0,0 +1,209 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use futures::StreamExt as _;
+
+use crate::{
+    executor::{IncrementalData, IncrementalPayload, PathSegment, Variables},
+    schema::model::RootNode,
+    types::scalars::{EmptyMutation, EmptySubscription},
+    value::{DefaultScalarValue, Value},
+    Extensions,
+};
+
+static RESOLVED_PARENTS: AtomicUsize = AtomicUsize::new(0);
+
+struct TestType;
+
+struct Parent;
+
+#[crate::graphql_object]
+impl Parent {
+    fn a() -> &str {
+        "a"
+    }
+
+    fn b() -> &str {
+        "b"
+    }
+}
+
+#[crate::graphql_object]
+impl TestType {
+    fn a() -> &str {
+        "a"
+    }
+
+    fn b() -> &str {
+        "b"
+    }
+
+    fn letters() -> Vec<&str> {
+        vec!["a", "b", "c"]
+    }
+
+    fn parent() -> Parent {
+        RESOLVED_PARENTS.fetch_add(1, Ordering::SeqCst);
+        Parent
+    }
+
+    fn parents() -> Vec<Parent> {
+        vec![Parent, Parent]
+    }
+}
+
+async fn run_query(
+    query: &str,
+) -> (
+    Value<DefaultScalarValue>,
+    Vec<IncrementalPayload<DefaultScalarValue>>,
+) {
+    let schema = RootNode::new(
+        TestType,
+        EmptyMutation::<()>::new(),
+        EmptySubscription::<()>::new(),
+    )
+    .with_incremental_delivery();
+    let vars = Variables::new();
+    let extensions = Extensions::new();
+
+    let (result, errs, subsequent) =
+        crate::execute_incremental(query, None, &schema, &vars, &(), &extensions)
+            .await
+            .expect("Execution failed");
+
+    assert_eq!(errs, []);
+
+    let payloads = match subsequent {
+        Some(subsequent) => subsequent.collect().await,
+        None => vec![],
+    };
+    (result, payloads)
+}
+
+#[tokio::test]
+async fn defers_fragment() {
+    let (result, payloads) = run_query("{ a ... @defer(label: \"b\") { b } }").await;
+
+    assert_eq!(result, graphql_value!({ "a": "a" }));
+    assert_eq!(payloads.len(), 1);
+    assert_eq!(payloads[0].label(), Some("b"));
+    assert_eq!(payloads[0].path(), &[]);
+    assert_eq!(
+        payloads[0].data(),
+        &IncrementalData::Data(graphql_value!({ "b": "b" })),
+    );
+    assert!(!payloads[0].has_next());
+}
+
+#[tokio::test]
+async fn ignores_disabled_defer() {
+    let (result, payloads) = run_query("{ a ... @defer(if: false) { b } }").await;
+
+    assert_eq!(result, graphql_value!({ "a": "a", "b": "b" }));
+    assert_eq!(payloads, []);
+}
+
+#[tokio::test]
+async fn streams_list() {
+    let (result, payloads) = run_query("{ letters @stream(initialCount: 1) }").await;
+
+    assert_eq!(result, graphql_value!({ "letters": ["a"] }));
+    assert_eq!(payloads.len(), 1);
+    assert_eq!(
+        payloads[0].path(),
+        &[PathSegment::Field("letters".into()), PathSegment::Index(1)],
+    );
+    assert_eq!(
+        payloads[0].data(),
+        &IncrementalData::Items(graphql_value!(["b", "c"])),
+    );
+    assert!(!payloads[0].has_next());
+}
+
+#[tokio::test]
+async fn resolves_deferred_fragments_from_parent_values() {
+    let (result, payloads) = run_query("{ parent { a ... @defer { b } } }").await;
+
+    assert_eq!(result, graphql_value!({ "parent": { "a": "a" } }));
+    assert_eq!(payloads.len(), 1);
+    assert_eq!(payloads[0].path(), &[PathSegment::Field("parent".into())]);
+    assert_eq!(
+        payloads[0].data(),
+        &IncrementalData::Data(graphql_value!({ "b": "b" })),
+    );
+    assert_eq!(RESOLVED_PARENTS.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn delivers_nested_fragments_after_enclosing_ones() {
+    let (result, payloads) = run_query(
+        "{ a ... @defer(label: \"outer\") { b ... @defer(label: \"inner\") { letters } } }",
+    )
+    .await;
+
+    assert_eq!(result, graphql_value!({ "a": "a" }));
+    assert_eq!(payloads.len(), 2);
+    assert_eq!(payloads[0].label(), Some("outer"));
+    assert_eq!(
+        payloads[0].data(),
+        &IncrementalData::Data(graphql_value!({ "b": "b" })),
+    );
+    assert!(payloads[0].has_next());
+    assert_eq!(payloads[1].label(), Some("inner"));
+    assert_eq!(
+        payloads[1].data(),
+        &IncrementalData::Data(graphql_value!({ "letters": ["a", "b", "c"] })),
+    );
+    assert!(!payloads[1].has_next());
+}
+
+#[tokio::test]
+async fn defers_fragments_of_streamed_items() {
+    let (result, mut payloads) =
+        run_query("{ parents @stream(initialCount: 1) { a ... @defer { b } } }").await;
+
+    assert_eq!(result, graphql_value!({ "parents": [{ "a": "a" }] }));
+    assert_eq!(payloads.len(), 3);
+    assert!(!payloads.last().unwrap().has_next());
+
+    let streamed = payloads
+        .iter()
+        .position(|p| matches!(p.data(), IncrementalData::Items(_)))
+        .expect("No streamed items");
+    let deferred_of_streamed = payloads
+        .iter()
+        .position(|p| {
+            matches!(p.data(), IncrementalData::Data(_))
+                && p.path().last() == Some(&PathSegment::Index(1))
+        })
+        .expect("No fragment deferred in the streamed item");
+    assert!(deferred_of_streamed > streamed);
+    let streamed = payloads.remove(streamed);
+    assert_eq!(
+        streamed.path(),
+        &[PathSegment::Field("parents".into()), PathSegment::Index(1)],
+    );
+    assert_eq!(
+        streamed.data(),
+        &IncrementalData::Items(graphql_value!([{ "a": "a" }])),
+    );
+
+    let mut paths = payloads
+        .iter()
+        .map(|p| p.path().to_vec())
+        .collect::<Vec<_>>();
+    paths.sort();
+    assert_eq!(
+        paths,
+        [0, 1]
+            .iter()
+            .map(|&i| vec![PathSegment::Field("parents".into()), PathSegment::Index(i)])
+            .collect::<Vec<_>>(),
+    );
+    for payload in &payloads {
+        assert_eq!(
+            payload.data(),
+            &IncrementalData::Data(graphql_value!({ "b": "b" })),
+        );
+    }
+}
@@ -278,7 +278,7 @@ async fn interface_introspection() {
     );
     assert_eq!(
         type_info.get_field_value("interfaces"),
-        Some(&Value::null())
+        Some(&Value::list(vec![]))
     );
     assert_eq!(
         type_info.get_field_value("enumValues"),
@@ -1,6 +1,9 @@
+mod custom_directives;
 mod directives;
 mod enums;
 mod executor;
+mod extensions;
+mod incremental;
 mod introspection;
 mod variables;
 
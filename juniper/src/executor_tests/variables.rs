@@ -61,6 +61,12 @@ struct InputWithDefaults {
     a: i32,
 }
 
+#[derive(GraphQLInputObject, Debug)]
+enum TestOneOfInputObject {
+    A(String),
+    B(i32),
+}
+
 struct TestType;
 
 #[graphql_object]
@@ -92,6 +98,10 @@ impl TestType {
         format!("{:?}", input)
     }
 
+    fn field_with_one_of_input(input: TestOneOfInputObject) -> String {
+        format!("{:?}", input)
+    }
+
     fn list(input: Option<Vec<Option<String>>>) -> String {
         format!("{:?}", input)
     }
@@ -385,6 +395,91 @@ async fn variable_error_on_omit_non_null() {
     );
 }
 
+#[tokio::test]
+async fn one_of_input_object_with_one_field() {
+    run_variable_query(
+        r#"query q($input: TestOneOfInputObject!) { fieldWithOneOfInput(input: $input) }"#,
+        vec![(
+            "input".to_owned(),
+            InputValue::object(vec![("b", InputValue::scalar(1))].into_iter().collect()),
+        )]
+        .into_iter()
+        .collect(),
+        |result: &Object<DefaultScalarValue>| {
+            assert_eq!(
+                result.get_field_value("fieldWithOneOfInput"),
+                Some(&Value::scalar("B(1)"))
+            );
+        },
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn variable_error_on_one_of_input_object_with_two_fields() {
+    let schema = RootNode::new(
+        TestType,
+        EmptyMutation::<()>::new(),
+        EmptySubscription::<()>::new(),
+    );
+
+    let query = r#"query q($input: TestOneOfInputObject!) { fieldWithOneOfInput(input: $input) }"#;
+    let vars = vec![(
+        "input".to_owned(),
+        InputValue::object(
+            vec![
+                ("a", InputValue::scalar("foo")),
+                ("b", InputValue::scalar(1)),
+            ]
+            .into_iter()
+            .collect(),
+        ),
+    )]
+    .into_iter()
+    .collect();
+
+    let error = crate::execute(query, None, &schema, &vars, &())
+        .await
+        .unwrap_err();
+
+    assert_eq!(
+        error,
+        ValidationError(vec![RuleError::new(
+            r#"Variable "$input" got invalid value. Exactly one field of OneOf input object "TestOneOfInputObject" must be given."#,
+            &[SourcePosition::new(8, 0, 8)],
+        )])
+    );
+}
+
+#[tokio::test]
+async fn variable_error_on_one_of_input_object_with_null_field() {
+    let schema = RootNode::new(
+        TestType,
+        EmptyMutation::<()>::new(),
+        EmptySubscription::<()>::new(),
+    );
+
+    let query = r#"query q($input: TestOneOfInputObject!) { fieldWithOneOfInput(input: $input) }"#;
+    let vars = vec![(
+        "input".to_owned(),
+        InputValue::object(vec![("a", InputValue::null())].into_iter().collect()),
+    )]
+    .into_iter()
+    .collect();
+
+    let error = crate::execute(query, None, &schema, &vars, &())
+        .await
+        .unwrap_err();
+
+    assert_eq!(
+        error,
+        ValidationError(vec![RuleError::new(
+            r#"Variable "$input" got invalid value. In field "a": Field of OneOf input object "TestOneOfInputObject" must not be null."#,
+            &[SourcePosition::new(8, 0, 8)],
+        )])
+    );
+}
+
 #[tokio::test]
 async fn variable_multiple_errors_with_nesting() {
     let schema = RootNode::new(
@@ -1223,7 +1318,7 @@ mod integers {
         assert_eq!(
             error,
             ValidationError(vec![RuleError::new(
-                r#"Variable "$var" got invalid value. Expected "Int"."#,
+                r#"Variable "$var" got invalid value. Expected "Int": Int cannot represent non-integer value: 10."#,
                 &[SourcePosition::new(8, 0, 8)],
             )])
         );
@@ -1249,7 +1344,7 @@ mod integers {
         assert_eq!(
             error,
             ValidationError(vec![RuleError::new(
-                r#"Variable "$var" got invalid value. Expected "Int"."#,
+                r#"Variable "$var" got invalid value. Expected "Int": Int cannot represent non-integer value: "10"."#,
                 &[SourcePosition::new(8, 0, 8)],
             )])
         );
@@ -1313,7 +1408,7 @@ mod floats {
         assert_eq!(
             error,
             ValidationError(vec![RuleError::new(
-                r#"Variable "$var" got invalid value. Expected "Float"."#,
+                r#"Variable "$var" got invalid value. Expected "Float": Float cannot represent non numeric value: "10"."#,
                 &[SourcePosition::new(8, 0, 8)],
             )])
         );
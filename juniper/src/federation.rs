@@ -0,0 +1,911 @@
+//! Support for [Apollo Federation][1] subgraphs.
+//!
+//! Calling [`RootNode::federated`] adds the `_service` field to the query type, so that a gateway
+//! can fetch the schema of the subgraph. Each type registered with [`RootNode::with_entity`] is
+//! added to the `_Entity` union, and can be resolved by the gateway through the `_entities` field.
+//!
+//! The federation directives are applied to types and fields with the codegen attributes, e.g.
+//!
+//! ```rust
+//! # use juniper::{federation::{GraphQLEntity, Representation}, FieldResult, GraphQLObject};
+//! #[derive(GraphQLObject)]
+//! #[graphql(key = "id")]
+//! struct User {
+//!     id: String,
+//!     name: String,
+//! }
+//!
+//! impl GraphQLEntity for User {
+//!     fn resolve_reference(representation: &Representation, _: &()) -> FieldResult<Option<Self>> {
+//!         Ok(representation.get::<String>("id").map(|id| User {
+//!             name: format!("User {}", id),
+//!             id,
+//!         }))
+//!     }
+//! }
+//! ```
+//!
+//! [1]: https://www.apollographql.com/docs/federation/federation-spec/
+
+use std::{borrow::Cow, fmt};
+
+use fnv::FnvHashMap;
+use futures::future;
+use graphql_parser::schema::{Definition, Document, TypeDefinition};
+
+use crate::{
+    ast::{FromInputValue, InputValue, Selection, ToInputValue, Type},
+    executor::{ExecutionResult, Executor, FieldError, FieldResult, Registry},
+    parser::{ParseError, ScalarToken, Token},
+    schema::{
+        meta::{Complexity, DeprecationStatus, Field, MetaType, ObjectMeta, UnionMeta},
+        model::{DirectiveLocation, DirectiveType, RootNode, SchemaType},
+        translate::{graphql_parser::GraphQLParserTranslator, SchemaTranslator},
+        visibility::HiddenItems,
+    },
+    types::{
+        async_await::GraphQLValueAsync,
+        base::{Arguments, GraphQLType, GraphQLValue},
+        marker::IsInputType,
+    },
+    value::{DefaultScalarValue, Object, ParseScalarResult, ParseScalarValue, ScalarValue, Value},
+    BoxFuture,
+};
+
+/// An object type which can be resolved by the gateway from its representation
+///
+/// The type is usually annotated with one or more `@key` directives, which select the fields that
+/// make up its representation.
+pub trait GraphQLEntity<S = DefaultScalarValue>: GraphQLType<S, TypeInfo = ()> + Sized
+where
+    S: ScalarValue,
+{
+    /// Resolves the entity referenced by `representation`, returning `None` if it doesn't exist.
+    fn resolve_reference(
+        representation: &Representation<S>,
+        context: &Self::Context,
+    ) -> FieldResult<Option<Self>, S>;
+}
+
+/// The representation of an entity, as sent by the gateway to the `_entities` field
+///
+/// This is the `_Any` scalar of the federation spec: an object containing the `__typename` of the
+/// entity along with its key fields.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Representation<S = DefaultScalarValue> {
+    typename: String,
+    value: InputValue<S>,
+}
+
+impl<S> Representation<S>
+where
+    S: ScalarValue,
+{
+    /// Name of the entity type.
+    pub fn typename(&self) -> &str {
+        &self.typename
+    }
+
+    /// Gets a field of the representation, converted to the given type.
+    ///
+    /// Returns `None` if the field is missing, or can't be converted.
+    pub fn get<T>(&self, field: &str) -> Option<T>
+    where
+        T: FromInputValue<S>,
+    {
+        self.value
+            .to_object_value()
+            .and_then(|o| o.get(field).copied())
+            .and_then(T::from_input_value)
+    }
+
+    /// The whole representation, including the `__typename` field.
+    pub fn value(&self) -> &InputValue<S> {
+        &self.value
+    }
+}
+
+impl<S> GraphQLType<S> for Representation<S>
+where
+    S: ScalarValue,
+{
+    fn name(_: &()) -> Option<&'static str> {
+        Some("_Any")
+    }
+
+    fn meta<'r>(info: &(), registry: &mut Registry<'r, S>) -> MetaType<'r, S>
+    where
+        S: 'r,
+    {
+        registry.build_scalar_type::<Self>(info).into_meta()
+    }
+}
+
+impl<S> GraphQLValue<S> for Representation<S>
+where
+    S: ScalarValue,
+{
+    type Context = ();
+    type TypeInfo = ();
+
+    fn type_name<'i>(&self, info: &'i ()) -> Option<&'i str> {
+        <Self as GraphQLType<S>>::name(info)
+    }
+
+    fn resolve(
+        &self,
+        _: &(),
+        _: Option<&[Selection<S>]>,
+        _: &Executor<Self::Context, S>,
+    ) -> ExecutionResult<S> {
+        Ok(input_to_output(&self.value))
+    }
+}
+
+impl<S> GraphQLValueAsync<S> for Representation<S> where S: ScalarValue + Send + Sync {}
+
+impl<S> FromInputValue<S> for Representation<S>
+where
+    S: ScalarValue,
+{
+    fn from_input_value(v: &InputValue<S>) -> Option<Self> {
+        let typename = v
+            .to_object_value()?
+            .get("__typename")?
+            .as_string_value()?
+            .to_owned();
+        Some(Representation {
+            typename,
+            value: v.clone(),
+        })
+    }
+}
+
+impl<S> ToInputValue<S> for Representation<S>
+where
+    S: Clone,
+{
+    fn to_input_value(&self) -> InputValue<S> {
+        self.value.clone()
+    }
+}
+
+impl<S> ParseScalarValue<S> for Representation<S>
+where
+    S: ScalarValue,
+{
+    fn from_str(value: ScalarToken<'_>) -> ParseScalarResult<'_, S> {
+        Err(ParseError::UnexpectedToken(Token::Scalar(value)))
+    }
+}
+
+impl<S> IsInputType<S> for Representation<S> where S: ScalarValue {}
+
+fn input_to_output<S: ScalarValue>(input: &InputValue<S>) -> Value<S> {
+    match input {
+        InputValue::Null | InputValue::Variable(_) => Value::null(),
+        InputValue::Scalar(s) => Value::Scalar(s.clone()),
+        InputValue::Enum(e) => Value::scalar(e.clone()),
+        InputValue::List(l) => Value::list(l.iter().map(|v| input_to_output(&v.item)).collect()),
+        InputValue::Object(o) => Value::object(
+            o.iter()
+                .map(|(k, v)| (k.item.as_str(), input_to_output(&v.item)))
+                .collect::<Object<S>>(),
+        ),
+    }
+}
+
+/// The `_FieldSet` scalar, used as the argument of federation directives
+struct FieldSet(String);
+
+#[crate::graphql_scalar(name = "_FieldSet")]
+impl<S> GraphQLScalar for FieldSet
+where
+    S: ScalarValue,
+{
+    fn resolve(&self) -> Value {
+        Value::scalar(self.0.clone())
+    }
+
+    fn from_input_value(v: &InputValue) -> Option<FieldSet> {
+        v.as_string_value().map(|s| FieldSet(s.to_owned()))
+    }
+
+    fn from_str<'a>(value: ScalarToken<'a>) -> ParseScalarResult<'a, S> {
+        <String as ParseScalarValue<S>>::from_str(value)
+    }
+}
+
+/// The `_Service` type, exposing the schema of the subgraph to the gateway
+#[derive(crate::GraphQLObject)]
+#[graphql(name = "_Service", internal)]
+struct Service {
+    sdl: String,
+}
+
+/// The `_Entity` union, resolving to the concrete type of an entity
+struct Entity<T>(T);
+
+impl<T, S> GraphQLValue<S> for Entity<T>
+where
+    T: GraphQLValue<S, TypeInfo = ()>,
+    S: ScalarValue,
+{
+    type Context = T::Context;
+    type TypeInfo = ();
+
+    fn type_name<'i>(&self, _: &'i ()) -> Option<&'i str> {
+        Some("_Entity")
+    }
+
+    fn concrete_type_name(&self, context: &Self::Context, info: &()) -> String {
+        self.0.concrete_type_name(context, info)
+    }
+
+    fn resolve_into_type(
+        &self,
+        info: &(),
+        type_name: &str,
+        _: Option<&[Selection<S>]>,
+        executor: &Executor<Self::Context, S>,
+    ) -> ExecutionResult<S> {
+        if self.0.type_name(info) == Some(type_name) {
+            executor.resolve(info, &self.0)
+        } else {
+            Ok(Value::null())
+        }
+    }
+}
+
+impl<T, S> GraphQLValueAsync<S> for Entity<T>
+where
+    T: GraphQLValueAsync<S, TypeInfo = ()>,
+    T::Context: Sync,
+    S: ScalarValue + Send + Sync,
+{
+    fn resolve_into_type_async<'a>(
+        &'a self,
+        info: &'a (),
+        type_name: &str,
+        _: Option<&'a [Selection<'a, S>]>,
+        executor: &'a Executor<'a, 'a, Self::Context, S>,
+    ) -> BoxFuture<'a, ExecutionResult<S>> {
+        if self.0.type_name(info) == Some(type_name) {
+            Box::pin(executor.resolve_async(info, &self.0))
+        } else {
+            Box::pin(future::ready(Ok(Value::null())))
+        }
+    }
+}
+
+type ResolveEntityFn<CtxT, S> = fn(&Representation<S>, &Executor<CtxT, S>) -> ExecutionResult<S>;
+
+type ResolveEntityAsyncFn<CtxT, S> = for<'b> fn(
+    &'b Representation<S>,
+    &'b Executor<'b, 'b, CtxT, S>,
+) -> BoxFuture<'b, ExecutionResult<S>>;
+
+type EntityResolvers<CtxT, S> = (ResolveEntityFn<CtxT, S>, ResolveEntityAsyncFn<CtxT, S>);
+
+fn resolve_entity<T, S>(
+    representation: &Representation<S>,
+    executor: &Executor<T::Context, S>,
+) -> ExecutionResult<S>
+where
+    T: GraphQLEntity<S>,
+    S: ScalarValue,
+{
+    match T::resolve_reference(representation, executor.context())? {
+        Some(entity) => executor.resolve(&(), &Entity(entity)),
+        None => Ok(Value::null()),
+    }
+}
+
+fn resolve_entity_async<'b, T, S>(
+    representation: &'b Representation<S>,
+    executor: &'b Executor<'b, 'b, T::Context, S>,
+) -> BoxFuture<'b, ExecutionResult<S>>
+where
+    T: GraphQLEntity<S> + GraphQLValueAsync<S> + Send,
+    T::Context: Sync,
+    S: ScalarValue + Send + Sync,
+{
+    Box::pin(async move {
+        match T::resolve_reference(representation, executor.context())? {
+            Some(entity) => executor.resolve_async(&(), &Entity(entity)).await,
+            None => Ok(Value::null()),
+        }
+    })
+}
+
+/// The entities of a federated [`RootNode`], by type name.
+#[doc(hidden)]
+pub struct Federation<CtxT, S> {
+    entities: FnvHashMap<String, EntityResolvers<CtxT, S>>,
+}
+
+impl<CtxT, S> fmt::Debug for Federation<CtxT, S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Federation")
+            .field("entities", &self.entities.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl<CtxT, S> Federation<CtxT, S>
+where
+    S: ScalarValue,
+{
+    /// Resolves the `_service` and `_entities` fields of the query type.
+    ///
+    /// The items `hidden` from the request are left out of the SDL of the `_service` field.
+    pub(crate) fn resolve_field(
+        &self,
+        schema: &SchemaType<S>,
+        hidden: Option<&HiddenItems>,
+        field_name: &str,
+        arguments: &Arguments<S>,
+        executor: &Executor<CtxT, S>,
+    ) -> ExecutionResult<S> {
+        if field_name == "_service" {
+            let sdl = match hidden {
+                Some(hidden) => subgraph_sdl(&hidden.strip(schema)),
+                None => subgraph_sdl(schema),
+            };
+            return executor
+                .replaced_context(&())
+                .resolve(&(), &Service { sdl });
+        }
+
+        let representations = arguments
+            .get::<Vec<Representation<S>>>("representations")
+            .unwrap_or_default();
+
+        Ok(Value::list(
+            representations
+                .iter()
+                .map(|representation| {
+                    self.entity(representation)
+                        .and_then(|(resolve, _)| resolve(representation, executor))
+                        .unwrap_or_else(|e| {
+                            executor.push_error(e);
+                            Value::null()
+                        })
+                })
+                .collect(),
+        ))
+    }
+
+    /// Resolves the `_service` and `_entities` fields of the query type asynchronously.
+    pub(crate) fn resolve_field_async<'b>(
+        &'b self,
+        schema: &'b SchemaType<S>,
+        hidden: Option<&HiddenItems>,
+        field_name: &'b str,
+        arguments: &'b Arguments<S>,
+        executor: &'b Executor<'b, 'b, CtxT, S>,
+    ) -> BoxFuture<'b, ExecutionResult<S>>
+    where
+        CtxT: Sync,
+        S: Send + Sync,
+    {
+        if field_name == "_service" {
+            let v = self.resolve_field(schema, hidden, field_name, arguments, executor);
+            return Box::pin(future::ready(v));
+        }
+
+        Box::pin(async move {
+            let representations = arguments
+                .get::<Vec<Representation<S>>>("representations")
+                .unwrap_or_default();
+
+            let values =
+                future::join_all(representations.iter().map(|representation| async move {
+                    let result = match self.entity(representation) {
+                        Ok((_, resolve_async)) => resolve_async(representation, executor).await,
+                        Err(e) => Err(e),
+                    };
+                    result.unwrap_or_else(|e| {
+                        executor.push_error(e);
+                        Value::null()
+                    })
+                }))
+                .await;
+
+            Ok(Value::list(values))
+        })
+    }
+
+    fn entity(
+        &self,
+        representation: &Representation<S>,
+    ) -> FieldResult<EntityResolvers<CtxT, S>, S> {
+        self.entities
+            .get(representation.typename())
+            .copied()
+            .ok_or_else(|| {
+                FieldError::from(format!(
+                    r#"Type "{}" is not an entity"#,
+                    representation.typename(),
+                ))
+            })
+    }
+}
+
+impl<'a, QueryT, MutationT, SubscriptionT, S> RootNode<'a, QueryT, MutationT, SubscriptionT, S>
+where
+    QueryT: GraphQLType<S>,
+    MutationT: GraphQLType<S>,
+    SubscriptionT: GraphQLType<S>,
+    S: ScalarValue + 'a,
+{
+    /// Turns the schema into a federation subgraph.
+    ///
+    /// This adds the `_service` field to the query type, along with the definitions of the
+    /// federation directives and scalars.
+    pub fn federated(mut self) -> Self {
+        if self.federation.is_some() {
+            return self;
+        }
+
+        let schema = &mut self.schema;
+        let service = {
+            let mut registry = Registry::new(std::mem::take(&mut schema.types));
+            registry.get_type::<Representation<S>>(&());
+            let service = registry.field::<Service>("_service", &());
+            schema.types = registry.types;
+            service
+        };
+        schema.query_fields_mut().push(service);
+
+        let fields_arg = |registry: &mut Registry<'a, S>| registry.arg::<FieldSet>("fields", &());
+        let type_locations = [DirectiveLocation::Object, DirectiveLocation::Interface];
+        schema.register_directive(|registry| {
+            DirectiveType::new("key", &type_locations, &[fields_arg(registry)])
+        });
+        schema.register_directive(|_| DirectiveType::new("extends", &type_locations, &[]));
+        schema.register_directive(|_| {
+            DirectiveType::new("external", &[DirectiveLocation::FieldDefinition], &[])
+        });
+        for name in &["requires", "provides"] {
+            schema.register_directive(|registry| {
+                DirectiveType::new(
+                    name,
+                    &[DirectiveLocation::FieldDefinition],
+                    &[fields_arg(registry)],
+                )
+            });
+        }
+
+        self.federation = Some(Federation {
+            entities: FnvHashMap::default(),
+        });
+        self
+    }
+
+    /// Registers an entity type, which can then be resolved through the `_entities` field.
+    ///
+    /// The schema is turned into a federation subgraph if it isn't one already.
+    pub fn with_entity<T>(self) -> Self
+    where
+        T: GraphQLEntity<S, Context = QueryT::Context> + GraphQLValueAsync<S> + Send,
+        QueryT::Context: Sync,
+        S: Send + Sync,
+    {
+        let mut root = self.federated();
+        let schema = &mut root.schema;
+
+        let mut registry = Registry::new(std::mem::take(&mut schema.types));
+        let name = registry.get_type::<T>(&()).innermost_name().to_owned();
+        let representations = registry.arg::<Vec<Representation<S>>>("representations", &());
+        schema.types = registry.types;
+
+        let mut entity_types = match schema.concrete_type_by_name("_Entity") {
+            Some(MetaType::Union(union)) => union.of_type_names.clone(),
+            _ => {
+                schema.query_fields_mut().push(Field {
+                    name: "_entities".to_owned(),
+                    description: None,
+                    arguments: Some(vec![representations]),
+                    field_type: Type::NonNullList(Box::new(Type::Named("_Entity".into()))),
+                    deprecation_status: DeprecationStatus::Current,
+                    directives: vec![],
+                    visibility: None,
+                    complexity: Complexity::default(),
+                });
+                vec![]
+            }
+        };
+        if !entity_types.contains(&name) {
+            entity_types.push(name.clone());
+        }
+        let entity_types = entity_types
+            .into_iter()
+            .map(|t| Type::Named(t.into()))
+            .collect::<Vec<_>>();
+        schema.types.insert(
+            "_Entity".parse().unwrap(),
+            MetaType::Union(UnionMeta::new(Cow::Borrowed("_Entity"), &entity_types)),
+        );
+
+        root.federation
+            .as_mut()
+            .unwrap()
+            .entities
+            .insert(name, (resolve_entity::<T, S>, resolve_entity_async::<T, S>));
+        root
+    }
+}
+
+impl<'a, S> SchemaType<'a, S> {
+    fn query_fields_mut(&mut self) -> &mut Vec<Field<'a, S>> {
+        match self.types.get_mut(self.query_type_name.as_str()) {
+            Some(MetaType::Object(ObjectMeta { fields, .. })) => fields,
+            _ => panic!("Query type is not an object"),
+        }
+    }
+}
+
+const FEDERATION_TYPES: &[&str] = &["_Any", "_Entity", "_FieldSet", "_Service"];
+
+const FEDERATION_DIRECTIVES: &[&str] = &["extends", "external", "key", "provides", "requires"];
+
+/// Prints the schema as seen by the gateway, without the types and fields added for federation.
+fn subgraph_sdl<S>(schema: &SchemaType<S>) -> String
+where
+    S: ScalarValue,
+{
+    let mut doc: Document<&str> = GraphQLParserTranslator::translate_schema(schema);
+    doc.definitions.retain(|definition| match definition {
+        Definition::TypeDefinition(TypeDefinition::Scalar(t)) => {
+            !FEDERATION_TYPES.contains(&t.name)
+        }
+        Definition::TypeDefinition(TypeDefinition::Union(t)) => !FEDERATION_TYPES.contains(&t.name),
+        Definition::TypeDefinition(TypeDefinition::Object(t)) => {
+            !FEDERATION_TYPES.contains(&t.name)
+        }
+        Definition::DirectiveDefinition(d) => !FEDERATION_DIRECTIVES.contains(&d.name),
+        _ => true,
+    });
+    for definition in &mut doc.definitions {
+        if let Definition::TypeDefinition(TypeDefinition::Object(t)) = definition {
+            if t.name == schema.query_type_name {
+                t.fields
+                    .retain(|f| f.name != "_service" && f.name != "_entities");
+            }
+        }
+    }
+    doc.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GraphQLEntity, Representation};
+    use crate::{
+        execute, execute_sync, graphql_object, graphql_value, EmptyMutation, EmptySubscription,
+        FieldResult, GraphQLObject, InputValue, RootNode, Value, Variables,
+    };
+
+    struct Database {
+        users: Vec<(i32, &'static str)>,
+        internal: bool,
+    }
+
+    impl crate::Context for Database {}
+
+    #[derive(GraphQLObject)]
+    #[graphql(context = Database, key = "id")]
+    struct User {
+        id: i32,
+        name: String,
+    }
+
+    impl GraphQLEntity for User {
+        fn resolve_reference(
+            representation: &Representation,
+            context: &Database,
+        ) -> FieldResult<Option<Self>> {
+            let id = representation
+                .get::<i32>("id")
+                .ok_or("Missing field \"id\" in representation")?;
+            Ok(context
+                .users
+                .iter()
+                .find(|(user_id, _)| *user_id == id)
+                .map(|(id, name)| User {
+                    id: *id,
+                    name: (*name).into(),
+                }))
+        }
+    }
+
+    #[derive(GraphQLObject)]
+    #[graphql(context = Database, key = "upc", extends)]
+    struct Product {
+        #[graphql(external)]
+        upc: String,
+        #[graphql(external)]
+        weight: i32,
+        #[graphql(requires = "weight")]
+        shipping_estimate: i32,
+        #[graphql(provides = "name")]
+        author: User,
+    }
+
+    impl GraphQLEntity for Product {
+        fn resolve_reference(
+            representation: &Representation,
+            _: &Database,
+        ) -> FieldResult<Option<Self>> {
+            let upc = representation.get::<String>("upc").unwrap_or_default();
+            let weight = representation.get::<i32>("weight").unwrap_or_default();
+            Ok(Some(Product {
+                upc,
+                weight,
+                shipping_estimate: weight / 2,
+                author: User {
+                    id: 1,
+                    name: "Alice".into(),
+                },
+            }))
+        }
+    }
+
+    struct Query;
+
+    #[graphql_object(context = Database)]
+    impl Query {
+        fn me(context: &Database) -> Option<User> {
+            context.users.first().map(|(id, name)| User {
+                id: *id,
+                name: (*name).into(),
+            })
+        }
+
+        #[graphql(visibility = "internal")]
+        fn user_count(context: &Database) -> i32 {
+            context.users.len() as i32
+        }
+    }
+
+    type Schema = RootNode<'static, Query, EmptyMutation<Database>, EmptySubscription<Database>>;
+
+    fn schema() -> Schema {
+        Schema::new(Query, EmptyMutation::new(), EmptySubscription::new())
+            .federated()
+            .with_entity::<User>()
+            .with_entity::<Product>()
+            .with_visibility("internal", |db| db.internal)
+    }
+
+    fn database() -> Database {
+        Database {
+            users: vec![(1, "Alice"), (2, "Bob")],
+            internal: false,
+        }
+    }
+
+    const ENTITIES_QUERY: &str = r#"
+        query($representations: [_Any!]!) {
+            _entities(representations: $representations) {
+                __typename
+                ... on User { id name }
+                ... on Product { upc shippingEstimate }
+            }
+        }
+    "#;
+
+    fn representations() -> Variables {
+        vec![(
+            "representations".to_owned(),
+            InputValue::list(vec![
+                InputValue::object(
+                    vec![
+                        ("__typename", InputValue::scalar("User")),
+                        ("id", InputValue::scalar(2)),
+                    ]
+                    .into_iter()
+                    .collect(),
+                ),
+                InputValue::object(
+                    vec![
+                        ("__typename", InputValue::scalar("Product")),
+                        ("upc", InputValue::scalar("1")),
+                        ("weight", InputValue::scalar(10)),
+                    ]
+                    .into_iter()
+                    .collect(),
+                ),
+                InputValue::object(
+                    vec![
+                        ("__typename", InputValue::scalar("User")),
+                        ("id", InputValue::scalar(3)),
+                    ]
+                    .into_iter()
+                    .collect(),
+                ),
+            ]),
+        )]
+        .into_iter()
+        .collect()
+    }
+
+    fn expected_entities() -> Value {
+        graphql_value!({
+            "_entities": [
+                {"__typename": "User", "id": 2, "name": "Bob"},
+                {"__typename": "Product", "upc": "1", "shippingEstimate": 5},
+                None,
+            ],
+        })
+    }
+
+    fn service_sdl(context: &Database) -> String {
+        let query = "{ _service { sdl } }";
+        let (result, errs) = execute_sync(query, None, &schema(), &Variables::new(), context)
+            .expect("Execution failed");
+        assert_eq!(errs, []);
+
+        result
+            .as_object_value()
+            .and_then(|o| o.get_field_value("_service"))
+            .and_then(|v| v.as_object_value())
+            .and_then(|o| o.get_field_value("sdl"))
+            .and_then(|v| v.as_string_value())
+            .expect("sdl is not a string")
+            .to_owned()
+    }
+
+    #[test]
+    fn service_sdl_contains_directives_but_no_federation_types() {
+        let sdl = service_sdl(&database());
+
+        for expected in &[
+            r#"type User @key(fields: "id") {"#,
+            r#"type Product @key(fields: "upc") @extends {"#,
+            "upc: String! @external",
+            r#"shippingEstimate: Int! @requires(fields: "weight")"#,
+            r#"author: User! @provides(fields: "name")"#,
+            "me: User",
+        ] {
+            assert!(
+                sdl.contains(expected),
+                "{:?} not found in:\n{}",
+                expected,
+                sdl
+            );
+        }
+        for unexpected in &["_Any", "_Entity", "_Service", "_FieldSet", "directive @key"] {
+            assert!(
+                !sdl.contains(unexpected),
+                "{:?} found in:\n{}",
+                unexpected,
+                sdl
+            );
+        }
+    }
+
+    #[test]
+    fn service_sdl_leaves_out_hidden_items() {
+        let sdl = service_sdl(&database());
+        assert!(!sdl.contains("userCount"), "userCount found in:\n{}", sdl);
+
+        let sdl = service_sdl(&Database {
+            internal: true,
+            ..database()
+        });
+        assert!(
+            sdl.contains("userCount: Int!"),
+            "userCount not found in:\n{}",
+            sdl
+        );
+    }
+
+    #[test]
+    fn schema_language_contains_federation_types() {
+        let sdl = schema().as_schema_language();
+
+        for expected in &[
+            "scalar _Any",
+            "union _Entity = User | Product",
+            "_service: _Service!",
+            "_entities(representations: [_Any!]!): [_Entity]!",
+        ] {
+            assert!(
+                sdl.contains(expected),
+                "{:?} not found in:\n{}",
+                expected,
+                sdl
+            );
+        }
+    }
+
+    #[test]
+    fn resolves_entities() {
+        let (result, errs) = execute_sync(
+            ENTITIES_QUERY,
+            None,
+            &schema(),
+            &representations(),
+            &database(),
+        )
+        .expect("Execution failed");
+
+        assert_eq!(errs, []);
+        assert_eq!(result, expected_entities());
+    }
+
+    #[tokio::test]
+    async fn resolves_entities_async() {
+        let (result, errs) = execute(
+            ENTITIES_QUERY,
+            None,
+            &schema(),
+            &representations(),
+            &database(),
+        )
+        .await
+        .expect("Execution failed");
+
+        assert_eq!(errs, []);
+        assert_eq!(result, expected_entities());
+    }
+
+    #[test]
+    fn resolves_entities_from_literal() {
+        let query = r#"{
+            _entities(representations: [{__typename: "User", id: 1}]) {
+                ... on User { name }
+            }
+        }"#;
+        let (result, errs) = execute_sync(query, None, &schema(), &Variables::new(), &database())
+            .expect("Execution failed");
+
+        assert_eq!(errs, []);
+        assert_eq!(result, graphql_value!({"_entities": [{"name": "Alice"}]}));
+    }
+
+    #[test]
+    fn errors_on_unknown_entity_type() {
+        let query = r#"{
+            _entities(representations: [{__typename: "Review", id: 1}]) {
+                __typename
+            }
+        }"#;
+        let (result, errs) = execute_sync(query, None, &schema(), &Variables::new(), &database())
+            .expect("Execution failed");
+
+        assert_eq!(result, graphql_value!({ "_entities": [None] }));
+        assert_eq!(errs.len(), 1);
+        assert_eq!(
+            errs[0].error().message(),
+            r#"Type "Review" is not an entity"#,
+        );
+    }
+
+    #[test]
+    fn rejects_representation_without_typename() {
+        let query = r#"{ _entities(representations: [{id: 1}]) { __typename } }"#;
+        let schema = schema();
+        let res = execute_sync(query, None, &schema, &Variables::new(), &database());
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn is_opt_in() {
+        let schema = RootNode::new(
+            Query,
+            EmptyMutation::<Database>::new(),
+            EmptySubscription::<Database>::new(),
+        );
+        assert!(execute_sync(
+            "{ _service { sdl } }",
+            None,
+            &schema,
+            &Variables::new(),
+            &database()
+        )
+        .is_err());
+    }
+}
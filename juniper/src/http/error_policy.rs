@@ -0,0 +1,260 @@
+//! Utilities for hiding the messages of unexpected errors from clients
+//!
+//! Errors returned by resolvers are sent to clients as they are by default, which may leak
+//! internal details, like the messages of database errors. With [`ErrorPolicy::mask`], the field
+//! errors which weren't marked with [`FieldError::user_facing`] are replaced by a generic
+//! `Internal server error`, along with a correlation ID identifying the original error:
+//!
+//! ```json
+//! {
+//!   "message": "Internal server error",
+//!   "locations": [{"line": 1, "column": 3}],
+//!   "path": ["user"],
+//!   "extensions": {"code": "INTERNAL_SERVER_ERROR", "correlationId": "5f2b1c0a9d3e4f67"}
+//! }
+//! ```
+//!
+//! The policy is applied to the field errors of every execution of a schema, e.g. with
+//! [`crate::execute`] or [`crate::http::GraphQLRequest::execute`], once provided with
+//! [`crate::RootNode::with_error_policy`]:
+//!
+//! ```rust
+//! # use juniper::{http::error_policy::ErrorPolicy, EmptyMutation, EmptySubscription, RootNode};
+//! # struct Query;
+//! # #[juniper::graphql_object]
+//! # impl Query {
+//! #     fn ping() -> bool {
+//! #         true
+//! #     }
+//! # }
+//! let schema = RootNode::new(Query, EmptyMutation::<()>::new(), EmptySubscription::<()>::new())
+//!     .with_error_policy(ErrorPolicy::mask().on_masked(|correlation_id, message| {
+//!         eprintln!("Internal error {}: {}", correlation_id, message);
+//!     }));
+//! ```
+
+use std::{
+    collections::hash_map::RandomState,
+    fmt,
+    hash::{BuildHasher, Hasher},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use crate::{
+    executor::{ExecutionError, FieldError},
+    value::{Object, ScalarValue, Value},
+};
+
+/// The message of the errors masked by an [`ErrorPolicy`]
+pub const MASKED_MESSAGE: &str = "Internal server error";
+
+/// The `extensions.code` of the errors masked by an [`ErrorPolicy`]
+pub const MASKED_CODE: &str = "INTERNAL_SERVER_ERROR";
+
+type OnMaskedFn = dyn Fn(&str, &str) + Send + Sync;
+
+/// Decides which field errors are exposed to clients
+///
+/// The default policy exposes all of them.
+#[derive(Clone, Default)]
+pub struct ErrorPolicy {
+    mask: bool,
+    on_masked: Option<Arc<OnMaskedFn>>,
+}
+
+impl ErrorPolicy {
+    /// Exposes all the field errors to clients
+    pub fn expose() -> Self {
+        Self::default()
+    }
+
+    /// Masks the field errors which weren't marked with [`FieldError::user_facing`]
+    pub fn mask() -> Self {
+        ErrorPolicy {
+            mask: true,
+            on_masked: None,
+        }
+    }
+
+    /// Calls `f` with the correlation ID and the original message of every masked error, e.g. to
+    /// log them.
+    pub fn on_masked<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&str, &str) + Send + Sync + 'static,
+    {
+        self.on_masked = Some(Arc::new(f));
+        self
+    }
+
+    /// Whether the policy masks unexpected errors
+    pub fn is_masking(&self) -> bool {
+        self.mask
+    }
+
+    /// Applies the policy to a field error, masking it if needed.
+    pub fn apply_to_field_error<S>(&self, error: FieldError<S>) -> FieldError<S>
+    where
+        S: ScalarValue,
+    {
+        if !self.mask || error.is_user_facing() {
+            return error;
+        }
+
+        let correlation_id = correlation_id();
+        if let Some(on_masked) = &self.on_masked {
+            on_masked(&correlation_id, error.message());
+        }
+
+        let mut extensions = Object::with_capacity(2);
+        extensions.add_field("code", Value::scalar(MASKED_CODE.to_owned()));
+        extensions.add_field("correlationId", Value::scalar(correlation_id));
+        FieldError::new(MASKED_MESSAGE, Value::Object(extensions))
+    }
+
+    /// Applies the policy to an execution error, masking it if needed.
+    pub fn apply<S>(&self, error: ExecutionError<S>) -> ExecutionError<S>
+    where
+        S: ScalarValue,
+    {
+        error.map_error(|e| self.apply_to_field_error(e))
+    }
+}
+
+impl fmt::Debug for ErrorPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ErrorPolicy")
+            .field("mask", &self.mask)
+            .finish()
+    }
+}
+
+/// Generates a new random ID for a masked error
+fn correlation_id() -> String {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_usize(COUNTER.fetch_add(1, Ordering::Relaxed));
+    format!("{:016x}", hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::{ErrorPolicy, MASKED_MESSAGE};
+    use crate::{
+        executor::PathSegment, http::GraphQLRequest, parser::SourcePosition,
+        value::DefaultScalarValue, EmptyMutation, EmptySubscription, FieldError, FieldResult,
+        RootNode, Variables,
+    };
+
+    #[test]
+    fn exposes_errors_by_default() {
+        let error = FieldError::<DefaultScalarValue>::from("Connection refused");
+
+        assert_eq!(
+            ErrorPolicy::default().apply_to_field_error(error),
+            FieldError::from("Connection refused"),
+        );
+    }
+
+    #[test]
+    fn masks_unexpected_errors() {
+        let reported = Arc::new(Mutex::new(vec![]));
+        let policy = ErrorPolicy::mask().on_masked({
+            let reported = reported.clone();
+            move |id, message| {
+                reported
+                    .lock()
+                    .unwrap()
+                    .push((id.to_owned(), message.to_owned()))
+            }
+        });
+
+        let error = policy.apply_to_field_error(FieldError::<DefaultScalarValue>::new(
+            "Connection refused",
+            graphql_value!({ "host": "db" }),
+        ));
+
+        assert_eq!(error.message(), MASKED_MESSAGE);
+        let extensions = error.extensions().as_object_value().unwrap();
+        assert_eq!(
+            extensions.get_field_value("code"),
+            Some(&graphql_value!("INTERNAL_SERVER_ERROR")),
+        );
+        let id = extensions
+            .get_field_value("correlationId")
+            .and_then(|id| id.as_string_value())
+            .unwrap();
+        assert_eq!(
+            *reported.lock().unwrap(),
+            vec![(id.to_owned(), "Connection refused".to_owned())],
+        );
+    }
+
+    #[test]
+    fn passes_user_facing_errors_through() {
+        let policy = ErrorPolicy::mask();
+        let error = crate::ExecutionError::<DefaultScalarValue>::new(
+            SourcePosition::new_origin(),
+            &["user"],
+            FieldError::from("User not found").user_facing(),
+        );
+
+        assert_eq!(policy.apply(error).error().message(), "User not found");
+    }
+
+    struct Query;
+
+    #[crate::graphql_object]
+    impl Query {
+        fn internal() -> FieldResult<Option<i32>> {
+            Err("Connection refused".into())
+        }
+
+        fn user() -> FieldResult<i32> {
+            Err(FieldError::from("User not found").user_facing())
+        }
+    }
+
+    #[test]
+    fn masks_errors_of_responses() {
+        let schema = RootNode::new(
+            Query,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        )
+        .with_error_policy(ErrorPolicy::mask());
+        let request = GraphQLRequest::new("{ internal user }".into(), None, None);
+
+        let response = serde_json::to_value(request.execute_sync(&schema, &())).unwrap();
+
+        let messages = response["errors"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["message"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(messages, vec![MASKED_MESSAGE, "User not found"]);
+    }
+
+    #[test]
+    fn masks_errors_of_executions() {
+        let schema = RootNode::new(
+            Query,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        )
+        .with_error_policy(ErrorPolicy::mask());
+
+        let (_, errors) =
+            crate::execute_sync("{ internal }", None, &schema, &Variables::new(), &()).unwrap();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].error().message(), MASKED_MESSAGE);
+        assert_eq!(errors[0].path(), &[PathSegment::Field("internal".into())]);
+    }
+}
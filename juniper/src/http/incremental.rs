@@ -0,0 +1,292 @@
+//! Utilities for the incremental delivery of responses with the `@defer` and `@stream`
+//! directives, over [`multipart/mixed`][1] HTTP responses
+//!
+//! Once the directives are enabled with [`RootNode::with_incremental_delivery`], requests executed
+//! with [`GraphQLRequest::execute_incremental`] return their initial response first, and then a
+//! payload for every deferred fragment and streamed list. Clients accepting `multipart/mixed`
+//! responses, as told by [`accepts_multipart`], receive every payload as a part of the response:
+//!
+//! ```text
+//!
+//! ---
+//! Content-Type: application/json; charset=utf-8
+//!
+//! {"data":{"hero":{"name":"R2-D2"}},"hasNext":true}
+//! ---
+//! Content-Type: application/json; charset=utf-8
+//!
+//! {"data":{"friends":[{"name":"Luke Skywalker"}]},"path":["hero"],"hasNext":false}
+//! -----
+//! ```
+//!
+//! The integrations build such bodies with [`IncrementalResponse::into_multipart`], or with
+//! [`execute_owned`] when the body has to own the schema and the context.
+//!
+//! [1]: https://github.com/graphql/graphql-over-http/blob/main/rfcs/IncrementalDelivery.md
+
+use std::sync::Arc;
+
+use futures::{
+    future::{self, FutureExt as _},
+    stream::{self, BoxStream, StreamExt as _},
+};
+use serde::ser::{self, Serialize};
+
+use crate::{
+    executor::{ExecutionError, IncrementalPayload, IncrementalStream},
+    http::{GraphQLRequest, GraphQLResponse},
+    value::{DefaultScalarValue, Object, ScalarValue},
+    GraphQLError, GraphQLType, GraphQLTypeAsync, RootNode, Value,
+};
+
+/// The `Content-Type` of `multipart/mixed` responses
+pub const CONTENT_TYPE: &str = "multipart/mixed; boundary=\"-\"";
+
+/// Checks whether the `Accept` header of a request allows `multipart/mixed` responses
+pub fn accepts_multipart(accept: &str) -> bool {
+    accept.split(',').any(|range| {
+        range
+            .split(';')
+            .next()
+            .is_some_and(|t| t.trim().eq_ignore_ascii_case("multipart/mixed"))
+    })
+}
+
+/// The response to a request executed with [`GraphQLRequest::execute_incremental`]
+pub struct IncrementalResponse<'a, S = DefaultScalarValue> {
+    initial: GraphQLResponse<'a, S>,
+    subsequent: Option<IncrementalStream<'a, S>>,
+}
+
+/// The result of a query executed with [`crate::execute_incremental`]
+type IncrementalResult<'a, S> = Result<
+    (
+        Value<S>,
+        Vec<ExecutionError<S>>,
+        Option<IncrementalStream<'a, S>>,
+    ),
+    GraphQLError<'a>,
+>;
+
+/// A payload of an [`IncrementalResponse`], serialized as a part of a `multipart/mixed` body
+#[derive(Debug)]
+pub enum Payload<'r, 'a, S = DefaultScalarValue> {
+    /// The initial response
+    Initial(&'r GraphQLResponse<'a, S>),
+    /// A payload following the initial response
+    Subsequent(&'r IncrementalPayload<S>),
+}
+
+impl<'a, S> IncrementalResponse<'a, S>
+where
+    S: ScalarValue + Send + Sync,
+{
+    pub(crate) fn new(result: IncrementalResult<'a, S>, extensions: Object<S>) -> Self {
+        let (result, subsequent) = match result {
+            Ok((value, errors, subsequent)) => (Ok((value, errors)), subsequent),
+            Err(e) => (Err(e), None),
+        };
+        let mut initial = GraphQLResponse::from_result(result).with_extensions(extensions);
+        if subsequent.is_some() {
+            initial = initial.with_has_next(true);
+        }
+        IncrementalResponse {
+            initial,
+            subsequent,
+        }
+    }
+
+    /// The initial response
+    pub fn initial(&self) -> &GraphQLResponse<'a, S> {
+        &self.initial
+    }
+
+    /// Whether payloads follow the initial response, i.e. whether the query deferred a fragment
+    /// or streamed a list
+    pub fn is_incremental(&self) -> bool {
+        self.subsequent.is_some()
+    }
+
+    /// Splits the response into the initial response and the stream of the payloads following
+    /// it, if any
+    pub fn into_parts(self) -> (GraphQLResponse<'a, S>, Option<IncrementalStream<'a, S>>) {
+        (self.initial, self.subsequent)
+    }
+
+    /// Converts the response into the chunks of a `multipart/mixed` body, serializing every
+    /// payload to JSON with `serialize`, e.g. with `serde_json::to_string`.
+    pub fn into_multipart<F>(self, serialize: F) -> BoxStream<'a, String>
+    where
+        F: Fn(Payload<'_, 'a, S>) -> String + Send + 'a,
+    {
+        let first = format!(
+            "\r\n---{}",
+            part(&serialize(Payload::Initial(&self.initial)))
+        );
+        let rest = stream::iter(self.subsequent)
+            .flatten()
+            .map(move |payload| part(&serialize(Payload::Subsequent(&payload))));
+
+        stream::once(future::ready(first))
+            .chain(rest)
+            .chain(stream::once(future::ready("--\r\n".to_owned())))
+            .boxed()
+    }
+}
+
+/// Formats a part of a `multipart/mixed` body, followed by a delimiter
+fn part(json: &str) -> String {
+    format!(
+        "\r\nContent-Type: application/json; charset=utf-8\r\n\r\n{}\r\n---",
+        json,
+    )
+}
+
+impl<'r, 'a, T> Serialize for Payload<'r, 'a, T>
+where
+    T: ScalarValue,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        match *self {
+            Payload::Initial(response) => response.serialize(serializer),
+            Payload::Subsequent(payload) => payload.serialize(serializer),
+        }
+    }
+}
+
+/// The body of the response to a request executed with [`execute_owned`]
+pub enum IncrementalBody {
+    /// The JSON response to a request which wasn't delivered incrementally, and whether the
+    /// request was successful
+    Json(String, bool),
+    /// The chunks of a `multipart/mixed` body, see [`CONTENT_TYPE`]
+    Multipart(BoxStream<'static, String>),
+}
+
+/// Executes a request with [`GraphQLRequest::execute_incremental`], owning the schema and the
+/// context so its payloads can be streamed as the body of a response
+///
+/// Returns once the initial response is ready, so the status and the headers of the response
+/// can be chosen: the remaining payloads are resolved as the body is polled. Payloads are
+/// serialized to JSON with `serialize`, e.g. with `serde_json::to_string`.
+pub async fn execute_owned<QueryT, MutationT, SubscriptionT, CtxT, S, F>(
+    request: GraphQLRequest<S>,
+    root_node: Arc<RootNode<'static, QueryT, MutationT, SubscriptionT, S>>,
+    context: Arc<CtxT>,
+    serialize: F,
+) -> IncrementalBody
+where
+    QueryT: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+    QueryT::TypeInfo: Send + Sync,
+    MutationT: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+    MutationT::TypeInfo: Send + Sync,
+    SubscriptionT: GraphQLType<S, Context = CtxT> + Send + Sync + 'static,
+    SubscriptionT::TypeInfo: Send + Sync,
+    CtxT: Send + Sync + 'static,
+    S: ScalarValue + Send + Sync,
+    F: Fn(Payload<'_, '_, S>) -> String + Send + 'static,
+{
+    let owned = (request, root_node, context);
+    let (initial, chunks) =
+        super::resolve_owned_body(owned, move |(request, root_node, context)| {
+            async move {
+                let response = request.execute_incremental(root_node, context).await;
+                if !response.is_incremental() {
+                    let initial = response.initial();
+                    let json = serialize(Payload::Initial(initial));
+                    return (Some((json, initial.is_ok())), None);
+                }
+                (None, Some(response.into_multipart(serialize)))
+            }
+            .boxed()
+        })
+        .await;
+
+    match initial {
+        Some((json, is_ok)) => IncrementalBody::Json(json, is_ok),
+        None => IncrementalBody::Multipart(chunks),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use futures::StreamExt as _;
+
+    use super::{accepts_multipart, execute_owned, IncrementalBody};
+    use crate::{http::GraphQLRequest, EmptyMutation, EmptySubscription, RootNode};
+
+    struct Query;
+
+    #[crate::graphql_object]
+    impl Query {
+        fn a() -> &str {
+            "a"
+        }
+
+        fn b() -> &str {
+            "b"
+        }
+    }
+
+    async fn execute(query: &str) -> IncrementalBody {
+        let root_node = RootNode::new(
+            Query,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        )
+        .with_incremental_delivery();
+        let request = GraphQLRequest::new(query.into(), None, None);
+        execute_owned(request, Arc::new(root_node), Arc::new(()), |payload| {
+            serde_json::to_string(&payload).unwrap()
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn streams_owned_payloads_as_multipart_body() {
+        let chunks = match execute("{ a ... @defer { b } }").await {
+            IncrementalBody::Multipart(chunks) => chunks.collect::<Vec<_>>().await,
+            IncrementalBody::Json(json, _) => panic!("Unexpected JSON response: {}", json),
+        };
+
+        assert_eq!(
+            chunks.concat(),
+            concat!(
+                "\r\n---",
+                "\r\nContent-Type: application/json; charset=utf-8\r\n\r\n",
+                r#"{"data":{"a":"a"},"hasNext":true}"#,
+                "\r\n---",
+                "\r\nContent-Type: application/json; charset=utf-8\r\n\r\n",
+                r#"{"data":{"b":"b"},"path":[],"hasNext":false}"#,
+                "\r\n-----\r\n",
+            ),
+        );
+    }
+
+    #[tokio::test]
+    async fn returns_json_if_not_incremental() {
+        match execute("{ a b }").await {
+            IncrementalBody::Json(json, is_ok) => {
+                assert_eq!(json, r#"{"data":{"a":"a","b":"b"}}"#);
+                assert!(is_ok);
+            }
+            IncrementalBody::Multipart(_) => panic!("Unexpected multipart response"),
+        }
+    }
+
+    #[test]
+    fn accepts_multipart_mixed() {
+        assert!(accepts_multipart("multipart/mixed"));
+        assert!(accepts_multipart(
+            "application/json, multipart/mixed; deferSpec=20220824"
+        ));
+        assert!(accepts_multipart("Multipart/Mixed;q=0.9"));
+        assert!(!accepts_multipart("application/json"));
+        assert!(!accepts_multipart("multipart/form-data"));
+    }
+}
@@ -1,8 +1,20 @@
 //! Utilities for building HTTP endpoints in a library-agnostic manner
 
+pub mod error_policy;
 pub mod graphiql;
+pub mod incremental;
+pub mod multipart;
+pub mod persisted_query;
 pub mod playground;
 
+use std::sync::OnceLock;
+
+use futures::{
+    channel::{mpsc, oneshot},
+    future::{self, BoxFuture, Either, FutureExt as _},
+    stream::{self, BoxStream, StreamExt as _},
+    SinkExt as _,
+};
 use serde::{
     de,
     ser::{self, SerializeMap},
@@ -12,28 +24,57 @@ use serde::{
 use crate::{
     ast::InputValue,
     executor::{ExecutionError, ValuesStream},
-    value::{DefaultScalarValue, ScalarValue},
+    value::{DefaultScalarValue, Object, ScalarValue},
     FieldError, GraphQLError, GraphQLSubscriptionType, GraphQLType, GraphQLTypeAsync, RootNode,
     Value, Variables,
 };
 
+use self::{
+    incremental::IncrementalResponse,
+    multipart::Uploads,
+    persisted_query::{sha256_hash, PersistedQuery, PersistedQueryStore},
+};
+
 /// The expected structure of the decoded JSON document for either POST or GET requests.
 ///
 /// For POST, you can use Serde to deserialize the incoming JSON data directly
 /// into this struct - it derives Deserialize for exactly this reason.
 ///
 /// For GET, you will need to parse the query string and extract "query",
-/// "operationName", and "variables" manually.
+/// "operationName", "variables" and "extensions" manually.
+///
+/// The query may be missing when the hash of a persisted query is sent in the
+/// extensions instead, see the [`persisted_query`] module.
 #[derive(Deserialize, Clone, Serialize, PartialEq, Debug)]
 pub struct GraphQLRequest<S = DefaultScalarValue>
 where
     S: ScalarValue,
 {
+    #[serde(default)]
     query: String,
     #[serde(rename = "operationName")]
     operation_name: Option<String>,
     #[serde(bound(deserialize = "InputValue<S>: Deserialize<'de> + Serialize"))]
     variables: Option<InputValue<S>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    extensions: Option<GraphQLRequestExtensions>,
+    #[serde(skip)]
+    uploads: Uploads,
+    /// The query of a persisted query, once it's loaded from the store while executing
+    #[serde(skip)]
+    stored_query: OnceLock<String>,
+}
+
+/// The extensions of a [`GraphQLRequest`] understood by Juniper
+#[derive(Default, Deserialize, Clone, Serialize, PartialEq, Debug)]
+pub struct GraphQLRequestExtensions {
+    /// The hash of a persisted query, sent along with or instead of the query
+    #[serde(
+        rename = "persistedQuery",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub persisted_query: Option<PersistedQuery>,
 }
 
 impl<S> GraphQLRequest<S>
@@ -68,13 +109,95 @@ where
             query,
             operation_name,
             variables,
+            extensions: None,
+            uploads: Uploads::default(),
+            stored_query: OnceLock::new(),
+        }
+    }
+
+    /// Sets the extensions of this request
+    pub fn with_extensions(mut self, extensions: GraphQLRequestExtensions) -> Self {
+        self.extensions = Some(extensions);
+        self
+    }
+
+    fn persisted_query(&self) -> Option<&PersistedQuery> {
+        self.extensions
+            .as_ref()
+            .and_then(|ext| ext.persisted_query.as_ref())
+    }
+
+    /// Loads the query of a request sending only the hash of a persisted query
+    /// from the store of the schema, if it's stored there.
+    ///
+    /// Executing a request loads its query from the store too, so this is only
+    /// needed to look at the query before executing it.
+    pub fn load_persisted_query<QueryT, MutationT, SubscriptionT>(
+        &mut self,
+        root_node: &RootNode<QueryT, MutationT, SubscriptionT, S>,
+    ) where
+        QueryT: GraphQLType<S>,
+        MutationT: GraphQLType<S>,
+        SubscriptionT: GraphQLType<S>,
+    {
+        if !self.query.is_empty() {
+            return;
+        }
+        let store = match root_node.persisted_queries {
+            Some(ref store) => store,
+            None => return,
+        };
+        let query = self
+            .persisted_query()
+            .filter(|pq| pq.version == 1)
+            .and_then(|pq| store.get(&pq.sha256_hash));
+        if let Some(query) = query {
+            self.query = query;
+            // The query comes from the store, so its hash doesn't need to be checked again.
+            if let Some(ref mut ext) = self.extensions {
+                ext.persisted_query = None;
+            }
+        }
+    }
+
+    /// Returns the query to execute, checking its persisted query hash and
+    /// storing it if there's one, or loading it from the store if only the
+    /// hash was sent.
+    fn checked_query(
+        &self,
+        store: Option<&dyn PersistedQueryStore>,
+    ) -> Result<&str, GraphQLError<'static>> {
+        let (persisted_query, store) = match (self.persisted_query(), store) {
+            (Some(pq), Some(store)) => (pq, store),
+            (Some(_), None) if self.query.is_empty() => {
+                return Err(GraphQLError::PersistedQueryNotSupported)
+            }
+            _ => return Ok(&self.query),
+        };
+
+        if persisted_query.version != 1 {
+            Err(GraphQLError::PersistedQueryNotSupported)
+        } else if self.query.is_empty() {
+            if let Some(query) = self.stored_query.get() {
+                return Ok(query);
+            }
+            store
+                .get(&persisted_query.sha256_hash)
+                .map(|query| self.stored_query.get_or_init(|| query).as_str())
+                .ok_or(GraphQLError::PersistedQueryNotFound)
+        } else if sha256_hash(&self.query) != persisted_query.sha256_hash {
+            Err(GraphQLError::PersistedQueryHashMismatch)
+        } else {
+            store.insert(persisted_query.sha256_hash.clone(), self.query.clone());
+            Ok(&self.query)
         }
     }
 
     /// Execute a GraphQL request synchronously using the specified schema and context
     ///
     /// This is a simple wrapper around the `execute_sync` function exposed at the
-    /// top level of this crate.
+    /// top level of this crate, adding the results of the extensions of the schema
+    /// to the response.
     pub fn execute_sync<'a, QueryT, MutationT, SubscriptionT>(
         &'a self,
         root_node: &'a RootNode<QueryT, MutationT, SubscriptionT, S>,
@@ -86,19 +209,28 @@ where
         MutationT: GraphQLType<S, Context = QueryT::Context>,
         SubscriptionT: GraphQLType<S, Context = QueryT::Context>,
     {
-        GraphQLResponse(crate::execute_sync(
-            &self.query,
-            self.operation_name(),
-            root_node,
-            &self.variables(),
-            context,
-        ))
+        let extensions = root_node.create_extensions();
+        let result = match self.checked_query(root_node.persisted_queries.as_deref()) {
+            Ok(query) => self.uploads.scope(|| {
+                crate::execute_sync_with_extensions(
+                    query,
+                    self.operation_name(),
+                    root_node,
+                    &self.variables(),
+                    context,
+                    &extensions,
+                )
+            }),
+            Err(err) => Err(err),
+        };
+        GraphQLResponse::from_result(result).with_extensions(extensions.results())
     }
 
     /// Execute a GraphQL request using the specified schema and context
     ///
     /// This is a simple wrapper around the `execute` function exposed at the
-    /// top level of this crate.
+    /// top level of this crate, adding the results of the extensions of the schema
+    /// to the response.
     pub async fn execute<'a, QueryT, MutationT, SubscriptionT>(
         &'a self,
         root_node: &'a RootNode<'a, QueryT, MutationT, SubscriptionT, S>,
@@ -114,10 +246,65 @@ where
         SubscriptionT::TypeInfo: Sync,
         S: ScalarValue + Send + Sync,
     {
-        let op = self.operation_name();
-        let vars = &self.variables();
-        let res = crate::execute(&self.query, op, root_node, vars, context).await;
-        GraphQLResponse(res)
+        let extensions = root_node.create_extensions();
+        let res = match self.checked_query(root_node.persisted_queries.as_deref()) {
+            Ok(query) => {
+                let op = self.operation_name();
+                let vars = &self.variables();
+                let res = crate::execute_with_extensions(
+                    query,
+                    op,
+                    root_node,
+                    vars,
+                    context,
+                    &extensions,
+                );
+                self.uploads.scope_future(res).await
+            }
+            Err(err) => Err(err),
+        };
+        GraphQLResponse::from_result(res).with_extensions(extensions.results())
+    }
+
+    /// Execute a GraphQL request using the specified schema and context, delivering its
+    /// deferred fragments and streamed lists incrementally
+    ///
+    /// This is a simple wrapper around the `execute_incremental` function exposed at the
+    /// top level of this crate, adding the results of the extensions of the schema
+    /// to the initial response. See the [`incremental`] module for serving the response.
+    pub async fn execute_incremental<'a, QueryT, MutationT, SubscriptionT>(
+        &'a self,
+        root_node: &'a RootNode<'a, QueryT, MutationT, SubscriptionT, S>,
+        context: &'a QueryT::Context,
+    ) -> IncrementalResponse<'a, S>
+    where
+        QueryT: GraphQLTypeAsync<S>,
+        QueryT::TypeInfo: Sync,
+        QueryT::Context: Sync,
+        MutationT: GraphQLTypeAsync<S, Context = QueryT::Context>,
+        MutationT::TypeInfo: Sync,
+        SubscriptionT: GraphQLType<S, Context = QueryT::Context> + Sync,
+        SubscriptionT::TypeInfo: Sync,
+        S: ScalarValue + Send + Sync,
+    {
+        let extensions = root_node.create_extensions();
+        let res = match self.checked_query(root_node.persisted_queries.as_deref()) {
+            Ok(query) => {
+                let op = self.operation_name();
+                let vars = &self.variables();
+                let res =
+                    crate::execute_incremental(query, op, root_node, vars, context, &extensions);
+                self.uploads
+                    .scope_future(res)
+                    .await
+                    .map(|(value, errors, subsequent)| {
+                        let subsequent = subsequent.map(|s| self.uploads.scope_stream(s));
+                        (value, errors, subsequent)
+                    })
+            }
+            Err(err) => Err(err),
+        };
+        IncrementalResponse::new(res, extensions.results())
     }
 }
 
@@ -143,10 +330,61 @@ where
     SubscriptionT::TypeInfo: Sync,
     S: ScalarValue + Send + Sync,
 {
+    let query = req.checked_query(root_node.persisted_queries.as_deref())?;
     let op = req.operation_name();
     let vars = req.variables();
 
-    crate::resolve_into_stream(&req.query, op, root_node, &vars, context).await
+    req.uploads
+        .scope_future(crate::resolve_into_stream(
+            query, op, root_node, &vars, context,
+        ))
+        .await
+}
+
+/// Resolves a response whose body borrows `owned`, e.g. the request, the schema and the context,
+/// as a stream owning it
+///
+/// `resolve` returns the head of the response, e.g. its initial payload or an error, along with
+/// the chunks of its body, if any. Returns once the head is resolved, so the status and the headers
+/// of the response can be chosen: the chunks are then resolved as the returned stream is polled.
+pub async fn resolve_owned_body<T, H, F>(owned: T, resolve: F) -> (H, BoxStream<'static, String>)
+where
+    T: Send + Sync + 'static,
+    H: Send + 'static,
+    F: for<'a> FnOnce(&'a T) -> BoxFuture<'a, (H, Option<BoxStream<'a, String>>)> + Send + 'static,
+{
+    let (head_tx, head_rx) = oneshot::channel();
+    let (mut tx, rx) = mpsc::channel(1);
+
+    // The chunks borrow `owned`, so they are polled by a future owning it, sending them through a
+    // channel. The channel is bounded, so the chunks are only polled as fast as the body is sent.
+    let driver = async move {
+        let (head, chunks) = resolve(&owned).await;
+        let _ = head_tx.send(head);
+
+        let mut chunks = match chunks {
+            Some(chunks) => chunks,
+            None => return,
+        };
+        while let Some(chunk) = chunks.next().await {
+            if tx.send(chunk).await.is_err() {
+                // The body was dropped.
+                break;
+            }
+        }
+    }
+    .boxed();
+
+    let (head, driver) = match future::select(driver, head_rx).await {
+        Either::Left(((), head_rx)) => (head_rx.await, None),
+        Either::Right((head, driver)) => (head, Some(driver)),
+    };
+    let head = head.expect("Head of the response is always sent");
+
+    let driver = stream::iter(driver)
+        .then(|driver| driver)
+        .filter_map(|()| future::ready(None));
+    (head, stream::select(driver, rx).boxed())
 }
 
 /// Simple wrapper around the result from executing a GraphQL query
@@ -155,9 +393,11 @@ where
 /// to JSON and send it over the wire. Use the `is_ok` method to determine
 /// whether to send a 200 or 400 HTTP status code.
 #[derive(Debug)]
-pub struct GraphQLResponse<'a, S = DefaultScalarValue>(
-    Result<(Value<S>, Vec<ExecutionError<S>>), GraphQLError<'a>>,
-);
+pub struct GraphQLResponse<'a, S = DefaultScalarValue> {
+    result: Result<(Value<S>, Vec<ExecutionError<S>>), GraphQLError<'a>>,
+    extensions: Object<S>,
+    has_next: Option<bool>,
+}
 
 impl<'a, S> GraphQLResponse<'a, S>
 where
@@ -165,20 +405,53 @@ where
 {
     /// Constructs new `GraphQLResponse` using the given result
     pub fn from_result(r: Result<(Value<S>, Vec<ExecutionError<S>>), GraphQLError<'a>>) -> Self {
-        Self(r)
+        GraphQLResponse {
+            result: r,
+            extensions: Object::with_capacity(0),
+            has_next: None,
+        }
     }
 
     /// Constructs an error response outside of the normal execution flow
     pub fn error(error: FieldError<S>) -> Self {
-        GraphQLResponse(Ok((Value::null(), vec![ExecutionError::at_origin(error)])))
+        Self::from_result(Ok((Value::null(), vec![ExecutionError::at_origin(error)])))
+    }
+
+    /// Adds entries to the top-level `extensions` map of the response
+    pub fn with_extensions(mut self, extensions: Object<S>) -> Self {
+        for (name, value) in extensions {
+            self.extensions.add_field(name, value);
+        }
+        self
+    }
+
+    /// The entries of the top-level `extensions` map of the response
+    pub fn extensions(&self) -> &Object<S> {
+        &self.extensions
+    }
+
+    /// Sets the `hasNext` entry of the response, telling whether payloads follow it when it's
+    /// delivered incrementally
+    pub fn with_has_next(mut self, has_next: bool) -> Self {
+        self.has_next = Some(has_next);
+        self
     }
 
     /// Was the request successful or not?
     ///
     /// Note that there still might be errors in the response even though it's
     /// considered OK. This is by design in GraphQL.
+    ///
+    /// Requests whose persisted query isn't found or supported are considered
+    /// OK too, as clients expect a successful response before retrying them
+    /// with the full query.
     pub fn is_ok(&self) -> bool {
-        self.0.is_ok()
+        match self.result {
+            Ok(_)
+            | Err(GraphQLError::PersistedQueryNotFound)
+            | Err(GraphQLError::PersistedQueryNotSupported) => true,
+            Err(_) => false,
+        }
     }
 }
 
@@ -193,10 +466,10 @@ where
     where
         S: ser::Serializer,
     {
-        match self.0 {
-            Ok((ref res, ref err)) => {
-                let mut map = serializer.serialize_map(None)?;
+        let mut map = serializer.serialize_map(None)?;
 
+        match self.result {
+            Ok((ref res, ref err)) => {
                 map.serialize_key("data")?;
                 map.serialize_value(res)?;
 
@@ -204,21 +477,29 @@ where
                     map.serialize_key("errors")?;
                     map.serialize_value(err)?;
                 }
-
-                map.end()
             }
             Err(ref err) => {
-                let mut map = serializer.serialize_map(Some(1))?;
                 map.serialize_key("errors")?;
                 map.serialize_value(err)?;
-                map.end()
             }
         }
+
+        if self.extensions.field_count() > 0 {
+            map.serialize_key("extensions")?;
+            map.serialize_value(&self.extensions)?;
+        }
+
+        if let Some(has_next) = self.has_next {
+            map.serialize_key("hasNext")?;
+            map.serialize_value(&has_next)?;
+        }
+
+        map.end()
     }
 }
 
 /// Simple wrapper around GraphQLRequest to allow the handling of Batch requests.
-#[derive(Debug, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Deserialize, PartialEq)]
 #[serde(untagged)]
 #[serde(bound = "InputValue<S>: Deserialize<'de>")]
 pub enum GraphQLBatchRequest<S = DefaultScalarValue>
@@ -313,6 +594,26 @@ where
         }
     }
 
+    /// Loads the queries of the requests sending only the hash of a persisted
+    /// query, see [`GraphQLRequest::load_persisted_query`].
+    pub fn load_persisted_queries<QueryT, MutationT, SubscriptionT>(
+        &mut self,
+        root_node: &RootNode<QueryT, MutationT, SubscriptionT, S>,
+    ) where
+        QueryT: GraphQLType<S>,
+        MutationT: GraphQLType<S>,
+        SubscriptionT: GraphQLType<S>,
+    {
+        match self {
+            Self::Single(req) => req.load_persisted_query(root_node),
+            Self::Batch(reqs) => {
+                for req in reqs {
+                    req.load_persisted_query(root_node);
+                }
+            }
+        }
+    }
+
     /// The operation names of the request.
     pub fn operation_names(&self) -> Vec<Option<&str>> {
         match self {
@@ -419,6 +720,9 @@ pub mod tests {
 
         println!("  - test_invalid_graphql_post");
         test_invalid_graphql_post(integration);
+
+        println!("  - test_persisted_query");
+        test_persisted_query(integration);
     }
 
     fn unwrap_json_response(response: &TestResponse) -> Json {
@@ -585,6 +889,54 @@ pub mod tests {
         assert_eq!(resp.status_code, 400);
     }
 
+    /// Expects the schema of the integration to store persisted queries.
+    fn test_persisted_query<T: HttpIntegration>(integration: &T) {
+        // {"persistedQuery": {"version": 1, "sha256Hash": sha256("{hero{id}}")}}
+        let extensions = r#"{"persistedQuery": {"version": 1, "sha256Hash": "15f9d3eba173740ea63d3f6f4cc3f8f728c0cd7fdc48c268f0119df08f90783d"}}"#;
+
+        let response = integration.post_json("/", &format!(r#"{{"extensions": {}}}"#, extensions));
+
+        assert_eq!(response.status_code, 200);
+        assert_eq!(
+            unwrap_json_response(&response),
+            serde_json::from_str::<Json>(
+                r#"{"errors": [{
+                    "message": "PersistedQueryNotFound",
+                    "extensions": {"code": "PERSISTED_QUERY_NOT_FOUND"}
+                }]}"#,
+            )
+            .expect("Invalid JSON constant in test"),
+        );
+
+        let response = integration.post_json(
+            "/",
+            &format!(
+                r#"{{"query": "{{hero{{name}}}}", "extensions": {}}}"#,
+                extensions
+            ),
+        );
+        assert_eq!(response.status_code, 400);
+
+        let response = integration.post_json(
+            "/",
+            &format!(
+                r#"{{"query": "{{hero{{id}}}}", "extensions": {}}}"#,
+                extensions
+            ),
+        );
+        assert_eq!(response.status_code, 200);
+
+        let response = integration.get("/?extensions=%7B%22persistedQuery%22%3A%7B%22version%22%3A1%2C%22sha256Hash%22%3A%2215f9d3eba173740ea63d3f6f4cc3f8f728c0cd7fdc48c268f0119df08f90783d%22%7D%7D");
+
+        assert_eq!(response.status_code, 200);
+        assert_eq!(response.content_type, "application/json");
+        assert_eq!(
+            unwrap_json_response(&response),
+            serde_json::from_str::<Json>(r#"{"data": {"hero": {"id": "2001"}}}"#)
+                .expect("Invalid JSON constant in test"),
+        );
+    }
+
     /// Normalized way to make requests to the WebSocket framework integration we are testing.
     pub trait WsIntegration {
         /// Runs a test with the given messages
@@ -742,7 +1094,8 @@ pub mod tests {
                         "locations":[{
                             "line":1,
                             "column":16
-                        }]
+                        }],
+                        "extensions":{"code":"GRAPHQL_VALIDATION_FAILED"}
                     }]
                 }"#
                 .to_owned(),
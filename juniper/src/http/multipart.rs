@@ -0,0 +1,464 @@
+//! Utilities for the [GraphQL multipart request spec][1], used to upload files along with a
+//! request
+//!
+//! A multipart request is sent as `multipart/form-data`, made of:
+//! - an `operations` field, holding the JSON-encoded request or batch of requests, in which the
+//!   variables receiving files are `null`;
+//! - a `map` field, holding a JSON object mapping the name of every file field to the paths of
+//!   the variables receiving it, e.g. `{ "0": ["variables.file"] }`, or
+//!   `{ "0": ["0.variables.file"] }` for a batch;
+//! - the file fields.
+//!
+//! The integrations parse the fields, and pass them to [`GraphQLBatchRequest::attach_uploads`],
+//! which puts the files into the variables. Resolvers then receive them as arguments of the
+//! [`Upload`] scalar type:
+//!
+//! ```rust
+//! # use juniper::http::multipart::Upload;
+//! struct Mutation;
+//!
+//! #[juniper::graphql_object]
+//! impl Mutation {
+//!     fn upload(file: Upload) -> i32 {
+//!         file.content().len() as i32
+//!     }
+//! }
+//! ```
+//!
+//! [1]: https://github.com/jaydenseric/graphql-multipart-request-spec
+
+use std::{cell::RefCell, collections::HashMap, error::Error, fmt, future::Future, sync::Arc};
+
+use futures::{
+    future,
+    stream::{self, BoxStream, StreamExt as _},
+};
+
+use crate::{
+    ast::InputValue,
+    http::{GraphQLBatchRequest, GraphQLRequest},
+    parser::{ParseError, Token},
+    value::{ParseScalarResult, ScalarValue},
+    Value,
+};
+
+/// A file uploaded with a multipart request
+#[derive(Debug, PartialEq)]
+pub struct UploadedFile {
+    filename: Option<String>,
+    content_type: Option<String>,
+    content: Vec<u8>,
+}
+
+impl UploadedFile {
+    /// Constructs a file from the parts of its multipart field
+    pub fn new(filename: Option<String>, content_type: Option<String>, content: Vec<u8>) -> Self {
+        UploadedFile {
+            filename,
+            content_type,
+            content,
+        }
+    }
+}
+
+/// A file uploaded with a multipart request, received as an argument
+///
+/// Uploads can only be passed in the variables of the [`GraphQLRequest`] they were sent with,
+/// and are only available while it's executed. Used as an output type, they resolve to the name
+/// of the file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Upload(Arc<UploadedFile>);
+
+impl Upload {
+    /// The name of the file on the client, if it was sent
+    pub fn filename(&self) -> Option<&str> {
+        self.0.filename.as_deref()
+    }
+
+    /// The content type of the file, if it was sent
+    pub fn content_type(&self) -> Option<&str> {
+        self.0.content_type.as_deref()
+    }
+
+    /// The content of the file
+    pub fn content(&self) -> &[u8] {
+        &self.0.content
+    }
+}
+
+#[crate::graphql_scalar(description = "A file uploaded with a multipart request")]
+impl<S> GraphQLScalar for Upload
+where
+    S: ScalarValue,
+{
+    fn resolve(&self) -> Value {
+        match self.filename() {
+            Some(filename) => Value::scalar(filename.to_owned()),
+            None => Value::null(),
+        }
+    }
+
+    fn from_input_value(v: &InputValue) -> Option<Upload> {
+        v.as_string_value()
+            .and_then(|name| CURRENT_UPLOADS.with(|uploads| uploads.borrow().get(name)))
+    }
+
+    fn from_str<'a>(value: ScalarToken<'a>) -> ParseScalarResult<'a, S> {
+        Err(ParseError::UnexpectedToken(Token::Scalar(value)))
+    }
+}
+
+thread_local! {
+    /// The files of the request being executed on this thread
+    ///
+    /// Arguments are converted without access to the request they were sent with, so the
+    /// request makes its files available here while it's polled, and the [`Upload`] scalar
+    /// looks them up by the name of their field. A request can't get the files of another one,
+    /// as they are never available at the same time.
+    static CURRENT_UPLOADS: RefCell<Uploads> = RefCell::new(Uploads::default());
+}
+
+/// The files uploaded with a request, by the name of their field
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Uploads(Arc<HashMap<String, Arc<UploadedFile>>>);
+
+impl Uploads {
+    fn get(&self, name: &str) -> Option<Upload> {
+        self.0.get(name).map(|file| Upload(file.clone()))
+    }
+
+    /// Makes these files available to the [`Upload`] scalar while `f` runs
+    pub(crate) fn scope<R>(&self, f: impl FnOnce() -> R) -> R {
+        /// Restores the files of the outer scope, even if `f` panics
+        struct Restore(Option<Uploads>);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                let outer = self.0.take().unwrap_or_default();
+                CURRENT_UPLOADS.with(|uploads| *uploads.borrow_mut() = outer);
+            }
+        }
+
+        let outer = CURRENT_UPLOADS.with(|uploads| uploads.replace(self.clone()));
+        let _restore = Restore(Some(outer));
+        f()
+    }
+
+    /// Makes these files available to the [`Upload`] scalar whenever `future` is polled
+    pub(crate) async fn scope_future<F: Future>(&self, future: F) -> F::Output {
+        if self.0.is_empty() {
+            return future.await;
+        }
+        futures::pin_mut!(future);
+        future::poll_fn(|cx| self.scope(|| future.as_mut().poll(cx))).await
+    }
+
+    /// Makes these files available to the [`Upload`] scalar whenever `stream` is polled
+    pub(crate) fn scope_stream<'a, T: 'a>(&self, mut stream: BoxStream<'a, T>) -> BoxStream<'a, T> {
+        if self.0.is_empty() {
+            return stream;
+        }
+        let uploads = self.clone();
+        stream::poll_fn(move |cx| uploads.scope(|| stream.poll_next_unpin(cx))).boxed()
+    }
+}
+
+impl PartialEq for Uploads {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.len() == other.0.len()
+            && self.0.iter().all(
+                |(name, file)| matches!(other.0.get(name), Some(other) if Arc::ptr_eq(file, other)),
+            )
+    }
+}
+
+/// An error mapping the files of a multipart request into its variables
+#[derive(Debug, PartialEq)]
+pub enum MultipartError {
+    /// A path of the `map` field doesn't point to a variable of the operations
+    InvalidPath(String),
+    /// A file of the `map` field wasn't sent
+    MissingFile(String),
+}
+
+impl fmt::Display for MultipartError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MultipartError::InvalidPath(path) => {
+                write!(
+                    f,
+                    "'{}' isn't the path of a variable of the operations",
+                    path
+                )
+            }
+            MultipartError::MissingFile(name) => write!(f, "File '{}' is missing", name),
+        }
+    }
+}
+
+impl Error for MultipartError {}
+
+impl<S> GraphQLBatchRequest<S>
+where
+    S: ScalarValue,
+{
+    /// Puts the files of a multipart request into the variables of its operations
+    ///
+    /// `map` is the parsed `map` field of the request, and `files` are its file fields, by name.
+    /// Files which aren't mapped are ignored.
+    pub fn attach_uploads(
+        &mut self,
+        map: HashMap<String, Vec<String>>,
+        mut files: HashMap<String, UploadedFile>,
+    ) -> Result<(), MultipartError> {
+        for (name, paths) in map {
+            let file = files
+                .remove(&name)
+                .ok_or_else(|| MultipartError::MissingFile(name.clone()))?;
+            let file = Arc::new(file);
+
+            for path in paths {
+                let invalid = || MultipartError::InvalidPath(path.clone());
+                let mut segments = path.split('.');
+                let request = match &mut *self {
+                    GraphQLBatchRequest::Single(req) => req,
+                    GraphQLBatchRequest::Batch(reqs) => segments
+                        .next()
+                        .and_then(|i| i.parse::<usize>().ok())
+                        .and_then(move |i| reqs.get_mut(i))
+                        .ok_or_else(invalid)?,
+                };
+                request
+                    .attach_upload(segments, &name, &file)
+                    .ok_or_else(invalid)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<S> GraphQLRequest<S>
+where
+    S: ScalarValue,
+{
+    /// Replaces the variable at `path` with the name of a file, and adds the file to the ones of
+    /// this request
+    fn attach_upload<'p>(
+        &mut self,
+        mut path: impl Iterator<Item = &'p str>,
+        name: &str,
+        file: &Arc<UploadedFile>,
+    ) -> Option<()> {
+        if path.next() != Some("variables") {
+            return None;
+        }
+        let mut value = self.variables.as_mut()?;
+        for segment in path {
+            value = match value {
+                InputValue::Object(fields) => fields
+                    .iter_mut()
+                    .find(|(name, _)| name.item == segment)
+                    .map(|(_, value)| &mut value.item)?,
+                InputValue::List(items) => segment
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(move |i| items.get_mut(i))
+                    .map(|item| &mut item.item)?,
+                _ => return None,
+            };
+        }
+        *value = InputValue::scalar(name.to_owned());
+        Arc::make_mut(&mut self.uploads.0).insert(name.to_owned(), file.clone());
+        Some(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use futures::future;
+
+    use super::{MultipartError, Upload, UploadedFile};
+    use crate::{
+        http::{GraphQLBatchRequest, GraphQLRequest},
+        value::DefaultScalarValue,
+        EmptySubscription, InputValue, RootNode,
+    };
+
+    struct Query;
+
+    #[crate::graphql_object]
+    impl Query {
+        fn ping() -> bool {
+            true
+        }
+    }
+
+    struct Mutation;
+
+    #[crate::graphql_object]
+    impl Mutation {
+        fn upload(file: Upload) -> String {
+            format!(
+                "{} {} {}",
+                file.filename().unwrap_or_default(),
+                file.content_type().unwrap_or_default(),
+                String::from_utf8_lossy(file.content()),
+            )
+        }
+
+        fn upload_many(files: Vec<Upload>) -> i32 {
+            files.iter().map(|f| f.content().len() as i32).sum()
+        }
+    }
+
+    type Schema = RootNode<'static, Query, Mutation, EmptySubscription<()>>;
+
+    fn schema() -> Schema {
+        RootNode::new(Query, Mutation, EmptySubscription::new())
+    }
+
+    fn file(name: &str, content: &str) -> UploadedFile {
+        UploadedFile::new(
+            Some(name.into()),
+            Some("text/plain".into()),
+            content.as_bytes().to_vec(),
+        )
+    }
+
+    fn request(query: &str, variables: InputValue) -> GraphQLRequest {
+        GraphQLRequest::new(query.into(), None, Some(variables))
+    }
+
+    fn map(entries: &[(&str, &[&str])]) -> HashMap<String, Vec<String>> {
+        entries
+            .iter()
+            .map(|(name, paths)| {
+                (
+                    (*name).to_owned(),
+                    paths.iter().map(|p| (*p).to_owned()).collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn uploads_files() {
+        let schema = schema();
+        let mut req = GraphQLBatchRequest::Single(request(
+            "mutation($file: Upload!) { upload(file: $file) }",
+            InputValue::object(vec![("file", InputValue::null())].into_iter().collect()),
+        ));
+        let mut files = HashMap::new();
+        files.insert("0".to_owned(), file("a.txt", "hello"));
+
+        req.attach_uploads(map(&[("0", &["variables.file"])]), files)
+            .unwrap();
+        let res = req.execute_sync(&schema, &());
+
+        assert_eq!(
+            serde_json::to_value(&res).unwrap(),
+            serde_json::json!({"data": {"upload": "a.txt text/plain hello"}}),
+        );
+    }
+
+    #[test]
+    fn uploads_lists_of_files_in_batches() {
+        let schema = schema();
+        let query = "mutation($files: [Upload!]!) { uploadMany(files: $files) }";
+        let variables = || {
+            InputValue::object(
+                vec![(
+                    "files",
+                    InputValue::list(vec![InputValue::null(), InputValue::null()]),
+                )]
+                .into_iter()
+                .collect(),
+            )
+        };
+        let mut req = GraphQLBatchRequest::<DefaultScalarValue>::Batch(vec![
+            request(query, variables()),
+            request(query, variables()),
+        ]);
+        let mut files = HashMap::new();
+        files.insert("0".to_owned(), file("a.txt", "a"));
+        files.insert("1".to_owned(), file("b.txt", "bbb"));
+
+        req.attach_uploads(
+            map(&[
+                ("0", &["0.variables.files.0", "1.variables.files.0"]),
+                ("1", &["0.variables.files.1", "1.variables.files.1"]),
+            ]),
+            files,
+        )
+        .unwrap();
+        let res = req.execute_sync(&schema, &());
+
+        assert_eq!(
+            serde_json::to_value(&res).unwrap(),
+            serde_json::json!([
+                {"data": {"uploadMany": 4}},
+                {"data": {"uploadMany": 4}},
+            ]),
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_maps() {
+        let req = || {
+            GraphQLBatchRequest::<DefaultScalarValue>::Single(request(
+                "mutation($file: Upload!) { upload(file: $file) }",
+                InputValue::object(vec![("file", InputValue::null())].into_iter().collect()),
+            ))
+        };
+        let files = || {
+            let mut files = HashMap::new();
+            files.insert("0".to_owned(), file("a.txt", "hello"));
+            files
+        };
+
+        assert_eq!(
+            req().attach_uploads(map(&[("1", &["variables.file"])]), files()),
+            Err(MultipartError::MissingFile("1".into())),
+        );
+        for &path in &[
+            "file",
+            "variables.other",
+            "variables.file.0",
+            "0.variables.file",
+        ] {
+            assert_eq!(
+                req().attach_uploads(map(&[("0", &[path])]), files()),
+                Err(MultipartError::InvalidPath(path.into())),
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn resolves_only_files_of_the_request() {
+        let schema = schema();
+        let mut req = GraphQLBatchRequest::Single(request(
+            "mutation($file: Upload!) { upload(file: $file) }",
+            InputValue::object(vec![("file", InputValue::null())].into_iter().collect()),
+        ));
+        let mut files = HashMap::new();
+        files.insert("0".to_owned(), file("a.txt", "hello"));
+        req.attach_uploads(map(&[("0", &["variables.file"])]), files)
+            .unwrap();
+
+        let forged = request(
+            "mutation($file: Upload!) { upload(file: $file) }",
+            InputValue::object(
+                vec![("file", InputValue::scalar("0"))]
+                    .into_iter()
+                    .collect(),
+            ),
+        );
+        assert!(!forged.execute_sync(&schema, &()).is_ok());
+        let (res, forged_res) =
+            future::join(req.execute(&schema, &()), forged.execute(&schema, &())).await;
+        assert!(res.is_ok());
+        assert!(!forged_res.is_ok());
+    }
+}
@@ -0,0 +1,251 @@
+//! Utilities for [Automatic Persisted Queries][1], letting clients send the hash of a query
+//! instead of the query itself
+//!
+//! A client first sends only the SHA-256 hash of its query in the `persistedQuery` extension of
+//! the request. If the query isn't stored yet, the request is rejected with a
+//! `PersistedQueryNotFound` error, and the client retries with both the query and its hash, which
+//! stores the query for the following requests. Executing a [`crate::http::GraphQLRequest`] loads
+//! its query from the store.
+//!
+//! Persisted queries are enabled by providing a store with [`crate::RootNode::with_persisted_queries`].
+//!
+//! [1]: https://www.apollographql.com/docs/apollo-server/performance/apq/
+
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::util::LruCache;
+
+/// The `persistedQuery` extension of a request
+#[derive(Deserialize, Clone, Serialize, PartialEq, Debug)]
+pub struct PersistedQuery {
+    /// The version of the protocol, of which only `1` is supported
+    pub version: u32,
+    /// The SHA-256 hash of the query, as lowercase hexadecimal
+    #[serde(rename = "sha256Hash")]
+    pub sha256_hash: String,
+}
+
+impl PersistedQuery {
+    /// Constructs the extension for the given query
+    pub fn new(query: &str) -> Self {
+        PersistedQuery {
+            version: 1,
+            sha256_hash: sha256_hash(query),
+        }
+    }
+}
+
+/// Computes the SHA-256 hash of a query, as lowercase hexadecimal
+pub fn sha256_hash(query: &str) -> String {
+    Sha256::digest(query.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// A store of persisted queries, resolving the hashes sent by clients to their queries
+pub trait PersistedQueryStore: Send + Sync {
+    /// Returns the query with the given hash, if it's stored
+    fn get(&self, sha256_hash: &str) -> Option<String>;
+
+    /// Stores a query under its hash
+    ///
+    /// The hash is checked to match the query before it's stored.
+    fn insert(&self, sha256_hash: String, query: String);
+}
+
+impl<T> PersistedQueryStore for Arc<T>
+where
+    T: PersistedQueryStore + ?Sized,
+{
+    fn get(&self, sha256_hash: &str) -> Option<String> {
+        (**self).get(sha256_hash)
+    }
+
+    fn insert(&self, sha256_hash: String, query: String) {
+        (**self).insert(sha256_hash, query)
+    }
+}
+
+impl fmt::Debug for dyn PersistedQueryStore {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("PersistedQueryStore")
+    }
+}
+
+/// A persisted query store keeping a bounded number of queries in memory
+///
+/// The least recently used query is evicted when a new query is stored in a full store.
+#[derive(Debug)]
+pub struct InMemoryPersistedQueryStore {
+    cache: Mutex<LruCache<String, String>>,
+}
+
+impl InMemoryPersistedQueryStore {
+    /// Constructs a store holding at most `capacity` queries
+    pub fn new(capacity: usize) -> Self {
+        InMemoryPersistedQueryStore {
+            cache: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+}
+
+impl PersistedQueryStore for InMemoryPersistedQueryStore {
+    fn get(&self, sha256_hash: &str) -> Option<String> {
+        self.cache.lock().unwrap().get(sha256_hash)
+    }
+
+    fn insert(&self, sha256_hash: String, query: String) {
+        self.cache.lock().unwrap().insert(sha256_hash, query)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sha256_hash, InMemoryPersistedQueryStore, PersistedQuery, PersistedQueryStore};
+
+    use crate::{
+        http::{GraphQLRequest, GraphQLRequestExtensions},
+        tests::fixtures::starwars::schema::{Database, Query},
+        value::DefaultScalarValue,
+        EmptyMutation, EmptySubscription, GraphQLError, RootNode,
+    };
+
+    type Schema = RootNode<'static, Query, EmptyMutation<Database>, EmptySubscription<Database>>;
+
+    const QUERY: &str = "{ hero { name } }";
+
+    fn request(query: &str, persisted_query: PersistedQuery) -> GraphQLRequest {
+        GraphQLRequest::<DefaultScalarValue>::new(query.into(), None, None).with_extensions(
+            GraphQLRequestExtensions {
+                persisted_query: Some(persisted_query),
+            },
+        )
+    }
+
+    fn execute(schema: &Schema, request: GraphQLRequest) -> Result<(), String> {
+        request
+            .execute_sync(schema, &Database::new())
+            .result
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+
+    fn schema() -> Schema {
+        Schema::new(Query, EmptyMutation::new(), EmptySubscription::new())
+    }
+
+    #[test]
+    fn stores_queries_sent_with_their_hash() {
+        let schema = schema().with_persisted_queries(InMemoryPersistedQueryStore::new(10));
+
+        assert_eq!(
+            execute(&schema, request("", PersistedQuery::new(QUERY))),
+            Err(GraphQLError::PersistedQueryNotFound.to_string()),
+        );
+        assert_eq!(
+            execute(&schema, request(QUERY, PersistedQuery::new(QUERY))),
+            Ok(()),
+        );
+        assert_eq!(
+            execute(&schema, request("", PersistedQuery::new(QUERY))),
+            Ok(()),
+        );
+    }
+
+    #[test]
+    fn loads_stored_queries_before_execution() {
+        let schema = schema().with_persisted_queries(InMemoryPersistedQueryStore::new(10));
+        execute(&schema, request(QUERY, PersistedQuery::new(QUERY))).unwrap();
+
+        let mut request = request("", PersistedQuery::new(QUERY));
+        request.load_persisted_query(&schema);
+
+        assert_eq!(
+            request,
+            GraphQLRequest::new(QUERY.into(), None, None)
+                .with_extensions(GraphQLRequestExtensions::default())
+        );
+        assert_eq!(execute(&schema, request), Ok(()));
+    }
+
+    #[test]
+    fn rejects_mismatching_hashes() {
+        let schema = schema().with_persisted_queries(InMemoryPersistedQueryStore::new(10));
+
+        assert_eq!(
+            execute(
+                &schema,
+                request(QUERY, PersistedQuery::new("{ hero { id } }"))
+            ),
+            Err(GraphQLError::PersistedQueryHashMismatch.to_string()),
+        );
+        assert_eq!(
+            execute(&schema, request("", PersistedQuery::new("{ hero { id } }"))),
+            Err(GraphQLError::PersistedQueryNotFound.to_string()),
+        );
+    }
+
+    #[test]
+    fn rejects_unsupported_versions() {
+        let schema = schema().with_persisted_queries(InMemoryPersistedQueryStore::new(10));
+        let persisted_query = PersistedQuery {
+            version: 2,
+            ..PersistedQuery::new(QUERY)
+        };
+
+        assert_eq!(
+            execute(&schema, request(QUERY, persisted_query)),
+            Err(GraphQLError::PersistedQueryNotSupported.to_string()),
+        );
+    }
+
+    #[test]
+    fn rejects_hashes_without_store() {
+        assert_eq!(
+            execute(&schema(), request("", PersistedQuery::new(QUERY))),
+            Err(GraphQLError::PersistedQueryNotSupported.to_string()),
+        );
+        assert_eq!(
+            execute(&schema(), request(QUERY, PersistedQuery::new(QUERY))),
+            Ok(()),
+        );
+    }
+
+    #[test]
+    fn hashes_queries() {
+        assert_eq!(
+            sha256_hash("{__typename}"),
+            "ecf4edb46db40b5132295c0291d62fb65d6759a9eedfa4d5d612dd5ec54a6b38",
+        );
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let store = InMemoryPersistedQueryStore::new(2);
+        store.insert("a".into(), "{ a }".into());
+        store.insert("b".into(), "{ b }".into());
+
+        assert_eq!(store.get("a"), Some("{ a }".into()));
+
+        store.insert("c".into(), "{ c }".into());
+
+        assert_eq!(store.get("a"), Some("{ a }".into()));
+        assert_eq!(store.get("b"), None);
+        assert_eq!(store.get("c"), Some("{ c }".into()));
+    }
+
+    #[test]
+    fn stores_nothing_without_capacity() {
+        let store = InMemoryPersistedQueryStore::new(0);
+        store.insert("a".into(), "{ a }".into());
+
+        assert_eq!(store.get("a"), None);
+    }
+}
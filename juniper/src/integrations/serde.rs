@@ -9,7 +9,7 @@ use std::fmt;
 
 use crate::{
     ast::InputValue,
-    executor::ExecutionError,
+    executor::{ExecutionError, IncrementalData, IncrementalPayload, PathSegment},
     parser::{ParseError, SourcePosition, Spanning},
     validation::RuleError,
     GraphQLError, Object, ScalarValue, Value,
@@ -18,6 +18,12 @@ use crate::{
 #[derive(Serialize)]
 struct SerializeHelper {
     message: &'static str,
+    extensions: CodeHelper,
+}
+
+#[derive(Serialize)]
+struct CodeHelper {
+    code: &'static str,
 }
 
 impl<T> ser::Serialize for ExecutionError<T>
@@ -54,31 +60,25 @@ impl<'a> ser::Serialize for GraphQLError<'a> {
     where
         S: ser::Serializer,
     {
-        match *self {
-            GraphQLError::ParseError(ref err) => vec![err].serialize(serializer),
-            GraphQLError::ValidationError(ref errs) => errs.serialize(serializer),
-            GraphQLError::NoOperationProvided => [SerializeHelper {
-                message: "Must provide an operation",
-            }]
-            .serialize(serializer),
-            GraphQLError::MultipleOperationsProvided => [SerializeHelper {
-                message: "Must provide operation name \
-                          if query contains multiple operations",
-            }]
-            .serialize(serializer),
-            GraphQLError::UnknownOperationName => [SerializeHelper {
-                message: "Unknown operation",
-            }]
-            .serialize(serializer),
-            GraphQLError::IsSubscription => [SerializeHelper {
-                message: "Expected query, got subscription",
-            }]
-            .serialize(serializer),
-            GraphQLError::NotSubscription => [SerializeHelper {
-                message: "Expected subscription, got query",
-            }]
-            .serialize(serializer),
-        }
+        let message = match *self {
+            GraphQLError::ParseError(ref err) => return vec![err].serialize(serializer),
+            GraphQLError::ValidationError(ref errs) => return errs.serialize(serializer),
+            GraphQLError::NoOperationProvided => "Must provide an operation",
+            GraphQLError::MultipleOperationsProvided => {
+                "Must provide operation name if query contains multiple operations"
+            }
+            GraphQLError::UnknownOperationName => "Unknown operation",
+            GraphQLError::IsSubscription => "Expected query, got subscription",
+            GraphQLError::NotSubscription => "Expected subscription, got query",
+            GraphQLError::PersistedQueryNotFound => "PersistedQueryNotFound",
+            GraphQLError::PersistedQueryNotSupported => "PersistedQueryNotSupported",
+            GraphQLError::PersistedQueryHashMismatch => "provided sha does not match query",
+        };
+        [SerializeHelper {
+            message,
+            extensions: CodeHelper { code: self.code() },
+        }]
+        .serialize(serializer)
     }
 }
 
@@ -316,7 +316,7 @@ impl ser::Serialize for RuleError {
     where
         S: ser::Serializer,
     {
-        let mut map = serializer.serialize_map(Some(2))?;
+        let mut map = serializer.serialize_map(Some(3))?;
 
         map.serialize_key("message")?;
         map.serialize_value(self.message())?;
@@ -324,6 +324,11 @@ impl ser::Serialize for RuleError {
         map.serialize_key("locations")?;
         map.serialize_value(self.locations())?;
 
+        map.serialize_key("extensions")?;
+        map.serialize_value(&CodeHelper {
+            code: "GRAPHQL_VALIDATION_FAILED",
+        })?;
+
         map.end()
     }
 }
@@ -352,7 +357,7 @@ impl<'a> ser::Serialize for Spanning<ParseError<'a>> {
     where
         S: ser::Serializer,
     {
-        let mut map = serializer.serialize_map(Some(2))?;
+        let mut map = serializer.serialize_map(Some(3))?;
 
         let message = format!("{}", self.item);
         map.serialize_key("message")?;
@@ -367,6 +372,11 @@ impl<'a> ser::Serialize for Spanning<ParseError<'a>> {
         map.serialize_key("locations")?;
         map.serialize_value(&locations)?;
 
+        map.serialize_key("extensions")?;
+        map.serialize_value(&CodeHelper {
+            code: "GRAPHQL_PARSE_FAILED",
+        })?;
+
         map.end()
     }
 }
@@ -407,11 +417,66 @@ where
     }
 }
 
+impl ser::Serialize for PathSegment {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        match *self {
+            PathSegment::Field(ref name) => serializer.serialize_str(name),
+            PathSegment::Index(index) => serializer.serialize_u64(index as u64),
+        }
+    }
+}
+
+impl<T> ser::Serialize for IncrementalPayload<T>
+where
+    T: ScalarValue,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        let mut map = serializer.serialize_map(None)?;
+
+        match *self.data() {
+            IncrementalData::Data(ref data) => {
+                map.serialize_key("data")?;
+                map.serialize_value(data)?;
+            }
+            IncrementalData::Items(ref items) => {
+                map.serialize_key("items")?;
+                map.serialize_value(items)?;
+            }
+        }
+
+        map.serialize_key("path")?;
+        map.serialize_value(self.path())?;
+
+        if let Some(label) = self.label() {
+            map.serialize_key("label")?;
+            map.serialize_value(label)?;
+        }
+
+        if !self.errors().is_empty() {
+            map.serialize_key("errors")?;
+            map.serialize_value(self.errors())?;
+        }
+
+        map.serialize_key("hasNext")?;
+        map.serialize_value(&self.has_next())?;
+
+        map.end()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{ExecutionError, GraphQLError};
     use crate::{
         ast::InputValue,
+        executor::{IncrementalData, IncrementalPayload, PathSegment},
+        parser::SourcePosition,
         value::{DefaultScalarValue, Object},
         FieldError, Value,
     };
@@ -442,7 +507,7 @@ mod tests {
     fn errors() {
         assert_eq!(
             to_string(&GraphQLError::UnknownOperationName).unwrap(),
-            r#"[{"message":"Unknown operation"}]"#
+            r#"[{"message":"Unknown operation","extensions":{"code":"BAD_USER_INPUT"}}]"#
         );
     }
 
@@ -459,4 +524,40 @@ mod tests {
             r#"{"message":"foo error","locations":[{"line":1,"column":1}],"path":[],"extensions":{"foo":"bar"}}"#
         );
     }
+
+    #[test]
+    fn error_paths() {
+        assert_eq!(
+            to_string(&ExecutionError::<DefaultScalarValue>::at_path(
+                SourcePosition::new(2, 0, 2),
+                vec![
+                    PathSegment::Field("users".into()),
+                    PathSegment::Index(3),
+                    PathSegment::Field("name".into()),
+                ],
+                FieldError::new("name error", Value::null()),
+            ))
+            .unwrap(),
+            r#"{"message":"name error","locations":[{"line":1,"column":3}],"path":["users",3,"name"]}"#
+        );
+    }
+
+    #[test]
+    fn incremental_payloads() {
+        let payload = IncrementalPayload::<DefaultScalarValue> {
+            label: Some("friends".into()),
+            path: vec![
+                PathSegment::Field("hero".into()),
+                PathSegment::Field("friends".into()),
+                PathSegment::Index(1),
+            ],
+            data: IncrementalData::Items(graphql_value!([{"name": "Leia"}])),
+            errors: vec![],
+            has_next: false,
+        };
+        assert_eq!(
+            to_string(&payload).unwrap(),
+            r#"{"items":[{"name":"Leia"}],"path":["hero","friends",1],"label":"friends","hasNext":false}"#
+        );
+    }
 }
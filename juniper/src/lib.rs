@@ -97,7 +97,7 @@ Juniper has not reached 1.0 yet, thus some API instability should be expected.
 // path correctly, without errors.
 extern crate self as juniper;
 
-use std::fmt;
+use std::{fmt, sync::Arc};
 
 // These are required by the code generated via the `juniper_codegen` macros.
 #[doc(hidden)]
@@ -112,6 +112,7 @@ pub use futures::future::{BoxFuture, LocalBoxFuture};
 pub use juniper_codegen::{
     graphql_interface, graphql_object, graphql_scalar, graphql_subscription, graphql_union,
     GraphQLEnum, GraphQLInputObject, GraphQLObject, GraphQLScalarValue, GraphQLUnion,
+    IntoFieldError,
 };
 
 #[macro_use]
@@ -119,9 +120,13 @@ mod value;
 #[macro_use]
 mod macros;
 pub mod ast;
+pub mod dataloader;
 pub mod executor;
+#[cfg(feature = "federation")]
+pub mod federation;
 mod introspection;
 pub mod parser;
+pub mod relay;
 pub(crate) mod schema;
 mod types;
 mod util;
@@ -143,27 +148,35 @@ mod executor_tests;
 pub use crate::util::to_camel_case;
 
 use crate::{
+    ast::{Document, Operation, OwnedDocument},
     executor::{execute_validated_query, get_operation},
     introspection::{INTROSPECTION_QUERY, INTROSPECTION_QUERY_WITHOUT_DESCRIPTIONS},
     parser::{parse_document_source, ParseError, Spanning},
-    validation::{validate_input_values, visit_all_rules, ValidatorContext},
+    schema::visibility::HiddenItems,
+    validation::{
+        validate_visible_input_values, visit_limit_rules, DocumentCache, RuleSet, ValidatorContext,
+    },
 };
 
 pub use crate::{
-    ast::{FromInputValue, InputValue, Selection, ToInputValue, Type},
+    ast::{FromInputValue, InputValue, InputValueError, Selection, ToInputValue, Type},
     executor::{
-        Applies, Context, ExecutionError, ExecutionResult, Executor, FieldError, FieldResult,
-        FromContext, IntoFieldError, IntoResolvable, LookAheadArgument, LookAheadMethods,
-        LookAheadSelection, LookAheadValue, OwnedExecutor, Registry, ValuesStream, Variables,
+        ApolloTracing, Applies, Context, DirectiveContext, DirectiveResolver, ExecutionError,
+        ExecutionResult, Executor, Extension, ExtensionFactory, Extensions, FieldError,
+        FieldResult, FromContext, Guard, IncrementalData, IncrementalPayload, IncrementalStream,
+        IntoFieldError, IntoResolvable, LookAheadArgument, LookAheadMethods, LookAheadSelection,
+        LookAheadValue, OwnedExecutor, PathSegment, Registry, ResolvedField, ValuesStream,
+        Variables,
     },
     introspection::IntrospectionFormat,
     macros::helper::{
         subscription::{ExtractTypeFromStream, IntoFieldResult},
-        AsDynGraphQLValue,
+        AsDynGraphQLValue, InterfaceGuards,
     },
     schema::{
+        compatibility::{ChangeCriticality, SchemaChange},
         meta,
-        model::{RootNode, SchemaType},
+        model::{DirectiveLocation, DirectiveType, RootNode, SchemaType},
     },
     types::{
         async_await::{DynGraphQLValueAsync, GraphQLTypeAsync, GraphQLValueAsync},
@@ -180,6 +193,9 @@ pub use crate::{
     value::{DefaultScalarValue, Object, ParseScalarResult, ParseScalarValue, ScalarValue, Value},
 };
 
+#[cfg(feature = "graphql-parser-integration")]
+pub use crate::schema::schema_language::SchemaLanguageError;
+
 /// An error that prevented query execution
 #[derive(Debug, PartialEq)]
 #[allow(missing_docs)]
@@ -191,6 +207,9 @@ pub enum GraphQLError<'a> {
     UnknownOperationName,
     IsSubscription,
     NotSubscription,
+    PersistedQueryNotFound,
+    PersistedQueryNotSupported,
+    PersistedQueryHashMismatch,
 }
 
 impl<'a> fmt::Display for GraphQLError<'a> {
@@ -208,6 +227,31 @@ impl<'a> fmt::Display for GraphQLError<'a> {
             GraphQLError::UnknownOperationName => write!(f, "Unknown operation name"),
             GraphQLError::IsSubscription => write!(f, "Operation is a subscription"),
             GraphQLError::NotSubscription => write!(f, "Operation is not a subscription"),
+            GraphQLError::PersistedQueryNotFound => write!(f, "Persisted query not found"),
+            GraphQLError::PersistedQueryNotSupported => {
+                write!(f, "Persisted queries are not supported")
+            }
+            GraphQLError::PersistedQueryHashMismatch => {
+                write!(f, "Persisted query hash doesn't match the query")
+            }
+        }
+    }
+}
+
+impl<'a> GraphQLError<'a> {
+    /// The `extensions.code` of the error, as sent to clients
+    pub fn code(&self) -> &'static str {
+        match *self {
+            GraphQLError::ParseError(_) => "GRAPHQL_PARSE_FAILED",
+            GraphQLError::ValidationError(_) => "GRAPHQL_VALIDATION_FAILED",
+            GraphQLError::NoOperationProvided
+            | GraphQLError::MultipleOperationsProvided
+            | GraphQLError::UnknownOperationName => "BAD_USER_INPUT",
+            GraphQLError::IsSubscription
+            | GraphQLError::NotSubscription
+            | GraphQLError::PersistedQueryHashMismatch => "BAD_REQUEST",
+            GraphQLError::PersistedQueryNotFound => "PERSISTED_QUERY_NOT_FOUND",
+            GraphQLError::PersistedQueryNotSupported => "PERSISTED_QUERY_NOT_SUPPORTED",
         }
     }
 }
@@ -215,6 +259,9 @@ impl<'a> fmt::Display for GraphQLError<'a> {
 impl<'a> std::error::Error for GraphQLError<'a> {}
 
 /// Execute a query synchronously in a provided schema
+///
+/// The document is looked up in the document cache of the schema, if it has one, and the
+/// extensions registered in the schema are notified.
 pub fn execute_sync<'a, S, QueryT, MutationT, SubscriptionT>(
     document_source: &'a str,
     operation_name: Option<&str>,
@@ -228,32 +275,129 @@ where
     MutationT: GraphQLType<S, Context = QueryT::Context>,
     SubscriptionT: GraphQLType<S, Context = QueryT::Context>,
 {
-    let document = parse_document_source(document_source, &root_node.schema)?;
-
-    {
-        let mut ctx = ValidatorContext::new(&root_node.schema, &document);
-        visit_all_rules(&mut ctx, &document);
-
-        let errors = ctx.into_errors();
-        if !errors.is_empty() {
-            return Err(GraphQLError::ValidationError(errors));
-        }
-    }
+    execute_sync_with_extensions(
+        document_source,
+        operation_name,
+        root_node,
+        variables,
+        context,
+        &root_node.create_extensions(),
+    )
+}
 
-    let operation = get_operation(&document, operation_name)?;
+/// Execute a query synchronously in a provided schema, validating it with the provided rules
+pub fn execute_sync_with_rules<'a, S, QueryT, MutationT, SubscriptionT>(
+    document_source: &'a str,
+    operation_name: Option<&str>,
+    root_node: &'a RootNode<QueryT, MutationT, SubscriptionT, S>,
+    variables: &Variables<S>,
+    context: &QueryT::Context,
+    rules: &RuleSet<S>,
+) -> Result<(Value<S>, Vec<ExecutionError<S>>), GraphQLError<'a>>
+where
+    S: ScalarValue,
+    QueryT: GraphQLType<S>,
+    MutationT: GraphQLType<S, Context = QueryT::Context>,
+    SubscriptionT: GraphQLType<S, Context = QueryT::Context>,
+{
+    execute_sync_impl(
+        document_source,
+        operation_name,
+        root_node,
+        variables,
+        context,
+        rules,
+        None,
+        &root_node.create_extensions(),
+    )
+}
 
-    {
-        let errors = validate_input_values(variables, operation, &root_node.schema);
+/// Execute a query synchronously in a provided schema, notifying the provided extensions
+///
+/// The results of the extensions can be collected with [`Extensions::results`] afterwards.
+pub fn execute_sync_with_extensions<'a, S, QueryT, MutationT, SubscriptionT>(
+    document_source: &'a str,
+    operation_name: Option<&str>,
+    root_node: &'a RootNode<QueryT, MutationT, SubscriptionT, S>,
+    variables: &Variables<S>,
+    context: &QueryT::Context,
+    extensions: &Extensions<S>,
+) -> Result<(Value<S>, Vec<ExecutionError<S>>), GraphQLError<'a>>
+where
+    S: ScalarValue,
+    QueryT: GraphQLType<S>,
+    MutationT: GraphQLType<S, Context = QueryT::Context>,
+    SubscriptionT: GraphQLType<S, Context = QueryT::Context>,
+{
+    execute_sync_impl(
+        document_source,
+        operation_name,
+        root_node,
+        variables,
+        context,
+        &root_node.rules,
+        root_node.document_cache.as_ref(),
+        extensions,
+    )
+}
 
-        if !errors.is_empty() {
-            return Err(GraphQLError::ValidationError(errors));
-        }
-    }
+#[allow(clippy::too_many_arguments)]
+fn execute_sync_impl<'a, S, QueryT, MutationT, SubscriptionT>(
+    document_source: &'a str,
+    operation_name: Option<&str>,
+    root_node: &'a RootNode<QueryT, MutationT, SubscriptionT, S>,
+    variables: &Variables<S>,
+    context: &QueryT::Context,
+    rules: &RuleSet<S>,
+    cache: Option<&DocumentCache<S>>,
+    extensions: &Extensions<S>,
+) -> Result<(Value<S>, Vec<ExecutionError<S>>), GraphQLError<'a>>
+where
+    S: ScalarValue,
+    QueryT: GraphQLType<S>,
+    MutationT: GraphQLType<S, Context = QueryT::Context>,
+    SubscriptionT: GraphQLType<S, Context = QueryT::Context>,
+{
+    extensions.parse_start(document_source);
+    let document = parse_document(document_source, &root_node.schema, cache);
+    extensions.parse_end();
+    let document = document?;
+
+    extensions.validation_start();
+    let hidden = root_node.hidden_items(context);
+    let operation = validate(
+        &document,
+        operation_name,
+        root_node,
+        hidden.as_deref(),
+        variables,
+        rules,
+        cache,
+    );
+    extensions.validation_end(validation_errors(&operation));
+    let operation = operation?;
+
+    extensions.execution_start(
+        operation.item.name.as_ref().map(|name| name.item),
+        &operation.item.operation_type,
+    );
+    let result = execute_validated_query(
+        document.document(),
+        operation,
+        root_node,
+        variables,
+        context,
+        extensions,
+    );
+    extensions.execution_end();
 
-    execute_validated_query(&document, operation, root_node, variables, context)
+    result
 }
 
 /// Execute a query in a provided schema
+///
+/// The document is looked up in the document cache of the schema, if it has one, and the
+/// extensions registered in the schema are notified.
 pub async fn execute<'a, S, QueryT, MutationT, SubscriptionT>(
     document_source: &'a str,
     operation_name: Option<&str>,
@@ -271,30 +415,218 @@ where
     SubscriptionT::TypeInfo: Sync,
     S: ScalarValue + Send + Sync,
 {
-    let document = parse_document_source(document_source, &root_node.schema)?;
+    execute_with_extensions(
+        document_source,
+        operation_name,
+        root_node,
+        variables,
+        context,
+        &root_node.create_extensions(),
+    )
+    .await
+}
 
-    {
-        let mut ctx = ValidatorContext::new(&root_node.schema, &document);
-        visit_all_rules(&mut ctx, &document);
+/// Execute a query in a provided schema, validating it with the provided rules
+pub async fn execute_with_rules<'a, S, QueryT, MutationT, SubscriptionT>(
+    document_source: &'a str,
+    operation_name: Option<&str>,
+    root_node: &'a RootNode<'a, QueryT, MutationT, SubscriptionT, S>,
+    variables: &Variables<S>,
+    context: &QueryT::Context,
+    rules: &RuleSet<S>,
+) -> Result<(Value<S>, Vec<ExecutionError<S>>), GraphQLError<'a>>
+where
+    QueryT: GraphQLTypeAsync<S>,
+    QueryT::TypeInfo: Sync,
+    QueryT::Context: Sync,
+    MutationT: GraphQLTypeAsync<S, Context = QueryT::Context>,
+    MutationT::TypeInfo: Sync,
+    SubscriptionT: GraphQLType<S, Context = QueryT::Context> + Sync,
+    SubscriptionT::TypeInfo: Sync,
+    S: ScalarValue + Send + Sync,
+{
+    execute_impl(
+        document_source,
+        operation_name,
+        root_node,
+        variables,
+        context,
+        rules,
+        None,
+        &root_node.create_extensions(),
+    )
+    .await
+}
 
-        let errors = ctx.into_errors();
-        if !errors.is_empty() {
-            return Err(GraphQLError::ValidationError(errors));
-        }
-    }
+/// Execute a query in a provided schema, notifying the provided extensions
+///
+/// The results of the extensions can be collected with [`Extensions::results`] afterwards.
+pub async fn execute_with_extensions<'a, S, QueryT, MutationT, SubscriptionT>(
+    document_source: &'a str,
+    operation_name: Option<&str>,
+    root_node: &'a RootNode<'a, QueryT, MutationT, SubscriptionT, S>,
+    variables: &Variables<S>,
+    context: &QueryT::Context,
+    extensions: &Extensions<S>,
+) -> Result<(Value<S>, Vec<ExecutionError<S>>), GraphQLError<'a>>
+where
+    QueryT: GraphQLTypeAsync<S>,
+    QueryT::TypeInfo: Sync,
+    QueryT::Context: Sync,
+    MutationT: GraphQLTypeAsync<S, Context = QueryT::Context>,
+    MutationT::TypeInfo: Sync,
+    SubscriptionT: GraphQLType<S, Context = QueryT::Context> + Sync,
+    SubscriptionT::TypeInfo: Sync,
+    S: ScalarValue + Send + Sync,
+{
+    execute_impl(
+        document_source,
+        operation_name,
+        root_node,
+        variables,
+        context,
+        &root_node.rules,
+        root_node.document_cache.as_ref(),
+        extensions,
+    )
+    .await
+}
 
-    let operation = get_operation(&document, operation_name)?;
+#[allow(clippy::too_many_arguments)]
+async fn execute_impl<'a, S, QueryT, MutationT, SubscriptionT>(
+    document_source: &'a str,
+    operation_name: Option<&str>,
+    root_node: &'a RootNode<'a, QueryT, MutationT, SubscriptionT, S>,
+    variables: &Variables<S>,
+    context: &QueryT::Context,
+    rules: &RuleSet<S>,
+    cache: Option<&DocumentCache<S>>,
+    extensions: &Extensions<S>,
+) -> Result<(Value<S>, Vec<ExecutionError<S>>), GraphQLError<'a>>
+where
+    QueryT: GraphQLTypeAsync<S>,
+    QueryT::TypeInfo: Sync,
+    QueryT::Context: Sync,
+    MutationT: GraphQLTypeAsync<S, Context = QueryT::Context>,
+    MutationT::TypeInfo: Sync,
+    SubscriptionT: GraphQLType<S, Context = QueryT::Context> + Sync,
+    SubscriptionT::TypeInfo: Sync,
+    S: ScalarValue + Send + Sync,
+{
+    extensions.parse_start(document_source);
+    let document = parse_document(document_source, &root_node.schema, cache);
+    extensions.parse_end();
+    let document = document?;
+
+    extensions.validation_start();
+    let hidden = root_node.hidden_items(context);
+    let operation = validate(
+        &document,
+        operation_name,
+        root_node,
+        hidden.as_deref(),
+        variables,
+        rules,
+        cache,
+    );
+    extensions.validation_end(validation_errors(&operation));
+    let operation = operation?;
+
+    extensions.execution_start(
+        operation.item.name.as_ref().map(|name| name.item),
+        &operation.item.operation_type,
+    );
+    let result = executor::execute_validated_query_async(
+        document.document(),
+        operation,
+        root_node,
+        variables,
+        context,
+        extensions,
+    )
+    .await;
+    extensions.execution_end();
 
-    {
-        let errors = validate_input_values(variables, operation, &root_node.schema);
+    result
+}
 
-        if !errors.is_empty() {
-            return Err(GraphQLError::ValidationError(errors));
-        }
-    }
+/// Execute a query in a provided schema, delivering its deferred fragments and streamed lists
+/// incrementally, and notifying the provided extensions
+///
+/// Returns the initial response, along with the stream of the subsequent payloads if the query
+/// deferred or streamed anything. The `@defer` and `@stream` directives have to be enabled with
+/// [`RootNode::with_incremental_delivery`], and mutations are always executed at once.
+///
+/// The deferred fragments and the remaining items of the streamed lists are resolved from the
+/// values resolved for the initial response, and each of them is delivered once the payload it
+/// was found in is. The extensions only observe the execution of the initial response.
+pub async fn execute_incremental<'a, S, QueryT, MutationT, SubscriptionT>(
+    document_source: &'a str,
+    operation_name: Option<&'a str>,
+    root_node: &'a RootNode<'a, QueryT, MutationT, SubscriptionT, S>,
+    variables: &Variables<S>,
+    context: &'a QueryT::Context,
+    extensions: &Extensions<S>,
+) -> Result<
+    (
+        Value<S>,
+        Vec<ExecutionError<S>>,
+        Option<IncrementalStream<'a, S>>,
+    ),
+    GraphQLError<'a>,
+>
+where
+    QueryT: GraphQLTypeAsync<S>,
+    QueryT::TypeInfo: Sync,
+    QueryT::Context: Sync,
+    MutationT: GraphQLTypeAsync<S, Context = QueryT::Context>,
+    MutationT::TypeInfo: Sync,
+    SubscriptionT: GraphQLType<S, Context = QueryT::Context> + Sync,
+    SubscriptionT::TypeInfo: Sync,
+    S: ScalarValue + Send + Sync + 'a,
+{
+    // The subsequent payloads borrow the document, so it can't be taken from the document cache.
+    extensions.parse_start(document_source);
+    let document = parse_document_source(document_source, &root_node.schema);
+    extensions.parse_end();
+    let document: Document<'a, S> = document?;
+
+    extensions.validation_start();
+    let hidden = root_node.hidden_items(context);
+    let operation = validate_document(
+        &document,
+        &root_node.schema,
+        hidden.as_deref(),
+        &root_node.rules,
+    )
+    .and_then(|()| {
+        validate_operation(
+            &document,
+            operation_name,
+            root_node,
+            hidden.as_deref(),
+            variables,
+        )
+    });
+    extensions.validation_end(validation_errors(&operation));
+    let operation = operation?;
+
+    extensions.execution_start(
+        operation.item.name.as_ref().map(|name| name.item),
+        &operation.item.operation_type,
+    );
+    let result = executor::execute_validated_query_incremental(
+        document,
+        operation_name,
+        root_node,
+        variables.clone(),
+        context,
+        extensions.clone(),
+    )
+    .await;
+    extensions.execution_end();
 
-    executor::execute_validated_query_async(&document, operation, root_node, variables, context)
-        .await
+    result
 }
 
 /// Resolve subscription into `ValuesStream`
@@ -315,12 +647,101 @@ where
     SubscriptionT::TypeInfo: Sync,
     S: ScalarValue + Send + Sync,
 {
-    let document: crate::ast::Document<'a, S> =
-        parse_document_source(document_source, &root_node.schema)?;
+    resolve_into_stream_with_rules(
+        document_source,
+        operation_name,
+        root_node,
+        variables,
+        context,
+        &root_node.rules,
+    )
+    .await
+}
+
+/// Resolve subscription into `ValuesStream`, validating it with the provided rules
+pub async fn resolve_into_stream_with_rules<'a, S, QueryT, MutationT, SubscriptionT>(
+    document_source: &'a str,
+    operation_name: Option<&str>,
+    root_node: &'a RootNode<'a, QueryT, MutationT, SubscriptionT, S>,
+    variables: &Variables<S>,
+    context: &'a QueryT::Context,
+    rules: &RuleSet<S>,
+) -> Result<(Value<ValuesStream<'a, S>>, Vec<ExecutionError<S>>), GraphQLError<'a>>
+where
+    QueryT: GraphQLTypeAsync<S>,
+    QueryT::TypeInfo: Sync,
+    QueryT::Context: Sync,
+    MutationT: GraphQLTypeAsync<S, Context = QueryT::Context>,
+    MutationT::TypeInfo: Sync,
+    SubscriptionT: GraphQLSubscriptionType<S, Context = QueryT::Context>,
+    SubscriptionT::TypeInfo: Sync,
+    S: ScalarValue + Send + Sync,
+{
+    // The resulting stream borrows the document, so it can't be taken from the document cache.
+    let document: Document<'a, S> = parse_document_source(document_source, &root_node.schema)?;
+    let hidden = root_node.hidden_items(context);
+    validate_document(&document, &root_node.schema, hidden.as_deref(), rules)?;
+    let operation = validate_operation(
+        &document,
+        operation_name,
+        root_node,
+        hidden.as_deref(),
+        variables,
+    )?;
+
+    executor::resolve_validated_subscription(&document, operation, root_node, variables, context)
+        .await
+}
+
+/// Runs the validation rules on a parsed document, as if the `hidden` items of the schema didn't
+/// exist
+fn validate_document<'e, S>(
+    document: &Document<S>,
+    schema: &SchemaType<S>,
+    hidden: Option<&HiddenItems>,
+    rules: &RuleSet<S>,
+) -> Result<(), GraphQLError<'e>>
+where
+    S: ScalarValue,
+{
+    let mut ctx = ValidatorContext::new(schema, document);
+    if let Some(hidden) = hidden {
+        ctx.hide(hidden);
+    }
+    rules.validate(&mut ctx, document);
 
+    let errors = ctx.into_errors();
+    if !errors.is_empty() {
+        return Err(GraphQLError::ValidationError(errors));
+    }
+    Ok(())
+}
+
+/// Returns the operation of a valid document to execute, checking it against the limits of the
+/// schema and the variables of the request, which can't use the `hidden` enum values
+fn validate_operation<'b, 'd, 'e, S, QueryT, MutationT, SubscriptionT>(
+    document: &'b Document<'d, S>,
+    operation_name: Option<&str>,
+    root_node: &RootNode<QueryT, MutationT, SubscriptionT, S>,
+    hidden: Option<&HiddenItems>,
+    variables: &Variables<S>,
+) -> Result<&'b Spanning<Operation<'d, S>>, GraphQLError<'e>>
+where
+    S: ScalarValue,
+    QueryT: GraphQLType<S>,
+    MutationT: GraphQLType<S>,
+    SubscriptionT: GraphQLType<S>,
+{
     {
-        let mut ctx = ValidatorContext::new(&root_node.schema, &document);
-        visit_all_rules(&mut ctx, &document);
+        let mut ctx = ValidatorContext::new(&root_node.schema, document);
+        visit_limit_rules(
+            &mut ctx,
+            document,
+            operation_name,
+            variables,
+            root_node.max_depth,
+            root_node.max_complexity,
+        );
 
         let errors = ctx.into_errors();
         if !errors.is_empty() {
@@ -328,18 +749,109 @@ where
         }
     }
 
-    let operation = get_operation(&document, operation_name)?;
+    let operation = get_operation(document, operation_name)?;
 
     {
-        let errors = validate_input_values(&variables, operation, &root_node.schema);
+        let errors = validate_visible_input_values(variables, operation, &root_node.schema, hidden);
 
         if !errors.is_empty() {
             return Err(GraphQLError::ValidationError(errors));
         }
     }
 
-    executor::resolve_validated_subscription(&document, operation, root_node, variables, context)
-        .await
+    Ok(operation)
+}
+
+/// A parsed document, which may be shared through a document cache
+enum ParsedDocument<'a, S> {
+    Borrowed(Document<'a, S>),
+    /// Stored in the cache once it passes validation
+    Owned(Arc<OwnedDocument<S>>),
+    /// Found in the cache, so it already passed validation
+    Cached(Arc<OwnedDocument<S>>),
+}
+
+impl<'a, S> ParsedDocument<'a, S> {
+    fn document(&self) -> &Document<'_, S> {
+        match self {
+            ParsedDocument::Borrowed(document) => document,
+            ParsedDocument::Owned(document) | ParsedDocument::Cached(document) => {
+                document.document()
+            }
+        }
+    }
+}
+
+/// Parses the source, or finds its document in the cache
+fn parse_document<'a, S>(
+    document_source: &'a str,
+    schema: &SchemaType<S>,
+    cache: Option<&DocumentCache<S>>,
+) -> Result<ParsedDocument<'a, S>, GraphQLError<'a>>
+where
+    S: ScalarValue,
+{
+    Ok(match cache {
+        Some(cache) => match cache.get(document_source) {
+            Some(document) => ParsedDocument::Cached(document),
+            None => ParsedDocument::Owned(Arc::new(OwnedDocument::parse(document_source, schema)?)),
+        },
+        None => ParsedDocument::Borrowed(parse_document_source(document_source, schema)?),
+    })
+}
+
+/// Validates a parsed document with `rules`, storing it in the cache if it's valid, and returns
+/// the operation to execute
+///
+/// Cached documents are validated again when some items of the schema are `hidden` from the
+/// request, as they may have been validated for a request seeing them.
+fn validate<'b, 'e, S, QueryT, MutationT, SubscriptionT>(
+    document: &'b ParsedDocument<S>,
+    operation_name: Option<&str>,
+    root_node: &RootNode<QueryT, MutationT, SubscriptionT, S>,
+    hidden: Option<&HiddenItems>,
+    variables: &Variables<S>,
+    rules: &RuleSet<S>,
+    cache: Option<&DocumentCache<S>>,
+) -> Result<&'b Spanning<Operation<'b, S>>, GraphQLError<'e>>
+where
+    S: ScalarValue,
+    QueryT: GraphQLType<S>,
+    MutationT: GraphQLType<S>,
+    SubscriptionT: GraphQLType<S>,
+{
+    match document {
+        ParsedDocument::Borrowed(document) => {
+            validate_document(document, &root_node.schema, hidden, rules)?;
+        }
+        ParsedDocument::Owned(document) => {
+            validate_document(document.document(), &root_node.schema, hidden, rules)?;
+            if let Some(cache) = cache {
+                cache.insert(document.clone());
+            }
+        }
+        ParsedDocument::Cached(document) => {
+            if hidden.is_some() {
+                validate_document(document.document(), &root_node.schema, hidden, rules)?;
+            }
+        }
+    }
+
+    validate_operation(
+        document.document(),
+        operation_name,
+        root_node,
+        hidden,
+        variables,
+    )
+}
+
+/// The errors to report to extensions when validation ends
+fn validation_errors<'r, T>(result: &'r Result<T, GraphQLError<'_>>) -> &'r [RuleError] {
+    match result {
+        Err(GraphQLError::ValidationError(errors)) => errors,
+        _ => &[],
+    }
 }
 
 /// Execute the reference introspection query in the provided schema
@@ -2,7 +2,10 @@
 
 pub mod subscription;
 
-use crate::{DefaultScalarValue, DynGraphQLValue, DynGraphQLValueAsync, ScalarValue};
+use crate::{
+    Arguments, DefaultScalarValue, DynGraphQLValue, DynGraphQLValueAsync, FieldResult,
+    GraphQLValue, ScalarValue,
+};
 
 /// Conversion of a [`GraphQLValue`] to its [trait object][1].
 ///
@@ -32,3 +35,22 @@ pub trait AsDynGraphQLValue<S: ScalarValue = DefaultScalarValue> {
 }
 
 crate::sa::assert_obj_safe!(AsDynGraphQLValue<Context = (), TypeInfo = ()>);
+
+/// [`Guard`]s declared on the fields of a [GraphQL interface][1], which are checked as well when
+/// the fields are resolved on the objects implementing the interface.
+///
+/// [`Guard`]: crate::Guard
+/// [1]: https://spec.graphql.org/June2018/#sec-Interfaces
+pub trait InterfaceGuards<S: ScalarValue = DefaultScalarValue>: GraphQLValue<S> {
+    /// Checks the [`Guard`]s of the field named `field_name`, including the ones declared on the
+    /// interfaces implemented by this interface.
+    ///
+    /// Fields without [`Guard`]s, or not declared on this interface, are always allowed.
+    ///
+    /// [`Guard`]: crate::Guard
+    fn check_field_guards(
+        field_name: &str,
+        context: &Self::Context,
+        args: &Arguments<S>,
+    ) -> FieldResult<(), S>;
+}
@@ -31,15 +31,16 @@ where
                 item: Token::CurlyOpen,
                 ..
             },
-            None,
-        ) => parse_object_literal(parser, is_const, schema, None),
+            Some(MetaType::InputObject(o)),
+        ) => parse_object_literal(parser, is_const, schema, Some(o)),
+        // Objects given for other types are left to validation, as scalars may accept them.
         (
             &Spanning {
                 item: Token::CurlyOpen,
                 ..
             },
-            Some(&MetaType::InputObject(ref o)),
-        ) => parse_object_literal(parser, is_const, schema, Some(o)),
+            _,
+        ) => parse_object_literal(parser, is_const, schema, None),
         (
             &Spanning {
                 item: Token::Dollar,
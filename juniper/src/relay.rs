@@ -0,0 +1,659 @@
+//! Helpers for [Relay cursor connections][1]
+//!
+//! A [`Connection`] of nodes is exposed as an object type named after the type of its nodes, along
+//! with the type of its edges, e.g. `UserConnection` and `UserEdge` for a `Connection<User>`. The
+//! `first`, `after`, `last` and `before` arguments of a field returning a connection are gathered
+//! in [`ConnectionArgs`], which slices a list of nodes into the requested page:
+//!
+//! ```rust
+//! # use juniper::{relay::{Connection, ConnectionArgs}, FieldResult, GraphQLObject};
+//! #[derive(GraphQLObject)]
+//! struct User {
+//!     name: String,
+//! }
+//!
+//! struct Query;
+//!
+//! #[juniper::graphql_object]
+//! impl Query {
+//!     fn users(
+//!         first: Option<i32>,
+//!         after: Option<String>,
+//!         last: Option<i32>,
+//!         before: Option<String>,
+//!     ) -> FieldResult<Connection<User>> {
+//!         let args = ConnectionArgs::new(first, after, last, before)?;
+//!         let users = vec![
+//!             User { name: "Alice".to_owned() },
+//!             User { name: "Bob".to_owned() },
+//!         ];
+//!         Ok(Connection::from_vec(users, &args)?)
+//!     }
+//! }
+//! ```
+//!
+//! Pages of offset queries are built with [`ConnectionArgs::offset_range`] and
+//! [`Connection::from_offset`], and pages of keyset queries with [`ConnectionArgs::limit`] and
+//! [`Connection::from_keyset`].
+//!
+//! [1]: https://relay.dev/graphql/connections.htm
+
+use std::{collections::BTreeMap, fmt, ops::Range, sync::Mutex};
+
+use crate::{
+    executor::{ExecutionResult, Executor, Registry},
+    schema::meta::MetaType,
+    types::{
+        async_await::GraphQLValueAsync,
+        base::{Arguments, GraphQLType, GraphQLValue},
+        marker::{GraphQLObjectType, IsOutputType},
+    },
+    value::ScalarValue,
+    BoxFuture,
+};
+
+/// A page of nodes, exposed as the `<Node>Connection` type
+#[derive(Clone, Debug, PartialEq)]
+pub struct Connection<N> {
+    /// The edges of the page
+    pub edges: Vec<Edge<N>>,
+    /// Information about the page
+    pub page_info: PageInfo,
+}
+
+/// A node along with its cursor, exposed as the `<Node>Edge` type
+#[derive(Clone, Debug, PartialEq)]
+pub struct Edge<N> {
+    /// The node
+    pub node: N,
+    /// The cursor of the node, which can be passed to the `after` and `before` arguments
+    pub cursor: String,
+}
+
+/// Information about a page of a [`Connection`]
+#[derive(Clone, Debug, Default, PartialEq, crate::GraphQLObject)]
+pub struct PageInfo {
+    /// Whether there are nodes after the page
+    pub has_next_page: bool,
+    /// Whether there are nodes before the page
+    pub has_previous_page: bool,
+    /// The cursor of the first node of the page
+    pub start_cursor: Option<String>,
+    /// The cursor of the last node of the page
+    pub end_cursor: Option<String>,
+}
+
+/// The `first`, `after`, `last` and `before` arguments of a field returning a [`Connection`]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConnectionArgs {
+    /// Number of nodes to return from the start of the page
+    pub first: Option<usize>,
+    /// Cursor of the node the page starts after
+    pub after: Option<String>,
+    /// Number of nodes to return from the end of the page
+    pub last: Option<usize>,
+    /// Cursor of the node the page ends before
+    pub before: Option<String>,
+}
+
+/// An error of the arguments of a field returning a [`Connection`]
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConnectionError {
+    /// The `first` or `last` argument is negative
+    NegativeCount(&'static str),
+    /// A cursor couldn't be decoded
+    InvalidCursor(String),
+}
+
+impl fmt::Display for ConnectionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConnectionError::NegativeCount(arg) => write!(f, "`{}` can't be negative", arg),
+            ConnectionError::InvalidCursor(cursor) => write!(f, "Invalid cursor `{}`", cursor),
+        }
+    }
+}
+
+impl std::error::Error for ConnectionError {}
+
+impl ConnectionArgs {
+    /// Gathers the arguments of a field, checking that `first` and `last` aren't negative.
+    pub fn new(
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+    ) -> Result<Self, ConnectionError> {
+        let count = |n: Option<i32>, arg| match n {
+            Some(n) if n < 0 => Err(ConnectionError::NegativeCount(arg)),
+            n => Ok(n.map(|n| n as usize)),
+        };
+        Ok(ConnectionArgs {
+            first: count(first, "first")?,
+            after,
+            last: count(last, "last")?,
+            before,
+        })
+    }
+
+    /// Computes the range of the page among `total` nodes, using the cursors of
+    /// [`Connection::from_vec`] and [`Connection::from_offset`]
+    ///
+    /// These cursors are opaque, encoding the offset of their node in base64 like the cursors of
+    /// `graphql-relay-js`.
+    ///
+    /// The page is usually fetched with an `OFFSET` of the start of the range, and a `LIMIT` of
+    /// its length.
+    pub fn offset_range(&self, total: usize) -> Result<Range<usize>, ConnectionError> {
+        let mut start = match &self.after {
+            Some(cursor) => decode_offset_cursor(cursor)?
+                .checked_add(1)
+                .ok_or_else(|| ConnectionError::InvalidCursor(cursor.to_owned()))?,
+            None => 0,
+        };
+        let mut end = match &self.before {
+            Some(cursor) => decode_offset_cursor(cursor)?,
+            None => total,
+        };
+        end = end.min(total);
+        start = start.min(end);
+
+        if let Some(first) = self.first {
+            end = end.min(start.saturating_add(first));
+        }
+        if let Some(last) = self.last {
+            start = start.max(end.saturating_sub(last));
+        }
+        Ok(start..end)
+    }
+
+    /// Number of nodes a keyset query should fetch for [`Connection::from_keyset`]
+    ///
+    /// This is one more node than requested, which tells whether there is another page.
+    pub fn limit(&self) -> Option<usize> {
+        self.first.or(self.last).map(|n| n + 1)
+    }
+}
+
+const OFFSET_CURSOR_PREFIX: &str = "arrayconnection:";
+
+fn offset_cursor(offset: usize) -> String {
+    base64::encode(format!("{}{}", OFFSET_CURSOR_PREFIX, offset))
+}
+
+fn decode_offset_cursor(cursor: &str) -> Result<usize, ConnectionError> {
+    base64::decode(cursor)
+        .ok()
+        .and_then(|decoded| String::from_utf8(decoded).ok())
+        .and_then(|decoded| {
+            decoded
+                .strip_prefix(OFFSET_CURSOR_PREFIX)
+                .and_then(|offset| offset.parse().ok())
+        })
+        .ok_or_else(|| ConnectionError::InvalidCursor(cursor.to_owned()))
+}
+
+impl<N> Connection<N> {
+    /// Builds a connection from its edges, filling the cursors of [`PageInfo`] with the cursors
+    /// of the first and the last edges.
+    pub fn new(edges: Vec<Edge<N>>, has_previous_page: bool, has_next_page: bool) -> Self {
+        let page_info = PageInfo {
+            has_next_page,
+            has_previous_page,
+            start_cursor: edges.first().map(|e| e.cursor.clone()),
+            end_cursor: edges.last().map(|e| e.cursor.clone()),
+        };
+        Connection { edges, page_info }
+    }
+
+    /// Slices all the `nodes` into the page requested by `args`.
+    pub fn from_vec(mut nodes: Vec<N>, args: &ConnectionArgs) -> Result<Self, ConnectionError> {
+        let total = nodes.len();
+        let range = args.offset_range(total)?;
+        let start = range.start;
+        nodes.truncate(range.end);
+        nodes.drain(..start);
+        Ok(Self::from_offset(nodes, start, total))
+    }
+
+    /// Builds a connection from the `nodes` of a page starting at `offset`, among `total` nodes
+    /// (see [`ConnectionArgs::offset_range`]).
+    pub fn from_offset(nodes: Vec<N>, offset: usize, total: usize) -> Self {
+        let end = offset + nodes.len();
+        let edges = nodes
+            .into_iter()
+            .enumerate()
+            .map(|(i, node)| Edge {
+                node,
+                cursor: offset_cursor(offset + i),
+            })
+            .collect();
+        Self::new(edges, offset > 0, end < total)
+    }
+
+    /// Builds a connection from the `nodes` of a keyset query, encoding the cursor of every node
+    /// with `cursor`.
+    ///
+    /// The query fetches the nodes following the `after` cursor and preceding the `before`
+    /// cursor, in the order of the connection. When `first` is given, these are the first
+    /// [`ConnectionArgs::limit`] nodes, otherwise the last ones when `last` is given.
+    pub fn from_keyset<F>(mut nodes: Vec<N>, args: &ConnectionArgs, cursor: F) -> Self
+    where
+        F: Fn(&N) -> String,
+    {
+        let mut has_previous_page = args.after.is_some();
+        let mut has_next_page = args.before.is_some();
+        if let Some(first) = args.first {
+            if nodes.len() > first {
+                nodes.truncate(first);
+                has_next_page = true;
+            }
+        }
+        if let Some(last) = args.last {
+            if nodes.len() > last {
+                nodes.drain(..nodes.len() - last);
+                has_previous_page = true;
+            }
+        }
+
+        let edges = nodes
+            .into_iter()
+            .map(|node| Edge {
+                cursor: cursor(&node),
+                node,
+            })
+            .collect();
+        Self::new(edges, has_previous_page, has_next_page)
+    }
+}
+
+/// Appends `suffix` to the name of a node type, keeping the name around for the `'static`
+/// lifetime expected by [`GraphQLType::name`]
+///
+/// Every name is only allocated once, so there's a single one per node type and suffix.
+fn suffixed_name(name: &str, suffix: &'static str) -> &'static str {
+    static NAMES: Mutex<BTreeMap<(String, &'static str), &'static str>> =
+        Mutex::new(BTreeMap::new());
+
+    NAMES
+        .lock()
+        .unwrap()
+        .entry((name.to_owned(), suffix))
+        .or_insert_with(|| Box::leak(format!("{}{}", name, suffix).into_boxed_str()))
+}
+
+impl<N, S> GraphQLType<S> for Connection<N>
+where
+    N: GraphQLType<S>,
+    S: ScalarValue,
+{
+    fn name(info: &N::TypeInfo) -> Option<&str> {
+        N::name(info).map(|name| suffixed_name(name, "Connection"))
+    }
+
+    fn meta<'r>(info: &N::TypeInfo, registry: &mut Registry<'r, S>) -> MetaType<'r, S>
+    where
+        S: 'r,
+    {
+        let fields = [
+            registry.field::<Vec<Edge<N>>>("edges", info),
+            registry.field::<PageInfo>("pageInfo", &()),
+        ];
+        registry
+            .build_object_type::<Self>(info, &fields)
+            .into_meta()
+    }
+}
+
+impl<N, S> GraphQLValue<S> for Connection<N>
+where
+    N: GraphQLType<S>,
+    S: ScalarValue,
+{
+    type Context = N::Context;
+    type TypeInfo = N::TypeInfo;
+
+    fn type_name<'i>(&self, info: &'i N::TypeInfo) -> Option<&'i str> {
+        <Self as GraphQLType<S>>::name(info)
+    }
+
+    fn concrete_type_name(&self, _: &Self::Context, info: &Self::TypeInfo) -> String {
+        <Self as GraphQLType<S>>::name(info)
+            .expect("Connection of an unnamed node type")
+            .to_owned()
+    }
+
+    fn resolve_field(
+        &self,
+        info: &N::TypeInfo,
+        field_name: &str,
+        _: &Arguments<S>,
+        executor: &Executor<Self::Context, S>,
+    ) -> ExecutionResult<S> {
+        match field_name {
+            "edges" => executor.resolve(info, &self.edges),
+            "pageInfo" => executor.resolve_with_ctx(&(), &self.page_info),
+            _ => panic!("Field {} not found on type Connection", field_name),
+        }
+    }
+}
+
+impl<N, S> GraphQLValueAsync<S> for Connection<N>
+where
+    N: GraphQLType<S> + GraphQLValueAsync<S>,
+    N::TypeInfo: Sync,
+    N::Context: Sync,
+    S: ScalarValue + Send + Sync,
+{
+    fn resolve_field_async<'a>(
+        &'a self,
+        info: &'a N::TypeInfo,
+        field_name: &'a str,
+        _: &'a Arguments<S>,
+        executor: &'a Executor<Self::Context, S>,
+    ) -> BoxFuture<'a, ExecutionResult<S>> {
+        match field_name {
+            "edges" => Box::pin(executor.resolve_async(info, &self.edges)),
+            "pageInfo" => Box::pin(executor.resolve_with_ctx_async(&(), &self.page_info)),
+            _ => panic!("Field {} not found on type Connection", field_name),
+        }
+    }
+}
+
+impl<N, S> GraphQLObjectType<S> for Connection<N>
+where
+    N: GraphQLType<S>,
+    S: ScalarValue,
+{
+}
+
+impl<N, S> IsOutputType<S> for Connection<N>
+where
+    N: IsOutputType<S>,
+    S: ScalarValue,
+{
+}
+
+impl<N, S> GraphQLType<S> for Edge<N>
+where
+    N: GraphQLType<S>,
+    S: ScalarValue,
+{
+    fn name(info: &N::TypeInfo) -> Option<&str> {
+        N::name(info).map(|name| suffixed_name(name, "Edge"))
+    }
+
+    fn meta<'r>(info: &N::TypeInfo, registry: &mut Registry<'r, S>) -> MetaType<'r, S>
+    where
+        S: 'r,
+    {
+        let fields = [
+            registry.field::<N>("node", info),
+            registry.field::<String>("cursor", &()),
+        ];
+        registry
+            .build_object_type::<Self>(info, &fields)
+            .into_meta()
+    }
+}
+
+impl<N, S> GraphQLValue<S> for Edge<N>
+where
+    N: GraphQLType<S>,
+    S: ScalarValue,
+{
+    type Context = N::Context;
+    type TypeInfo = N::TypeInfo;
+
+    fn type_name<'i>(&self, info: &'i N::TypeInfo) -> Option<&'i str> {
+        <Self as GraphQLType<S>>::name(info)
+    }
+
+    fn concrete_type_name(&self, _: &Self::Context, info: &Self::TypeInfo) -> String {
+        <Self as GraphQLType<S>>::name(info)
+            .expect("Edge of an unnamed node type")
+            .to_owned()
+    }
+
+    fn resolve_field(
+        &self,
+        info: &N::TypeInfo,
+        field_name: &str,
+        _: &Arguments<S>,
+        executor: &Executor<Self::Context, S>,
+    ) -> ExecutionResult<S> {
+        match field_name {
+            "node" => executor.resolve(info, &self.node),
+            "cursor" => executor.resolve_with_ctx(&(), &self.cursor),
+            _ => panic!("Field {} not found on type Edge", field_name),
+        }
+    }
+}
+
+impl<N, S> GraphQLValueAsync<S> for Edge<N>
+where
+    N: GraphQLType<S> + GraphQLValueAsync<S>,
+    N::TypeInfo: Sync,
+    N::Context: Sync,
+    S: ScalarValue + Send + Sync,
+{
+    fn resolve_field_async<'a>(
+        &'a self,
+        info: &'a N::TypeInfo,
+        field_name: &'a str,
+        _: &'a Arguments<S>,
+        executor: &'a Executor<Self::Context, S>,
+    ) -> BoxFuture<'a, ExecutionResult<S>> {
+        match field_name {
+            "node" => Box::pin(executor.resolve_async(info, &self.node)),
+            "cursor" => Box::pin(executor.resolve_with_ctx_async(&(), &self.cursor)),
+            _ => panic!("Field {} not found on type Edge", field_name),
+        }
+    }
+}
+
+impl<N, S> GraphQLObjectType<S> for Edge<N>
+where
+    N: GraphQLType<S>,
+    S: ScalarValue,
+{
+}
+
+impl<N, S> IsOutputType<S> for Edge<N>
+where
+    N: IsOutputType<S>,
+    S: ScalarValue,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{offset_cursor, Connection, ConnectionArgs, ConnectionError, Edge};
+    use crate::{EmptyMutation, EmptySubscription, RootNode, Variables};
+
+    fn args(
+        first: Option<i32>,
+        after: Option<&str>,
+        last: Option<i32>,
+        before: Option<&str>,
+    ) -> ConnectionArgs {
+        ConnectionArgs::new(first, after.map(Into::into), last, before.map(Into::into)).unwrap()
+    }
+
+    #[test]
+    fn computes_offset_ranges() {
+        assert_eq!(args(None, None, None, None).offset_range(5), Ok(0..5));
+        assert_eq!(args(Some(2), None, None, None).offset_range(5), Ok(0..2));
+        assert_eq!(
+            args(Some(2), Some("YXJyYXljb25uZWN0aW9uOjE="), None, None).offset_range(5),
+            Ok(2..4),
+        );
+        assert_eq!(args(None, None, Some(2), None).offset_range(5), Ok(3..5));
+        assert_eq!(
+            args(None, None, Some(2), Some("YXJyYXljb25uZWN0aW9uOjM=")).offset_range(5),
+            Ok(1..3),
+        );
+        assert_eq!(
+            args(Some(10), Some("YXJyYXljb25uZWN0aW9uOjc="), None, None).offset_range(5),
+            Ok(5..5),
+        );
+        assert_eq!(
+            args(None, Some("7"), None, None).offset_range(5),
+            Err(ConnectionError::InvalidCursor("7".into())),
+        );
+
+        let max = offset_cursor(usize::MAX);
+        assert_eq!(
+            args(None, Some(&max), None, None).offset_range(5),
+            Err(ConnectionError::InvalidCursor(max)),
+        );
+    }
+
+    #[test]
+    fn rejects_negative_counts() {
+        assert_eq!(
+            ConnectionArgs::new(Some(-1), None, None, None),
+            Err(ConnectionError::NegativeCount("first")),
+        );
+        assert_eq!(
+            ConnectionArgs::new(None, None, Some(-1), None),
+            Err(ConnectionError::NegativeCount("last")),
+        );
+    }
+
+    #[test]
+    fn slices_vec() {
+        let conn = Connection::from_vec(
+            vec![1, 2, 3],
+            &args(Some(1), Some("YXJyYXljb25uZWN0aW9uOjA="), None, None),
+        )
+        .unwrap();
+
+        assert_eq!(
+            conn.edges,
+            vec![Edge {
+                node: 2,
+                cursor: "YXJyYXljb25uZWN0aW9uOjE=".into(),
+            }],
+        );
+        assert!(conn.page_info.has_previous_page);
+        assert!(conn.page_info.has_next_page);
+        assert_eq!(
+            conn.page_info.start_cursor.as_deref(),
+            Some("YXJyYXljb25uZWN0aW9uOjE=")
+        );
+        assert_eq!(
+            conn.page_info.end_cursor.as_deref(),
+            Some("YXJyYXljb25uZWN0aW9uOjE=")
+        );
+    }
+
+    #[test]
+    fn pages_keyset_queries() {
+        let first = args(Some(2), None, None, None);
+        assert_eq!(first.limit(), Some(3));
+        let conn = Connection::from_keyset(vec![1, 2, 3], &first, |n| n.to_string());
+        assert_eq!(conn.edges.len(), 2);
+        assert!(!conn.page_info.has_previous_page);
+        assert!(conn.page_info.has_next_page);
+        assert_eq!(conn.page_info.end_cursor.as_deref(), Some("2"));
+
+        let last = args(None, None, Some(2), Some("4"));
+        let conn = Connection::from_keyset(vec![2, 3], &last, |n| n.to_string());
+        assert_eq!(conn.edges.len(), 2);
+        assert!(!conn.page_info.has_previous_page);
+        assert!(conn.page_info.has_next_page);
+        assert_eq!(conn.page_info.start_cursor.as_deref(), Some("2"));
+    }
+
+    #[derive(crate::GraphQLObject)]
+    struct User {
+        name: String,
+    }
+
+    struct Query;
+
+    #[crate::graphql_object]
+    impl Query {
+        fn users(
+            first: Option<i32>,
+            after: Option<String>,
+            last: Option<i32>,
+            before: Option<String>,
+        ) -> crate::FieldResult<Connection<User>> {
+            let users = ["Alice", "Bob", "Carol"]
+                .iter()
+                .map(|name| User {
+                    name: (*name).to_owned(),
+                })
+                .collect();
+            let args = ConnectionArgs::new(first, after, last, before)?;
+            Ok(Connection::from_vec(users, &args)?)
+        }
+    }
+
+    #[tokio::test]
+    async fn resolves_connection() {
+        let schema = RootNode::new(
+            Query,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        );
+        let query = r#"{
+            users(first: 2, after: "YXJyYXljb25uZWN0aW9uOjA=") {
+                __typename
+                edges { node { name } cursor }
+                pageInfo { hasNextPage hasPreviousPage startCursor endCursor }
+            }
+            __type(name: "UserEdge") { name }
+        }"#;
+
+        let (result, errs) = crate::execute(query, None, &schema, &Variables::new(), &())
+            .await
+            .expect("Execution failed");
+
+        assert_eq!(errs, []);
+        assert_eq!(
+            result,
+            graphql_value!({
+                "users": {
+                    "__typename": "UserConnection",
+                    "edges": [
+                        {"node": {"name": "Bob"}, "cursor": "YXJyYXljb25uZWN0aW9uOjE="},
+                        {"node": {"name": "Carol"}, "cursor": "YXJyYXljb25uZWN0aW9uOjI="},
+                    ],
+                    "pageInfo": {
+                        "hasNextPage": false,
+                        "hasPreviousPage": true,
+                        "startCursor": "YXJyYXljb25uZWN0aW9uOjE=",
+                        "endCursor": "YXJyYXljb25uZWN0aW9uOjI=",
+                    },
+                },
+                "__type": {"name": "UserEdge"},
+            }),
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_arguments() {
+        let schema = RootNode::new(
+            Query,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        );
+
+        let (result, errs) = crate::execute(
+            "{ users(first: -1) { pageInfo { hasNextPage } } }",
+            None,
+            &schema,
+            &Variables::new(),
+            &(),
+        )
+        .await
+        .expect("Execution failed");
+
+        assert_eq!(result, graphql_value!(None));
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].error().message(), "`first` can't be negative");
+    }
+}
@@ -0,0 +1,823 @@
+//! Detecting changes between two versions of a schema, and how they affect existing clients.
+
+use std::{borrow::Borrow, fmt};
+
+use crate::{
+    ast::Type,
+    schema::{
+        meta::{Argument, EnumMeta, Field, MetaType, UnionMeta},
+        model::{DirectiveType, RootNode, SchemaType},
+    },
+    types::base::GraphQLType,
+    value::ScalarValue,
+};
+
+/// How a [`SchemaChange`] affects clients written against the previous version of a schema.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ChangeCriticality {
+    /// Existing queries may stop working, e.g. because a field they select was removed.
+    Breaking,
+    /// Existing queries keep working, but may observe values they don't expect, e.g. a new
+    /// enum value.
+    Dangerous,
+    /// Existing queries are not affected.
+    Safe,
+}
+
+impl fmt::Display for ChangeCriticality {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ChangeCriticality::Breaking => write!(f, "breaking"),
+            ChangeCriticality::Dangerous => write!(f, "dangerous"),
+            ChangeCriticality::Safe => write!(f, "safe"),
+        }
+    }
+}
+
+/// A single difference between two versions of a schema.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SchemaChange {
+    /// How the change affects existing clients.
+    pub criticality: ChangeCriticality,
+    /// Schema coordinate of the changed element, e.g. `Query.hero(episode:)`.
+    pub path: String,
+    /// Human readable description of the change.
+    pub message: String,
+}
+
+impl SchemaChange {
+    fn new<P, M>(criticality: ChangeCriticality, path: P, message: M) -> Self
+    where
+        P: Into<String>,
+        M: Into<String>,
+    {
+        Self {
+            criticality,
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+
+    /// Whether the change may break existing queries.
+    pub fn is_breaking(&self) -> bool {
+        self.criticality == ChangeCriticality::Breaking
+    }
+}
+
+impl fmt::Display for SchemaChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[{}] {}", self.criticality, self.message)
+    }
+}
+
+impl<'a, S> SchemaType<'a, S>
+where
+    S: ScalarValue + 'a,
+{
+    /// Lists the changes made to the `previous` version of a schema to get to this one.
+    ///
+    /// Changes are ordered by the schema coordinate they affect. Built-in types, like the
+    /// introspection ones, are ignored, as they are the same for every schema.
+    pub fn changes_since(&self, previous: &SchemaType<S>) -> Vec<SchemaChange> {
+        let mut changes = vec![];
+
+        compare_root_types(previous, self, &mut changes);
+
+        for (name, old_type) in &previous.types {
+            let name: &str = name.borrow();
+            if old_type.is_builtin() {
+                continue;
+            }
+            match self.concrete_type_by_name(name) {
+                Some(new_type) => compare_types(old_type, new_type, &mut changes),
+                None => changes.push(SchemaChange::new(
+                    ChangeCriticality::Breaking,
+                    name,
+                    format!("Type `{}` was removed", name),
+                )),
+            }
+        }
+        for (name, new_type) in &self.types {
+            let name: &str = name.borrow();
+            if !new_type.is_builtin() && previous.concrete_type_by_name(name).is_none() {
+                changes.push(SchemaChange::new(
+                    ChangeCriticality::Safe,
+                    name,
+                    format!("Type `{}` was added", name),
+                ));
+            }
+        }
+
+        for old in previous.directive_list() {
+            match self.directive_by_name(&old.name) {
+                Some(new) => compare_directives(old, new, &mut changes),
+                None => changes.push(SchemaChange::new(
+                    ChangeCriticality::Breaking,
+                    format!("@{}", old.name),
+                    format!("Directive `@{}` was removed", old.name),
+                )),
+            }
+        }
+        for new in self.directive_list() {
+            if previous.directive_by_name(&new.name).is_none() {
+                changes.push(SchemaChange::new(
+                    ChangeCriticality::Safe,
+                    format!("@{}", new.name),
+                    format!("Directive `@{}` was added", new.name),
+                ));
+            }
+        }
+
+        changes.sort_by(|a, b| a.path.cmp(&b.path).then(a.message.cmp(&b.message)));
+        changes
+    }
+}
+
+impl<'a, QueryT, MutationT, SubscriptionT, S> RootNode<'a, QueryT, MutationT, SubscriptionT, S>
+where
+    QueryT: GraphQLType<S>,
+    MutationT: GraphQLType<S>,
+    SubscriptionT: GraphQLType<S>,
+    S: ScalarValue + 'a,
+{
+    /// Lists the changes made to the `previous` version of this schema, e.g. a snapshot built
+    /// with [`SchemaType::from_schema_language`].
+    ///
+    /// See [`SchemaType::changes_since`].
+    pub fn changes_since(&self, previous: &SchemaType<S>) -> Vec<SchemaChange> {
+        self.schema.changes_since(previous)
+    }
+}
+
+fn compare_root_types<S>(
+    old: &SchemaType<S>,
+    new: &SchemaType<S>,
+    changes: &mut Vec<SchemaChange>,
+) {
+    if old.query_type_name != new.query_type_name {
+        changes.push(SchemaChange::new(
+            ChangeCriticality::Breaking,
+            "schema.query",
+            format!(
+                "Query root type changed from `{}` to `{}`",
+                old.query_type_name, new.query_type_name,
+            ),
+        ));
+    }
+
+    for (operation, old_name, new_name) in &[
+        ("mutation", &old.mutation_type_name, &new.mutation_type_name),
+        (
+            "subscription",
+            &old.subscription_type_name,
+            &new.subscription_type_name,
+        ),
+    ] {
+        let (criticality, message) = match (old_name, new_name) {
+            (Some(o), Some(n)) if o == n => continue,
+            (None, None) => continue,
+            (Some(o), Some(n)) => (
+                ChangeCriticality::Breaking,
+                format!("{} root type changed from `{}` to `{}`", operation, o, n),
+            ),
+            (Some(o), None) => (
+                ChangeCriticality::Breaking,
+                format!("{} root type `{}` was removed", operation, o),
+            ),
+            (None, Some(n)) => (
+                ChangeCriticality::Safe,
+                format!("{} root type `{}` was added", operation, n),
+            ),
+        };
+        changes.push(SchemaChange::new(
+            criticality,
+            format!("schema.{}", operation),
+            capitalize(&message),
+        ));
+    }
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    chars
+        .next()
+        .map(|c| c.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+fn kind_name<S>(meta: &MetaType<S>) -> &'static str {
+    match meta {
+        MetaType::Scalar(_) => "scalar",
+        MetaType::List(_) => "list",
+        MetaType::Nullable(_) => "nullable",
+        MetaType::Object(_) => "object",
+        MetaType::Enum(_) => "enum",
+        MetaType::Interface(_) => "interface",
+        MetaType::Union(_) => "union",
+        MetaType::InputObject(_) => "input object",
+        MetaType::Placeholder(_) => "placeholder",
+    }
+}
+
+fn compare_types<S>(old: &MetaType<S>, new: &MetaType<S>, changes: &mut Vec<SchemaChange>)
+where
+    S: ScalarValue,
+{
+    let name = old.name().unwrap_or_default();
+
+    if old.description() != new.description() {
+        changes.push(SchemaChange::new(
+            ChangeCriticality::Safe,
+            name,
+            format!("Description of type `{}` changed", name),
+        ));
+    }
+
+    match (old, new) {
+        (MetaType::Object(o), MetaType::Object(n)) => {
+            compare_fields(name, &o.fields, &n.fields, changes);
+            compare_members(
+                name,
+                "interface",
+                &o.interface_names,
+                &n.interface_names,
+                changes,
+            );
+        }
+        (MetaType::Interface(o), MetaType::Interface(n)) => {
+            compare_fields(name, &o.fields, &n.fields, changes);
+            compare_members(
+                name,
+                "interface",
+                &o.interface_names,
+                &n.interface_names,
+                changes,
+            );
+        }
+        (
+            MetaType::Union(UnionMeta {
+                of_type_names: o, ..
+            }),
+            MetaType::Union(n),
+        ) => compare_members(name, "member", o, &n.of_type_names, changes),
+        (MetaType::Enum(EnumMeta { values: o, .. }), MetaType::Enum(n)) => {
+            for old_value in o {
+                let path = format!("{}.{}", name, old_value.name);
+                match n.values.iter().find(|v| v.name == old_value.name) {
+                    Some(new_value) => compare_deprecation(
+                        &path,
+                        &format!("Enum value `{}`", path),
+                        old_value.deprecation_status.is_deprecated(),
+                        new_value.deprecation_status.is_deprecated(),
+                        changes,
+                    ),
+                    None => changes.push(SchemaChange::new(
+                        ChangeCriticality::Breaking,
+                        &path,
+                        format!("Enum value `{}` was removed", path),
+                    )),
+                }
+            }
+            for new_value in &n.values {
+                if !o.iter().any(|v| v.name == new_value.name) {
+                    let path = format!("{}.{}", name, new_value.name);
+                    changes.push(SchemaChange::new(
+                        ChangeCriticality::Dangerous,
+                        &path,
+                        format!("Enum value `{}` was added", path),
+                    ));
+                }
+            }
+        }
+        (MetaType::InputObject(o), MetaType::InputObject(n)) => {
+            compare_arguments(
+                &|field| format!("{}.{}", name, field),
+                "Input field",
+                &o.input_fields,
+                &n.input_fields,
+                changes,
+            );
+            if !o.is_one_of && n.is_one_of {
+                changes.push(SchemaChange::new(
+                    ChangeCriticality::Breaking,
+                    name,
+                    format!("Input object `{}` became a OneOf input object", name),
+                ));
+            } else if o.is_one_of && !n.is_one_of {
+                changes.push(SchemaChange::new(
+                    ChangeCriticality::Safe,
+                    name,
+                    format!("Input object `{}` is no longer a OneOf input object", name),
+                ));
+            }
+        }
+        (MetaType::Scalar(_), MetaType::Scalar(_)) => {}
+        _ => changes.push(SchemaChange::new(
+            ChangeCriticality::Breaking,
+            name,
+            format!(
+                "Type `{}` changed from {} to {}",
+                name,
+                kind_name(old),
+                kind_name(new),
+            ),
+        )),
+    }
+}
+
+/// Compares the interfaces implemented by an object type, or the members of an union.
+fn compare_members(
+    type_name: &str,
+    member_kind: &str,
+    old: &[String],
+    new: &[String],
+    changes: &mut Vec<SchemaChange>,
+) {
+    for name in old.iter().filter(|n| !new.contains(n)) {
+        changes.push(SchemaChange::new(
+            ChangeCriticality::Breaking,
+            type_name,
+            format!(
+                "{} `{}` was removed from `{}`",
+                capitalize(member_kind),
+                name,
+                type_name,
+            ),
+        ));
+    }
+    for name in new.iter().filter(|n| !old.contains(n)) {
+        changes.push(SchemaChange::new(
+            ChangeCriticality::Dangerous,
+            type_name,
+            format!(
+                "{} `{}` was added to `{}`",
+                capitalize(member_kind),
+                name,
+                type_name,
+            ),
+        ));
+    }
+}
+
+fn compare_fields<S>(
+    type_name: &str,
+    old: &[Field<S>],
+    new: &[Field<S>],
+    changes: &mut Vec<SchemaChange>,
+) where
+    S: ScalarValue,
+{
+    let is_schema_field = |f: &&Field<S>| !f.name.starts_with("__");
+
+    for old_field in old.iter().filter(is_schema_field) {
+        let path = format!("{}.{}", type_name, old_field.name);
+        let new_field = match new.iter().find(|f| f.name == old_field.name) {
+            Some(f) => f,
+            None => {
+                changes.push(SchemaChange::new(
+                    ChangeCriticality::Breaking,
+                    &path,
+                    format!("Field `{}` was removed", path),
+                ));
+                continue;
+            }
+        };
+
+        if !is_safe_output_change(&old_field.field_type, &new_field.field_type) {
+            changes.push(SchemaChange::new(
+                ChangeCriticality::Breaking,
+                &path,
+                format!(
+                    "Field `{}` changed type from `{}` to `{}`",
+                    path, old_field.field_type, new_field.field_type,
+                ),
+            ));
+        } else if old_field.field_type != new_field.field_type {
+            changes.push(SchemaChange::new(
+                ChangeCriticality::Safe,
+                &path,
+                format!(
+                    "Field `{}` changed type from `{}` to `{}`",
+                    path, old_field.field_type, new_field.field_type,
+                ),
+            ));
+        }
+
+        if old_field.description != new_field.description {
+            changes.push(SchemaChange::new(
+                ChangeCriticality::Safe,
+                &path,
+                format!("Description of field `{}` changed", path),
+            ));
+        }
+
+        compare_deprecation(
+            &path,
+            &format!("Field `{}`", path),
+            old_field.deprecation_status.is_deprecated(),
+            new_field.deprecation_status.is_deprecated(),
+            changes,
+        );
+
+        compare_arguments(
+            &|arg| format!("{}({}:)", path, arg),
+            "Argument",
+            old_field.arguments.as_deref().unwrap_or(&[]),
+            new_field.arguments.as_deref().unwrap_or(&[]),
+            changes,
+        );
+    }
+
+    for new_field in new.iter().filter(is_schema_field) {
+        if !old.iter().any(|f| f.name == new_field.name) {
+            let path = format!("{}.{}", type_name, new_field.name);
+            changes.push(SchemaChange::new(
+                ChangeCriticality::Safe,
+                &path,
+                format!("Field `{}` was added", path),
+            ));
+        }
+    }
+}
+
+fn compare_deprecation(
+    path: &str,
+    subject: &str,
+    was_deprecated: bool,
+    is_deprecated: bool,
+    changes: &mut Vec<SchemaChange>,
+) {
+    let message = match (was_deprecated, is_deprecated) {
+        (false, true) => format!("{} was deprecated", subject),
+        (true, false) => format!("{} is no longer deprecated", subject),
+        _ => return,
+    };
+    changes.push(SchemaChange::new(ChangeCriticality::Safe, path, message));
+}
+
+/// Compares the arguments of a field or a directive, or the fields of an input object.
+fn compare_arguments<S>(
+    path: &dyn Fn(&str) -> String,
+    subject: &str,
+    old: &[Argument<S>],
+    new: &[Argument<S>],
+    changes: &mut Vec<SchemaChange>,
+) where
+    S: ScalarValue,
+{
+    for old_arg in old {
+        let path = path(&old_arg.name);
+        let new_arg = match new.iter().find(|a| a.name == old_arg.name) {
+            Some(a) => a,
+            None => {
+                changes.push(SchemaChange::new(
+                    ChangeCriticality::Breaking,
+                    &path,
+                    format!("{} `{}` was removed", subject, path),
+                ));
+                continue;
+            }
+        };
+
+        if !is_safe_input_change(&old_arg.arg_type, &new_arg.arg_type) {
+            changes.push(SchemaChange::new(
+                ChangeCriticality::Breaking,
+                &path,
+                format!(
+                    "{} `{}` changed type from `{}` to `{}`",
+                    subject, path, old_arg.arg_type, new_arg.arg_type,
+                ),
+            ));
+        } else if old_arg.arg_type != new_arg.arg_type {
+            changes.push(SchemaChange::new(
+                ChangeCriticality::Safe,
+                &path,
+                format!(
+                    "{} `{}` changed type from `{}` to `{}`",
+                    subject, path, old_arg.arg_type, new_arg.arg_type,
+                ),
+            ));
+        }
+
+        let old_default = old_arg.default_value.as_ref().map(ToString::to_string);
+        let new_default = new_arg.default_value.as_ref().map(ToString::to_string);
+        if old_default != new_default {
+            changes.push(SchemaChange::new(
+                ChangeCriticality::Dangerous,
+                &path,
+                format!(
+                    "Default value of {} `{}` changed from `{}` to `{}`",
+                    subject.to_lowercase(),
+                    path,
+                    old_default.as_deref().unwrap_or("none"),
+                    new_default.as_deref().unwrap_or("none"),
+                ),
+            ));
+        }
+
+        if old_arg.description != new_arg.description {
+            changes.push(SchemaChange::new(
+                ChangeCriticality::Safe,
+                &path,
+                format!(
+                    "Description of {} `{}` changed",
+                    subject.to_lowercase(),
+                    path
+                ),
+            ));
+        }
+    }
+
+    for new_arg in new {
+        if !old.iter().any(|a| a.name == new_arg.name) {
+            let path = path(&new_arg.name);
+            let (criticality, kind) =
+                if new_arg.arg_type.is_non_null() && new_arg.default_value.is_none() {
+                    (ChangeCriticality::Breaking, "Required")
+                } else {
+                    (ChangeCriticality::Safe, "Optional")
+                };
+            changes.push(SchemaChange::new(
+                criticality,
+                &path,
+                format!("{} {} `{}` was added", kind, subject.to_lowercase(), path),
+            ));
+        }
+    }
+}
+
+fn compare_directives<S>(
+    old: &DirectiveType<S>,
+    new: &DirectiveType<S>,
+    changes: &mut Vec<SchemaChange>,
+) where
+    S: ScalarValue,
+{
+    let path = format!("@{}", old.name);
+
+    for location in old.locations.iter().filter(|l| !new.locations.contains(l)) {
+        changes.push(SchemaChange::new(
+            ChangeCriticality::Breaking,
+            &path,
+            format!(
+                "Location `{:?}` was removed from directive `{}`",
+                location, path,
+            ),
+        ));
+    }
+    for location in new.locations.iter().filter(|l| !old.locations.contains(l)) {
+        changes.push(SchemaChange::new(
+            ChangeCriticality::Safe,
+            &path,
+            format!(
+                "Location `{:?}` was added to directive `{}`",
+                location, path
+            ),
+        ));
+    }
+
+    compare_arguments(
+        &|arg| format!("{}({}:)", path, arg),
+        "Argument",
+        &old.arguments,
+        &new.arguments,
+        changes,
+    );
+}
+
+/// Whether a field may change its type from `old` to `new` without breaking clients.
+///
+/// This is the case when every value of the `new` type is also a value of the `old` one, e.g.
+/// when a nullable field becomes non-null.
+fn is_safe_output_change(old: &Type, new: &Type) -> bool {
+    match (old, new) {
+        (Type::Named(o), Type::Named(n))
+        | (Type::Named(o), Type::NonNullNamed(n))
+        | (Type::NonNullNamed(o), Type::NonNullNamed(n)) => o == n,
+        (Type::List(o), Type::List(n))
+        | (Type::List(o), Type::NonNullList(n))
+        | (Type::NonNullList(o), Type::NonNullList(n)) => is_safe_output_change(o, n),
+        _ => false,
+    }
+}
+
+/// Whether an argument or input field may change its type from `old` to `new` without breaking
+/// clients.
+///
+/// This is the case when every value of the `old` type is also a value of the `new` one, e.g.
+/// when a non-null argument becomes nullable.
+fn is_safe_input_change(old: &Type, new: &Type) -> bool {
+    is_safe_output_change(new, old)
+}
+
+#[cfg(all(test, feature = "graphql-parser-integration"))]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        schema::model::{RootNode, SchemaType},
+        tests::fixtures::starwars::{
+            schema::{Database, Query},
+            schema_language::STATIC_GRAPHQL_SCHEMA_DEFINITION,
+        },
+        types::scalars::{EmptyMutation, EmptySubscription},
+        value::DefaultScalarValue,
+    };
+
+    use super::ChangeCriticality::{self, *};
+
+    fn changes(old: &str, new: &str) -> Vec<(ChangeCriticality, String)> {
+        let old = SchemaType::<DefaultScalarValue>::from_schema_language(old).unwrap();
+        let new = SchemaType::<DefaultScalarValue>::from_schema_language(new).unwrap();
+        new.changes_since(&old)
+            .into_iter()
+            .map(|c| (c.criticality, c.message))
+            .collect()
+    }
+
+    #[test]
+    fn identical_schemas() {
+        let sdl = r#"
+            type Query { a(x: Int = 1): [String!] }
+            enum E { A B }
+            union U = Query
+            input I { a: Int! }
+            directive @foo(a: Int) on FIELD
+        "#;
+        assert_eq!(changes(sdl, sdl), vec![]);
+    }
+
+    #[test]
+    fn root_node_matches_snapshot() {
+        let schema = RootNode::new(
+            Query,
+            EmptyMutation::<Database>::new(),
+            EmptySubscription::<Database>::new(),
+        );
+        let snapshot = SchemaType::from_schema_language(STATIC_GRAPHQL_SCHEMA_DEFINITION).unwrap();
+
+        assert_eq!(schema.changes_since(&snapshot), vec![]);
+    }
+
+    #[test]
+    fn types() {
+        assert_eq!(
+            changes(
+                "type Query { a: Int } type A { a: Int } enum B { X }",
+                "type Query { a: Int } type C { a: Int } input B { x: Int }",
+            ),
+            vec![
+                (Breaking, "Type `A` was removed".into()),
+                (
+                    Breaking,
+                    "Type `B` changed from enum to input object".into()
+                ),
+                (Safe, "Type `C` was added".into()),
+            ],
+        );
+    }
+
+    #[test]
+    fn fields() {
+        assert_eq!(
+            changes(
+                "type Query { a: Int b: Int c: Int! d: [Int] e: Int }",
+                r#"type Query { b: Int! c: Int d: [Int!]! e: String @deprecated f: Int }"#,
+            ),
+            vec![
+                (Breaking, "Field `Query.a` was removed".into()),
+                (
+                    Safe,
+                    "Field `Query.b` changed type from `Int` to `Int!`".into()
+                ),
+                (
+                    Breaking,
+                    "Field `Query.c` changed type from `Int!` to `Int`".into()
+                ),
+                (
+                    Safe,
+                    "Field `Query.d` changed type from `[Int]` to `[Int!]!`".into()
+                ),
+                (
+                    Breaking,
+                    "Field `Query.e` changed type from `Int` to `String`".into()
+                ),
+                (Safe, "Field `Query.e` was deprecated".into()),
+                (Safe, "Field `Query.f` was added".into()),
+            ],
+        );
+    }
+
+    #[test]
+    fn arguments() {
+        assert_eq!(
+            changes(
+                "type Query { a(x: Int! y: Int z: Int = 1 w: Int): Int }",
+                "type Query { a(x: Int y: Int! z: Int = 2 v: Int! u: Int): Int }",
+            ),
+            vec![
+                (Safe, "Optional argument `Query.a(u:)` was added".into()),
+                (Breaking, "Required argument `Query.a(v:)` was added".into()),
+                (Breaking, "Argument `Query.a(w:)` was removed".into()),
+                (
+                    Safe,
+                    "Argument `Query.a(x:)` changed type from `Int!` to `Int`".into()
+                ),
+                (
+                    Breaking,
+                    "Argument `Query.a(y:)` changed type from `Int` to `Int!`".into()
+                ),
+                (
+                    Dangerous,
+                    "Default value of argument `Query.a(z:)` changed from `1` to `2`".into()
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn enums_unions_and_interfaces() {
+        assert_eq!(
+            changes(
+                r#"
+                type Query implements I { a: Int }
+                interface I { a: Int }
+                union U = Query | A
+                type A { a: Int }
+                enum E { X Y }
+                "#,
+                r#"
+                type Query { a: Int }
+                interface I { a: Int }
+                union U = A | B
+                type A { a: Int }
+                type B { a: Int }
+                enum E { X Z }
+                "#,
+            ),
+            vec![
+                (Safe, "Type `B` was added".into()),
+                (Breaking, "Enum value `E.Y` was removed".into()),
+                (Dangerous, "Enum value `E.Z` was added".into()),
+                (Breaking, "Interface `I` was removed from `Query`".into()),
+                (Dangerous, "Member `B` was added to `U`".into()),
+                (Breaking, "Member `Query` was removed from `U`".into()),
+            ],
+        );
+    }
+
+    #[test]
+    fn input_objects() {
+        assert_eq!(
+            changes(
+                "type Query { a(i: I): Int } input I { a: Int b: Int }",
+                "type Query { a(i: I): Int } input I { a: Int c: Int d: Int! e: Int! = 1 }",
+            ),
+            vec![
+                (Breaking, "Input field `I.b` was removed".into()),
+                (Safe, "Optional input field `I.c` was added".into()),
+                (Breaking, "Required input field `I.d` was added".into()),
+                (Safe, "Optional input field `I.e` was added".into()),
+            ],
+        );
+    }
+
+    #[test]
+    fn one_of_input_objects() {
+        assert_eq!(
+            changes(
+                "type Query { a(i: I): Int } input I { a: Int b: Int }",
+                "type Query { a(i: I): Int } input I @oneOf { a: Int b: Int }",
+            ),
+            vec![
+                (Safe, "Directive `@oneOf` was added".into()),
+                (
+                    Breaking,
+                    "Input object `I` became a OneOf input object".into()
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn root_types_and_directives() {
+        assert_eq!(
+            changes(
+                "type Query { a: Int } type Mutation { a: Int } directive @a on FIELD",
+                "type Query { a: Int } type Subscription { a: Int } \
+                 directive @b(x: Int) on FIELD | QUERY",
+            ),
+            vec![
+                (Breaking, "Directive `@a` was removed".into()),
+                (Safe, "Directive `@b` was added".into()),
+                (Breaking, "Type `Mutation` was removed".into()),
+                (Safe, "Type `Subscription` was added".into()),
+                (Breaking, "Mutation root type `Mutation` was removed".into()),
+                (
+                    Safe,
+                    "Subscription root type `Subscription` was added".into()
+                ),
+            ],
+        );
+    }
+}
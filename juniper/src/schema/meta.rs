@@ -6,7 +6,7 @@ use std::{
 };
 
 use crate::{
-    ast::{FromInputValue, InputValue, Type},
+    ast::{FromInputValue, InputValue, InputValueError, Type},
     parser::{ParseError, ScalarToken},
     schema::model::SchemaType,
     types::base::TypeKind,
@@ -40,32 +40,111 @@ impl DeprecationStatus {
     }
 }
 
+/// A type system directive applied to an element of a schema, like `@key(fields: "id")`
+///
+/// Applied directives carry no runtime behavior, and are only exposed in the
+/// [GraphQL Schema Language](https://graphql.org/learn/schema/#type-language) representation of
+/// a schema.
+#[derive(Debug, PartialEq, Clone)]
+pub struct AppliedDirective {
+    /// The name of the directive, without the leading `@`
+    pub name: String,
+    /// The arguments of the directive, in the order they were given
+    pub arguments: Vec<(String, InputValue)>,
+}
+
+impl AppliedDirective {
+    /// Construct a new directive application with the provided name and no arguments
+    pub fn new(name: &str) -> AppliedDirective {
+        AppliedDirective {
+            name: name.to_owned(),
+            arguments: vec![],
+        }
+    }
+
+    /// Add an argument to the directive application
+    pub fn argument(mut self, name: &str, value: InputValue) -> AppliedDirective {
+        self.arguments.push((name.to_owned(), value));
+        self
+    }
+}
+
+/// Hints for computing the complexity of a field in a query
+///
+/// The complexity of a field is its `cost` plus the complexity of its selection set times the
+/// multiplier. The multiplier is taken from the integer `multiplier_argument` when it is given in
+/// the query, e.g. the `first` argument of a paginated list, and defaults to `multiplier`
+/// otherwise.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Complexity {
+    /// The cost of resolving the field itself
+    pub cost: usize,
+    /// The multiplier of the selection set's complexity
+    pub multiplier: usize,
+    /// The name of the argument providing the multiplier
+    pub multiplier_argument: Option<String>,
+}
+
+impl Complexity {
+    /// Construct a complexity with the provided cost and a multiplier of `1`
+    pub fn new(cost: usize) -> Complexity {
+        Complexity {
+            cost,
+            multiplier: 1,
+            multiplier_argument: None,
+        }
+    }
+
+    /// Set the multiplier of the selection set's complexity
+    pub fn multiplier(mut self, multiplier: usize) -> Complexity {
+        self.multiplier = multiplier;
+        self
+    }
+
+    /// Take the multiplier from the named argument, when it is given
+    pub fn multiplier_argument(mut self, name: &str) -> Complexity {
+        self.multiplier_argument = Some(name.to_owned());
+        self
+    }
+}
+
+impl Default for Complexity {
+    fn default() -> Complexity {
+        Complexity::new(1)
+    }
+}
+
 /// Scalar type metadata
+#[derive(Clone)]
 pub struct ScalarMeta<'a, S> {
     #[doc(hidden)]
     pub name: Cow<'a, str>,
     #[doc(hidden)]
     pub description: Option<String>,
-    pub(crate) try_parse_fn: for<'b> fn(&'b InputValue<S>) -> bool,
+    #[doc(hidden)]
+    pub directives: Vec<AppliedDirective>,
+    #[doc(hidden)]
+    pub visibility: Option<String>,
+    pub(crate) try_parse_fn: for<'b> fn(&'b InputValue<S>) -> Result<(), InputValueError>,
     pub(crate) parse_fn: for<'b> fn(ScalarToken<'b>) -> Result<S, ParseError<'b>>,
 }
 
 /// List type metadata
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct ListMeta<'a> {
     #[doc(hidden)]
     pub of_type: Type<'a>,
 }
 
 /// Nullable type metadata
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct NullableMeta<'a> {
     #[doc(hidden)]
     pub of_type: Type<'a>,
 }
 
 /// Object type metadata
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct ObjectMeta<'a, S> {
     #[doc(hidden)]
     pub name: Cow<'a, str>,
@@ -75,9 +154,14 @@ pub struct ObjectMeta<'a, S> {
     pub fields: Vec<Field<'a, S>>,
     #[doc(hidden)]
     pub interface_names: Vec<String>,
+    #[doc(hidden)]
+    pub directives: Vec<AppliedDirective>,
+    #[doc(hidden)]
+    pub visibility: Option<String>,
 }
 
 /// Enum type metadata
+#[derive(Clone)]
 pub struct EnumMeta<'a, S> {
     #[doc(hidden)]
     pub name: Cow<'a, str>,
@@ -85,11 +169,15 @@ pub struct EnumMeta<'a, S> {
     pub description: Option<String>,
     #[doc(hidden)]
     pub values: Vec<EnumValue>,
-    pub(crate) try_parse_fn: for<'b> fn(&'b InputValue<S>) -> bool,
+    #[doc(hidden)]
+    pub directives: Vec<AppliedDirective>,
+    #[doc(hidden)]
+    pub visibility: Option<String>,
+    pub(crate) try_parse_fn: for<'b> fn(&'b InputValue<S>) -> Result<(), InputValueError>,
 }
 
 /// Interface type metadata
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct InterfaceMeta<'a, S> {
     #[doc(hidden)]
     pub name: Cow<'a, str>,
@@ -97,10 +185,16 @@ pub struct InterfaceMeta<'a, S> {
     pub description: Option<String>,
     #[doc(hidden)]
     pub fields: Vec<Field<'a, S>>,
+    #[doc(hidden)]
+    pub interface_names: Vec<String>,
+    #[doc(hidden)]
+    pub directives: Vec<AppliedDirective>,
+    #[doc(hidden)]
+    pub visibility: Option<String>,
 }
 
 /// Union type metadata
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct UnionMeta<'a> {
     #[doc(hidden)]
     pub name: Cow<'a, str>,
@@ -108,9 +202,14 @@ pub struct UnionMeta<'a> {
     pub description: Option<String>,
     #[doc(hidden)]
     pub of_type_names: Vec<String>,
+    #[doc(hidden)]
+    pub directives: Vec<AppliedDirective>,
+    #[doc(hidden)]
+    pub visibility: Option<String>,
 }
 
 /// Input object metadata
+#[derive(Clone)]
 pub struct InputObjectMeta<'a, S> {
     #[doc(hidden)]
     pub name: Cow<'a, str>,
@@ -118,21 +217,27 @@ pub struct InputObjectMeta<'a, S> {
     pub description: Option<String>,
     #[doc(hidden)]
     pub input_fields: Vec<Argument<'a, S>>,
-    pub(crate) try_parse_fn: for<'b> fn(&'b InputValue<S>) -> bool,
+    #[doc(hidden)]
+    pub directives: Vec<AppliedDirective>,
+    #[doc(hidden)]
+    pub visibility: Option<String>,
+    #[doc(hidden)]
+    pub is_one_of: bool,
+    pub(crate) try_parse_fn: for<'b> fn(&'b InputValue<S>) -> Result<(), InputValueError>,
 }
 
 /// A placeholder for not-yet-registered types
 ///
 /// After a type's `meta` method has been called but before it has returned, a placeholder type
 /// is inserted into a registry to indicate existence.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct PlaceholderMeta<'a> {
     #[doc(hidden)]
     pub of_type: Type<'a>,
 }
 
 /// Generic type metadata
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum MetaType<'a, S = DefaultScalarValue> {
     #[doc(hidden)]
     Scalar(ScalarMeta<'a, S>),
@@ -167,6 +272,12 @@ pub struct Field<'a, S> {
     pub field_type: Type<'a>,
     #[doc(hidden)]
     pub deprecation_status: DeprecationStatus,
+    #[doc(hidden)]
+    pub directives: Vec<AppliedDirective>,
+    #[doc(hidden)]
+    pub visibility: Option<String>,
+    #[doc(hidden)]
+    pub complexity: Complexity,
 }
 
 impl<'a, S> Field<'a, S> {
@@ -188,6 +299,8 @@ pub struct Argument<'a, S> {
     pub arg_type: Type<'a>,
     #[doc(hidden)]
     pub default_value: Option<InputValue<S>>,
+    #[doc(hidden)]
+    pub directives: Vec<AppliedDirective>,
 }
 
 impl<'a, S> Argument<'a, S> {
@@ -212,6 +325,11 @@ pub struct EnumValue {
     pub description: Option<String>,
     /// Whether the field is deprecated or not, with an optional reason.
     pub deprecation_status: DeprecationStatus,
+    /// Type system directives applied to the enum value.
+    pub directives: Vec<AppliedDirective>,
+    /// The name of the visibility predicate deciding whether the enum value is visible to a
+    /// request, or `None` if it's visible to all of them.
+    pub visibility: Option<String>,
 }
 
 impl<'a, S> MetaType<'a, S> {
@@ -257,6 +375,23 @@ impl<'a, S> MetaType<'a, S> {
         }
     }
 
+    /// Access the name of the visibility predicate of the type, if applicable
+    ///
+    /// Lists, nullable wrappers, and placeholders don't have visibilities.
+    pub fn visibility(&self) -> Option<&str> {
+        match *self {
+            MetaType::Scalar(ScalarMeta { ref visibility, .. })
+            | MetaType::Object(ObjectMeta { ref visibility, .. })
+            | MetaType::Enum(EnumMeta { ref visibility, .. })
+            | MetaType::Interface(InterfaceMeta { ref visibility, .. })
+            | MetaType::Union(UnionMeta { ref visibility, .. })
+            | MetaType::InputObject(InputObjectMeta { ref visibility, .. }) => {
+                visibility.as_deref()
+            }
+            _ => None,
+        }
+    }
+
     /// Construct a `TypeKind` for a given type
     ///
     /// # Panics
@@ -300,6 +435,14 @@ impl<'a, S> MetaType<'a, S> {
         }
     }
 
+    /// Returns true if the type is a OneOf input object, of which exactly one field must be given
+    pub fn is_one_of(&self) -> bool {
+        match *self {
+            MetaType::InputObject(InputObjectMeta { is_one_of, .. }) => is_one_of,
+            _ => false,
+        }
+    }
+
     /// Construct a `Type` literal instance based on the metadata
     pub fn as_type(&self) -> Type<'a> {
         match *self {
@@ -326,10 +469,13 @@ impl<'a, S> MetaType<'a, S> {
     /// Access the input value parse function, if applicable
     ///
     /// An input value parse function is a function that takes an `InputValue` instance and returns
-    /// `true` if it can be parsed as the provided type.
+    /// an error telling why it can't be parsed as the provided type, if it can't.
     ///
     /// Only scalars, enums, and input objects have parse functions.
-    pub fn input_value_parse_fn(&self) -> Option<for<'b> fn(&'b InputValue<S>) -> bool> {
+    #[allow(clippy::type_complexity)]
+    pub fn input_value_parse_fn(
+        &self,
+    ) -> Option<for<'b> fn(&'b InputValue<S>) -> Result<(), InputValueError>> {
         match *self {
             MetaType::Scalar(ScalarMeta {
                 ref try_parse_fn, ..
@@ -431,6 +577,8 @@ where
         ScalarMeta {
             name,
             description: None,
+            directives: vec![],
+            visibility: None,
             try_parse_fn: try_parse_fn::<S, T>,
             parse_fn: <T as ParseScalarValue<S>>::from_str,
         }
@@ -444,6 +592,23 @@ where
         self
     }
 
+    /// Apply a type system directive
+    ///
+    /// Directives are emitted in the order they were applied.
+    pub fn directive(mut self, directive: AppliedDirective) -> ScalarMeta<'a, S> {
+        self.directives.push(directive);
+        self
+    }
+
+    /// Make the type visible only to the requests the named visibility predicate holds for
+    ///
+    /// See [`RootNode::with_visibility`](crate::RootNode::with_visibility). If a visibility was
+    /// set prior to calling this method, it will be overwritten.
+    pub fn visibility(mut self, name: &str) -> ScalarMeta<'a, S> {
+        self.visibility = Some(name.to_owned());
+        self
+    }
+
     /// Wrap the scalar in a generic meta type
     pub fn into_meta(self) -> MetaType<'a, S> {
         MetaType::Scalar(self)
@@ -485,6 +650,8 @@ where
             description: None,
             fields: fields.to_vec(),
             interface_names: vec![],
+            directives: vec![],
+            visibility: None,
         }
     }
 
@@ -496,6 +663,23 @@ where
         self
     }
 
+    /// Apply a type system directive
+    ///
+    /// Directives are emitted in the order they were applied.
+    pub fn directive(mut self, directive: AppliedDirective) -> ObjectMeta<'a, S> {
+        self.directives.push(directive);
+        self
+    }
+
+    /// Make the type visible only to the requests the named visibility predicate holds for
+    ///
+    /// See [`RootNode::with_visibility`](crate::RootNode::with_visibility). If a visibility was
+    /// set prior to calling this method, it will be overwritten.
+    pub fn visibility(mut self, name: &str) -> ObjectMeta<'a, S> {
+        self.visibility = Some(name.to_owned());
+        self
+    }
+
     /// Set the interfaces this type implements
     ///
     /// If a list of interfaces already was provided prior to calling this method, they will be
@@ -527,6 +711,8 @@ where
             name,
             description: None,
             values: values.to_vec(),
+            directives: vec![],
+            visibility: None,
             try_parse_fn: try_parse_fn::<S, T>,
         }
     }
@@ -539,6 +725,23 @@ where
         self
     }
 
+    /// Apply a type system directive
+    ///
+    /// Directives are emitted in the order they were applied.
+    pub fn directive(mut self, directive: AppliedDirective) -> EnumMeta<'a, S> {
+        self.directives.push(directive);
+        self
+    }
+
+    /// Make the type visible only to the requests the named visibility predicate holds for
+    ///
+    /// See [`RootNode::with_visibility`](crate::RootNode::with_visibility). If a visibility was
+    /// set prior to calling this method, it will be overwritten.
+    pub fn visibility(mut self, name: &str) -> EnumMeta<'a, S> {
+        self.visibility = Some(name.to_owned());
+        self
+    }
+
     /// Wrap this enum type in a generic meta type
     pub fn into_meta(self) -> MetaType<'a, S> {
         MetaType::Enum(self)
@@ -555,6 +758,9 @@ where
             name,
             description: None,
             fields: fields.to_vec(),
+            interface_names: vec![],
+            directives: vec![],
+            visibility: None,
         }
     }
 
@@ -566,6 +772,35 @@ where
         self
     }
 
+    /// Apply a type system directive
+    ///
+    /// Directives are emitted in the order they were applied.
+    pub fn directive(mut self, directive: AppliedDirective) -> InterfaceMeta<'a, S> {
+        self.directives.push(directive);
+        self
+    }
+
+    /// Make the type visible only to the requests the named visibility predicate holds for
+    ///
+    /// See [`RootNode::with_visibility`](crate::RootNode::with_visibility). If a visibility was
+    /// set prior to calling this method, it will be overwritten.
+    pub fn visibility(mut self, name: &str) -> InterfaceMeta<'a, S> {
+        self.visibility = Some(name.to_owned());
+        self
+    }
+
+    /// Set the interfaces this type implements
+    ///
+    /// If a list of interfaces already was provided prior to calling this method, they will be
+    /// overwritten.
+    pub fn interfaces(mut self, interfaces: &[Type<'a>]) -> InterfaceMeta<'a, S> {
+        self.interface_names = interfaces
+            .iter()
+            .map(|t| t.innermost_name().to_owned())
+            .collect();
+        self
+    }
+
     /// Wrap this interface type in a generic meta type
     pub fn into_meta(self) -> MetaType<'a, S> {
         MetaType::Interface(self)
@@ -582,6 +817,8 @@ impl<'a> UnionMeta<'a> {
                 .iter()
                 .map(|t| t.innermost_name().to_owned())
                 .collect(),
+            directives: vec![],
+            visibility: None,
         }
     }
 
@@ -593,6 +830,23 @@ impl<'a> UnionMeta<'a> {
         self
     }
 
+    /// Apply a type system directive
+    ///
+    /// Directives are emitted in the order they were applied.
+    pub fn directive(mut self, directive: AppliedDirective) -> UnionMeta<'a> {
+        self.directives.push(directive);
+        self
+    }
+
+    /// Make the type visible only to the requests the named visibility predicate holds for
+    ///
+    /// See [`RootNode::with_visibility`](crate::RootNode::with_visibility). If a visibility was
+    /// set prior to calling this method, it will be overwritten.
+    pub fn visibility(mut self, name: &str) -> UnionMeta<'a> {
+        self.visibility = Some(name.to_owned());
+        self
+    }
+
     /// Wrap this union type in a generic meta type
     pub fn into_meta<S>(self) -> MetaType<'a, S> {
         MetaType::Union(self)
@@ -612,6 +866,9 @@ where
             name,
             description: None,
             input_fields: input_fields.to_vec(),
+            directives: vec![],
+            visibility: None,
+            is_one_of: false,
             try_parse_fn: try_parse_fn::<S, T>,
         }
     }
@@ -624,6 +881,31 @@ where
         self
     }
 
+    /// Apply a type system directive
+    ///
+    /// Directives are emitted in the order they were applied.
+    pub fn directive(mut self, directive: AppliedDirective) -> InputObjectMeta<'a, S> {
+        self.directives.push(directive);
+        self
+    }
+
+    /// Make the type visible only to the requests the named visibility predicate holds for
+    ///
+    /// See [`RootNode::with_visibility`](crate::RootNode::with_visibility). If a visibility was
+    /// set prior to calling this method, it will be overwritten.
+    pub fn visibility(mut self, name: &str) -> InputObjectMeta<'a, S> {
+        self.visibility = Some(name.to_owned());
+        self
+    }
+
+    /// Make the type a OneOf input object, of which exactly one field must be given
+    ///
+    /// The input fields of a OneOf input object should all be nullable, with no default value.
+    pub fn one_of(mut self) -> InputObjectMeta<'a, S> {
+        self.is_one_of = true;
+        self
+    }
+
     /// Wrap this union type in a generic meta type
     pub fn into_meta(self) -> MetaType<'a, S> {
         MetaType::InputObject(self)
@@ -639,6 +921,23 @@ impl<'a, S> Field<'a, S> {
         self
     }
 
+    /// Apply a type system directive
+    ///
+    /// Directives are emitted in the order they were applied.
+    pub fn directive(mut self, directive: AppliedDirective) -> Self {
+        self.directives.push(directive);
+        self
+    }
+
+    /// Make the field visible only to the requests the named visibility predicate holds for
+    ///
+    /// See [`RootNode::with_visibility`](crate::RootNode::with_visibility). If a visibility was
+    /// set prior to calling this method, it will be overwritten.
+    pub fn visibility(mut self, name: &str) -> Self {
+        self.visibility = Some(name.to_owned());
+        self
+    }
+
     /// Add an argument to the field
     ///
     /// Arguments are unordered and can't contain duplicates by name.
@@ -662,6 +961,14 @@ impl<'a, S> Field<'a, S> {
         self.deprecation_status = DeprecationStatus::Deprecated(reason.map(ToOwned::to_owned));
         self
     }
+
+    /// Set the complexity hints of the field
+    ///
+    /// This overwrites the complexity if any was previously set.
+    pub fn complexity(mut self, complexity: Complexity) -> Self {
+        self.complexity = complexity;
+        self
+    }
 }
 
 impl<'a, S> Argument<'a, S> {
@@ -672,6 +979,7 @@ impl<'a, S> Argument<'a, S> {
             description: None,
             arg_type,
             default_value: None,
+            directives: vec![],
         }
     }
 
@@ -683,6 +991,14 @@ impl<'a, S> Argument<'a, S> {
         self
     }
 
+    /// Apply a type system directive
+    ///
+    /// Directives are emitted in the order they were applied.
+    pub fn directive(mut self, directive: AppliedDirective) -> Self {
+        self.directives.push(directive);
+        self
+    }
+
     /// Set the default value of the argument
     ///
     /// This overwrites the default value if any was previously set.
@@ -699,6 +1015,8 @@ impl EnumValue {
             name: name.to_owned(),
             description: None,
             deprecation_status: DeprecationStatus::Current,
+            directives: vec![],
+            visibility: None,
         }
     }
 
@@ -710,6 +1028,26 @@ impl EnumValue {
         self
     }
 
+    /// Apply a type system directive
+    ///
+    /// Directives are emitted in the order they were applied.
+    pub fn directive(mut self, directive: AppliedDirective) -> EnumValue {
+        self.directives.push(directive);
+        self
+    }
+
+    /// Make the enum value visible only to the requests the named visibility predicate holds for
+    ///
+    /// Hidden values are left out of the introspection and of the schema language, and rejected
+    /// as input, but they're still returned as output.
+    ///
+    /// See [`RootNode::with_visibility`](crate::RootNode::with_visibility). If a visibility was
+    /// set prior to calling this method, it will be overwritten.
+    pub fn visibility(mut self, name: &str) -> EnumValue {
+        self.visibility = Some(name.to_owned());
+        self
+    }
+
     /// Set the enum value to be deprecated with an optional reason.
     ///
     /// This overwrites the deprecation reason if any was previously set.
@@ -744,13 +1082,15 @@ impl<'a, S: fmt::Debug> fmt::Debug for InputObjectMeta<'a, S> {
             .field("name", &self.name)
             .field("description", &self.description)
             .field("input_fields", &self.input_fields)
+            .field("is_one_of", &self.is_one_of)
             .finish()
     }
 }
 
-fn try_parse_fn<S, T>(v: &InputValue<S>) -> bool
+fn try_parse_fn<S, T>(v: &InputValue<S>) -> Result<(), InputValueError>
 where
+    S: ScalarValue,
     T: FromInputValue<S>,
 {
-    <T as FromInputValue<S>>::from_input_value(v).is_some()
+    <T as FromInputValue<S>>::try_from_input_value(v).map(drop)
 }
@@ -1,6 +1,10 @@
 #![allow(clippy::module_inception)]
 
+pub mod compatibility;
 pub mod meta;
 pub mod model;
 pub mod schema;
+#[cfg(feature = "graphql-parser-integration")]
+pub mod schema_language;
 pub mod translate;
+pub mod visibility;
@@ -1,4 +1,4 @@
-use std::fmt;
+use std::{collections::HashSet, fmt, mem, sync::Arc};
 
 use fnv::FnvHashMap;
 #[cfg(feature = "graphql-parser-integration")]
@@ -6,9 +6,14 @@ use graphql_parser::schema::Document;
 
 use crate::{
     ast::Type,
-    executor::{Context, Registry},
-    schema::meta::{Argument, InterfaceMeta, MetaType, ObjectMeta, PlaceholderMeta, UnionMeta},
+    executor::{Context, DirectiveResolver, Extension, ExtensionFactory, Extensions, Registry},
+    http::{error_policy::ErrorPolicy, persisted_query::PersistedQueryStore},
+    schema::{
+        meta::{Argument, InterfaceMeta, MetaType, ObjectMeta, PlaceholderMeta, UnionMeta},
+        visibility::{HiddenItems, Visibility},
+    },
     types::{base::GraphQLType, name::Name},
+    validation::{DocumentCache, RuleSet},
     value::{DefaultScalarValue, ScalarValue},
     GraphQLEnum,
 };
@@ -44,6 +49,25 @@ pub struct RootNode<
     pub subscription_info: SubscriptionT::TypeInfo,
     #[doc(hidden)]
     pub schema: SchemaType<'a, S>,
+    #[doc(hidden)]
+    pub rules: RuleSet<S>,
+    #[doc(hidden)]
+    pub max_depth: Option<usize>,
+    #[doc(hidden)]
+    pub max_complexity: Option<usize>,
+    #[doc(hidden)]
+    pub persisted_queries: Option<Box<dyn PersistedQueryStore>>,
+    #[doc(hidden)]
+    pub document_cache: Option<DocumentCache<S>>,
+    #[doc(hidden)]
+    pub extensions: Vec<Box<dyn ExtensionFactory<S>>>,
+    #[doc(hidden)]
+    pub error_policy: ErrorPolicy,
+    #[doc(hidden)]
+    pub visibility: Visibility<QueryT::Context>,
+    #[cfg(feature = "federation")]
+    #[doc(hidden)]
+    pub federation: Option<crate::federation::Federation<QueryT::Context, S>>,
 }
 
 /// Metadata for a schema
@@ -53,7 +77,7 @@ pub struct SchemaType<'a, S> {
     pub(crate) query_type_name: String,
     pub(crate) mutation_type_name: Option<String>,
     pub(crate) subscription_type_name: Option<String>,
-    directives: FnvHashMap<String, DirectiveType<'a, S>>,
+    pub(crate) directives: FnvHashMap<String, DirectiveType<'a, S>>,
 }
 
 impl<'a, S> Context for SchemaType<'a, S> {}
@@ -65,12 +89,28 @@ pub enum TypeType<'a, S: 'a> {
     List(Box<TypeType<'a, S>>),
 }
 
-#[derive(Debug)]
+#[derive(Clone)]
 pub struct DirectiveType<'a, S> {
     pub name: String,
     pub description: Option<String>,
     pub locations: Vec<DirectiveLocation>,
     pub arguments: Vec<Argument<'a, S>>,
+    pub(crate) resolver: Option<Arc<dyn DirectiveResolver<S> + 'a>>,
+}
+
+impl<'a, S> fmt::Debug for DirectiveType<'a, S>
+where
+    S: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DirectiveType")
+            .field("name", &self.name)
+            .field("description", &self.description)
+            .field("locations", &self.locations)
+            .field("arguments", &self.arguments)
+            .field("resolver", &self.resolver.as_ref().map(|_| ".."))
+            .finish()
+    }
 }
 
 #[derive(Clone, PartialEq, Eq, Debug, GraphQLEnum)]
@@ -86,6 +126,22 @@ pub enum DirectiveLocation {
     FragmentSpread,
     #[graphql(name = "INLINE_FRAGMENT")]
     InlineFragment,
+    Schema,
+    Scalar,
+    Object,
+    #[graphql(name = "FIELD_DEFINITION")]
+    FieldDefinition,
+    #[graphql(name = "ARGUMENT_DEFINITION")]
+    ArgumentDefinition,
+    Interface,
+    Union,
+    Enum,
+    #[graphql(name = "ENUM_VALUE")]
+    EnumValue,
+    #[graphql(name = "INPUT_OBJECT")]
+    InputObject,
+    #[graphql(name = "INPUT_FIELD_DEFINITION")]
+    InputFieldDefinition,
 }
 
 impl<'a, QueryT, MutationT, SubscriptionT>
@@ -128,6 +184,23 @@ where
         format!("{}", doc)
     }
 
+    #[cfg(feature = "schema-language")]
+    /// The schema definition seen by a request with the given `context` as a `String` in the
+    /// [GraphQL Schema Language](https://graphql.org/learn/schema/#type-language) format, without
+    /// the items hidden from the request.
+    ///
+    /// See [`RootNode::with_visibility`].
+    pub fn as_schema_language_for(&self, context: &QueryT::Context) -> String {
+        match self.hidden_items(context) {
+            Some(hidden) => {
+                let schema = hidden.strip(&self.schema);
+                let doc: Document<&str> = GraphQLParserTranslator::translate_schema(&schema);
+                format!("{}", doc)
+            }
+            None => self.as_schema_language(),
+        }
+    }
+
     #[cfg(feature = "graphql-parser-integration")]
     /// The schema definition as a [`graphql_parser`](https://crates.io/crates/graphql-parser)
     /// [`Document`](https://docs.rs/graphql-parser/latest/graphql_parser/schema/struct.Document.html).
@@ -166,7 +239,152 @@ where
             query_info,
             mutation_info,
             subscription_info,
+            rules: RuleSet::default(),
+            max_depth: None,
+            max_complexity: None,
+            persisted_queries: None,
+            document_cache: None,
+            extensions: vec![],
+            error_policy: ErrorPolicy::default(),
+            visibility: Visibility::default(),
+            #[cfg(feature = "federation")]
+            federation: None,
+        }
+    }
+
+    /// Registers a custom directive, like [`SchemaType::register_directive`].
+    pub fn with_directive<F>(mut self, build: F) -> Self
+    where
+        F: FnOnce(&mut Registry<'a, S>) -> DirectiveType<'a, S>,
+    {
+        self.schema.register_directive(build);
+        self
+    }
+
+    /// Enables the incremental delivery of queries executed with [`crate::execute_incremental`],
+    /// registering the `@defer` and `@stream` directives.
+    ///
+    /// Fragments marked with `@defer` and the items of lists marked with `@stream` past their
+    /// `initialCount` are delivered after the initial response. Other ways of executing
+    /// queries resolve them at once.
+    pub fn with_incremental_delivery(mut self) -> Self {
+        self.schema.register_directive(DirectiveType::new_defer);
+        self.schema.register_directive(DirectiveType::new_stream);
+        self
+    }
+
+    /// Replaces the validation rules run by [`crate::execute`], [`crate::execute_sync`] and
+    /// [`crate::resolve_into_stream`], which are the rules of the GraphQL specification by default.
+    pub fn with_rules(mut self, rules: RuleSet<S>) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Rejects operations whose selections are nested deeper than `limit` fields.
+    ///
+    /// Fragments are expanded when computing the depth, and introspection fields count too.
+    pub fn with_max_depth(mut self, limit: usize) -> Self {
+        self.max_depth = Some(limit);
+        self
+    }
+
+    /// Rejects operations whose complexity exceeds `limit`.
+    ///
+    /// Every field costs `1` by default, and the complexity of its selection set is added to it.
+    /// The cost and a multiplier of the selection set, e.g. for paginated lists, can be set
+    /// with [`meta::Field::complexity`](crate::meta::Field::complexity), or the
+    /// `#[graphql(complexity = ...)]` attribute in code generation.
+    pub fn with_max_complexity(mut self, limit: usize) -> Self {
+        self.max_complexity = Some(limit);
+        self
+    }
+
+    /// Enables [Automatic Persisted Queries][1], storing the queries sent by clients in `store`.
+    ///
+    /// See the [`crate::http::persisted_query`] module for details.
+    ///
+    /// [1]: https://www.apollographql.com/docs/apollo-server/performance/apq/
+    pub fn with_persisted_queries<P>(mut self, store: P) -> Self
+    where
+        P: PersistedQueryStore + 'static,
+    {
+        self.persisted_queries = Some(Box::new(store));
+        self
+    }
+
+    /// Caches up to `capacity` parsed and validated documents, so [`crate::execute`] and
+    /// [`crate::execute_sync`] skip parsing and validating queries they've seen before.
+    ///
+    /// See [`DocumentCache`] for details.
+    pub fn with_document_cache(mut self, capacity: usize) -> Self {
+        self.document_cache = Some(DocumentCache::new(capacity));
+        self
+    }
+
+    /// Registers an [`Extension`] notified during the execution of every request, created by
+    /// `factory` for each of them.
+    ///
+    /// Extensions are notified by [`crate::execute`], [`crate::execute_sync`] and their variants,
+    /// and their results are added to the responses built by [`crate::http::GraphQLRequest`].
+    pub fn with_extension<F, E>(mut self, factory: F) -> Self
+    where
+        F: Fn() -> E + Send + Sync + 'static,
+        E: Extension<S> + 'static,
+    {
+        self.extensions.push(Box::new(factory));
+        self
+    }
+
+    /// Sets the [`ErrorPolicy`] applied to the field errors raised while executing queries,
+    /// mutations and subscriptions, e.g. to mask the messages of unexpected errors.
+    ///
+    /// See the [`crate::http::error_policy`] module for details.
+    pub fn with_error_policy(mut self, policy: ErrorPolicy) -> Self {
+        self.error_policy = policy;
+        self
+    }
+
+    /// Registers the visibility predicate `name`, deciding from the context of a request whether
+    /// the types, fields and enum values marked with it are visible to the request.
+    ///
+    /// Items are marked with the `visibility` method of their metadata, or the
+    /// `#[graphql(visibility = "...")]` attribute in code generation. The hidden items are left
+    /// out of the introspection, of the SDL served by the `_service` field of federated schemas
+    /// and of [`RootNode::as_schema_language_for`], and the documents selecting hidden fields or
+    /// referring to hidden types fail validation. Fields returning a hidden type are hidden too.
+    ///
+    /// Hidden enum values are rejected as input, but they're still returned as output.
+    ///
+    /// Items marked with a name which isn't registered are hidden from every request.
+    pub fn with_visibility<F>(mut self, name: &str, predicate: F) -> Self
+    where
+        F: Fn(&QueryT::Context) -> bool + Send + Sync + 'static,
+    {
+        self.visibility.insert(name, predicate);
+        self
+    }
+
+    /// Creates the extensions registered with [`RootNode::with_extension`] for a new request.
+    pub fn create_extensions(&self) -> Extensions<S> {
+        let mut extensions = Extensions::new();
+        for factory in &self.extensions {
+            extensions.push(factory.create());
         }
+        extensions
+    }
+}
+
+impl<'a, S, QueryT, MutationT, SubscriptionT> RootNode<'a, QueryT, MutationT, SubscriptionT, S>
+where
+    QueryT: GraphQLType<S>,
+    MutationT: GraphQLType<S>,
+    SubscriptionT: GraphQLType<S>,
+    S: ScalarValue,
+{
+    /// Returns the items of the schema hidden from a request with the given `context`, or `None`
+    /// if the whole schema is visible to it.
+    pub(crate) fn hidden_items(&self, context: &QueryT::Context) -> Option<Arc<HiddenItems>> {
+        self.visibility.hidden_items(&self.schema, context)
     }
 }
 
@@ -211,11 +429,14 @@ impl<'a, S> SchemaType<'a, S> {
             "include".to_owned(),
             DirectiveType::new_include(&mut registry),
         );
+        if registry.types.values().any(MetaType::is_one_of) {
+            directives.insert("oneOf".to_owned(), DirectiveType::new_one_of());
+        }
 
         let mut meta_fields = vec![
             registry.field::<SchemaType<S>>("__schema", &()),
             registry
-                .field::<TypeType<S>>("__type", &())
+                .field::<Option<TypeType<S>>>("__type", &())
                 .argument(registry.arg::<String>("name", &())),
         ];
 
@@ -256,6 +477,18 @@ impl<'a, S> SchemaType<'a, S> {
         self.directives.insert(directive.name.clone(), directive);
     }
 
+    /// Add a custom directive, registering the types of its arguments.
+    pub fn register_directive<F>(&mut self, build: F)
+    where
+        S: ScalarValue + 'a,
+        F: FnOnce(&mut Registry<'a, S>) -> DirectiveType<'a, S>,
+    {
+        let mut registry = Registry::new(mem::take(&mut self.types));
+        let directive = build(&mut registry);
+        self.types = registry.types;
+        self.add_directive(directive);
+    }
+
     /// Get a type by name.
     pub fn type_by_name(&self, name: &str) -> Option<TypeType<S>> {
         self.types.get(name).map(|t| TypeType::Concrete(t))
@@ -395,10 +628,7 @@ impl<'a, S> SchemaType<'a, S> {
                 .concrete_type_list()
                 .into_iter()
                 .filter(|t| match **t {
-                    MetaType::Object(ObjectMeta {
-                        ref interface_names,
-                        ..
-                    }) => interface_names.iter().any(|iname| iname == name),
+                    MetaType::Object(_) => self.implements_interface(t, name),
                     _ => false,
                 })
                 .collect(),
@@ -406,6 +636,37 @@ impl<'a, S> SchemaType<'a, S> {
         }
     }
 
+    /// If the type implements the interface, either directly or through the interfaces it
+    /// implements.
+    pub fn implements_interface(&self, t: &MetaType<S>, interface_name: &str) -> bool {
+        let mut visited = HashSet::new();
+        let mut pending = vec![t];
+
+        while let Some(t) = pending.pop() {
+            let interface_names = match *t {
+                MetaType::Object(ObjectMeta {
+                    ref interface_names,
+                    ..
+                })
+                | MetaType::Interface(InterfaceMeta {
+                    ref interface_names,
+                    ..
+                }) => interface_names,
+                _ => continue,
+            };
+            for iname in interface_names {
+                if iname == interface_name {
+                    return true;
+                }
+                if visited.insert(iname.as_str()) {
+                    pending.extend(self.concrete_type_by_name(iname));
+                }
+            }
+        }
+
+        false
+    }
+
     /// If the abstract type is possible.
     pub fn is_possible_type(
         &self,
@@ -448,7 +709,9 @@ impl<'a, S> SchemaType<'a, S> {
             self.concrete_type_by_name(sub_type_name),
             self.concrete_type_by_name(super_type_name),
         ) {
-            super_type.is_abstract() && self.is_possible_type(super_type, sub_type)
+            super_type.is_abstract()
+                && (self.is_possible_type(super_type, sub_type)
+                    || self.implements_interface(sub_type, super_type_name))
         } else {
             false
         }
@@ -504,10 +767,11 @@ where
             description: None,
             locations: locations.to_vec(),
             arguments: arguments.to_vec(),
+            resolver: None,
         }
     }
 
-    fn new_skip(registry: &mut Registry<'a, S>) -> DirectiveType<'a, S>
+    pub(crate) fn new_skip(registry: &mut Registry<'a, S>) -> DirectiveType<'a, S>
     where
         S: ScalarValue,
     {
@@ -522,7 +786,7 @@ where
         )
     }
 
-    fn new_include(registry: &mut Registry<'a, S>) -> DirectiveType<'a, S>
+    pub(crate) fn new_include(registry: &mut Registry<'a, S>) -> DirectiveType<'a, S>
     where
         S: ScalarValue,
     {
@@ -537,10 +801,58 @@ where
         )
     }
 
+    pub(crate) fn new_defer(registry: &mut Registry<'a, S>) -> DirectiveType<'a, S>
+    where
+        S: ScalarValue,
+    {
+        Self::new(
+            "defer",
+            &[
+                DirectiveLocation::FragmentSpread,
+                DirectiveLocation::InlineFragment,
+            ],
+            &[
+                registry.arg_with_default::<bool>("if", &true, &()),
+                registry.arg::<Option<String>>("label", &()),
+            ],
+        )
+    }
+
+    pub(crate) fn new_stream(registry: &mut Registry<'a, S>) -> DirectiveType<'a, S>
+    where
+        S: ScalarValue,
+    {
+        Self::new(
+            "stream",
+            &[DirectiveLocation::Field],
+            &[
+                registry.arg_with_default::<bool>("if", &true, &()),
+                registry.arg::<Option<String>>("label", &()),
+                registry.arg_with_default::<i32>("initialCount", &0, &()),
+            ],
+        )
+    }
+
+    /// The `@oneOf` directive, marking OneOf input objects, which is only part of schemas using
+    /// them
+    pub(crate) fn new_one_of() -> DirectiveType<'a, S> {
+        Self::new("oneOf", &[DirectiveLocation::InputObject], &[])
+    }
+
     pub fn description(mut self, description: &str) -> DirectiveType<'a, S> {
         self.description = Some(description.to_owned());
         self
     }
+
+    /// Sets the resolver invoked around the resolution of every field this directive is
+    /// applied to.
+    pub fn resolver<R>(mut self, resolver: R) -> DirectiveType<'a, S>
+    where
+        R: DirectiveResolver<S> + 'a,
+    {
+        self.resolver = Some(Arc::new(resolver));
+        self
+    }
 }
 
 impl fmt::Display for DirectiveLocation {
@@ -553,6 +865,17 @@ impl fmt::Display for DirectiveLocation {
             DirectiveLocation::FragmentDefinition => "fragment definition",
             DirectiveLocation::FragmentSpread => "fragment spread",
             DirectiveLocation::InlineFragment => "inline fragment",
+            DirectiveLocation::Schema => "schema",
+            DirectiveLocation::Scalar => "scalar",
+            DirectiveLocation::Object => "object",
+            DirectiveLocation::FieldDefinition => "field definition",
+            DirectiveLocation::ArgumentDefinition => "argument definition",
+            DirectiveLocation::Interface => "interface",
+            DirectiveLocation::Union => "union",
+            DirectiveLocation::Enum => "enum",
+            DirectiveLocation::EnumValue => "enum value",
+            DirectiveLocation::InputObject => "input object",
+            DirectiveLocation::InputFieldDefinition => "input field definition",
         })
     }
 }
@@ -59,14 +59,22 @@ where
         executor: &Executor<Self::Context, S>,
     ) -> ExecutionResult<S> {
         match field {
-            "__schema" => executor
-                .replaced_context(&self.schema)
-                .resolve(&(), &self.schema),
-            "__type" => {
-                let type_name: String = args.get("name").unwrap();
-                executor
-                    .replaced_context(&self.schema)
-                    .resolve(&(), &self.schema.type_by_name(&type_name))
+            "__schema" | "__type" => match self.hidden_items(executor.context()) {
+                Some(hidden) => {
+                    resolve_introspection(&hidden.strip(&self.schema), field, args, executor)
+                }
+                None => resolve_introspection(&self.schema, field, args, executor),
+            },
+            #[cfg(feature = "federation")]
+            "_service" | "_entities" if self.federation.is_some() => {
+                let hidden = self.hidden_items(executor.context());
+                self.federation.as_ref().unwrap().resolve_field(
+                    &self.schema,
+                    hidden.as_deref(),
+                    field,
+                    args,
+                    executor,
+                )
             }
             _ => self.query_type.resolve_field(info, field, args, executor),
         }
@@ -93,6 +101,25 @@ where
     }
 }
 
+/// Resolves the `__schema` or `__type` field of the query type with the given `schema`
+fn resolve_introspection<CtxT, S>(
+    schema: &SchemaType<S>,
+    field: &str,
+    args: &Arguments<S>,
+    executor: &Executor<CtxT, S>,
+) -> ExecutionResult<S>
+where
+    S: ScalarValue,
+{
+    let executor = executor.replaced_context(schema);
+    if field == "__schema" {
+        executor.resolve(&(), schema)
+    } else {
+        let type_name: String = args.get("name").unwrap();
+        executor.resolve(&(), &schema.type_by_name(&type_name))
+    }
+}
+
 impl<'a, S, QueryT, MutationT, SubscriptionT> GraphQLValueAsync<S>
     for RootNode<'a, QueryT, MutationT, SubscriptionT, S>
 where
@@ -118,6 +145,17 @@ where
                 let v = self.resolve_field(info, field_name, arguments, executor);
                 Box::pin(ready(v))
             }
+            #[cfg(feature = "federation")]
+            "_service" | "_entities" if self.federation.is_some() => {
+                let hidden = self.hidden_items(executor.context());
+                self.federation.as_ref().unwrap().resolve_field_async(
+                    &self.schema,
+                    hidden.as_deref(),
+                    field_name,
+                    arguments,
+                    executor,
+                )
+            }
             _ => self
                 .query_type
                 .resolve_field_async(info, field_name, arguments, executor),
@@ -239,6 +277,10 @@ where
             TypeType::Concrete(&MetaType::Object(ObjectMeta {
                 ref interface_names,
                 ..
+            }))
+            | TypeType::Concrete(&MetaType::Interface(InterfaceMeta {
+                ref interface_names,
+                ..
             })) => Some(
                 interface_names
                     .iter()
@@ -259,29 +301,12 @@ where
                     .filter_map(|tn| schema.type_by_name(tn))
                     .collect(),
             ),
-            TypeType::Concrete(&MetaType::Interface(InterfaceMeta {
-                name: ref iface_name,
-                ..
-            })) => Some(
+            TypeType::Concrete(t @ &MetaType::Interface(_)) => Some(
                 schema
-                    .concrete_type_list()
-                    .iter()
-                    .filter_map(|&ct| {
-                        if let MetaType::Object(ObjectMeta {
-                            ref name,
-                            ref interface_names,
-                            ..
-                        }) = *ct
-                        {
-                            if interface_names.contains(&iface_name.to_string()) {
-                                schema.type_by_name(name)
-                            } else {
-                                None
-                            }
-                        } else {
-                            None
-                        }
-                    })
+                    .possible_types(t)
+                    .into_iter()
+                    .filter_map(|ct| ct.name())
+                    .filter_map(|name| schema.type_by_name(name))
                     .collect(),
             ),
             _ => None,
@@ -300,6 +325,15 @@ where
             _ => None,
         }
     }
+
+    fn is_one_of(&self) -> Option<bool> {
+        match *self {
+            TypeType::Concrete(&MetaType::InputObject(InputObjectMeta { is_one_of, .. })) => {
+                Some(is_one_of)
+            }
+            _ => None,
+        }
+    }
 }
 
 #[crate::graphql_object(
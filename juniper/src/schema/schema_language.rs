@@ -0,0 +1,988 @@
+//! Building a [`SchemaType`] from a
+//! [GraphQL Schema Language](https://graphql.org/learn/schema/#type-language) document.
+
+use std::{
+    borrow::{Borrow, Cow},
+    convert::TryFrom,
+    fmt,
+};
+
+use fnv::FnvHashMap;
+use graphql_parser::{
+    query::{Type as ExternalType, Value as ExternalValue},
+    schema::{
+        parse_schema, Definition, Directive as ExternalDirective, DirectiveDefinition,
+        DirectiveLocation as ExternalDirectiveLocation, EnumValue as ExternalEnumValue,
+        Field as ExternalField, InputValue as ExternalInputValue, SchemaDefinition, TypeDefinition,
+        TypeExtension,
+    },
+};
+use indexmap::IndexMap;
+
+use crate::{
+    ast::{InputValue, InputValueError, Type},
+    executor::Registry,
+    parser::{ParseError, ScalarToken, Token},
+    schema::{
+        meta::{
+            AppliedDirective, Argument, Complexity, DeprecationStatus, EnumMeta, EnumValue, Field,
+            InputObjectMeta, InterfaceMeta, MetaType, ObjectMeta, ScalarMeta, UnionMeta,
+        },
+        model::{DirectiveLocation, DirectiveType, SchemaType, TypeType},
+    },
+    types::{name::Name, scalars::ID},
+    value::ScalarValue,
+};
+
+/// An error that prevented a [`SchemaType`] from being built out of a GraphQL Schema Language
+/// document.
+#[derive(Debug, PartialEq)]
+pub enum SchemaLanguageError {
+    /// The document is not a syntactically valid GraphQL Schema Language document.
+    ParseError(String),
+    /// A type, field, argument, enum value or directive is defined more than once.
+    DuplicateDefinition(String),
+    /// A type is referenced, but never defined.
+    UnknownType {
+        /// Name of the unknown type.
+        name: String,
+        /// Place the type is referenced from.
+        location: String,
+    },
+    /// A type is used in a position it's not allowed in, e.g. an object type as an argument.
+    InvalidTypeUsage {
+        /// Name of the misused type.
+        name: String,
+        /// Place the type is used in.
+        location: String,
+    },
+    /// An object or interface type doesn't satisfy the contract of an interface it claims to
+    /// implement.
+    InvalidInterfaceImplementation {
+        /// Name of the implementing object or interface type.
+        object: String,
+        /// Name of the implemented interface type.
+        interface: String,
+        /// Explanation of what is wrong with the implementation.
+        reason: String,
+    },
+    /// The schema doesn't define a query root type.
+    NoQueryType,
+    /// The document contains a definition Juniper can't represent in a schema.
+    UnsupportedDefinition(String),
+}
+
+impl fmt::Display for SchemaLanguageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SchemaLanguageError::ParseError(e) => write!(f, "{}", e),
+            SchemaLanguageError::DuplicateDefinition(what) => {
+                write!(f, "{} is defined more than once", what)
+            }
+            SchemaLanguageError::UnknownType { name, location } => {
+                write!(f, r#"Unknown type "{}" referenced by {}"#, name, location)
+            }
+            SchemaLanguageError::InvalidTypeUsage { name, location } => {
+                write!(f, r#"Type "{}" can't be used as {}"#, name, location)
+            }
+            SchemaLanguageError::InvalidInterfaceImplementation {
+                object,
+                interface,
+                reason,
+            } => write!(
+                f,
+                r#"Type "{}" doesn't implement interface "{}" correctly: {}"#,
+                object, interface, reason,
+            ),
+            SchemaLanguageError::NoQueryType => write!(f, "Schema doesn't define a query type"),
+            SchemaLanguageError::UnsupportedDefinition(what) => {
+                write!(f, "Unsupported definition: {}", what)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SchemaLanguageError {}
+
+type ExternalTypeDefinition<'d> = TypeDefinition<'d, String>;
+
+impl<'a, S> SchemaType<'a, S>
+where
+    S: ScalarValue + 'a,
+{
+    /// Builds a new schema out of the given
+    /// [GraphQL Schema Language](https://graphql.org/learn/schema/#type-language) document.
+    ///
+    /// The resulting schema contains the same built-in types, fields and directives as a schema
+    /// built out of Rust types, so it can be compared with one, or used as a source of metadata
+    /// for types resolving dynamically via their `TypeInfo` (see
+    /// [`Registry::build_type_from_schema`]).
+    ///
+    /// Custom scalars accept any scalar input value.
+    pub fn from_schema_language(source: &str) -> Result<Self, SchemaLanguageError> {
+        let document = parse_schema::<String>(source)
+            .map_err(|e| SchemaLanguageError::ParseError(e.to_string()))?;
+
+        let mut types = IndexMap::<String, ExternalTypeDefinition>::new();
+        let mut extensions = vec![];
+        let mut schema_def = None;
+        let mut directive_defs = vec![];
+
+        for def in document.definitions {
+            match def {
+                Definition::SchemaDefinition(def) => {
+                    if schema_def.is_some() {
+                        return Err(SchemaLanguageError::DuplicateDefinition(
+                            "Schema definition".into(),
+                        ));
+                    }
+                    schema_def = Some(def);
+                }
+                Definition::TypeDefinition(def) => {
+                    let name = type_definition_name(&def).to_owned();
+                    if name.starts_with("__") {
+                        return Err(SchemaLanguageError::UnsupportedDefinition(format!(
+                            r#"type "{}" uses a name reserved for introspection"#,
+                            name,
+                        )));
+                    }
+                    if is_builtin_scalar(&name) || types.contains_key(&name) {
+                        return Err(SchemaLanguageError::DuplicateDefinition(format!(
+                            r#"Type "{}""#,
+                            name,
+                        )));
+                    }
+                    types.insert(name, def);
+                }
+                Definition::TypeExtension(ext) => extensions.push(ext),
+                Definition::DirectiveDefinition(def) => directive_defs.push(def),
+            }
+        }
+
+        for ext in extensions {
+            apply_extension(&mut types, ext)?;
+        }
+
+        let mut builder = Builder {
+            definitions: &types,
+            registry: Registry::new(FnvHashMap::default()),
+        };
+        builder.registry.get_type::<SchemaType<S>>(&());
+
+        for def in types.values() {
+            let meta = builder.translate_definition(def)?;
+            let name = type_definition_name(def)
+                .parse::<Name>()
+                .map_err(|e| SchemaLanguageError::ParseError(e.to_string()))?;
+            builder.registry.types.insert(name, meta);
+        }
+
+        let mut directives = FnvHashMap::default();
+        directives.insert(
+            "skip".to_owned(),
+            DirectiveType::new_skip(&mut builder.registry),
+        );
+        directives.insert(
+            "include".to_owned(),
+            DirectiveType::new_include(&mut builder.registry),
+        );
+        for def in &directive_defs {
+            let directive = builder.translate_directive(def)?;
+            if directives.contains_key(&directive.name) {
+                return Err(SchemaLanguageError::DuplicateDefinition(format!(
+                    r#"Directive "@{}""#,
+                    directive.name,
+                )));
+            }
+            directives.insert(directive.name.clone(), directive);
+        }
+        if builder.registry.types.values().any(MetaType::is_one_of) {
+            directives
+                .entry("oneOf".to_owned())
+                .or_insert_with(DirectiveType::new_one_of);
+        }
+
+        let (query, mutation, subscription) = builder.root_type_names(schema_def.as_ref())?;
+
+        let mut meta_fields = vec![
+            builder.registry.field::<SchemaType<S>>("__schema", &()),
+            builder
+                .registry
+                .field::<TypeType<S>>("__type", &())
+                .argument(builder.registry.arg::<String>("name", &())),
+        ];
+        if let Some(MetaType::Object(ObjectMeta { ref mut fields, .. })) =
+            builder.registry.types.get_mut(&query)
+        {
+            fields.append(&mut meta_fields);
+        }
+
+        let schema = SchemaType {
+            types: builder.registry.types,
+            query_type_name: query,
+            mutation_type_name: mutation,
+            subscription_type_name: subscription,
+            directives,
+        };
+        validate_interface_implementations(&schema)?;
+
+        Ok(schema)
+    }
+}
+
+impl<'r, S> Registry<'r, S>
+where
+    S: ScalarValue + 'r,
+{
+    /// Create a meta type by copying the type with the given name out of an existing schema
+    ///
+    /// All the other types of the schema are registered as well, so the types referenced by the
+    /// returned metadata are available. This allows a `GraphQLType` implementation to take its
+    /// metadata from a schema built with [`SchemaType::from_schema_language`], and resolve its
+    /// fields dynamically based on its `TypeInfo`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the schema doesn't contain the type.
+    pub fn build_type_from_schema(
+        &mut self,
+        schema: &SchemaType<'r, S>,
+        name: &str,
+    ) -> MetaType<'r, S> {
+        for (type_name, meta) in &schema.types {
+            let type_name_str: &str = type_name.borrow();
+            if type_name_str.starts_with("__") || type_name_str == name {
+                continue;
+            }
+            self.types
+                .entry(type_name.clone())
+                .or_insert_with(|| without_schema_meta_fields(meta));
+        }
+
+        without_schema_meta_fields(
+            schema
+                .types
+                .get(name)
+                .unwrap_or_else(|| panic!("Type {} not found in schema", name)),
+        )
+    }
+}
+
+/// Clones the given meta type without the `__schema` and `__type` fields, which are added to the
+/// query root type by the schema itself.
+fn without_schema_meta_fields<'r, S: Clone>(meta: &MetaType<'r, S>) -> MetaType<'r, S> {
+    let mut meta = meta.clone();
+    if let MetaType::Object(ObjectMeta { ref mut fields, .. }) = meta {
+        fields.retain(|f| f.name != "__schema" && f.name != "__type");
+    }
+    meta
+}
+
+struct Builder<'d, 'r, S> {
+    definitions: &'d IndexMap<String, ExternalTypeDefinition<'d>>,
+    registry: Registry<'r, S>,
+}
+
+/// Position a type is referenced from, which determines the kinds of types allowed there.
+#[derive(Clone, Copy, PartialEq)]
+enum Position {
+    Input,
+    Output,
+}
+
+impl<'d, 'r, S> Builder<'d, 'r, S>
+where
+    S: ScalarValue + 'r,
+{
+    fn translate_definition(
+        &mut self,
+        def: &ExternalTypeDefinition,
+    ) -> Result<MetaType<'r, S>, SchemaLanguageError> {
+        Ok(match def {
+            TypeDefinition::Scalar(s) => ScalarMeta {
+                name: Cow::Owned(s.name.clone()),
+                description: s.description.clone(),
+                directives: applied_directives(&s.directives)?,
+                visibility: None,
+                try_parse_fn: is_scalar_input::<S>,
+                parse_fn: parse_custom_scalar::<S>,
+            }
+            .into_meta(),
+            TypeDefinition::Object(o) => {
+                let mut fields = self.translate_fields(&o.name, &o.fields)?;
+                fields.push(self.registry.field::<String>("__typename", &()));
+                let interfaces = self.translate_interfaces(&o.name, &o.implements_interfaces)?;
+
+                let mut meta =
+                    ObjectMeta::new(Cow::Owned(o.name.clone()), &fields).interfaces(&interfaces);
+                meta.description = o.description.clone();
+                meta.directives = applied_directives(&o.directives)?;
+                meta.into_meta()
+            }
+            TypeDefinition::Interface(i) => {
+                let mut fields = self.translate_fields(&i.name, &i.fields)?;
+                fields.push(self.registry.field::<String>("__typename", &()));
+                let interfaces = self.translate_interfaces(&i.name, &i.implements_interfaces)?;
+
+                let mut meta =
+                    InterfaceMeta::new(Cow::Owned(i.name.clone()), &fields).interfaces(&interfaces);
+                meta.description = i.description.clone();
+                meta.directives = applied_directives(&i.directives)?;
+                meta.into_meta()
+            }
+            TypeDefinition::Union(u) => {
+                let mut members = vec![];
+                for name in &u.types {
+                    if members.iter().any(|t: &Type| t.innermost_name() == name) {
+                        return Err(SchemaLanguageError::DuplicateDefinition(format!(
+                            r#"Member "{}" of union "{}""#,
+                            name, u.name,
+                        )));
+                    }
+                    match self.definitions.get(name) {
+                        Some(TypeDefinition::Object(_)) => {
+                            members.push(Type::NonNullNamed(Cow::Owned(name.clone())))
+                        }
+                        Some(_) => {
+                            return Err(SchemaLanguageError::InvalidTypeUsage {
+                                name: name.clone(),
+                                location: format!(r#"a member of union "{}""#, u.name),
+                            })
+                        }
+                        None => {
+                            return Err(SchemaLanguageError::UnknownType {
+                                name: name.clone(),
+                                location: format!(r#"union "{}""#, u.name),
+                            })
+                        }
+                    }
+                }
+
+                let mut meta = UnionMeta::new(Cow::Owned(u.name.clone()), &members);
+                meta.description = u.description.clone();
+                meta.directives = applied_directives(&u.directives)?;
+                meta.into_meta()
+            }
+            TypeDefinition::Enum(e) => {
+                let values = translate_enum_values(&e.name, &e.values)?;
+
+                EnumMeta {
+                    name: Cow::Owned(e.name.clone()),
+                    description: e.description.clone(),
+                    values,
+                    directives: applied_directives(&e.directives)?,
+                    visibility: None,
+                    try_parse_fn: is_enum_input::<S>,
+                }
+                .into_meta()
+            }
+            TypeDefinition::InputObject(io) => {
+                let mut input_fields = vec![];
+                for f in &io.fields {
+                    if input_fields.iter().any(|a: &Argument<S>| a.name == f.name) {
+                        return Err(SchemaLanguageError::DuplicateDefinition(format!(
+                            r#"Input field "{}.{}""#,
+                            io.name, f.name,
+                        )));
+                    }
+                    let location = format!(r#"the type of input field "{}.{}""#, io.name, f.name);
+                    input_fields.push(self.translate_argument(f, location)?);
+                }
+
+                InputObjectMeta {
+                    name: Cow::Owned(io.name.clone()),
+                    description: io.description.clone(),
+                    input_fields,
+                    directives: applied_directives(&io.directives)?,
+                    visibility: None,
+                    is_one_of: io.directives.iter().any(|d| d.name == "oneOf"),
+                    try_parse_fn: is_object_input::<S>,
+                }
+                .into_meta()
+            }
+        })
+    }
+
+    fn translate_fields(
+        &mut self,
+        type_name: &str,
+        fields: &[ExternalField<String>],
+    ) -> Result<Vec<Field<'r, S>>, SchemaLanguageError> {
+        let mut result: Vec<Field<S>> = vec![];
+        for f in fields {
+            if result.iter().any(|r| r.name == f.name) {
+                return Err(SchemaLanguageError::DuplicateDefinition(format!(
+                    r#"Field "{}.{}""#,
+                    type_name, f.name,
+                )));
+            }
+
+            let field_type = self.translate_type(
+                &f.field_type,
+                Position::Output,
+                format!(r#"the type of field "{}.{}""#, type_name, f.name),
+            )?;
+            let mut arguments: Vec<Argument<S>> = vec![];
+            for a in &f.arguments {
+                if arguments.iter().any(|r| r.name == a.name) {
+                    return Err(SchemaLanguageError::DuplicateDefinition(format!(
+                        r#"Argument "{}.{}({}:)""#,
+                        type_name, f.name, a.name,
+                    )));
+                }
+                let location = format!(
+                    r#"the type of argument "{}.{}({}:)""#,
+                    type_name, f.name, a.name,
+                );
+                arguments.push(self.translate_argument(a, location)?);
+            }
+
+            result.push(Field {
+                name: f.name.clone(),
+                description: f.description.clone(),
+                arguments: if arguments.is_empty() {
+                    None
+                } else {
+                    Some(arguments)
+                },
+                field_type,
+                deprecation_status: deprecation_status(&f.directives),
+                directives: applied_directives(&f.directives)?,
+                visibility: None,
+                complexity: Complexity::default(),
+            });
+        }
+        Ok(result)
+    }
+
+    fn translate_interfaces(
+        &self,
+        type_name: &str,
+        interface_names: &[String],
+    ) -> Result<Vec<Type<'r>>, SchemaLanguageError> {
+        let mut interfaces = vec![];
+        for name in interface_names {
+            if interfaces.iter().any(|t: &Type| t.innermost_name() == name) {
+                return Err(SchemaLanguageError::DuplicateDefinition(format!(
+                    r#"Implementation of interface "{}" by type "{}""#,
+                    name, type_name,
+                )));
+            }
+            match self.definitions.get(name) {
+                Some(TypeDefinition::Interface(_)) => {
+                    interfaces.push(Type::NonNullNamed(Cow::Owned(name.clone())))
+                }
+                Some(_) => {
+                    return Err(SchemaLanguageError::InvalidTypeUsage {
+                        name: name.clone(),
+                        location: format!(r#"an interface of type "{}""#, type_name),
+                    })
+                }
+                None => {
+                    return Err(SchemaLanguageError::UnknownType {
+                        name: name.clone(),
+                        location: format!(r#"type "{}""#, type_name),
+                    })
+                }
+            }
+        }
+        Ok(interfaces)
+    }
+
+    fn translate_argument(
+        &mut self,
+        input: &ExternalInputValue<String>,
+        location: String,
+    ) -> Result<Argument<'r, S>, SchemaLanguageError> {
+        let arg_type = self.translate_type(&input.value_type, Position::Input, location)?;
+        let mut arg = Argument::new(&input.name, arg_type);
+        arg.description = input.description.clone();
+        arg.directives = applied_directives(&input.directives)?;
+        if let Some(ref default_value) = input.default_value {
+            arg.default_value = Some(translate_value(default_value)?);
+        }
+        Ok(arg)
+    }
+
+    fn translate_type(
+        &mut self,
+        input: &ExternalType<String>,
+        position: Position,
+        location: String,
+    ) -> Result<Type<'r>, SchemaLanguageError> {
+        Ok(match input {
+            ExternalType::NamedType(name) => {
+                self.check_named_type(name, position, location)?;
+                Type::Named(Cow::Owned(name.clone()))
+            }
+            ExternalType::ListType(inner) => {
+                Type::List(Box::new(self.translate_type(inner, position, location)?))
+            }
+            ExternalType::NonNullType(inner) => {
+                match self.translate_type(inner, position, location)? {
+                    Type::Named(name) => Type::NonNullNamed(name),
+                    Type::List(inner) => Type::NonNullList(inner),
+                    t => t,
+                }
+            }
+        })
+    }
+
+    fn check_named_type(
+        &mut self,
+        name: &str,
+        position: Position,
+        location: String,
+    ) -> Result<(), SchemaLanguageError> {
+        let valid = match self.definitions.get(name) {
+            Some(TypeDefinition::Scalar(_)) | Some(TypeDefinition::Enum(_)) => true,
+            Some(TypeDefinition::InputObject(_)) => position == Position::Input,
+            Some(_) => position == Position::Output,
+            None => {
+                if !self.register_builtin_scalar(name) {
+                    return Err(SchemaLanguageError::UnknownType {
+                        name: name.to_owned(),
+                        location,
+                    });
+                }
+                true
+            }
+        };
+
+        if valid {
+            Ok(())
+        } else {
+            Err(SchemaLanguageError::InvalidTypeUsage {
+                name: name.to_owned(),
+                location,
+            })
+        }
+    }
+
+    /// Registers the built-in scalar with the given name, returning `false` if there is none.
+    fn register_builtin_scalar(&mut self, name: &str) -> bool {
+        match name {
+            "Int" => self.registry.get_type::<i32>(&()),
+            "Float" => self.registry.get_type::<f64>(&()),
+            "String" => self.registry.get_type::<String>(&()),
+            "Boolean" => self.registry.get_type::<bool>(&()),
+            "ID" => self.registry.get_type::<ID>(&()),
+            _ => return false,
+        };
+        true
+    }
+
+    fn translate_directive(
+        &mut self,
+        def: &DirectiveDefinition<String>,
+    ) -> Result<DirectiveType<'r, S>, SchemaLanguageError> {
+        let mut locations = vec![];
+        for location in &def.locations {
+            locations.push(match location {
+                ExternalDirectiveLocation::Query => DirectiveLocation::Query,
+                ExternalDirectiveLocation::Mutation => DirectiveLocation::Mutation,
+                ExternalDirectiveLocation::Subscription => DirectiveLocation::Subscription,
+                ExternalDirectiveLocation::Field => DirectiveLocation::Field,
+                ExternalDirectiveLocation::FragmentDefinition => {
+                    DirectiveLocation::FragmentDefinition
+                }
+                ExternalDirectiveLocation::FragmentSpread => DirectiveLocation::FragmentSpread,
+                ExternalDirectiveLocation::InlineFragment => DirectiveLocation::InlineFragment,
+                ExternalDirectiveLocation::Schema => DirectiveLocation::Schema,
+                ExternalDirectiveLocation::Scalar => DirectiveLocation::Scalar,
+                ExternalDirectiveLocation::Object => DirectiveLocation::Object,
+                ExternalDirectiveLocation::FieldDefinition => DirectiveLocation::FieldDefinition,
+                ExternalDirectiveLocation::ArgumentDefinition => {
+                    DirectiveLocation::ArgumentDefinition
+                }
+                ExternalDirectiveLocation::Interface => DirectiveLocation::Interface,
+                ExternalDirectiveLocation::Union => DirectiveLocation::Union,
+                ExternalDirectiveLocation::Enum => DirectiveLocation::Enum,
+                ExternalDirectiveLocation::EnumValue => DirectiveLocation::EnumValue,
+                ExternalDirectiveLocation::InputObject => DirectiveLocation::InputObject,
+                ExternalDirectiveLocation::InputFieldDefinition => {
+                    DirectiveLocation::InputFieldDefinition
+                }
+                ExternalDirectiveLocation::VariableDefinition => {
+                    return Err(SchemaLanguageError::UnsupportedDefinition(format!(
+                        r#"directive "@{}" located on variable definitions"#,
+                        def.name,
+                    )));
+                }
+            });
+        }
+
+        let mut arguments: Vec<Argument<S>> = vec![];
+        for a in &def.arguments {
+            if arguments.iter().any(|r| r.name == a.name) {
+                return Err(SchemaLanguageError::DuplicateDefinition(format!(
+                    r#"Argument "@{}({}:)""#,
+                    def.name, a.name,
+                )));
+            }
+            let location = format!(r#"the type of argument "@{}({}:)""#, def.name, a.name);
+            arguments.push(self.translate_argument(a, location)?);
+        }
+
+        let mut directive = DirectiveType::new(&def.name, &locations, &arguments);
+        directive.description = def.description.clone();
+        Ok(directive)
+    }
+
+    fn root_type_names(
+        &self,
+        def: Option<&SchemaDefinition<String>>,
+    ) -> Result<(String, Option<String>, Option<String>), SchemaLanguageError> {
+        let (query, mutation, subscription) = match def {
+            Some(def) => (
+                def.query.clone(),
+                def.mutation.clone(),
+                def.subscription.clone(),
+            ),
+            None => {
+                let default = |name: &str| {
+                    if self.definitions.contains_key(name) {
+                        Some(name.to_owned())
+                    } else {
+                        None
+                    }
+                };
+                (
+                    default("Query"),
+                    default("Mutation"),
+                    default("Subscription"),
+                )
+            }
+        };
+
+        for (name, operation) in [
+            (&query, "query"),
+            (&mutation, "mutation"),
+            (&subscription, "subscription"),
+        ]
+        .iter()
+        {
+            if let Some(name) = name {
+                match self.definitions.get(name) {
+                    Some(TypeDefinition::Object(_)) => {}
+                    Some(_) => {
+                        return Err(SchemaLanguageError::InvalidTypeUsage {
+                            name: name.clone(),
+                            location: format!("the {} root type", operation),
+                        })
+                    }
+                    None => {
+                        return Err(SchemaLanguageError::UnknownType {
+                            name: name.clone(),
+                            location: "the schema definition".into(),
+                        })
+                    }
+                }
+            }
+        }
+
+        Ok((
+            query.ok_or(SchemaLanguageError::NoQueryType)?,
+            mutation,
+            subscription,
+        ))
+    }
+}
+
+fn type_definition_name<'d>(def: &'d ExternalTypeDefinition) -> &'d str {
+    match def {
+        TypeDefinition::Scalar(t) => &t.name,
+        TypeDefinition::Object(t) => &t.name,
+        TypeDefinition::Interface(t) => &t.name,
+        TypeDefinition::Union(t) => &t.name,
+        TypeDefinition::Enum(t) => &t.name,
+        TypeDefinition::InputObject(t) => &t.name,
+    }
+}
+
+fn is_builtin_scalar(name: &str) -> bool {
+    name == "Int" || name == "Float" || name == "String" || name == "Boolean" || name == "ID"
+}
+
+/// Merges a type extension into the definition of the type it extends.
+fn apply_extension<'d>(
+    types: &mut IndexMap<String, ExternalTypeDefinition<'d>>,
+    ext: TypeExtension<'d, String>,
+) -> Result<(), SchemaLanguageError> {
+    let name = match &ext {
+        TypeExtension::Scalar(e) => &e.name,
+        TypeExtension::Object(e) => &e.name,
+        TypeExtension::Interface(e) => &e.name,
+        TypeExtension::Union(e) => &e.name,
+        TypeExtension::Enum(e) => &e.name,
+        TypeExtension::InputObject(e) => &e.name,
+    }
+    .clone();
+
+    let def = types
+        .get_mut(&name)
+        .ok_or_else(|| SchemaLanguageError::UnknownType {
+            name: name.clone(),
+            location: "a type extension".into(),
+        })?;
+
+    match (def, ext) {
+        (TypeDefinition::Scalar(_), TypeExtension::Scalar(_)) => {}
+        (TypeDefinition::Object(d), TypeExtension::Object(e)) => {
+            d.implements_interfaces.extend(e.implements_interfaces);
+            d.fields.extend(e.fields);
+        }
+        (TypeDefinition::Interface(d), TypeExtension::Interface(e)) => {
+            d.implements_interfaces.extend(e.implements_interfaces);
+            d.fields.extend(e.fields);
+        }
+        (TypeDefinition::Union(d), TypeExtension::Union(e)) => d.types.extend(e.types),
+        (TypeDefinition::Enum(d), TypeExtension::Enum(e)) => d.values.extend(e.values),
+        (TypeDefinition::InputObject(d), TypeExtension::InputObject(e)) => {
+            d.fields.extend(e.fields)
+        }
+        _ => {
+            return Err(SchemaLanguageError::InvalidTypeUsage {
+                name,
+                location: "an extension of a different kind of type".into(),
+            })
+        }
+    }
+    Ok(())
+}
+
+fn translate_enum_values(
+    enum_name: &str,
+    values: &[ExternalEnumValue<String>],
+) -> Result<Vec<EnumValue>, SchemaLanguageError> {
+    let mut result: Vec<EnumValue> = vec![];
+    for v in values {
+        if result.iter().any(|r| r.name == v.name) {
+            return Err(SchemaLanguageError::DuplicateDefinition(format!(
+                r#"Enum value "{}.{}""#,
+                enum_name, v.name,
+            )));
+        }
+        result.push(EnumValue {
+            name: v.name.clone(),
+            description: v.description.clone(),
+            deprecation_status: deprecation_status(&v.directives),
+            directives: applied_directives(&v.directives)?,
+            visibility: None,
+        });
+    }
+    Ok(result)
+}
+
+fn deprecation_status(directives: &[ExternalDirective<String>]) -> DeprecationStatus {
+    directives
+        .iter()
+        .find(|d| d.name == "deprecated")
+        .map(|d| {
+            DeprecationStatus::Deprecated(d.arguments.iter().find_map(
+                |(name, value)| match value {
+                    ExternalValue::String(reason) if name == "reason" => Some(reason.clone()),
+                    _ => None,
+                },
+            ))
+        })
+        .unwrap_or(DeprecationStatus::Current)
+}
+
+/// Translates the type system directives applied to a schema element, except `@deprecated` and
+/// `@oneOf`, which are represented as a [`DeprecationStatus`] and as
+/// [`InputObjectMeta::is_one_of`].
+fn applied_directives(
+    directives: &[ExternalDirective<String>],
+) -> Result<Vec<AppliedDirective>, SchemaLanguageError> {
+    directives
+        .iter()
+        .filter(|d| d.name != "deprecated" && d.name != "oneOf")
+        .map(|d| {
+            Ok(AppliedDirective {
+                name: d.name.clone(),
+                arguments: d
+                    .arguments
+                    .iter()
+                    .map(|(name, value)| Ok((name.clone(), translate_value(value)?)))
+                    .collect::<Result<_, _>>()?,
+            })
+        })
+        .collect()
+}
+
+fn translate_value<S>(input: &ExternalValue<String>) -> Result<InputValue<S>, SchemaLanguageError>
+where
+    S: ScalarValue,
+{
+    Ok(match input {
+        ExternalValue::Variable(name) => {
+            return Err(SchemaLanguageError::UnsupportedDefinition(format!(
+                r#"variable "${}" used as a default value"#,
+                name,
+            )))
+        }
+        ExternalValue::Int(n) => match n.as_i64().map(i32::try_from) {
+            Some(Ok(i)) => InputValue::scalar(i),
+            _ => InputValue::scalar(n.as_i64().unwrap_or_default() as f64),
+        },
+        ExternalValue::Float(f) => InputValue::scalar(*f),
+        ExternalValue::String(s) => InputValue::scalar(s.clone()),
+        ExternalValue::Boolean(b) => InputValue::scalar(*b),
+        ExternalValue::Null => InputValue::null(),
+        ExternalValue::Enum(e) => InputValue::enum_value(e),
+        ExternalValue::List(l) => InputValue::list(
+            l.iter()
+                .map(translate_value)
+                .collect::<Result<Vec<_>, _>>()?,
+        ),
+        ExternalValue::Object(o) => InputValue::object(
+            o.iter()
+                .map(|(k, v)| Ok((k.as_str(), translate_value(v)?)))
+                .collect::<Result<IndexMap<_, _>, _>>()?,
+        ),
+    })
+}
+
+/// Checks that every interface implemented by an object or interface type of the schema is
+/// implemented according to <https://spec.graphql.org/October2021/#IsValidImplementation()>.
+fn validate_interface_implementations<S>(schema: &SchemaType<S>) -> Result<(), SchemaLanguageError>
+where
+    S: ScalarValue,
+{
+    for meta in schema.types.values() {
+        let (name, fields, interface_names) = match meta {
+            MetaType::Object(o) => (&o.name, &o.fields, &o.interface_names),
+            MetaType::Interface(i) => (&i.name, &i.fields, &i.interface_names),
+            _ => continue,
+        };
+
+        if schema.implements_interface(meta, name) {
+            return Err(SchemaLanguageError::InvalidInterfaceImplementation {
+                object: name.to_string(),
+                interface: name.to_string(),
+                reason: "interfaces can't implement themselves".into(),
+            });
+        }
+
+        for interface_name in interface_names {
+            let interface = match schema.concrete_type_by_name(interface_name) {
+                Some(MetaType::Interface(i)) => i,
+                _ => continue,
+            };
+            let error = |reason: String| SchemaLanguageError::InvalidInterfaceImplementation {
+                object: name.to_string(),
+                interface: interface.name.to_string(),
+                reason,
+            };
+
+            for interface_field in interface.fields.iter().filter(|f| !f.is_builtin()) {
+                let object_field = fields
+                    .iter()
+                    .find(|f| f.name == interface_field.name)
+                    .ok_or_else(|| {
+                        error(format!(r#"field "{}" is missing"#, interface_field.name))
+                    })?;
+
+                if !schema.is_subtype(&object_field.field_type, &interface_field.field_type) {
+                    return Err(error(format!(
+                        r#"field "{}" has type "{}", which is not a subtype of "{}""#,
+                        object_field.name, object_field.field_type, interface_field.field_type,
+                    )));
+                }
+
+                let interface_args = interface_field.arguments.as_deref().unwrap_or(&[]);
+                let object_args = object_field.arguments.as_deref().unwrap_or(&[]);
+
+                for interface_arg in interface_args {
+                    match object_args.iter().find(|a| a.name == interface_arg.name) {
+                        None => {
+                            return Err(error(format!(
+                                r#"argument "{}" of field "{}" is missing"#,
+                                interface_arg.name, object_field.name,
+                            )))
+                        }
+                        Some(object_arg) if object_arg.arg_type != interface_arg.arg_type => {
+                            return Err(error(format!(
+                                r#"argument "{}" of field "{}" has type "{}" instead of "{}""#,
+                                object_arg.name,
+                                object_field.name,
+                                object_arg.arg_type,
+                                interface_arg.arg_type,
+                            )))
+                        }
+                        Some(_) => {}
+                    }
+                }
+
+                for object_arg in object_args {
+                    if object_arg.arg_type.is_non_null()
+                        && !interface_args.iter().any(|a| a.name == object_arg.name)
+                    {
+                        return Err(error(format!(
+                            r#"additional argument "{}" of field "{}" must be nullable"#,
+                            object_arg.name, object_field.name,
+                        )));
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn is_scalar_input<S>(v: &InputValue<S>) -> Result<(), InputValueError>
+where
+    S: ScalarValue,
+{
+    match v {
+        InputValue::Scalar(_) => Ok(()),
+        _ => Err(InputValueError::new(format!(
+            "Expected a scalar, found: {}",
+            v
+        ))),
+    }
+}
+
+fn is_enum_input<S>(v: &InputValue<S>) -> Result<(), InputValueError>
+where
+    S: ScalarValue,
+{
+    match v {
+        InputValue::Enum(_) => Ok(()),
+        InputValue::Scalar(s) if s.as_str().is_some() => Ok(()),
+        _ => Err(InputValueError::new(format!(
+            "Expected an enum value, found: {}",
+            v
+        ))),
+    }
+}
+
+fn is_object_input<S>(v: &InputValue<S>) -> Result<(), InputValueError>
+where
+    S: ScalarValue,
+{
+    match v {
+        InputValue::Object(_) => Ok(()),
+        _ => Err(InputValueError::new(format!(
+            "Expected an input object, found: {}",
+            v
+        ))),
+    }
+}
+
+fn parse_custom_scalar<S>(token: ScalarToken) -> Result<S, ParseError>
+where
+    S: ScalarValue,
+{
+    let parsed = match token {
+        ScalarToken::String(s) => Some(S::from(s.to_owned())),
+        ScalarToken::Int(i) => i
+            .parse::<i32>()
+            .map(S::from)
+            .or_else(|_| i.parse::<f64>().map(S::from))
+            .ok(),
+        ScalarToken::Float(f) => f.parse::<f64>().map(S::from).ok(),
+    };
+    parsed.ok_or(ParseError::UnexpectedToken(Token::Scalar(token)))
+}
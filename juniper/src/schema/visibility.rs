@@ -0,0 +1,264 @@
+use std::{
+    fmt,
+    sync::{Arc, RwLock},
+};
+
+use fnv::{FnvHashMap, FnvHashSet};
+
+use crate::schema::{
+    meta::{Argument, EnumMeta, InputObjectMeta, InterfaceMeta, MetaType, ObjectMeta, UnionMeta},
+    model::SchemaType,
+};
+
+type Predicate<CtxT> = Box<dyn Fn(&CtxT) -> bool + Send + Sync>;
+
+/// The visibility predicates of a [`RootNode`](crate::RootNode), by name.
+///
+/// The parts of the schema hidden from a request only depend on which of the predicates reject
+/// its context, so they're computed once for every combination of rejecting predicates.
+#[doc(hidden)]
+pub struct Visibility<CtxT> {
+    predicates: FnvHashMap<String, Predicate<CtxT>>,
+    hidden: RwLock<FnvHashMap<Vec<String>, Option<Arc<HiddenItems>>>>,
+}
+
+impl<CtxT> Default for Visibility<CtxT> {
+    fn default() -> Self {
+        Visibility {
+            predicates: FnvHashMap::default(),
+            hidden: RwLock::new(FnvHashMap::default()),
+        }
+    }
+}
+
+impl<CtxT> fmt::Debug for Visibility<CtxT> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Visibility")
+            .field("predicates", &self.predicates.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl<CtxT> Visibility<CtxT> {
+    /// Registers the predicate deciding whether the items marked with `name` are visible.
+    pub(crate) fn insert<F>(&mut self, name: &str, predicate: F)
+    where
+        F: Fn(&CtxT) -> bool + Send + Sync + 'static,
+    {
+        self.predicates.insert(name.to_owned(), Box::new(predicate));
+        self.hidden.get_mut().unwrap().clear();
+    }
+
+    /// Returns the parts of `schema` hidden from a request with the given `context`, or `None`
+    /// if the whole schema is visible to it.
+    pub(crate) fn hidden_items<S>(
+        &self,
+        schema: &SchemaType<S>,
+        context: &CtxT,
+    ) -> Option<Arc<HiddenItems>> {
+        let mut rejecting = self
+            .predicates
+            .iter()
+            .filter(|(_, predicate)| !predicate(context))
+            .map(|(name, _)| name.clone())
+            .collect::<Vec<_>>();
+        rejecting.sort_unstable();
+
+        if let Some(hidden) = self.hidden.read().unwrap().get(&rejecting) {
+            return hidden.clone();
+        }
+
+        // Items marked with an unknown name are hidden from every request.
+        let hidden = HiddenItems::new(schema, |name| {
+            self.predicates.contains_key(name) && !rejecting.iter().any(|r| r == name)
+        });
+        let hidden = if hidden.is_empty() {
+            None
+        } else {
+            Some(Arc::new(hidden))
+        };
+        self.hidden
+            .write()
+            .unwrap()
+            .insert(rejecting, hidden.clone());
+        hidden
+    }
+}
+
+/// The types, fields and enum values of a schema hidden from a request
+///
+/// Besides the items whose visibility predicate rejects the request, the fields returning a
+/// hidden type or requiring an argument of a hidden type are hidden too. The query type is never
+/// hidden.
+#[derive(Debug, Default)]
+pub(crate) struct HiddenItems {
+    types: FnvHashSet<String>,
+    fields: FnvHashMap<String, FnvHashSet<String>>,
+    enum_values: FnvHashMap<String, FnvHashSet<String>>,
+}
+
+impl HiddenItems {
+    fn new<S, F>(schema: &SchemaType<S>, is_visible: F) -> Self
+    where
+        F: Fn(&str) -> bool,
+    {
+        let is_hidden = |visibility: Option<&str>| visibility.is_some_and(|v| !is_visible(v));
+
+        let mut hidden = HiddenItems {
+            types: schema
+                .types
+                .values()
+                .filter(|t| is_hidden(t.visibility()))
+                .filter_map(MetaType::name)
+                .filter(|name| *name != schema.query_type_name)
+                .map(ToOwned::to_owned)
+                .collect(),
+            ..HiddenItems::default()
+        };
+
+        for t in schema.types.values() {
+            match *t {
+                MetaType::Object(ObjectMeta {
+                    ref name,
+                    ref fields,
+                    ..
+                })
+                | MetaType::Interface(InterfaceMeta {
+                    ref name,
+                    ref fields,
+                    ..
+                }) => {
+                    let fields = fields
+                        .iter()
+                        .filter(|f| {
+                            is_hidden(f.visibility.as_deref())
+                                || hidden.is_type_hidden(f.field_type.innermost_name())
+                                || f.arguments.iter().flatten().any(|a| {
+                                    a.arg_type.is_non_null()
+                                        && a.default_value.is_none()
+                                        && hidden.is_argument_hidden(a)
+                                })
+                        })
+                        .map(|f| f.name.clone())
+                        .collect::<FnvHashSet<_>>();
+                    if !fields.is_empty() {
+                        hidden.fields.insert(name.to_string(), fields);
+                    }
+                }
+                MetaType::Enum(EnumMeta {
+                    ref name,
+                    ref values,
+                    ..
+                }) => {
+                    let values = values
+                        .iter()
+                        .filter(|v| is_hidden(v.visibility.as_deref()))
+                        .map(|v| v.name.clone())
+                        .collect::<FnvHashSet<_>>();
+                    if !values.is_empty() {
+                        hidden.enum_values.insert(name.to_string(), values);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        hidden
+    }
+
+    fn is_empty(&self) -> bool {
+        self.types.is_empty() && self.fields.is_empty() && self.enum_values.is_empty()
+    }
+
+    /// Returns true if the type with the given name is hidden
+    pub(crate) fn is_type_hidden(&self, name: &str) -> bool {
+        self.types.contains(name)
+    }
+
+    /// Returns true if the field of the given type is hidden
+    ///
+    /// The fields of hidden types aren't hidden on their own, as the type itself is rejected.
+    pub(crate) fn is_field_hidden(&self, type_name: &str, field_name: &str) -> bool {
+        self.fields
+            .get(type_name)
+            .is_some_and(|fields| fields.contains(field_name))
+    }
+
+    fn is_argument_hidden<S>(&self, argument: &Argument<S>) -> bool {
+        self.is_type_hidden(argument.arg_type.innermost_name())
+    }
+
+    /// Returns true if the value of the given enum type is hidden
+    pub(crate) fn is_enum_value_hidden(&self, enum_name: &str, value_name: &str) -> bool {
+        self.enum_values
+            .get(enum_name)
+            .is_some_and(|values| values.contains(value_name))
+    }
+
+    /// Returns a copy of `schema` without the hidden items, along with the optional arguments,
+    /// input fields, implemented interfaces and union members of hidden types.
+    pub(crate) fn strip<'a, S: Clone>(&self, schema: &SchemaType<'a, S>) -> SchemaType<'a, S> {
+        let visible_name = |name: &Option<String>| {
+            name.as_ref()
+                .filter(|name| !self.is_type_hidden(name))
+                .cloned()
+        };
+
+        SchemaType {
+            types: schema
+                .types
+                .iter()
+                .filter(|(_, t)| !t.name().is_some_and(|name| self.is_type_hidden(name)))
+                .map(|(name, t)| (name.clone(), self.strip_type(t)))
+                .collect(),
+            query_type_name: schema.query_type_name.clone(),
+            mutation_type_name: visible_name(&schema.mutation_type_name),
+            subscription_type_name: visible_name(&schema.subscription_type_name),
+            directives: schema.directives.clone(),
+        }
+    }
+
+    fn strip_type<'a, S: Clone>(&self, t: &MetaType<'a, S>) -> MetaType<'a, S> {
+        let mut t = t.clone();
+        match t {
+            MetaType::Object(ObjectMeta {
+                ref name,
+                ref mut fields,
+                ref mut interface_names,
+                ..
+            })
+            | MetaType::Interface(InterfaceMeta {
+                ref name,
+                ref mut fields,
+                ref mut interface_names,
+                ..
+            }) => {
+                fields.retain(|f| !self.is_field_hidden(name, &f.name));
+                for field in fields.iter_mut() {
+                    if let Some(ref mut arguments) = field.arguments {
+                        arguments.retain(|a| !self.is_argument_hidden(a));
+                    }
+                    if field.arguments.as_ref().is_some_and(Vec::is_empty) {
+                        field.arguments = None;
+                    }
+                }
+                interface_names.retain(|n| !self.is_type_hidden(n));
+            }
+            MetaType::Union(UnionMeta {
+                ref mut of_type_names,
+                ..
+            }) => of_type_names.retain(|n| !self.is_type_hidden(n)),
+            MetaType::Enum(EnumMeta {
+                ref name,
+                ref mut values,
+                ..
+            }) => values.retain(|v| !self.is_enum_value_hidden(name, &v.name)),
+            MetaType::InputObject(InputObjectMeta {
+                ref mut input_fields,
+                ..
+            }) => input_fields.retain(|f| !self.is_argument_hidden(f)),
+            _ => {}
+        }
+        t
+    }
+}
@@ -7,6 +7,8 @@ mod introspection_tests;
 mod query_tests;
 #[cfg(test)]
 mod schema_introspection;
+#[cfg(all(test, feature = "graphql-parser-integration"))]
+mod schema_language_tests;
 #[cfg(test)]
 mod subscriptions;
 #[cfg(test)]
@@ -581,6 +581,18 @@ pub(crate) fn schema_introspection_result() -> value::Value {
                   },
                   "isDeprecated": false,
                   "deprecationReason": Null
+                },
+                {
+                  "name": "isOneOf",
+                  "description": Null,
+                  "args": [],
+                  "type": {
+                    "kind": "SCALAR",
+                    "name": "Boolean",
+                    "ofType": Null
+                  },
+                  "isDeprecated": false,
+                  "deprecationReason": Null
                 }
               ],
               "inputFields": Null,
@@ -1026,6 +1038,72 @@ pub(crate) fn schema_introspection_result() -> value::Value {
                   "description": Null,
                   "isDeprecated": false,
                   "deprecationReason": Null
+                },
+                {
+                  "name": "SCHEMA",
+                  "description": Null,
+                  "isDeprecated": false,
+                  "deprecationReason": Null
+                },
+                {
+                  "name": "SCALAR",
+                  "description": Null,
+                  "isDeprecated": false,
+                  "deprecationReason": Null
+                },
+                {
+                  "name": "OBJECT",
+                  "description": Null,
+                  "isDeprecated": false,
+                  "deprecationReason": Null
+                },
+                {
+                  "name": "FIELD_DEFINITION",
+                  "description": Null,
+                  "isDeprecated": false,
+                  "deprecationReason": Null
+                },
+                {
+                  "name": "ARGUMENT_DEFINITION",
+                  "description": Null,
+                  "isDeprecated": false,
+                  "deprecationReason": Null
+                },
+                {
+                  "name": "INTERFACE",
+                  "description": Null,
+                  "isDeprecated": false,
+                  "deprecationReason": Null
+                },
+                {
+                  "name": "UNION",
+                  "description": Null,
+                  "isDeprecated": false,
+                  "deprecationReason": Null
+                },
+                {
+                  "name": "ENUM",
+                  "description": Null,
+                  "isDeprecated": false,
+                  "deprecationReason": Null
+                },
+                {
+                  "name": "ENUM_VALUE",
+                  "description": Null,
+                  "isDeprecated": false,
+                  "deprecationReason": Null
+                },
+                {
+                  "name": "INPUT_OBJECT",
+                  "description": Null,
+                  "isDeprecated": false,
+                  "deprecationReason": Null
+                },
+                {
+                  "name": "INPUT_FIELD_DEFINITION",
+                  "description": Null,
+                  "isDeprecated": false,
+                  "deprecationReason": Null
                 }
               ],
               "possibleTypes": Null
@@ -1113,7 +1191,7 @@ pub(crate) fn schema_introspection_result() -> value::Value {
                 }
               ],
               "inputFields": Null,
-              "interfaces": Null,
+              "interfaces": [],
               "enumValues": Null,
               "possibleTypes": [
                 {
@@ -1826,6 +1904,17 @@ pub(crate) fn schema_introspection_result_without_descriptions() -> value::Value
                   },
                   "isDeprecated": false,
                   "deprecationReason": Null
+                },
+                {
+                  "name": "isOneOf",
+                  "args": [],
+                  "type": {
+                    "kind": "SCALAR",
+                    "name": "Boolean",
+                    "ofType": Null
+                  },
+                  "isDeprecated": false,
+                  "deprecationReason": Null
                 }
               ],
               "inputFields": Null,
@@ -2234,6 +2323,61 @@ pub(crate) fn schema_introspection_result_without_descriptions() -> value::Value
                   "name": "INLINE_FRAGMENT",
                   "isDeprecated": false,
                   "deprecationReason": Null
+                },
+                {
+                  "name": "SCHEMA",
+                  "isDeprecated": false,
+                  "deprecationReason": Null
+                },
+                {
+                  "name": "SCALAR",
+                  "isDeprecated": false,
+                  "deprecationReason": Null
+                },
+                {
+                  "name": "OBJECT",
+                  "isDeprecated": false,
+                  "deprecationReason": Null
+                },
+                {
+                  "name": "FIELD_DEFINITION",
+                  "isDeprecated": false,
+                  "deprecationReason": Null
+                },
+                {
+                  "name": "ARGUMENT_DEFINITION",
+                  "isDeprecated": false,
+                  "deprecationReason": Null
+                },
+                {
+                  "name": "INTERFACE",
+                  "isDeprecated": false,
+                  "deprecationReason": Null
+                },
+                {
+                  "name": "UNION",
+                  "isDeprecated": false,
+                  "deprecationReason": Null
+                },
+                {
+                  "name": "ENUM",
+                  "isDeprecated": false,
+                  "deprecationReason": Null
+                },
+                {
+                  "name": "ENUM_VALUE",
+                  "isDeprecated": false,
+                  "deprecationReason": Null
+                },
+                {
+                  "name": "INPUT_OBJECT",
+                  "isDeprecated": false,
+                  "deprecationReason": Null
+                },
+                {
+                  "name": "INPUT_FIELD_DEFINITION",
+                  "isDeprecated": false,
+                  "deprecationReason": Null
                 }
               ],
               "possibleTypes": Null
@@ -2316,7 +2460,7 @@ pub(crate) fn schema_introspection_result_without_descriptions() -> value::Value
                 }
               ],
               "inputFields": Null,
-              "interfaces": Null,
+              "interfaces": [],
               "enumValues": Null,
               "possibleTypes": [
                 {
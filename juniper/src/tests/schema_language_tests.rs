@@ -0,0 +1,508 @@
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+use pretty_assertions::assert_eq;
+
+use crate::{
+    executor::{ExecutionResult, Executor, Registry, Variables},
+    schema::{
+        meta::MetaType,
+        model::{DirectiveLocation, RootNode, SchemaType},
+        schema_language::SchemaLanguageError,
+        translate::{graphql_parser::GraphQLParserTranslator, SchemaTranslator},
+    },
+    tests::fixtures::starwars::{
+        schema::{Database, Query},
+        schema_language::STATIC_GRAPHQL_SCHEMA_DEFINITION,
+    },
+    types::{
+        base::{Arguments, GraphQLType, GraphQLValue},
+        scalars::{EmptyMutation, EmptySubscription},
+    },
+    value::{DefaultScalarValue, Value},
+    GraphQLError,
+};
+
+/// Renders every definition of the schema separately, in a stable order.
+fn sorted_definitions(schema: &SchemaType<DefaultScalarValue>) -> Vec<String> {
+    let doc: graphql_parser::schema::Document<&str> =
+        GraphQLParserTranslator::translate_schema(schema);
+    let mut definitions = doc
+        .definitions
+        .iter()
+        .map(|d| d.to_string())
+        .collect::<Vec<_>>();
+    definitions.sort();
+    definitions
+}
+
+fn parse_error(source: &str) -> SchemaLanguageError {
+    SchemaType::<DefaultScalarValue>::from_schema_language(source).unwrap_err()
+}
+
+#[test]
+fn matches_schema_built_from_types() {
+    let expected = RootNode::new(
+        Query,
+        EmptyMutation::<Database>::new(),
+        EmptySubscription::<Database>::new(),
+    );
+    let schema = SchemaType::from_schema_language(STATIC_GRAPHQL_SCHEMA_DEFINITION).unwrap();
+
+    assert_eq!(
+        sorted_definitions(&expected.schema),
+        sorted_definitions(&schema),
+    );
+    assert!(schema.directive_by_name("skip").is_some());
+    assert!(schema.directive_by_name("include").is_some());
+    assert!(schema
+        .query_type()
+        .to_concrete()
+        .unwrap()
+        .field_by_name("__schema")
+        .is_some());
+}
+
+#[test]
+fn merges_extensions_and_reads_deprecations() {
+    let schema = SchemaType::<DefaultScalarValue>::from_schema_language(
+        r#"
+        type Query {
+            a: Int
+        }
+
+        extend type Query {
+            b: String @deprecated(reason: "Use a")
+        }
+    "#,
+    )
+    .unwrap();
+
+    let query = schema.concrete_type_by_name("Query").unwrap();
+    assert!(query.field_by_name("a").is_some());
+    assert_eq!(
+        query
+            .field_by_name("b")
+            .unwrap()
+            .deprecation_status
+            .reason(),
+        Some(&"Use a".to_owned()),
+    );
+}
+
+#[test]
+fn keeps_type_system_directives() {
+    let source = r#"
+        directive @key(fields: String!) on OBJECT | INTERFACE
+
+        type Query @key(fields: "id") {
+            id: ID! @external
+            color(format: String @lowercase): Color @deprecated(reason: "No more colors")
+        }
+
+        enum Color @inaccessible {
+            RED @tag(names: ["warm"])
+        }
+    "#;
+    let schema = SchemaType::<DefaultScalarValue>::from_schema_language(source).unwrap();
+
+    let key = schema.directive_by_name("key").unwrap();
+    assert_eq!(
+        key.locations,
+        [DirectiveLocation::Object, DirectiveLocation::Interface],
+    );
+
+    let definitions = sorted_definitions(&schema);
+    assert_eq!(
+        definitions,
+        sorted_definitions(&SchemaType::from_schema_language(&definitions.join("\n")).unwrap()),
+    );
+    assert!(
+        definitions.contains(&"directive @key(fields: String!) on OBJECT | INTERFACE\n".to_owned())
+    );
+    assert!(definitions.contains(
+        &r#"enum Color @inaccessible {
+  RED @tag(names: ["warm"])
+}
+"#
+        .to_owned()
+    ));
+    assert!(definitions.contains(
+        &r#"type Query @key(fields: "id") {
+  id: ID! @external
+  color(format: String @lowercase): Color @deprecated(reason: "No more colors")
+}
+"#
+        .to_owned()
+    ));
+}
+
+#[test]
+fn sorts_directive_definitions_by_name() {
+    let schema = SchemaType::<DefaultScalarValue>::from_schema_language(
+        r#"
+        directive @delta on FIELD_DEFINITION
+        directive @alpha on OBJECT
+        directive @charlie on ENUM
+        directive @bravo on SCALAR
+
+        type Query {
+            a: Int
+        }
+    "#,
+    )
+    .unwrap();
+
+    let doc: graphql_parser::schema::Document<&str> =
+        GraphQLParserTranslator::translate_schema(&schema);
+    let directives = doc
+        .definitions
+        .iter()
+        .filter_map(|d| match d {
+            graphql_parser::schema::Definition::DirectiveDefinition(d) => Some(d.name),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(directives, ["alpha", "bravo", "charlie", "delta"]);
+}
+
+#[test]
+fn reads_one_of_input_objects() {
+    let schema = SchemaType::<DefaultScalarValue>::from_schema_language(
+        r#"
+        type Query {
+            user(by: UserBy!): String
+        }
+
+        input UserBy @oneOf {
+            id: ID
+            email: String
+        }
+    "#,
+    )
+    .unwrap();
+
+    assert!(schema.concrete_type_by_name("UserBy").unwrap().is_one_of());
+    assert!(schema.directive_by_name("oneOf").is_some());
+
+    let definitions = sorted_definitions(&schema);
+    assert!(definitions.contains(
+        &r#"input UserBy @oneOf {
+  id: ID
+  email: String
+}
+"#
+        .to_owned()
+    ));
+    assert!(!definitions
+        .iter()
+        .any(|d| d.starts_with("directive @oneOf")));
+}
+
+#[test]
+fn rejects_unknown_type() {
+    assert_eq!(
+        parse_error("type Query { a: Missing }"),
+        SchemaLanguageError::UnknownType {
+            name: "Missing".into(),
+            location: r#"the type of field "Query.a""#.into(),
+        },
+    );
+}
+
+#[test]
+fn rejects_duplicate_definitions() {
+    assert_eq!(
+        parse_error("type Query { a: Int } type Query { b: Int }"),
+        SchemaLanguageError::DuplicateDefinition(r#"Type "Query""#.into()),
+    );
+    assert_eq!(
+        parse_error("type Query { a: Int a: String }"),
+        SchemaLanguageError::DuplicateDefinition(r#"Field "Query.a""#.into()),
+    );
+    assert_eq!(
+        parse_error("scalar String type Query { a: String }"),
+        SchemaLanguageError::DuplicateDefinition(r#"Type "String""#.into()),
+    );
+}
+
+#[test]
+fn rejects_invalid_type_usage() {
+    assert_eq!(
+        parse_error("type Query { a(arg: Query): Int }"),
+        SchemaLanguageError::InvalidTypeUsage {
+            name: "Query".into(),
+            location: r#"the type of argument "Query.a(arg:)""#.into(),
+        },
+    );
+    assert_eq!(
+        parse_error("input In { a: Int } type Query { a: In }"),
+        SchemaLanguageError::InvalidTypeUsage {
+            name: "In".into(),
+            location: r#"the type of field "Query.a""#.into(),
+        },
+    );
+}
+
+#[test]
+fn rejects_invalid_interface_implementations() {
+    assert_eq!(
+        parse_error("interface Node { id: ID! } type Query implements Node { a: Int }"),
+        SchemaLanguageError::InvalidInterfaceImplementation {
+            object: "Query".into(),
+            interface: "Node".into(),
+            reason: r#"field "id" is missing"#.into(),
+        },
+    );
+    assert_eq!(
+        parse_error("interface Node { id: ID! } type Query implements Node { id: ID }"),
+        SchemaLanguageError::InvalidInterfaceImplementation {
+            object: "Query".into(),
+            interface: "Node".into(),
+            reason: r#"field "id" has type "ID", which is not a subtype of "ID!""#.into(),
+        },
+    );
+    assert_eq!(
+        parse_error(
+            "interface Node { id(a: Int): ID } type Query implements Node { id(a: Int, b: Int!): ID }"
+        ),
+        SchemaLanguageError::InvalidInterfaceImplementation {
+            object: "Query".into(),
+            interface: "Node".into(),
+            reason: r#"additional argument "b" of field "id" must be nullable"#.into(),
+        },
+    );
+    assert_eq!(
+        parse_error("union Node = Query type Query implements Node { id: ID }"),
+        SchemaLanguageError::InvalidTypeUsage {
+            name: "Node".into(),
+            location: r#"an interface of type "Query""#.into(),
+        },
+    );
+    assert_eq!(
+        parse_error(
+            "interface Node { id: ID } interface Resource implements Node { url: String } \
+             type Query { a: Resource }"
+        ),
+        SchemaLanguageError::InvalidInterfaceImplementation {
+            object: "Resource".into(),
+            interface: "Node".into(),
+            reason: r#"field "id" is missing"#.into(),
+        },
+    );
+    assert_eq!(
+        parse_error("interface Node implements Node { id: ID } type Query { a: Node }"),
+        SchemaLanguageError::InvalidInterfaceImplementation {
+            object: "Node".into(),
+            interface: "Node".into(),
+            reason: "interfaces can't implement themselves".into(),
+        },
+    );
+}
+
+#[test]
+fn reads_interfaces_implementing_interfaces() {
+    let schema = SchemaType::<DefaultScalarValue>::from_schema_language(
+        r#"
+        interface Node {
+            id: ID!
+        }
+
+        interface Resource implements Node {
+            id: ID!
+            url: String
+        }
+
+        type File implements Resource {
+            id: ID!
+            url: String
+        }
+
+        type Query {
+            resource: Resource
+        }
+    "#,
+    )
+    .unwrap();
+
+    let node = schema.concrete_type_by_name("Node").unwrap();
+    let file = schema.concrete_type_by_name("File").unwrap();
+    assert!(schema.is_possible_type(node, file));
+    assert!(schema.is_named_subtype("Resource", "Node"));
+
+    let definitions = sorted_definitions(&schema);
+    assert!(definitions.contains(
+        &r#"interface Resource implements Node {
+  id: ID!
+  url: String
+}
+"#
+        .to_owned()
+    ));
+}
+
+#[test]
+fn requires_query_type() {
+    assert_eq!(
+        parse_error("type Root { a: Int }"),
+        SchemaLanguageError::NoQueryType,
+    );
+    assert!(SchemaType::<DefaultScalarValue>::from_schema_language(
+        "schema { query: Root } type Root { a: Int }"
+    )
+    .is_ok());
+}
+
+struct DynamicInfo {
+    schema: Arc<SchemaType<'static, DefaultScalarValue>>,
+    name: String,
+}
+
+enum DynamicField {
+    Scalar(String),
+    Object(DynamicObject),
+    List(Vec<DynamicObject>),
+}
+
+struct DynamicObject {
+    fields: IndexMap<String, DynamicField>,
+}
+
+impl GraphQLType for DynamicObject {
+    fn name(info: &DynamicInfo) -> Option<&str> {
+        Some(&info.name)
+    }
+
+    fn meta<'r>(info: &DynamicInfo, registry: &mut Registry<'r>) -> MetaType<'r>
+    where
+        DefaultScalarValue: 'r,
+    {
+        registry.build_type_from_schema(&info.schema, &info.name)
+    }
+}
+
+impl GraphQLValue for DynamicObject {
+    type Context = ();
+    type TypeInfo = DynamicInfo;
+
+    fn type_name<'i>(&self, info: &'i DynamicInfo) -> Option<&'i str> {
+        <Self as GraphQLType>::name(info)
+    }
+
+    fn concrete_type_name(&self, _: &(), info: &DynamicInfo) -> String {
+        info.name.clone()
+    }
+
+    fn resolve_field(
+        &self,
+        info: &DynamicInfo,
+        field_name: &str,
+        _: &Arguments,
+        executor: &Executor<()>,
+    ) -> ExecutionResult {
+        let field_type = info
+            .schema
+            .concrete_type_by_name(&info.name)
+            .and_then(|t| t.field_by_name(field_name))
+            .unwrap()
+            .field_type
+            .innermost_name()
+            .to_owned();
+        let field_info = DynamicInfo {
+            schema: info.schema.clone(),
+            name: field_type,
+        };
+
+        match &self.fields[field_name] {
+            DynamicField::Scalar(s) => executor.resolve(&(), s),
+            DynamicField::Object(o) => executor.resolve(&field_info, o),
+            DynamicField::List(l) => executor.resolve(&field_info, l),
+        }
+    }
+}
+
+#[test]
+fn resolves_dynamic_types() {
+    let schema = Arc::new(
+        SchemaType::from_schema_language(
+            r#"
+            type Query {
+                user: User!
+            }
+
+            type User {
+                name: String!
+                friends: [User!]!
+            }
+        "#,
+        )
+        .unwrap(),
+    );
+
+    let user = |name: &str, friends| DynamicObject {
+        fields: vec![
+            ("name".to_owned(), DynamicField::Scalar(name.to_owned())),
+            ("friends".to_owned(), DynamicField::List(friends)),
+        ]
+        .into_iter()
+        .collect(),
+    };
+    let query = DynamicObject {
+        fields: vec![(
+            "user".to_owned(),
+            DynamicField::Object(user("Luke", vec![user("Leia", vec![])])),
+        )]
+        .into_iter()
+        .collect(),
+    };
+    let root = RootNode::new_with_info(
+        query,
+        EmptyMutation::new(),
+        EmptySubscription::new(),
+        DynamicInfo {
+            schema,
+            name: "Query".into(),
+        },
+        (),
+        (),
+    );
+
+    assert_eq!(
+        crate::execute_sync(
+            "{ user { __typename name friends { name } } }",
+            None,
+            &root,
+            &Variables::new(),
+            &(),
+        ),
+        Ok((
+            Value::object(
+                vec![(
+                    "user",
+                    Value::object(
+                        vec![
+                            ("__typename", Value::scalar("User")),
+                            ("name", Value::scalar("Luke")),
+                            (
+                                "friends",
+                                Value::list(vec![Value::object(
+                                    vec![("name", Value::scalar("Leia"))].into_iter().collect(),
+                                )]),
+                            ),
+                        ]
+                        .into_iter()
+                        .collect(),
+                    ),
+                )]
+                .into_iter()
+                .collect(),
+            ),
+            vec![],
+        )),
+    );
+
+    assert!(matches!(
+        crate::execute_sync("{ user { age } }", None, &root, &Variables::new(), &()),
+        Err(GraphQLError::ValidationError(_)),
+    ));
+}
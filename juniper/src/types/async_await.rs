@@ -1,7 +1,11 @@
+use std::sync::RwLock;
+
+use futures::FutureExt as _;
+
 use crate::{
     ast::Selection,
-    executor::{ExecutionResult, Executor},
-    parser::Spanning,
+    executor::{drive_early, early_channel, ExecutionResult, Executor, FieldDirectives, Resolving},
+    parser::{SourcePosition, Spanning},
     value::{DefaultScalarValue, Object, ScalarValue, Value},
 };
 
@@ -185,19 +189,31 @@ where
     T::Context: Sync,
     S: ScalarValue + Send + Sync,
 {
-    use futures::stream::{FuturesOrdered, StreamExt as _};
-
     #[derive(futures_enum::Future)]
-    enum AsyncValueFuture<A, B, C, D> {
+    enum AsyncValueFuture<A, B, C, D, E, F> {
         Field(A),
         FragmentSpread(B),
         InlineFragment1(C),
         InlineFragment2(D),
+        DeferredFragmentSpread(E),
+        DeferredInlineFragment(F),
     }
 
     let mut object = Object::with_capacity(selection_set.len());
 
-    let mut async_values = FuturesOrdered::<AsyncValueFuture<_, _, _, _>>::new();
+    let mut async_values = Resolving::<AsyncValueFuture<_, _, _, _, _, _>, _>::new();
+
+    let incremental = executor.incremental();
+    let path = incremental.map_or_else(Vec::new, |_| executor.response_path());
+    // The values of the selections are waited for, so they publish them early when executing
+    // incrementally.
+    let value_channel = || match incremental {
+        Some(_) => {
+            let (early, early_rx) = early_channel();
+            (Some(early), Some(early_rx))
+        }
+        None => (None, None),
+    };
 
     let meta_type = executor
         .schema()
@@ -240,12 +256,17 @@ where
 
                 let exec_vars = executor.variables();
 
-                let sub_exec = executor.field_sub_executor(
-                    &response_name,
-                    f.name.item,
-                    *start_pos,
-                    f.selection_set.as_ref().map(|v| &v[..]),
-                );
+                let (early, early_rx) = value_channel();
+                let sub_exec = executor
+                    .field_sub_executor(
+                        response_name,
+                        f.name.item,
+                        *start_pos,
+                        f.selection_set.as_ref().map(|v| &v[..]),
+                    )
+                    .with_incremental(incremental.and_then(|incremental| {
+                        early.map(|early| incremental.field(&f.directives, exec_vars, early))
+                    }));
                 let args = Arguments::new(
                     f.arguments.as_ref().map(|m| {
                         m.item
@@ -256,34 +277,59 @@ where
                     &meta_field.arguments,
                 );
 
+                let directives =
+                    FieldDirectives::new(executor.schema(), f.name.item, &f.directives, exec_vars);
+
                 let pos = *start_pos;
                 let is_non_null = meta_field.field_type.is_non_null();
 
                 let response_name = response_name.to_string();
+                let slot = async_values.reserve(incremental.is_some());
                 async_values.push(AsyncValueFuture::Field(async move {
                     // TODO: implement custom future type instead of
                     //       two-level boxing.
-                    let res = instance
-                        .resolve_field_async(info, f.name.item, &args, &sub_exec)
-                        .await;
-
-                    let value = match res {
-                        Ok(Value::Null) if is_non_null => None,
-                        Ok(v) => Some(v),
-                        Err(e) => {
-                            sub_exec.push_error_at(e, pos);
-
-                            if is_non_null {
-                                None
-                            } else {
-                                Some(Value::null())
-                            }
+                    let started_at = sub_exec.field_start();
+                    let (entered, res) = directives.before_resolve();
+                    let on_value = |res| {
+                        let res = directives.after_resolve(entered, res);
+                        if let Some(started_at) = started_at {
+                            sub_exec.field_resolved(
+                                meta_type.name().unwrap_or_default(),
+                                f.name.item,
+                                &meta_field.field_type,
+                                started_at,
+                            );
                         }
+
+                        let value = match res {
+                            Ok(Value::Null) if is_non_null => None,
+                            Ok(v) => Some(v),
+                            Err(e) => {
+                                sub_exec.push_error_at(e, pos);
+
+                                if is_non_null {
+                                    None
+                                } else {
+                                    Some(Value::null())
+                                }
+                            }
+                        };
+                        slot.publish(AsyncValue::Field(AsyncField {
+                            name: response_name,
+                            value,
+                        }))
                     };
-                    AsyncValue::Field(AsyncField {
-                        name: response_name,
-                        value,
-                    })
+                    match res {
+                        Some(res) => on_value(res),
+                        None => {
+                            drive_early(
+                                instance.resolve_field_async(info, f.name.item, &args, &sub_exec),
+                                early_rx.map(|early_rx| early_rx.map(|value| value.map(Ok))),
+                                on_value,
+                            )
+                            .await
+                        }
+                    }
                 }));
             }
 
@@ -293,18 +339,56 @@ where
                 if is_excluded(&spread.directives, executor.variables()) {
                     continue;
                 }
+
+                let deferred = incremental.and_then(|incremental| {
+                    incremental.defer(&path, &spread.directives, executor.variables())
+                });
+                if let Some(part) = deferred {
+                    async_values.push(AsyncValueFuture::DeferredFragmentSpread(async move {
+                        let fragment = &executor
+                            .fragment_by_name(spread.name.item)
+                            .expect("Fragment could not be found");
+                        let errors = RwLock::new(Vec::new());
+                        let (part_incremental, channels) = part.executor_state();
+                        let sub_exec = executor.part_sub_executor(&errors, part_incremental);
+                        let resolving = resolve_selection_set_into_async(
+                            instance,
+                            info,
+                            &fragment.selection_set[..],
+                            &sub_exec,
+                        );
+                        part.deliver(resolving, channels, &errors).await;
+                        None
+                    }));
+                    continue;
+                }
+
+                // The fields of a fragment spread are resolved as if they were in the
+                // selection set of the executor.
+                let (early, early_rx) = value_channel();
+                let sub_exec = executor
+                    .type_sub_executor(None, executor.current_selection_set())
+                    .with_incremental(
+                        incremental
+                            .and_then(|incremental| early.map(|early| incremental.nested(early))),
+                    );
+
+                let slot = async_values.reserve(incremental.is_some());
                 async_values.push(AsyncValueFuture::FragmentSpread(async move {
                     let fragment = &executor
                         .fragment_by_name(spread.name.item)
                         .expect("Fragment could not be found");
-                    let value = resolve_selection_set_into_async(
-                        instance,
-                        info,
-                        &fragment.selection_set[..],
-                        executor,
+                    drive_early(
+                        resolve_selection_set_into_async(
+                            instance,
+                            info,
+                            &fragment.selection_set[..],
+                            &sub_exec,
+                        ),
+                        early_rx,
+                        |value| slot.publish(AsyncValue::Nested(value)),
                     )
-                    .await;
-                    AsyncValue::Nested(value)
+                    .await
                 }));
             }
 
@@ -317,43 +401,93 @@ where
                     continue;
                 }
 
-                let sub_exec = executor.type_sub_executor(
-                    fragment.type_condition.as_ref().map(|c| c.item),
-                    Some(&fragment.selection_set[..]),
-                );
+                let pos = *start_pos;
 
-                if let Some(ref type_condition) = fragment.type_condition {
-                    let sub_result = instance
-                        .resolve_into_type_async(
-                            info,
-                            type_condition.item,
+                let deferred = incremental.and_then(|incremental| {
+                    incremental.defer(&path, &fragment.directives, executor.variables())
+                });
+                if let Some(part) = deferred {
+                    async_values.push(AsyncValueFuture::DeferredInlineFragment(async move {
+                        let errors = RwLock::new(Vec::new());
+                        let (part_incremental, channels) = part.executor_state();
+                        let part_exec = executor.part_sub_executor(&errors, part_incremental);
+                        let sub_exec = part_exec.type_sub_executor(
+                            fragment.type_condition.as_ref().map(|c| c.item),
                             Some(&fragment.selection_set[..]),
-                            &sub_exec,
+                        );
+                        let resolving = async {
+                            match fragment.type_condition {
+                                Some(ref type_condition) => fragment_fields(
+                                    instance
+                                        .resolve_into_type_async(
+                                            info,
+                                            type_condition.item,
+                                            Some(&fragment.selection_set[..]),
+                                            &sub_exec,
+                                        )
+                                        .await,
+                                    &sub_exec,
+                                    pos,
+                                ),
+                                None => {
+                                    resolve_selection_set_into_async(
+                                        instance,
+                                        info,
+                                        &fragment.selection_set[..],
+                                        &sub_exec,
+                                    )
+                                    .await
+                                }
+                            }
+                        };
+                        part.deliver(resolving, channels, &errors).await;
+                        None
+                    }));
+                    continue;
+                }
+
+                let (early, early_rx) = value_channel();
+                let sub_exec = executor
+                    .type_sub_executor(
+                        fragment.type_condition.as_ref().map(|c| c.item),
+                        Some(&fragment.selection_set[..]),
+                    )
+                    .with_incremental(
+                        incremental
+                            .and_then(|incremental| early.map(|early| incremental.nested(early))),
+                    );
+
+                let slot = async_values.reserve(incremental.is_some());
+                if let Some(ref type_condition) = fragment.type_condition {
+                    async_values.push(AsyncValueFuture::InlineFragment1(async move {
+                        drive_early(
+                            instance.resolve_into_type_async(
+                                info,
+                                type_condition.item,
+                                Some(&fragment.selection_set[..]),
+                                &sub_exec,
+                            ),
+                            early_rx.map(|early_rx| early_rx.map(|value| value.map(Ok))),
+                            |res| {
+                                let value = fragment_fields(res, &sub_exec, pos);
+                                slot.publish(AsyncValue::Nested(value))
+                            },
                         )
-                        .await;
-
-                    if let Ok(Value::Object(obj)) = sub_result {
-                        for (k, v) in obj {
-                            async_values.push(AsyncValueFuture::InlineFragment1(async move {
-                                AsyncValue::Field(AsyncField {
-                                    name: k,
-                                    value: Some(v),
-                                })
-                            }));
-                        }
-                    } else if let Err(e) = sub_result {
-                        sub_exec.push_error_at(e, *start_pos);
-                    }
+                        .await
+                    }));
                 } else {
                     async_values.push(AsyncValueFuture::InlineFragment2(async move {
-                        let value = resolve_selection_set_into_async(
-                            instance,
-                            info,
-                            &fragment.selection_set[..],
-                            &sub_exec,
+                        drive_early(
+                            resolve_selection_set_into_async(
+                                instance,
+                                info,
+                                &fragment.selection_set[..],
+                                &sub_exec,
+                            ),
+                            early_rx,
+                            |value| slot.publish(AsyncValue::Nested(value)),
                         )
-                        .await;
-                        AsyncValue::Nested(value)
+                        .await
                     }));
                 }
             }
@@ -366,12 +500,12 @@ where
                 if let Some(value) = value {
                     merge_key_into(&mut object, &name, value);
                 } else {
-                    return Value::null();
+                    return async_values.finish(incremental, Value::null()).await;
                 }
             }
             AsyncValue::Nested(obj) => match obj {
                 v @ Value::Null => {
-                    return v;
+                    return async_values.finish(incremental, v).await;
                 }
                 Value::Object(obj) => {
                     for (k, v) in obj {
@@ -383,5 +517,27 @@ where
         }
     }
 
-    Value::Object(object)
+    async_values
+        .finish(incremental, Value::Object(object))
+        .await
+}
+
+/// The fields resolved for an inline fragment with a type condition, which are left out if it
+/// doesn't resolve to an object
+fn fragment_fields<S, CtxT>(
+    res: ExecutionResult<S>,
+    executor: &Executor<CtxT, S>,
+    pos: SourcePosition,
+) -> Value<S>
+where
+    S: ScalarValue,
+{
+    match res {
+        Ok(value @ Value::Object(_)) => value,
+        Ok(_) => Value::Object(Object::with_capacity(0)),
+        Err(e) => {
+            executor.push_error_at(e, pos);
+            Value::Object(Object::with_capacity(0))
+        }
+    }
 }
@@ -1,8 +1,8 @@
 use indexmap::IndexMap;
 
 use crate::{
-    ast::{Directive, FromInputValue, InputValue, Selection},
-    executor::{ExecutionResult, Executor, Registry, Variables},
+    ast::{Directive, FromInputValue, InputValue, InputValueError, Selection},
+    executor::{ExecutionResult, Executor, FieldDirectives, Registry, Variables},
     parser::Spanning,
     schema::meta::{Argument, MetaType},
     value::{DefaultScalarValue, Object, ScalarValue, Value},
@@ -116,6 +116,21 @@ where
             .and_then(InputValue::convert)
     }
 
+    /// Get and convert an argument into the desired type, telling why the conversion failed if
+    /// it did.
+    ///
+    /// Returns `Ok(None)` if the argument is absent and no default argument has been provided.
+    pub fn try_get<T>(&self, key: &str) -> Result<Option<T>, InputValueError>
+    where
+        T: FromInputValue<S>,
+    {
+        self.args
+            .as_ref()
+            .and_then(|args| args.get(key))
+            .map(InputValue::try_convert)
+            .transpose()
+    }
+
     /// Get an interator over the arg values
     pub fn iter(&self) -> Option<impl Iterator<Item = (&&str, &InputValue<S>)>> {
         self.args.as_ref().map(|args| args.iter())
@@ -470,22 +485,34 @@ where
                     f.selection_set.as_ref().map(|v| &v[..]),
                 );
 
-                let field_result = instance.resolve_field(
-                    info,
-                    f.name.item,
-                    &Arguments::new(
-                        f.arguments.as_ref().map(|m| {
-                            m.item
-                                .iter()
-                                .map(|&(ref k, ref v)| {
-                                    (k.item, v.item.clone().into_const(exec_vars))
-                                })
-                                .collect()
-                        }),
-                        &meta_field.arguments,
-                    ),
-                    &sub_exec,
-                );
+                let directives =
+                    FieldDirectives::new(executor.schema(), f.name.item, &f.directives, exec_vars);
+
+                let started_at = sub_exec.field_start();
+                let field_result = directives.resolve(|| {
+                    instance.resolve_field(
+                        info,
+                        f.name.item,
+                        &Arguments::new(
+                            f.arguments.as_ref().map(|m| {
+                                m.item
+                                    .iter()
+                                    .map(|(k, v)| (k.item, v.item.clone().into_const(exec_vars)))
+                                    .collect()
+                            }),
+                            &meta_field.arguments,
+                        ),
+                        &sub_exec,
+                    )
+                });
+                if let Some(started_at) = started_at {
+                    sub_exec.field_resolved(
+                        meta_type.name().unwrap_or_default(),
+                        f.name.item,
+                        &meta_field.field_type,
+                        started_at,
+                    );
+                }
 
                 match field_result {
                     Ok(Value::Null) if meta_field.field_type.is_non_null() => return false,
@@ -580,6 +607,14 @@ where
             ..
         } in directives
         {
+            let (is_skip, is_include) = (
+                directive.name.item == "skip",
+                directive.name.item == "include",
+            );
+            if !is_skip && !is_include {
+                continue;
+            }
+
             let condition: bool = directive
                 .arguments
                 .iter()
@@ -588,9 +623,7 @@ where
                 .next()
                 .unwrap();
 
-            if (directive.name.item == "skip" && condition)
-                || (directive.name.item == "include" && !condition)
-            {
+            if (is_skip && condition) || (is_include && !condition) {
                 return true;
             }
         }
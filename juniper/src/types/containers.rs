@@ -1,6 +1,12 @@
+use std::sync::RwLock;
+
+use futures::future;
+
 use crate::{
-    ast::{FromInputValue, InputValue, Selection, ToInputValue},
-    executor::{ExecutionResult, Executor, Registry},
+    ast::{FromInputValue, InputValue, InputValueError, Selection, ToInputValue},
+    executor::{
+        drive_early, early_channel, ExecutionResult, Executor, PathSegment, Registry, Resolving,
+    },
     schema::meta::MetaType,
     types::{
         async_await::GraphQLValueAsync,
@@ -86,6 +92,13 @@ where
             v => v.convert().map(Some),
         }
     }
+
+    fn try_from_input_value(v: &InputValue<S>) -> Result<Option<T>, InputValueError> {
+        match v {
+            &InputValue::Null => Ok(None),
+            v => v.try_convert().map(Some),
+        }
+    }
 }
 
 impl<S, T> ToInputValue<S> for Option<T>
@@ -178,6 +191,17 @@ where {
             ref other => other.convert().map(|e| vec![e]),
         }
     }
+
+    fn try_from_input_value(v: &InputValue<S>) -> Result<Vec<T>, InputValueError> {
+        match *v {
+            InputValue::List(ref ls) => ls
+                .iter()
+                .enumerate()
+                .map(|(i, item)| item.item.try_convert().map_err(|e| e.in_index(i)))
+                .collect(),
+            ref other => other.try_convert().map(|e| vec![e]),
+        }
+    }
 }
 
 impl<T, S> ToInputValue<S> for Vec<T>
@@ -274,8 +298,8 @@ where
         .is_non_null();
     let mut result = Vec::with_capacity(iter.len());
 
-    for o in iter {
-        let val = executor.resolve(info, o)?;
+    for (i, o) in iter.enumerate() {
+        let val = executor.index_sub_executor(i).resolve(info, o)?;
         if stop_on_null && val.is_null() {
             return Ok(val);
         } else {
@@ -298,25 +322,100 @@ where
     T::Context: Sync,
     S: ScalarValue + Send + Sync,
 {
-    use futures::stream::{FuturesOrdered, StreamExt as _};
-    use std::iter::FromIterator;
-
     let stop_on_null = executor
         .current_type()
         .list_contents()
         .expect("Current type is not a list type")
         .is_non_null();
 
-    let iter = items.map(|it| async move { executor.resolve_into_value_async(info, it).await });
-    let mut futures = FuturesOrdered::from_iter(iter);
+    let len = items.len();
+    let incremental = executor.incremental();
+    let streamed = incremental.and_then(|incremental| {
+        let count = incremental.streamed_count()?.min(len);
+        if count == len {
+            return None;
+        }
+        let mut path = executor.response_path();
+        path.push(PathSegment::Index(count));
+        Some((count, incremental.stream_rest(path)?))
+    });
+
+    let (count, part) = match streamed {
+        Some(streamed) => streamed,
+        None => {
+            return Ok(
+                resolve_list_items_async(executor, info, items.enumerate(), stop_on_null).await,
+            )
+        }
+    };
+
+    // The remaining items are resolved from the same iterator, and delivered after the list.
+    let mut items = items.enumerate();
+    let first = items.by_ref().take(count).collect::<Vec<_>>();
+    let rest = async move {
+        let errors = RwLock::new(Vec::new());
+        let (part_incremental, channels) = part.executor_state();
+        let part_exec = executor.part_sub_executor(&errors, part_incremental);
+        let resolving = resolve_list_items_async(&part_exec, info, items, stop_on_null);
+        part.deliver(resolving, channels, &errors).await;
+    };
+    let first = async {
+        let value = resolve_list_items_async(executor, info, first.into_iter(), stop_on_null).await;
+        if let Some(incremental) = incremental {
+            incremental.publish(&value);
+        }
+        value
+    };
+
+    Ok(future::join(first, rest).await.0)
+}
+
+/// Resolves the `items` of a list concurrently, along with their indices
+async fn resolve_list_items_async<'a, 't, S, T, I>(
+    executor: &'a Executor<'a, 'a, T::Context, S>,
+    info: &'a T::TypeInfo,
+    items: I,
+    stop_on_null: bool,
+) -> Value<S>
+where
+    I: Iterator<Item = (usize, &'t T)>,
+    T: GraphQLValueAsync<S> + ?Sized + 't,
+    T::TypeInfo: Sync,
+    T::Context: Sync,
+    S: ScalarValue + Send + Sync,
+{
+    let incremental = executor.incremental();
+
+    let mut resolving = Resolving::new();
+    for (i, item) in items {
+        // The values of the items are waited for, so they publish them early when executing
+        // incrementally.
+        let (early, early_rx) = match incremental {
+            Some(incremental) => {
+                let (early, early_rx) = early_channel();
+                (Some(incremental.nested(early)), Some(early_rx))
+            }
+            None => (None, None),
+        };
+        let slot = resolving.reserve(incremental.is_some());
+        resolving.push(async move {
+            let sub_exec = executor.index_sub_executor(i).with_incremental(early);
+            drive_early(
+                sub_exec.resolve_into_value_async(info, item),
+                early_rx,
+                |value| slot.publish(value),
+            )
+            .await
+        });
+    }
 
-    let mut values = Vec::with_capacity(futures.len());
-    while let Some(value) = futures.next().await {
+    let mut values = Vec::with_capacity(resolving.len());
+    while let Some(value) = resolving.next().await {
         if stop_on_null && value.is_null() {
-            return Ok(value);
+            return resolving.finish(incremental, value).await;
         }
         values.push(value);
     }
 
-    Ok(Value::list(values))
+    resolving.finish(incremental, Value::list(values)).await
 }
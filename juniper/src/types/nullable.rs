@@ -1,5 +1,5 @@
 use crate::{
-    ast::{FromInputValue, InputValue, Selection, ToInputValue},
+    ast::{FromInputValue, InputValue, InputValueError, Selection, ToInputValue},
     executor::{ExecutionResult, Executor, Registry},
     schema::meta::MetaType,
     types::{
@@ -302,6 +302,13 @@ where
         }
     }
 
+    fn try_from_input_value(v: &InputValue<S>) -> Result<Nullable<T>, InputValueError> {
+        match v {
+            &InputValue::Null => Ok(Self::ExplicitNull),
+            v => v.try_convert().map(Self::Some),
+        }
+    }
+
     fn from_implicit_null() -> Self {
         Self::ImplicitNull
     }
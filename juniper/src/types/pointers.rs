@@ -1,7 +1,7 @@
 use std::{fmt, sync::Arc};
 
 use crate::{
-    ast::{FromInputValue, InputValue, Selection, ToInputValue},
+    ast::{FromInputValue, InputValue, InputValueError, Selection, ToInputValue},
     executor::{ExecutionResult, Executor, Registry},
     schema::meta::MetaType,
     types::{
@@ -99,6 +99,10 @@ where
             None => None,
         }
     }
+
+    fn try_from_input_value(v: &InputValue<S>) -> Result<Box<T>, InputValueError> {
+        <T as FromInputValue<S>>::try_from_input_value(v).map(Box::new)
+    }
 }
 
 impl<T, S> ToInputValue<S> for Box<T>
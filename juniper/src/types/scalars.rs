@@ -59,7 +59,7 @@ where
         Value::scalar(self.0.clone())
     }
 
-    fn from_input_value(v: &InputValue) -> Option<ID> {
+    fn from_input_value(v: &InputValue) -> Result<ID, String> {
         match *v {
             InputValue::Scalar(ref s) => s
                 .as_string()
@@ -67,6 +67,7 @@ where
                 .map(ID),
             _ => None,
         }
+        .ok_or_else(|| format!("ID cannot represent value: {}", v))
     }
 
     fn from_str<'a>(value: ScalarToken<'a>) -> ParseScalarResult<'a, S> {
@@ -86,11 +87,12 @@ where
         Value::scalar(self.clone())
     }
 
-    fn from_input_value(v: &InputValue) -> Option<String> {
+    fn from_input_value(v: &InputValue) -> Result<String, String> {
         match *v {
             InputValue::Scalar(ref s) => s.as_string(),
             _ => None,
         }
+        .ok_or_else(|| format!("String cannot represent a non string value: {}", v))
     }
 
     fn from_str<'a>(value: ScalarToken<'a>) -> ParseScalarResult<'a, S> {
@@ -275,11 +277,12 @@ where
         Value::scalar(*self)
     }
 
-    fn from_input_value(v: &InputValue) -> Option<bool> {
+    fn from_input_value(v: &InputValue) -> Result<bool, String> {
         match *v {
             InputValue::Scalar(ref b) => b.as_boolean(),
             _ => None,
         }
+        .ok_or_else(|| format!("Boolean cannot represent a non boolean value: {}", v))
     }
 
     fn from_str<'a>(value: ScalarToken<'a>) -> ParseScalarResult<'a, S> {
@@ -297,11 +300,12 @@ where
         Value::scalar(*self)
     }
 
-    fn from_input_value(v: &InputValue) -> Option<i32> {
+    fn from_input_value(v: &InputValue) -> Result<i32, String> {
         match *v {
             InputValue::Scalar(ref i) => i.as_int(),
             _ => None,
         }
+        .ok_or_else(|| format!("Int cannot represent non-integer value: {}", v))
     }
 
     fn from_str<'a>(value: ScalarToken<'a>) -> ParseScalarResult<'a, S> {
@@ -324,11 +328,12 @@ where
         Value::scalar(*self)
     }
 
-    fn from_input_value(v: &InputValue) -> Option<f64> {
+    fn from_input_value(v: &InputValue) -> Result<f64, String> {
         match *v {
             InputValue::Scalar(ref s) => s.as_float(),
             _ => None,
         }
+        .ok_or_else(|| format!("Float cannot represent non numeric value: {}", v))
     }
 
     fn from_str<'a>(value: ScalarToken<'a>) -> ParseScalarResult<'a, S> {
@@ -1,7 +1,7 @@
 use crate::{
     ast::InputValue,
     schema::{
-        meta::{EnumMeta, InputObjectMeta, MetaType},
+        meta::{EnumMeta, InputObjectMeta, MetaType, ScalarMeta},
         model::{SchemaType, TypeType},
     },
     value::ScalarValue,
@@ -39,11 +39,30 @@ where
                 return false;
             }
 
+            // Enum literals must name one of the enum's values, regardless of
+            // how permissive its parse function is.
+            if let (InputValue::Enum(name), MetaType::Enum(EnumMeta { values, .. })) =
+                (arg_value, t)
+            {
+                if !values.iter().any(|v| &v.name == name) {
+                    return false;
+                }
+            }
+
+            // Scalars may accept list and object values too, e.g. the `_Any` scalar of
+            // federation.
+            if let (InputValue::List(_), MetaType::Scalar(ScalarMeta { try_parse_fn, .. }))
+            | (InputValue::Object(_), MetaType::Scalar(ScalarMeta { try_parse_fn, .. })) =
+                (arg_value, t)
+            {
+                return try_parse_fn(arg_value).is_ok();
+            }
+
             match *arg_value {
                 InputValue::Null | InputValue::Variable(_) => true,
                 ref v @ InputValue::Scalar(_) | ref v @ InputValue::Enum(_) => {
                     if let Some(parse_fn) = t.input_value_parse_fn() {
-                        parse_fn(v)
+                        parse_fn(v).is_ok()
                     } else {
                         false
                     }
@@ -51,7 +70,9 @@ where
                 InputValue::List(_) => false,
                 InputValue::Object(ref obj) => {
                     if let MetaType::InputObject(InputObjectMeta {
-                        ref input_fields, ..
+                        ref input_fields,
+                        is_one_of,
+                        ..
                     }) = *t
                     {
                         let mut remaining_required_fields = input_fields
@@ -79,7 +100,11 @@ where
                             }
                         });
 
-                        all_types_ok && remaining_required_fields.is_empty()
+                        // Exactly one field of a OneOf input object must be given, and not
+                        // be null.
+                        let one_of_ok = !is_one_of || (obj.len() == 1 && !obj[0].1.item.is_null());
+
+                        all_types_ok && remaining_required_fields.is_empty() && one_of_ok
                     } else {
                         false
                     }
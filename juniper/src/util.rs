@@ -1,4 +1,4 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, collections::HashMap, hash::Hash};
 
 /// Convert string to camel case.
 ///
@@ -35,6 +35,68 @@ pub fn to_camel_case(s: &'_ str) -> Cow<'_, str> {
     dest
 }
 
+/// A map holding a bounded number of entries, evicting the least recently used one when full
+#[derive(Debug)]
+pub(crate) struct LruCache<K, V> {
+    capacity: usize,
+    entries: HashMap<K, (V, u64)>,
+    tick: u64,
+}
+
+impl<K, V> LruCache<K, V>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+{
+    pub(crate) fn new(capacity: usize) -> Self {
+        LruCache {
+            capacity,
+            entries: HashMap::new(),
+            tick: 0,
+        }
+    }
+
+    pub(crate) fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub(crate) fn get<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: std::borrow::Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.tick += 1;
+        let tick = self.tick;
+
+        self.entries
+            .get_mut(key)
+            .map(|&mut (ref value, ref mut last_used)| {
+                *last_used = tick;
+                value.clone()
+            })
+    }
+
+    pub(crate) fn insert(&mut self, key: K, value: V) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        self.tick += 1;
+
+        if !self.entries.contains_key(&key) && self.entries.len() >= self.capacity {
+            let least_recently_used = self
+                .entries
+                .iter()
+                .min_by_key(|&(_, &(_, last_used))| last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(key) = least_recently_used {
+                self.entries.remove(&key);
+            }
+        }
+        self.entries.insert(key, (value, self.tick));
+    }
+}
+
 #[test]
 fn test_to_camel_case() {
     assert_eq!(&to_camel_case("test")[..], "test");
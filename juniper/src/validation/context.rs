@@ -3,9 +3,13 @@ use std::{
     fmt::{self, Debug},
 };
 
-use crate::ast::{Definition, Document, Type};
+use crate::ast::{Definition, Document, InputValue, Type};
 
-use crate::schema::{meta::MetaType, model::SchemaType};
+use crate::schema::{
+    meta::{EnumMeta, InputObjectMeta, MetaType},
+    model::{SchemaType, TypeType},
+    visibility::HiddenItems,
+};
 
 use crate::parser::SourcePosition;
 
@@ -16,9 +20,13 @@ pub struct RuleError {
     message: String,
 }
 
-#[doc(hidden)]
+/// The state of the validation of a document
+///
+/// Tracks the types of the nodes being visited, and collects the errors reported by the rules.
 pub struct ValidatorContext<'a, S: Debug + 'a> {
+    /// The schema the document is validated against
     pub schema: &'a SchemaType<'a, S>,
+    hidden: Option<&'a HiddenItems>,
     errors: Vec<RuleError>,
     type_stack: Vec<Option<&'a MetaType<'a, S>>>,
     type_literal_stack: Vec<Option<Type<'a>>>,
@@ -72,6 +80,7 @@ impl<'a, S: Debug> ValidatorContext<'a, S> {
         ValidatorContext {
             errors: Vec::new(),
             schema,
+            hidden: None,
             type_stack: Vec::new(),
             type_literal_stack: Vec::new(),
             parent_type_stack: Vec::new(),
@@ -87,12 +96,17 @@ impl<'a, S: Debug> ValidatorContext<'a, S> {
         }
     }
 
+    /// Validates the document as if the given items of the schema didn't exist
+    pub(crate) fn hide(&mut self, hidden: &'a HiddenItems) {
+        self.hidden = Some(hidden);
+    }
+
     #[doc(hidden)]
     pub fn append_errors(&mut self, mut errors: Vec<RuleError>) {
         self.errors.append(&mut errors);
     }
 
-    #[doc(hidden)]
+    /// Reports a validation error at the given positions of the document
     pub fn report_error(&mut self, message: &str, locations: &[SourcePosition]) {
         self.errors.push(RuleError::new(message, locations))
     }
@@ -160,12 +174,12 @@ impl<'a, S: Debug> ValidatorContext<'a, S> {
         res
     }
 
-    #[doc(hidden)]
+    /// The type of the node being visited, if it's known
     pub fn current_type(&self) -> Option<&'a MetaType<'a, S>> {
         *self.type_stack.last().unwrap_or(&None)
     }
 
-    #[doc(hidden)]
+    /// The type of the node being visited as written, e.g. including its wrapping lists
     pub fn current_type_literal(&self) -> Option<&Type<'a>> {
         match self.type_literal_stack.last() {
             Some(&Some(ref t)) => Some(t),
@@ -173,12 +187,12 @@ impl<'a, S: Debug> ValidatorContext<'a, S> {
         }
     }
 
-    #[doc(hidden)]
+    /// The type declaring the field being visited, if it's known
     pub fn parent_type(&self) -> Option<&'a MetaType<'a, S>> {
         *self.parent_type_stack.last().unwrap_or(&None)
     }
 
-    #[doc(hidden)]
+    /// The expected type of the input value being visited, if it's known
     pub fn current_input_type_literal(&self) -> Option<&Type<'a>> {
         match self.input_type_literal_stack.last() {
             Some(&Some(ref t)) => Some(t),
@@ -186,7 +200,53 @@ impl<'a, S: Debug> ValidatorContext<'a, S> {
         }
     }
 
-    #[doc(hidden)]
+    /// Whether the type with the given name is hidden from the request
+    pub(crate) fn is_hidden_type(&self, name: &str) -> bool {
+        self.hidden.is_some_and(|h| h.is_type_hidden(name))
+    }
+
+    /// Whether the field of the given type is hidden from the request
+    pub(crate) fn is_hidden_field(&self, type_name: &str, field_name: &str) -> bool {
+        self.hidden
+            .is_some_and(|h| h.is_field_hidden(type_name, field_name))
+    }
+
+    /// Whether the literal `value` of the given type uses an enum value hidden from the request
+    pub(crate) fn has_hidden_enum_value(
+        &self,
+        value_type: &TypeType<S>,
+        value: &InputValue<S>,
+    ) -> bool {
+        let hidden = match self.hidden {
+            Some(hidden) => hidden,
+            None => return false,
+        };
+
+        match (value_type, value) {
+            (TypeType::NonNull(inner), _) => self.has_hidden_enum_value(inner, value),
+            (TypeType::List(inner), InputValue::List(items)) => items
+                .iter()
+                .any(|item| self.has_hidden_enum_value(inner, &item.item)),
+            (TypeType::List(inner), _) => self.has_hidden_enum_value(inner, value),
+            (TypeType::Concrete(MetaType::Enum(EnumMeta { name, .. })), InputValue::Enum(v)) => {
+                hidden.is_enum_value_hidden(name, v)
+            }
+            (
+                TypeType::Concrete(MetaType::InputObject(InputObjectMeta { input_fields, .. })),
+                InputValue::Object(fields),
+            ) => fields.iter().any(|(key, value)| {
+                input_fields
+                    .iter()
+                    .find(|f| f.name == key.item)
+                    .is_some_and(|f| {
+                        self.has_hidden_enum_value(&self.schema.make_type(&f.arg_type), &value.item)
+                    })
+            }),
+            _ => false,
+        }
+    }
+
+    /// Whether the document defines a fragment with the given name
     pub fn is_known_fragment(&self, name: &str) -> bool {
         self.fragment_names.contains(name)
     }
@@ -0,0 +1,130 @@
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use crate::{ast::OwnedDocument, util::LruCache, value::DefaultScalarValue};
+
+/// A cache of parsed and validated documents, keyed by their source
+///
+/// [`crate::execute`] and [`crate::execute_sync`] look up the documents of a schema built with
+/// [`crate::RootNode::with_document_cache`] here, so queries sent repeatedly are parsed and
+/// validated with the rules of the schema only once. The least recently used document is evicted
+/// when a new document is stored in a full cache.
+///
+/// Documents failing to parse or validate aren't cached. The depth and complexity limits and the
+/// variables depend on the request, so they're still checked every time a document is executed.
+pub struct DocumentCache<S = DefaultScalarValue> {
+    cache: Mutex<LruCache<String, Arc<OwnedDocument<S>>>>,
+}
+
+impl<S> DocumentCache<S> {
+    /// Constructs a cache holding at most `capacity` documents
+    pub fn new(capacity: usize) -> Self {
+        DocumentCache {
+            cache: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+
+    /// Returns the valid document parsed from `source`, if it's cached
+    pub(crate) fn get(&self, source: &str) -> Option<Arc<OwnedDocument<S>>> {
+        self.cache.lock().unwrap().get(source)
+    }
+
+    /// Stores a document which passed validation
+    pub(crate) fn insert(&self, document: Arc<OwnedDocument<S>>) {
+        self.cache
+            .lock()
+            .unwrap()
+            .insert(document.source().to_owned(), document)
+    }
+}
+
+impl<S> fmt::Debug for DocumentCache<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DocumentCache")
+            .field("capacity", &self.cache.lock().unwrap().capacity())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{
+        tests::fixtures::starwars::schema::{Database, Query},
+        value::{DefaultScalarValue, Value},
+        EmptyMutation, EmptySubscription, GraphQLError, RootNode, Variables,
+    };
+
+    type Schema = RootNode<'static, Query, EmptyMutation<Database>, EmptySubscription<Database>>;
+
+    fn schema(capacity: usize) -> Schema {
+        Schema::new(Query, EmptyMutation::new(), EmptySubscription::new())
+            .with_document_cache(capacity)
+    }
+
+    fn execute(schema: &Schema, query: &str) -> Result<Value<DefaultScalarValue>, String> {
+        crate::execute_sync(query, None, schema, &Variables::new(), &Database::new())
+            .map(|(value, _)| value)
+            .map_err(|e| e.to_string())
+    }
+
+    fn cached(schema: &Schema, query: &str) -> bool {
+        schema.document_cache.as_ref().unwrap().get(query).is_some()
+    }
+
+    #[test]
+    fn caches_valid_documents() {
+        let schema = schema(10);
+        let query = "{ hero { name } }";
+
+        let first = execute(&schema, query);
+        assert!(cached(&schema, query));
+        let document = schema.document_cache.as_ref().unwrap().get(query).unwrap();
+
+        assert_eq!(execute(&schema, query), first);
+        assert!(Arc::ptr_eq(
+            &document,
+            &schema.document_cache.as_ref().unwrap().get(query).unwrap(),
+        ));
+    }
+
+    #[test]
+    fn does_not_cache_invalid_documents() {
+        let schema = schema(10);
+
+        assert!(execute(&schema, "{ hero { name }").is_err());
+        assert!(!cached(&schema, "{ hero { name }"));
+
+        assert!(execute(&schema, "{ hero { unknown } }").is_err());
+        assert!(!cached(&schema, "{ hero { unknown } }"));
+    }
+
+    #[test]
+    fn checks_variables_of_cached_documents() {
+        let schema = schema(10);
+        let query = "query ($id: String!) { human(id: $id) { name } }";
+
+        let mut vars = Variables::new();
+        vars.insert("id".into(), crate::InputValue::scalar("1000"));
+        assert!(crate::execute_sync(query, None, &schema, &vars, &Database::new()).is_ok());
+
+        assert!(matches!(
+            crate::execute_sync(query, None, &schema, &Variables::new(), &Database::new()),
+            Err(GraphQLError::ValidationError(_)),
+        ));
+    }
+
+    #[test]
+    fn evicts_least_recently_used_documents() {
+        let schema = schema(1);
+
+        execute(&schema, "{ hero { name } }").unwrap();
+        execute(&schema, "{ hero { id } }").unwrap();
+
+        assert!(!cached(&schema, "{ hero { name } }"));
+        assert!(cached(&schema, "{ hero { id } }"));
+    }
+}
@@ -7,6 +7,7 @@ use crate::{
     schema::{
         meta::{EnumMeta, InputObjectMeta, MetaType, ScalarMeta},
         model::{SchemaType, TypeType},
+        visibility::HiddenItems,
     },
     validation::RuleError,
     value::ScalarValue,
@@ -24,13 +25,27 @@ pub fn validate_input_values<S>(
     operation: &Spanning<Operation<S>>,
     schema: &SchemaType<S>,
 ) -> Vec<RuleError>
+where
+    S: ScalarValue,
+{
+    validate_visible_input_values(values, operation, schema, None)
+}
+
+/// Validates the variables of a request, as if the `hidden` enum values of the schema didn't
+/// exist
+pub(crate) fn validate_visible_input_values<S>(
+    values: &Variables<S>,
+    operation: &Spanning<Operation<S>>,
+    schema: &SchemaType<S>,
+    hidden: Option<&HiddenItems>,
+) -> Vec<RuleError>
 where
     S: ScalarValue,
 {
     let mut errs = vec![];
 
     if let Some(ref vars) = operation.item.variable_definitions {
-        validate_var_defs(values, &vars.item, schema, &mut errs);
+        validate_var_defs(values, &vars.item, schema, hidden, &mut errs);
     }
 
     errs.sort();
@@ -41,6 +56,7 @@ fn validate_var_defs<S>(
     values: &Variables<S>,
     var_defs: &VariableDefinitions<S>,
     schema: &SchemaType<S>,
+    hidden: Option<&HiddenItems>,
     errors: &mut Vec<RuleError>,
 ) where
     S: ScalarValue,
@@ -66,6 +82,7 @@ fn validate_var_defs<S>(
                         v,
                         &ct,
                         schema,
+                        hidden,
                         Path::Root,
                     ));
                 }
@@ -84,6 +101,7 @@ fn unify_value<'a, S>(
     value: &InputValue<S>,
     meta_type: &TypeType<'a, S>,
     schema: &SchemaType<S>,
+    hidden: Option<&HiddenItems>,
     path: Path<'a>,
 ) -> Vec<RuleError>
 where
@@ -102,7 +120,7 @@ where
                 ));
             } else {
                 errors.append(&mut unify_value(
-                    var_name, var_pos, value, inner, schema, path,
+                    var_name, var_pos, value, inner, schema, hidden, path,
                 ));
             }
         }
@@ -121,12 +139,13 @@ where
                             v,
                             inner,
                             schema,
+                            hidden,
                             Path::ArrayElement(i, &path),
                         ));
                     }
                 }
                 _ => errors.append(&mut unify_value(
-                    var_name, var_pos, value, inner, schema, path,
+                    var_name, var_pos, value, inner, schema, hidden, path,
                 )),
             }
         }
@@ -141,22 +160,20 @@ where
                     errors.append(&mut unify_scalar(var_name, var_pos, value, sm, &path))
                 }
                 MetaType::Enum(ref em) => {
-                    errors.append(&mut unify_enum(var_name, var_pos, value, em, &path))
+                    errors.append(&mut unify_enum(var_name, var_pos, value, em, hidden, &path))
                 }
                 MetaType::InputObject(ref iom) => {
-                    let mut e = unify_input_object(var_name, var_pos, value, iom, schema, &path);
+                    let mut e =
+                        unify_input_object(var_name, var_pos, value, iom, schema, hidden, &path);
                     if e.is_empty() {
                         // All the fields didn't have errors, see if there is an
                         // overall error when parsing the input value.
-                        if !(iom.try_parse_fn)(value) {
+                        if let Err(e) = (iom.try_parse_fn)(value) {
                             errors.push(unification_error(
                                 var_name,
                                 var_pos,
                                 &path,
-                                &format!(
-                                    r#"Expected input of type "{}". Got: "{}""#,
-                                    iom.name, value
-                                ),
+                                &format!(r#"Expected input of type "{}": {}"#, iom.name, e),
                             ));
                         }
                     } else {
@@ -180,33 +197,16 @@ fn unify_scalar<'a, S>(
 where
     S: fmt::Debug,
 {
-    let mut errors: Vec<RuleError> = vec![];
-
-    if !(meta.try_parse_fn)(value) {
+    if let Err(e) = (meta.try_parse_fn)(value) {
         return vec![unification_error(
             var_name,
             var_pos,
             path,
-            &format!(r#"Expected "{}""#, meta.name),
+            &format!(r#"Expected "{}": {}"#, meta.name, e),
         )];
     }
 
-    match *value {
-        InputValue::List(_) => errors.push(unification_error(
-            var_name,
-            var_pos,
-            path,
-            &format!(r#"Expected "{}", found list"#, meta.name),
-        )),
-        InputValue::Object(_) => errors.push(unification_error(
-            var_name,
-            var_pos,
-            path,
-            &format!(r#"Expected "{}", found object"#, meta.name),
-        )),
-        _ => (),
-    }
-    errors
+    vec![]
 }
 
 fn unify_enum<'a, S>(
@@ -214,18 +214,23 @@ fn unify_enum<'a, S>(
     var_pos: &SourcePosition,
     value: &InputValue<S>,
     meta: &EnumMeta<S>,
+    hidden: Option<&HiddenItems>,
     path: &Path<'a>,
 ) -> Vec<RuleError>
 where
     S: ScalarValue,
 {
     let mut errors: Vec<RuleError> = vec![];
+    let is_known = |name: &str| {
+        meta.values.iter().any(|ev| ev.name == name)
+            && !hidden.is_some_and(|h| h.is_enum_value_hidden(&meta.name, name))
+    };
 
     match *value {
         // TODO: avoid this bad duplicate as_str() call. (value system refactor)
         InputValue::Scalar(ref scalar) if scalar.as_str().is_some() => {
             if let Some(ref name) = scalar.as_str() {
-                if !meta.values.iter().any(|ev| ev.name == *name) {
+                if !is_known(name) {
                     errors.push(unification_error(
                         var_name,
                         var_pos,
@@ -236,7 +241,7 @@ where
             }
         }
         InputValue::Enum(ref name) => {
-            if !meta.values.iter().any(|ev| &ev.name == name) {
+            if !is_known(name) {
                 errors.push(unification_error(
                     var_name,
                     var_pos,
@@ -261,6 +266,7 @@ fn unify_input_object<'a, S>(
     value: &InputValue<S>,
     meta: &InputObjectMeta<S>,
     schema: &SchemaType<S>,
+    hidden: Option<&HiddenItems>,
     path: &Path<'a>,
 ) -> Vec<RuleError>
 where
@@ -285,6 +291,7 @@ where
                         value,
                         &schema.make_type(&input_field.arg_type),
                         schema,
+                        hidden,
                         Path::ObjectField(&input_field.name, path),
                     ));
                 }
@@ -308,6 +315,33 @@ where
                 "Unknown field",
             ));
         }
+
+        if meta.is_one_of {
+            match obj.iter().next() {
+                Some((key, value)) if obj.len() == 1 => {
+                    if value.is_null() {
+                        errors.push(unification_error(
+                            var_name,
+                            var_pos,
+                            &Path::ObjectField(key, path),
+                            &format!(
+                                r#"Field of OneOf input object "{}" must not be null"#,
+                                meta.name
+                            ),
+                        ));
+                    }
+                }
+                _ => errors.push(unification_error(
+                    var_name,
+                    var_pos,
+                    path,
+                    &format!(
+                        r#"Exactly one field of OneOf input object "{}" must be given"#,
+                        meta.name
+                    ),
+                )),
+            }
+        }
     } else {
         errors.push(unification_error(
             var_name,
@@ -1,8 +1,10 @@
 //! Query validation related methods and data structures
 
 mod context;
+mod document_cache;
 mod input_value;
 mod multi_visitor;
+mod rule_set;
 mod rules;
 mod traits;
 mod visitor;
@@ -10,10 +12,14 @@ mod visitor;
 #[cfg(test)]
 pub(crate) mod test_harness;
 
+pub(crate) use self::{input_value::validate_visible_input_values, rules::visit_limit_rules};
+
 pub use self::{
     context::{RuleError, ValidatorContext},
+    document_cache::DocumentCache,
     input_value::validate_input_values,
     multi_visitor::MultiVisitorNil,
+    rule_set::{RuleSet, ValidationRule},
     rules::visit_all_rules,
     traits::Visitor,
     visitor::visit,
@@ -263,3 +263,271 @@ where
         self.1.exit_object_field(ctx, f);
     }
 }
+
+impl<'a, S> Visitor<'a, S> for Vec<Box<dyn Visitor<'a, S> + 'a>>
+where
+    S: ScalarValue,
+{
+    fn enter_document(&mut self, ctx: &mut ValidatorContext<'a, S>, doc: &'a Document<S>) {
+        for v in self.iter_mut() {
+            v.enter_document(ctx, doc);
+        }
+    }
+    fn exit_document(&mut self, ctx: &mut ValidatorContext<'a, S>, doc: &'a Document<S>) {
+        for v in self.iter_mut() {
+            v.exit_document(ctx, doc);
+        }
+    }
+
+    fn enter_operation_definition(
+        &mut self,
+        ctx: &mut ValidatorContext<'a, S>,
+        op: &'a Spanning<Operation<S>>,
+    ) {
+        for v in self.iter_mut() {
+            v.enter_operation_definition(ctx, op);
+        }
+    }
+    fn exit_operation_definition(
+        &mut self,
+        ctx: &mut ValidatorContext<'a, S>,
+        op: &'a Spanning<Operation<S>>,
+    ) {
+        for v in self.iter_mut() {
+            v.exit_operation_definition(ctx, op);
+        }
+    }
+
+    fn enter_fragment_definition(
+        &mut self,
+        ctx: &mut ValidatorContext<'a, S>,
+        f: &'a Spanning<Fragment<S>>,
+    ) {
+        for v in self.iter_mut() {
+            v.enter_fragment_definition(ctx, f);
+        }
+    }
+    fn exit_fragment_definition(
+        &mut self,
+        ctx: &mut ValidatorContext<'a, S>,
+        f: &'a Spanning<Fragment<S>>,
+    ) {
+        for v in self.iter_mut() {
+            v.exit_fragment_definition(ctx, f);
+        }
+    }
+
+    fn enter_variable_definition(
+        &mut self,
+        ctx: &mut ValidatorContext<'a, S>,
+        def: &'a (Spanning<&'a str>, VariableDefinition<S>),
+    ) {
+        for v in self.iter_mut() {
+            v.enter_variable_definition(ctx, def);
+        }
+    }
+    fn exit_variable_definition(
+        &mut self,
+        ctx: &mut ValidatorContext<'a, S>,
+        def: &'a (Spanning<&'a str>, VariableDefinition<S>),
+    ) {
+        for v in self.iter_mut() {
+            v.exit_variable_definition(ctx, def);
+        }
+    }
+
+    fn enter_directive(
+        &mut self,
+        ctx: &mut ValidatorContext<'a, S>,
+        d: &'a Spanning<Directive<S>>,
+    ) {
+        for v in self.iter_mut() {
+            v.enter_directive(ctx, d);
+        }
+    }
+    fn exit_directive(&mut self, ctx: &mut ValidatorContext<'a, S>, d: &'a Spanning<Directive<S>>) {
+        for v in self.iter_mut() {
+            v.exit_directive(ctx, d);
+        }
+    }
+
+    fn enter_argument(
+        &mut self,
+        ctx: &mut ValidatorContext<'a, S>,
+        arg: &'a (Spanning<&'a str>, Spanning<InputValue<S>>),
+    ) {
+        for v in self.iter_mut() {
+            v.enter_argument(ctx, arg);
+        }
+    }
+    fn exit_argument(
+        &mut self,
+        ctx: &mut ValidatorContext<'a, S>,
+        arg: &'a (Spanning<&'a str>, Spanning<InputValue<S>>),
+    ) {
+        for v in self.iter_mut() {
+            v.exit_argument(ctx, arg);
+        }
+    }
+
+    fn enter_selection_set(&mut self, ctx: &mut ValidatorContext<'a, S>, s: &'a [Selection<S>]) {
+        for v in self.iter_mut() {
+            v.enter_selection_set(ctx, s);
+        }
+    }
+    fn exit_selection_set(&mut self, ctx: &mut ValidatorContext<'a, S>, s: &'a [Selection<S>]) {
+        for v in self.iter_mut() {
+            v.exit_selection_set(ctx, s);
+        }
+    }
+
+    fn enter_field(&mut self, ctx: &mut ValidatorContext<'a, S>, f: &'a Spanning<Field<S>>) {
+        for v in self.iter_mut() {
+            v.enter_field(ctx, f);
+        }
+    }
+    fn exit_field(&mut self, ctx: &mut ValidatorContext<'a, S>, f: &'a Spanning<Field<S>>) {
+        for v in self.iter_mut() {
+            v.exit_field(ctx, f);
+        }
+    }
+
+    fn enter_fragment_spread(
+        &mut self,
+        ctx: &mut ValidatorContext<'a, S>,
+        s: &'a Spanning<FragmentSpread<S>>,
+    ) {
+        for v in self.iter_mut() {
+            v.enter_fragment_spread(ctx, s);
+        }
+    }
+    fn exit_fragment_spread(
+        &mut self,
+        ctx: &mut ValidatorContext<'a, S>,
+        s: &'a Spanning<FragmentSpread<S>>,
+    ) {
+        for v in self.iter_mut() {
+            v.exit_fragment_spread(ctx, s);
+        }
+    }
+
+    fn enter_inline_fragment(
+        &mut self,
+        ctx: &mut ValidatorContext<'a, S>,
+        f: &'a Spanning<InlineFragment<S>>,
+    ) {
+        for v in self.iter_mut() {
+            v.enter_inline_fragment(ctx, f);
+        }
+    }
+    fn exit_inline_fragment(
+        &mut self,
+        ctx: &mut ValidatorContext<'a, S>,
+        f: &'a Spanning<InlineFragment<S>>,
+    ) {
+        for v in self.iter_mut() {
+            v.exit_inline_fragment(ctx, f);
+        }
+    }
+
+    fn enter_null_value(&mut self, ctx: &mut ValidatorContext<'a, S>, n: Spanning<()>) {
+        for v in self.iter_mut() {
+            v.enter_null_value(ctx, n);
+        }
+    }
+    fn exit_null_value(&mut self, ctx: &mut ValidatorContext<'a, S>, n: Spanning<()>) {
+        for v in self.iter_mut() {
+            v.exit_null_value(ctx, n);
+        }
+    }
+
+    fn enter_scalar_value(&mut self, ctx: &mut ValidatorContext<'a, S>, n: Spanning<&'a S>) {
+        for v in self.iter_mut() {
+            v.enter_scalar_value(ctx, n);
+        }
+    }
+    fn exit_scalar_value(&mut self, ctx: &mut ValidatorContext<'a, S>, n: Spanning<&'a S>) {
+        for v in self.iter_mut() {
+            v.exit_scalar_value(ctx, n);
+        }
+    }
+
+    fn enter_enum_value(&mut self, ctx: &mut ValidatorContext<'a, S>, s: Spanning<&'a String>) {
+        for v in self.iter_mut() {
+            v.enter_enum_value(ctx, s);
+        }
+    }
+    fn exit_enum_value(&mut self, ctx: &mut ValidatorContext<'a, S>, s: Spanning<&'a String>) {
+        for v in self.iter_mut() {
+            v.exit_enum_value(ctx, s);
+        }
+    }
+
+    fn enter_variable_value(&mut self, ctx: &mut ValidatorContext<'a, S>, s: Spanning<&'a String>) {
+        for v in self.iter_mut() {
+            v.enter_variable_value(ctx, s);
+        }
+    }
+    fn exit_variable_value(&mut self, ctx: &mut ValidatorContext<'a, S>, s: Spanning<&'a String>) {
+        for v in self.iter_mut() {
+            v.exit_variable_value(ctx, s);
+        }
+    }
+
+    fn enter_list_value(
+        &mut self,
+        ctx: &mut ValidatorContext<'a, S>,
+        l: Spanning<&'a Vec<Spanning<InputValue<S>>>>,
+    ) {
+        for v in self.iter_mut() {
+            v.enter_list_value(ctx, l);
+        }
+    }
+    fn exit_list_value(
+        &mut self,
+        ctx: &mut ValidatorContext<'a, S>,
+        l: Spanning<&'a Vec<Spanning<InputValue<S>>>>,
+    ) {
+        for v in self.iter_mut() {
+            v.exit_list_value(ctx, l);
+        }
+    }
+
+    fn enter_object_value(
+        &mut self,
+        ctx: &mut ValidatorContext<'a, S>,
+        o: Spanning<&'a Vec<(Spanning<String>, Spanning<InputValue<S>>)>>,
+    ) {
+        for v in self.iter_mut() {
+            v.enter_object_value(ctx, o);
+        }
+    }
+    fn exit_object_value(
+        &mut self,
+        ctx: &mut ValidatorContext<'a, S>,
+        o: Spanning<&'a Vec<(Spanning<String>, Spanning<InputValue<S>>)>>,
+    ) {
+        for v in self.iter_mut() {
+            v.exit_object_value(ctx, o);
+        }
+    }
+
+    fn enter_object_field(
+        &mut self,
+        ctx: &mut ValidatorContext<'a, S>,
+        f: &'a (Spanning<String>, Spanning<InputValue<S>>),
+    ) {
+        for v in self.iter_mut() {
+            v.enter_object_field(ctx, f);
+        }
+    }
+    fn exit_object_field(
+        &mut self,
+        ctx: &mut ValidatorContext<'a, S>,
+        f: &'a (Spanning<String>, Spanning<InputValue<S>>),
+    ) {
+        for v in self.iter_mut() {
+            v.exit_object_field(ctx, f);
+        }
+    }
+}
@@ -0,0 +1,294 @@
+use std::{fmt, sync::Arc};
+
+use crate::{
+    ast::Document,
+    validation::{
+        rules::{spec_rule, visit_all_rules, SPEC_RULES},
+        visit, ValidatorContext, Visitor,
+    },
+    value::{DefaultScalarValue, ScalarValue},
+};
+
+/// A validation rule which can be added to a [`RuleSet`]
+///
+/// A new [`Visitor`] is created for every validated document, so rules may keep state about the
+/// document they're visiting.
+///
+/// ```rust
+/// # use juniper::{
+/// #     ast::Operation,
+/// #     parser::Spanning,
+/// #     validation::{RuleSet, ValidationRule, ValidatorContext, Visitor},
+/// #     ScalarValue,
+/// # };
+/// struct RequireOperationNames;
+///
+/// impl<'a, S: ScalarValue> Visitor<'a, S> for RequireOperationNames {
+///     fn enter_operation_definition(
+///         &mut self,
+///         ctx: &mut ValidatorContext<'a, S>,
+///         op: &'a Spanning<Operation<S>>,
+///     ) {
+///         if op.item.name.is_none() {
+///             ctx.report_error("Operations must be named", &[op.start]);
+///         }
+///     }
+/// }
+///
+/// impl<S: ScalarValue> ValidationRule<S> for RequireOperationNames {
+///     fn name(&self) -> &str {
+///         "RequireOperationNames"
+///     }
+///
+///     fn visitor<'a>(&self) -> Box<dyn Visitor<'a, S> + 'a>
+///     where
+///         S: 'a,
+///     {
+///         Box::new(RequireOperationNames)
+///     }
+/// }
+///
+/// let rules = RuleSet::<juniper::DefaultScalarValue>::default().with_rule(RequireOperationNames);
+/// ```
+pub trait ValidationRule<S = DefaultScalarValue>: Send + Sync {
+    /// The name of the rule, used to remove it from a [`RuleSet`]
+    fn name(&self) -> &str;
+
+    /// Creates the visitor validating a single document
+    fn visitor<'a>(&self) -> Box<dyn Visitor<'a, S> + 'a>
+    where
+        S: 'a;
+}
+
+/// The validation rules run on a document before it's executed
+///
+/// The default rule set contains all the rules of the GraphQL specification, named like their
+/// counterparts in the reference implementation, e.g. `"NoUnusedFragments"` or
+/// `"OverlappingFieldsCanBeMerged"`. Rules can be added with [`RuleSet::with_rule`], and removed by
+/// name with [`RuleSet::without_rule`].
+///
+/// Removing rules of the specification allows invalid documents to be executed, and should only
+/// be done for rules whose violations the application tolerates, e.g. unused fragments.
+pub struct RuleSet<S = DefaultScalarValue> {
+    spec_rules: Vec<&'static str>,
+    rules: Vec<Arc<dyn ValidationRule<S>>>,
+}
+
+impl<S> RuleSet<S> {
+    /// Constructs a rule set with all the rules of the GraphQL specification
+    pub fn new() -> Self {
+        RuleSet {
+            spec_rules: SPEC_RULES.to_vec(),
+            rules: vec![],
+        }
+    }
+
+    /// Constructs a rule set without any rules
+    pub fn empty() -> Self {
+        RuleSet {
+            spec_rules: vec![],
+            rules: vec![],
+        }
+    }
+
+    /// Adds a rule, which is run along with the rules already in the set
+    pub fn with_rule<R>(mut self, rule: R) -> Self
+    where
+        R: ValidationRule<S> + 'static,
+    {
+        self.rules.push(Arc::new(rule));
+        self
+    }
+
+    /// Removes the rules with the given name
+    pub fn without_rule(mut self, name: &str) -> Self {
+        self.spec_rules.retain(|rule| *rule != name);
+        self.rules.retain(|rule| rule.name() != name);
+        self
+    }
+
+    /// The names of the rules in the set
+    pub fn rule_names(&self) -> impl Iterator<Item = &str> {
+        self.spec_rules
+            .iter()
+            .cloned()
+            .chain(self.rules.iter().map(|rule| rule.name()))
+    }
+
+    /// Runs the rules on the document, reporting errors to the context
+    pub fn validate<'a>(&self, ctx: &mut ValidatorContext<'a, S>, doc: &'a Document<S>)
+    where
+        S: ScalarValue + 'a,
+    {
+        if self.spec_rules.len() == SPEC_RULES.len() {
+            visit_all_rules(ctx, doc);
+        } else if !self.spec_rules.is_empty() {
+            let mut visitors = self
+                .spec_rules
+                .iter()
+                .filter_map(|name| spec_rule(name))
+                .collect::<Vec<_>>();
+            visit(&mut visitors, ctx, doc);
+        }
+
+        if !self.rules.is_empty() {
+            let mut visitors = self
+                .rules
+                .iter()
+                .map(|rule| rule.visitor())
+                .collect::<Vec<_>>();
+            visit(&mut visitors, ctx, doc);
+        }
+    }
+}
+
+impl<S> Default for RuleSet<S> {
+    fn default() -> Self {
+        RuleSet::new()
+    }
+}
+
+impl<S> Clone for RuleSet<S> {
+    fn clone(&self) -> Self {
+        RuleSet {
+            spec_rules: self.spec_rules.clone(),
+            rules: self.rules.clone(),
+        }
+    }
+}
+
+impl<S> fmt::Debug for RuleSet<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.rule_names()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RuleSet, ValidationRule};
+
+    use crate::{
+        ast::{Document, Field},
+        executor::Variables,
+        parser::{parse_document_source, SourcePosition, Spanning},
+        schema::model::RootNode,
+        validation::{
+            test_harness::{MutationRoot, QueryRoot, SubscriptionRoot},
+            RuleError, ValidatorContext, Visitor,
+        },
+        value::{DefaultScalarValue, ScalarValue},
+        GraphQLError,
+    };
+
+    struct NoAliens;
+
+    impl<'a, S: ScalarValue> Visitor<'a, S> for NoAliens {
+        fn enter_field(
+            &mut self,
+            ctx: &mut ValidatorContext<'a, S>,
+            field: &'a Spanning<Field<S>>,
+        ) {
+            if field.item.name.item == "alien" {
+                ctx.report_error("Aliens are banned", &[field.start]);
+            }
+        }
+    }
+
+    impl<S: ScalarValue> ValidationRule<S> for NoAliens {
+        fn name(&self) -> &str {
+            "NoAliens"
+        }
+
+        fn visitor<'a>(&self) -> Box<dyn Visitor<'a, S> + 'a>
+        where
+            S: 'a,
+        {
+            Box::new(NoAliens)
+        }
+    }
+
+    fn validate(rules: &RuleSet, query: &str) -> Vec<RuleError> {
+        let root = RootNode::new(QueryRoot, MutationRoot, SubscriptionRoot);
+        let doc: Document<DefaultScalarValue> =
+            parse_document_source(query, &root.schema).expect("Parse error");
+        let mut ctx = ValidatorContext::new(&root.schema, &doc);
+        rules.validate(&mut ctx, &doc);
+        ctx.into_errors()
+    }
+
+    #[test]
+    fn runs_spec_rules_by_default() {
+        let errors = validate(
+            &RuleSet::default(),
+            "{ dog { name } } fragment unused on Dog { name }",
+        );
+
+        assert_eq!(
+            errors,
+            [RuleError::new(
+                r#"Fragment "unused" is never used"#,
+                &[SourcePosition::new(17, 0, 17)],
+            )],
+        );
+    }
+
+    #[test]
+    fn removes_spec_rules() {
+        let rules = RuleSet::default().without_rule("NoUnusedFragments");
+        let errors = validate(&rules, "{ dog { name } } fragment unused on Dog { name }");
+
+        assert_eq!(errors, []);
+        assert_eq!(rules.rule_names().count(), 23);
+    }
+
+    #[test]
+    fn runs_custom_rules() {
+        let rules = RuleSet::default().with_rule(NoAliens);
+        let errors = validate(&rules, "{ dog { name } alien { name } }");
+
+        assert_eq!(
+            errors,
+            [RuleError::new(
+                "Aliens are banned",
+                &[SourcePosition::new(15, 0, 15)],
+            )],
+        );
+        assert_eq!(
+            validate(&rules.without_rule("NoAliens"), "{ alien { name } }"),
+            []
+        );
+    }
+
+    #[test]
+    fn runs_no_rules_when_empty() {
+        let errors = validate(&RuleSet::empty(), "{ dog { unknownField } }");
+
+        assert_eq!(errors, []);
+    }
+
+    #[test]
+    fn is_used_for_execution() {
+        let root = RootNode::new(QueryRoot, MutationRoot, SubscriptionRoot);
+        let rules = RuleSet::default().with_rule(NoAliens);
+
+        let result = crate::execute_sync_with_rules(
+            "{ alien { name } }",
+            None,
+            &root,
+            &Variables::new(),
+            &(),
+            &rules,
+        );
+        assert_eq!(
+            result,
+            Err(GraphQLError::ValidationError(vec![RuleError::new(
+                "Aliens are banned",
+                &[SourcePosition::new(2, 0, 2)],
+            )])),
+        );
+
+        let root = root.with_rules(RuleSet::empty().with_rule(NoAliens));
+        let result = crate::execute_sync("{ alien { name } }", None, &root, &Variables::new(), &());
+        assert!(matches!(result, Err(GraphQLError::ValidationError(_))));
+    }
+}
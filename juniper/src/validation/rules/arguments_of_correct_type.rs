@@ -57,7 +57,9 @@ where
         {
             let meta_type = ctx.schema.make_type(&argument_meta.arg_type);
 
-            if !is_valid_literal_value(ctx.schema, &meta_type, &arg_value.item) {
+            if !is_valid_literal_value(ctx.schema, &meta_type, &arg_value.item)
+                || ctx.has_hidden_enum_value(&meta_type, &arg_value.item)
+            {
                 ctx.report_error(
                     &error_message(arg_name.item, &format!("{}", argument_meta.arg_type)),
                     &[arg_value.start],
@@ -1076,6 +1078,56 @@ mod tests {
         );
     }
 
+    #[test]
+    fn one_of_object_with_one_field() {
+        expect_passes_rule::<_, _, DefaultScalarValue>(
+            factory,
+            r#"
+            {
+              complicatedArgs {
+                oneOfArgField(oneOfArg: { stringField: "foo" })
+              }
+            }
+        "#,
+        );
+    }
+
+    #[test]
+    fn one_of_object_with_two_fields() {
+        expect_fails_rule::<_, _, DefaultScalarValue>(
+            factory,
+            r#"
+            {
+              complicatedArgs {
+                oneOfArgField(oneOfArg: { stringField: "foo", intField: 1 })
+              }
+            }
+        "#,
+            &[RuleError::new(
+                &error_message("oneOfArg", "OneOfInput"),
+                &[SourcePosition::new(87, 3, 40)],
+            )],
+        );
+    }
+
+    #[test]
+    fn one_of_object_with_null_field() {
+        expect_fails_rule::<_, _, DefaultScalarValue>(
+            factory,
+            r#"
+            {
+              complicatedArgs {
+                oneOfArgField(oneOfArg: { stringField: null })
+              }
+            }
+        "#,
+            &[RuleError::new(
+                &error_message("oneOfArg", "OneOfInput"),
+                &[SourcePosition::new(87, 3, 40)],
+            )],
+        );
+    }
+
     #[test]
     fn directive_with_valid_types() {
         expect_passes_rule::<_, _, DefaultScalarValue>(
@@ -35,7 +35,9 @@ where
             } else {
                 let meta_type = ctx.schema.make_type(&var_def.var_type.item);
 
-                if !is_valid_literal_value(ctx.schema, &meta_type, var_value) {
+                if !is_valid_literal_value(ctx.schema, &meta_type, var_value)
+                    || ctx.has_hidden_enum_value(&meta_type, var_value)
+                {
                     ctx.report_error(
                         &type_error_message(var_name.item, &format!("{}", var_def.var_type.item)),
                         &[*start],
@@ -26,7 +26,9 @@ where
                 let field_name = &field.item.name;
                 let type_name = parent_type.name().unwrap_or("<unknown>");
 
-                if parent_type.field_by_name(field_name.item).is_none() {
+                if parent_type.field_by_name(field_name.item).is_none()
+                    || context.is_hidden_field(type_name, field_name.item)
+                {
                     if let MetaType::Union(..) = *parent_type {
                         // You can query for `__typename` on a union,
                         // but it isn't a field on the union...it is
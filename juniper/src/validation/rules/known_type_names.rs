@@ -50,7 +50,7 @@ fn validate_type<'a, S: Debug>(
     type_name: &str,
     location: &SourcePosition,
 ) {
-    if ctx.schema.type_by_name(type_name).is_none() {
+    if ctx.schema.type_by_name(type_name).is_none() || ctx.is_hidden_type(type_name) {
         ctx.report_error(&error_message(type_name), &[*location]);
     }
 }
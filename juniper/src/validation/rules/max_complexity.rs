@@ -0,0 +1,344 @@
+use std::collections::HashMap;
+
+use crate::{
+    ast::{Definition, Document, Field, Fragment, InputValue, Operation, OperationType, Selection},
+    executor::{get_operation, Variables},
+    parser::Spanning,
+    schema::{
+        meta::{self, Complexity, MetaType},
+        model::SchemaType,
+    },
+    validation::{ValidatorContext, Visitor},
+    value::ScalarValue,
+};
+
+pub struct MaxComplexity<'v, S> {
+    limit: usize,
+    operation_name: Option<&'v str>,
+    variables: &'v Variables<S>,
+}
+
+pub fn factory<'v, S>(
+    limit: usize,
+    operation_name: Option<&'v str>,
+    variables: &'v Variables<S>,
+) -> MaxComplexity<'v, S> {
+    MaxComplexity {
+        limit,
+        operation_name,
+        variables,
+    }
+}
+
+struct ComplexityCalculator<'a, 'v, S> {
+    schema: &'a SchemaType<'a, S>,
+    fragments: HashMap<&'a str, &'a Fragment<'a, S>>,
+    variables: &'v Variables<S>,
+    variable_defaults: HashMap<&'a str, &'a InputValue<S>>,
+    /// Complexity of every fragment computed so far, so that each one is only walked once
+    ///
+    /// A fragment is always computed against its own type condition, so its complexity is the
+    /// same wherever it's spread.
+    fragment_complexities: HashMap<&'a str, usize>,
+    spread_stack: Vec<&'a str>,
+}
+
+impl<'a, 'v, S> Visitor<'a, S> for MaxComplexity<'v, S>
+where
+    S: ScalarValue,
+{
+    fn enter_document(&mut self, ctx: &mut ValidatorContext<'a, S>, doc: &'a Document<S>) {
+        // Only the operation that is going to be executed is checked.
+        let op = match get_operation(doc, self.operation_name) {
+            Ok(op) => op,
+            Err(_) => return,
+        };
+
+        let mut calculator = ComplexityCalculator {
+            schema: ctx.schema,
+            fragments: doc
+                .iter()
+                .filter_map(|def| match *def {
+                    Definition::Fragment(ref frag) => Some((frag.item.name.item, &frag.item)),
+                    _ => None,
+                })
+                .collect(),
+            variables: self.variables,
+            variable_defaults: HashMap::new(),
+            fragment_complexities: HashMap::new(),
+            spread_stack: vec![],
+        };
+
+        let complexity = calculator.operation_complexity(&op.item);
+        if complexity > self.limit {
+            ctx.report_error(&error_message(op, complexity, self.limit), &[op.start]);
+        }
+    }
+}
+
+impl<'a, 'v, S> ComplexityCalculator<'a, 'v, S>
+where
+    S: ScalarValue,
+{
+    fn operation_complexity(&mut self, op: &'a Operation<S>) -> usize {
+        self.variable_defaults = op
+            .variable_definitions
+            .iter()
+            .flat_map(|defs| defs.item.iter())
+            .filter_map(|(name, def)| def.default_value.as_ref().map(|v| (name.item, &v.item)))
+            .collect();
+
+        let root_type = match op.operation_type {
+            OperationType::Query => Some(self.schema.concrete_query_type()),
+            OperationType::Mutation => self.schema.concrete_mutation_type(),
+            OperationType::Subscription => self.schema.concrete_subscription_type(),
+        };
+
+        self.selection_complexity(&op.selection_set, root_type)
+    }
+
+    fn selection_complexity(
+        &mut self,
+        selection_set: &'a [Selection<S>],
+        parent_type: Option<&'a MetaType<'a, S>>,
+    ) -> usize {
+        selection_set.iter().fold(0, |total, selection| {
+            let complexity = match *selection {
+                Selection::Field(ref field) => self.field_complexity(&field.item, parent_type),
+                Selection::InlineFragment(ref frag) => {
+                    let fragment_type = match frag.item.type_condition {
+                        Some(ref name) => self.schema.concrete_type_by_name(name.item),
+                        None => parent_type,
+                    };
+                    self.selection_complexity(&frag.item.selection_set, fragment_type)
+                }
+                Selection::FragmentSpread(ref spread) => {
+                    self.fragment_complexity(spread.item.name.item)
+                }
+            };
+            total.saturating_add(complexity)
+        })
+    }
+
+    fn fragment_complexity(&mut self, name: &'a str) -> usize {
+        if let Some(complexity) = self.fragment_complexities.get(name) {
+            return *complexity;
+        }
+        match self.fragments.get(name).cloned() {
+            Some(frag) if !self.spread_stack.contains(&name) => {
+                let fragment_type = self.schema.concrete_type_by_name(frag.type_condition.item);
+                self.spread_stack.push(name);
+                let complexity = self.selection_complexity(&frag.selection_set, fragment_type);
+                self.spread_stack.pop();
+                self.fragment_complexities.insert(name, complexity);
+                complexity
+            }
+            _ => 0,
+        }
+    }
+
+    fn field_complexity(
+        &mut self,
+        field: &'a Field<S>,
+        parent_type: Option<&'a MetaType<'a, S>>,
+    ) -> usize {
+        let meta_field = parent_type.and_then(|t| t.field_by_name(field.name.item));
+        let default_complexity = Complexity::default();
+        let complexity = meta_field.map_or(&default_complexity, |f| &f.complexity);
+
+        let children = match field.selection_set {
+            Some(ref selection_set) => {
+                let field_type = meta_field.and_then(|f| {
+                    self.schema
+                        .concrete_type_by_name(f.field_type.innermost_name())
+                });
+                self.selection_complexity(selection_set, field_type)
+            }
+            None => 0,
+        };
+
+        complexity.cost.saturating_add(
+            self.multiplier(field, meta_field, complexity)
+                .saturating_mul(children),
+        )
+    }
+
+    fn multiplier(
+        &self,
+        field: &'a Field<S>,
+        meta_field: Option<&meta::Field<S>>,
+        complexity: &Complexity,
+    ) -> usize {
+        complexity
+            .multiplier_argument
+            .as_ref()
+            .and_then(|name| {
+                let value = field
+                    .arguments
+                    .as_ref()
+                    .and_then(|args| args.item.get(name))
+                    .and_then(|value| match value.item {
+                        InputValue::Variable(ref var) => self
+                            .variables
+                            .get(var)
+                            .or_else(|| self.variable_defaults.get(var.as_str()).cloned()),
+                        ref value => Some(value),
+                    });
+                let default_value = || {
+                    meta_field
+                        .and_then(|f| f.arguments.as_ref())
+                        .and_then(|args| args.iter().find(|arg| &arg.name == name))
+                        .and_then(|arg| arg.default_value.as_ref())
+                };
+                value.or_else(default_value)
+            })
+            .and_then(InputValue::as_int_value)
+            .map_or(complexity.multiplier, |n| n.max(0) as usize)
+    }
+}
+
+fn error_message<S>(op: &Spanning<Operation<S>>, complexity: usize, limit: usize) -> String {
+    match op.item.name {
+        Some(ref name) => format!(
+            r#"Operation "{}" has a complexity of {}, which exceeds the maximum complexity of {}"#,
+            name.item, complexity, limit
+        ),
+        None => format!(
+            "Operation has a complexity of {}, which exceeds the maximum complexity of {}",
+            complexity, limit
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::factory;
+
+    use crate::{
+        executor::Variables,
+        parser::SourcePosition,
+        validation::{expect_fails_rule, expect_passes_rule, RuleError},
+        value::DefaultScalarValue,
+    };
+
+    #[test]
+    fn counts_every_field() {
+        let variables = Variables::new();
+        expect_passes_rule::<_, _, DefaultScalarValue>(
+            || factory(4, None, &variables),
+            r#"
+          {
+            human {
+              name
+              pets { name }
+            }
+          }
+        "#,
+        );
+        expect_fails_rule::<_, _, DefaultScalarValue>(
+            || factory(3, None, &variables),
+            r#"
+          {
+            human {
+              name
+              pets { name }
+            }
+          }
+        "#,
+            &[RuleError::new(
+                "Operation has a complexity of 4, which exceeds the maximum complexity of 3",
+                &[SourcePosition::new(11, 1, 10)],
+            )],
+        );
+    }
+
+    #[test]
+    fn counts_fragments() {
+        let variables = Variables::new();
+        expect_fails_rule::<_, _, DefaultScalarValue>(
+            || factory(3, None, &variables),
+            r#"
+          query Pets {
+            human { ...humanFields }
+            pet { ... on Dog { name barks } }
+          }
+
+          fragment humanFields on Human { name }
+        "#,
+            &[RuleError::new(
+                r#"Operation "Pets" has a complexity of 5, which exceeds the maximum complexity of 3"#,
+                &[SourcePosition::new(11, 1, 10)],
+            )],
+        );
+    }
+
+    #[test]
+    fn multiplies_selection_set() {
+        let variables = Variables::new();
+        expect_fails_rule::<_, _, DefaultScalarValue>(
+            || factory(20, None, &variables),
+            r#"
+          {
+            human {
+              relatives { name iq }
+            }
+          }
+        "#,
+            &[RuleError::new(
+                "Operation has a complexity of 22, which exceeds the maximum complexity of 20",
+                &[SourcePosition::new(11, 1, 10)],
+            )],
+        );
+    }
+
+    #[test]
+    fn checks_selected_operation() {
+        let variables = Variables::new();
+        let source = r#"
+          query Small { human { name } }
+          query Large { human { name pets { name } } }
+        "#;
+        expect_passes_rule::<_, _, DefaultScalarValue>(
+            || factory(3, Some("Small"), &variables),
+            source,
+        );
+        expect_fails_rule::<_, _, DefaultScalarValue>(
+            || factory(3, Some("Large"), &variables),
+            source,
+            &[RuleError::new(
+                r#"Operation "Large" has a complexity of 4, which exceeds the maximum complexity of 3"#,
+                &[SourcePosition::new(52, 2, 10)],
+            )],
+        );
+    }
+
+    #[test]
+    fn walks_every_fragment_once() {
+        // Without caching the complexity of each fragment, walking this document takes 2^40
+        // steps.
+        let variables = Variables::new();
+        let mut source = String::from("{ ...F0 }\n");
+        for i in 0..40 {
+            source.push_str(&format!(
+                "fragment F{} on QueryRoot {{ ...F{} ...F{} }}\n",
+                i,
+                i + 1,
+                i + 1,
+            ));
+        }
+        source.push_str("fragment F40 on QueryRoot { human { name } }\n");
+
+        expect_fails_rule::<_, _, DefaultScalarValue>(
+            || factory(1000, None, &variables),
+            &source,
+            &[RuleError::new(
+                &format!(
+                    "Operation has a complexity of {}, which exceeds the maximum \
+                     complexity of 1000",
+                    2u64 << 40,
+                ),
+                &[SourcePosition::new(0, 0, 0)],
+            )],
+        );
+    }
+}
@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+
+use crate::{
+    ast::{Definition, Document, Fragment, Operation, Selection},
+    executor::get_operation,
+    parser::Spanning,
+    validation::{ValidatorContext, Visitor},
+    value::ScalarValue,
+};
+
+pub struct MaxDepth<'n> {
+    limit: usize,
+    operation_name: Option<&'n str>,
+}
+
+pub fn factory(limit: usize, operation_name: Option<&str>) -> MaxDepth<'_> {
+    MaxDepth {
+        limit,
+        operation_name,
+    }
+}
+
+struct DepthCalculator<'a, S> {
+    fragments: HashMap<&'a str, &'a Fragment<'a, S>>,
+    /// Depth of every fragment computed so far, so that each one is only walked once
+    fragment_depths: HashMap<&'a str, usize>,
+    spread_stack: Vec<&'a str>,
+}
+
+impl<'a, 'n, S> Visitor<'a, S> for MaxDepth<'n>
+where
+    S: ScalarValue,
+{
+    fn enter_document(&mut self, ctx: &mut ValidatorContext<'a, S>, doc: &'a Document<S>) {
+        // Only the operation that is going to be executed is checked.
+        let op = match get_operation(doc, self.operation_name) {
+            Ok(op) => op,
+            Err(_) => return,
+        };
+
+        let mut calculator = DepthCalculator {
+            fragments: doc
+                .iter()
+                .filter_map(|def| match *def {
+                    Definition::Fragment(ref frag) => Some((frag.item.name.item, &frag.item)),
+                    _ => None,
+                })
+                .collect(),
+            fragment_depths: HashMap::new(),
+            spread_stack: vec![],
+        };
+
+        let depth = calculator.selection_depth(&op.item.selection_set);
+        if depth > self.limit {
+            ctx.report_error(&error_message(op, depth, self.limit), &[op.start]);
+        }
+    }
+}
+
+impl<'a, S> DepthCalculator<'a, S> {
+    fn selection_depth(&mut self, selection_set: &'a [Selection<S>]) -> usize {
+        selection_set
+            .iter()
+            .map(|selection| match *selection {
+                Selection::Field(ref field) => {
+                    1 + field
+                        .item
+                        .selection_set
+                        .as_ref()
+                        .map(|set| self.selection_depth(set))
+                        .unwrap_or(0)
+                }
+                Selection::InlineFragment(ref frag) => {
+                    self.selection_depth(&frag.item.selection_set)
+                }
+                Selection::FragmentSpread(ref spread) => self.fragment_depth(spread.item.name.item),
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn fragment_depth(&mut self, name: &'a str) -> usize {
+        if let Some(depth) = self.fragment_depths.get(name) {
+            return *depth;
+        }
+        match self.fragments.get(name).cloned() {
+            Some(frag) if !self.spread_stack.contains(&name) => {
+                self.spread_stack.push(name);
+                let depth = self.selection_depth(&frag.selection_set);
+                self.spread_stack.pop();
+                self.fragment_depths.insert(name, depth);
+                depth
+            }
+            _ => 0,
+        }
+    }
+}
+
+fn error_message<S>(op: &Spanning<Operation<S>>, depth: usize, limit: usize) -> String {
+    match op.item.name {
+        Some(ref name) => format!(
+            r#"Operation "{}" has a depth of {}, which exceeds the maximum depth of {}"#,
+            name.item, depth, limit
+        ),
+        None => format!(
+            "Operation has a depth of {}, which exceeds the maximum depth of {}",
+            depth, limit
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{factory, MaxDepth};
+
+    use crate::{
+        parser::SourcePosition,
+        validation::{expect_fails_rule, expect_passes_rule, RuleError},
+        value::DefaultScalarValue,
+    };
+
+    fn factory_3() -> MaxDepth<'static> {
+        factory(3, None)
+    }
+
+    #[test]
+    fn shallow_query() {
+        expect_passes_rule::<_, _, DefaultScalarValue>(
+            factory_3,
+            r#"
+          {
+            human {
+              name
+              pets { name }
+            }
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn deep_query() {
+        expect_fails_rule::<_, _, DefaultScalarValue>(
+            factory_3,
+            r#"
+          query Deep {
+            human {
+              relatives {
+                pets { name }
+              }
+            }
+          }
+        "#,
+            &[RuleError::new(
+                r#"Operation "Deep" has a depth of 4, which exceeds the maximum depth of 3"#,
+                &[SourcePosition::new(11, 1, 10)],
+            )],
+        );
+    }
+
+    #[test]
+    fn deep_through_fragments() {
+        expect_fails_rule::<_, _, DefaultScalarValue>(
+            factory_3,
+            r#"
+          {
+            human { ...relativeFields }
+          }
+
+          fragment relativeFields on Human {
+            relatives {
+              ... on Human {
+                pets { name }
+              }
+            }
+          }
+        "#,
+            &[RuleError::new(
+                "Operation has a depth of 4, which exceeds the maximum depth of 3",
+                &[SourcePosition::new(11, 1, 10)],
+            )],
+        );
+    }
+
+    #[test]
+    fn checks_selected_operation() {
+        let source = r#"
+          query Shallow { human { name } }
+          query Deep { human { relatives { pets { name } } } }
+        "#;
+        expect_passes_rule::<_, _, DefaultScalarValue>(|| factory(3, Some("Shallow")), source);
+        expect_fails_rule::<_, _, DefaultScalarValue>(
+            || factory(3, Some("Deep")),
+            source,
+            &[RuleError::new(
+                r#"Operation "Deep" has a depth of 4, which exceeds the maximum depth of 3"#,
+                &[SourcePosition::new(54, 2, 10)],
+            )],
+        );
+    }
+
+    #[test]
+    fn walks_every_fragment_once() {
+        // Without caching the depth of each fragment, walking this document takes 2^40 steps.
+        let mut source = String::from("{ ...F0 }\n");
+        for i in 0..40 {
+            source.push_str(&format!(
+                "fragment F{} on QueryRoot {{ ...F{} ...F{} }}\n",
+                i,
+                i + 1,
+                i + 1,
+            ));
+        }
+        source.push_str("fragment F40 on QueryRoot { human { name } }\n");
+
+        expect_passes_rule::<_, _, DefaultScalarValue>(factory_3, &source);
+    }
+}
@@ -7,6 +7,8 @@ mod known_directives;
 mod known_fragment_names;
 mod known_type_names;
 mod lone_anonymous_operation;
+mod max_complexity;
+mod max_depth;
 mod no_fragment_cycles;
 mod no_undefined_variables;
 mod no_unused_fragments;
@@ -25,7 +27,8 @@ mod variables_in_allowed_position;
 
 use crate::{
     ast::Document,
-    validation::{visit, MultiVisitorNil, ValidatorContext},
+    executor::Variables,
+    validation::{visit, MultiVisitorNil, ValidatorContext, Visitor},
     value::ScalarValue,
 };
 use std::fmt::Debug;
@@ -62,3 +65,102 @@ where
 
     visit(&mut mv, ctx, doc)
 }
+
+/// Names of the validation rules of the GraphQL specification, as run by [`visit_all_rules`]
+pub(crate) const SPEC_RULES: &[&str] = &[
+    "ArgumentsOfCorrectType",
+    "DefaultValuesOfCorrectType",
+    "FieldsOnCorrectType",
+    "FragmentsOnCompositeTypes",
+    "KnownArgumentNames",
+    "KnownDirectives",
+    "KnownFragmentNames",
+    "KnownTypeNames",
+    "LoneAnonymousOperation",
+    "NoFragmentCycles",
+    "NoUndefinedVariables",
+    "NoUnusedFragments",
+    "NoUnusedVariables",
+    "OverlappingFieldsCanBeMerged",
+    "PossibleFragmentSpreads",
+    "ProvidedNonNullArguments",
+    "ScalarLeafs",
+    "UniqueArgumentNames",
+    "UniqueFragmentNames",
+    "UniqueInputFieldNames",
+    "UniqueOperationNames",
+    "UniqueVariableNames",
+    "VariablesAreInputTypes",
+    "VariablesInAllowedPosition",
+];
+
+/// Creates the visitor of the named rule of the GraphQL specification
+pub(crate) fn spec_rule<'a, S>(name: &str) -> Option<Box<dyn Visitor<'a, S> + 'a>>
+where
+    S: ScalarValue + 'a,
+{
+    Some(match name {
+        "ArgumentsOfCorrectType" => Box::new(self::arguments_of_correct_type::factory()),
+        "DefaultValuesOfCorrectType" => Box::new(self::default_values_of_correct_type::factory()),
+        "FieldsOnCorrectType" => Box::new(self::fields_on_correct_type::factory()),
+        "FragmentsOnCompositeTypes" => Box::new(self::fragments_on_composite_types::factory()),
+        "KnownArgumentNames" => Box::new(self::known_argument_names::factory()),
+        "KnownDirectives" => Box::new(self::known_directives::factory()),
+        "KnownFragmentNames" => Box::new(self::known_fragment_names::factory()),
+        "KnownTypeNames" => Box::new(self::known_type_names::factory()),
+        "LoneAnonymousOperation" => Box::new(self::lone_anonymous_operation::factory()),
+        "NoFragmentCycles" => Box::new(self::no_fragment_cycles::factory()),
+        "NoUndefinedVariables" => Box::new(self::no_undefined_variables::factory()),
+        "NoUnusedFragments" => Box::new(self::no_unused_fragments::factory()),
+        "NoUnusedVariables" => Box::new(self::no_unused_variables::factory()),
+        "OverlappingFieldsCanBeMerged" => {
+            Box::new(self::overlapping_fields_can_be_merged::factory())
+        }
+        "PossibleFragmentSpreads" => Box::new(self::possible_fragment_spreads::factory()),
+        "ProvidedNonNullArguments" => Box::new(self::provided_non_null_arguments::factory()),
+        "ScalarLeafs" => Box::new(self::scalar_leafs::factory()),
+        "UniqueArgumentNames" => Box::new(self::unique_argument_names::factory()),
+        "UniqueFragmentNames" => Box::new(self::unique_fragment_names::factory()),
+        "UniqueInputFieldNames" => Box::new(self::unique_input_field_names::factory()),
+        "UniqueOperationNames" => Box::new(self::unique_operation_names::factory()),
+        "UniqueVariableNames" => Box::new(self::unique_variable_names::factory()),
+        "VariablesAreInputTypes" => Box::new(self::variables_are_input_types::factory()),
+        "VariablesInAllowedPosition" => Box::new(self::variables_in_allowed_position::factory()),
+        _ => return None,
+    })
+}
+
+/// Checks the configured depth and complexity limits of the operation of the document that is
+/// going to be executed
+///
+/// The document must have passed [`visit_all_rules`]. Variables are used to resolve the
+/// multiplier arguments of the complexity hints.
+pub(crate) fn visit_limit_rules<'a, S>(
+    ctx: &mut ValidatorContext<'a, S>,
+    doc: &'a Document<S>,
+    operation_name: Option<&'a str>,
+    variables: &'a Variables<S>,
+    max_depth: Option<usize>,
+    max_complexity: Option<usize>,
+) where
+    S: ScalarValue,
+{
+    if let Some(limit) = max_depth {
+        visit(
+            &mut MultiVisitorNil.with(self::max_depth::factory(limit, operation_name)),
+            ctx,
+            doc,
+        );
+    }
+    if let Some(limit) = max_complexity {
+        visit(
+            &mut MultiVisitorNil.with(self::max_complexity::factory(
+                limit,
+                operation_name,
+                variables,
+            )),
+            ctx,
+            doc,
+        );
+    }
+}
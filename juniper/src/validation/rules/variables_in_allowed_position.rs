@@ -405,6 +405,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn string_into_one_of_input_field() {
+        expect_fails_rule::<_, _, DefaultScalarValue>(
+            factory,
+            r#"
+          query Query($stringVar: String) {
+            complicatedArgs {
+              oneOfArgField(oneOfArg: { stringField: $stringVar })
+            }
+          }
+        "#,
+            &[RuleError::new(
+                &error_message("stringVar", "String", "String!"),
+                &[
+                    SourcePosition::new(23, 1, 22),
+                    SourcePosition::new(128, 3, 53),
+                ],
+            )],
+        );
+    }
+
     #[test]
     fn int_into_non_null_int_within_fragment() {
         expect_fails_rule::<_, _, DefaultScalarValue>(
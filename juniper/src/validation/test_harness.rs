@@ -3,7 +3,7 @@ use crate::{
     executor::Registry,
     parser::parse_document_source,
     schema::{
-        meta::{EnumValue, MetaType},
+        meta::{Complexity, EnumValue, MetaType},
         model::{DirectiveLocation, DirectiveType, RootNode},
     },
     types::{
@@ -40,6 +40,12 @@ struct TestInput {
     name: String,
 }
 
+#[derive(Debug, GraphQLInputObject)]
+enum OneOfInput {
+    StringField(String),
+    IntField(i32),
+}
+
 pub(crate) struct MutationRoot;
 
 pub(crate) struct SubscriptionRoot;
@@ -441,7 +447,9 @@ where
                 .field::<Option<String>>("name", i)
                 .argument(registry.arg::<Option<bool>>("surname", i)),
             registry.field::<Option<Vec<Option<Pet>>>>("pets", i),
-            registry.field::<Option<Vec<Human>>>("relatives", i),
+            registry
+                .field::<Option<Vec<Human>>>("relatives", i)
+                .complexity(Complexity::new(1).multiplier(10)),
             registry.field::<Option<i32>>("iq", i),
         ];
         registry
@@ -669,6 +677,9 @@ where
             registry
                 .field::<Option<String>>("complexArgField", i)
                 .argument(registry.arg::<Option<ComplexInput>>("complexArg", i)),
+            registry
+                .field::<Option<String>>("oneOfArgField", i)
+                .argument(registry.arg::<Option<OneOfInput>>("oneOfArg", i)),
             registry
                 .field::<Option<String>>("multipleReqs", i)
                 .argument(registry.arg::<i32>("req1", i))
@@ -8,20 +8,27 @@ use crate::{
     value::ScalarValue,
 };
 
-#[doc(hidden)]
+/// A validation rule, visiting the nodes of a document in order
+///
+/// Every method is called with the [`ValidatorContext`], which tracks the types of the visited
+/// nodes and collects the errors reported by the rule. All methods do nothing by default.
 pub trait Visitor<'a, S>
 where
     S: ScalarValue,
 {
+    /// Called when entering a document
     fn enter_document(&mut self, _: &mut ValidatorContext<'a, S>, _: &'a Document<S>) {}
+    /// Called when leaving a document
     fn exit_document(&mut self, _: &mut ValidatorContext<'a, S>, _: &'a Document<S>) {}
 
+    /// Called when entering an operation definition
     fn enter_operation_definition(
         &mut self,
         _: &mut ValidatorContext<'a, S>,
         _: &'a Spanning<Operation<S>>,
     ) {
     }
+    /// Called when leaving an operation definition
     fn exit_operation_definition(
         &mut self,
         _: &mut ValidatorContext<'a, S>,
@@ -29,12 +36,14 @@ where
     ) {
     }
 
+    /// Called when entering a fragment definition
     fn enter_fragment_definition(
         &mut self,
         _: &mut ValidatorContext<'a, S>,
         _: &'a Spanning<Fragment<S>>,
     ) {
     }
+    /// Called when leaving a fragment definition
     fn exit_fragment_definition(
         &mut self,
         _: &mut ValidatorContext<'a, S>,
@@ -42,12 +51,14 @@ where
     ) {
     }
 
+    /// Called when entering a variable definition
     fn enter_variable_definition(
         &mut self,
         _: &mut ValidatorContext<'a, S>,
         _: &'a (Spanning<&'a str>, VariableDefinition<S>),
     ) {
     }
+    /// Called when leaving a variable definition
     fn exit_variable_definition(
         &mut self,
         _: &mut ValidatorContext<'a, S>,
@@ -55,15 +66,19 @@ where
     ) {
     }
 
+    /// Called when entering a directive
     fn enter_directive(&mut self, _: &mut ValidatorContext<'a, S>, _: &'a Spanning<Directive<S>>) {}
+    /// Called when leaving a directive
     fn exit_directive(&mut self, _: &mut ValidatorContext<'a, S>, _: &'a Spanning<Directive<S>>) {}
 
+    /// Called when entering an argument
     fn enter_argument(
         &mut self,
         _: &mut ValidatorContext<'a, S>,
         _: &'a (Spanning<&'a str>, Spanning<InputValue<S>>),
     ) {
     }
+    /// Called when leaving an argument
     fn exit_argument(
         &mut self,
         _: &mut ValidatorContext<'a, S>,
@@ -71,18 +86,24 @@ where
     ) {
     }
 
+    /// Called when entering a selection set
     fn enter_selection_set(&mut self, _: &mut ValidatorContext<'a, S>, _: &'a [Selection<S>]) {}
+    /// Called when leaving a selection set
     fn exit_selection_set(&mut self, _: &mut ValidatorContext<'a, S>, _: &'a [Selection<S>]) {}
 
+    /// Called when entering a field
     fn enter_field(&mut self, _: &mut ValidatorContext<'a, S>, _: &'a Spanning<Field<S>>) {}
+    /// Called when leaving a field
     fn exit_field(&mut self, _: &mut ValidatorContext<'a, S>, _: &'a Spanning<Field<S>>) {}
 
+    /// Called when entering a fragment spread
     fn enter_fragment_spread(
         &mut self,
         _: &mut ValidatorContext<'a, S>,
         _: &'a Spanning<FragmentSpread<S>>,
     ) {
     }
+    /// Called when leaving a fragment spread
     fn exit_fragment_spread(
         &mut self,
         _: &mut ValidatorContext<'a, S>,
@@ -90,12 +111,14 @@ where
     ) {
     }
 
+    /// Called when entering an inline fragment
     fn enter_inline_fragment(
         &mut self,
         _: &mut ValidatorContext<'a, S>,
         _: &'a Spanning<InlineFragment<S>>,
     ) {
     }
+    /// Called when leaving an inline fragment
     fn exit_inline_fragment(
         &mut self,
         _: &mut ValidatorContext<'a, S>,
@@ -103,24 +126,34 @@ where
     ) {
     }
 
+    /// Called when entering a null value
     fn enter_null_value(&mut self, _: &mut ValidatorContext<'a, S>, _: Spanning<()>) {}
+    /// Called when leaving a null value
     fn exit_null_value(&mut self, _: &mut ValidatorContext<'a, S>, _: Spanning<()>) {}
 
+    /// Called when entering a scalar value
     fn enter_scalar_value(&mut self, _: &mut ValidatorContext<'a, S>, _: Spanning<&'a S>) {}
+    /// Called when leaving a scalar value
     fn exit_scalar_value(&mut self, _: &mut ValidatorContext<'a, S>, _: Spanning<&'a S>) {}
 
+    /// Called when entering an enum value
     fn enter_enum_value(&mut self, _: &mut ValidatorContext<'a, S>, _: Spanning<&'a String>) {}
+    /// Called when leaving an enum value
     fn exit_enum_value(&mut self, _: &mut ValidatorContext<'a, S>, _: Spanning<&'a String>) {}
 
+    /// Called when entering a variable value
     fn enter_variable_value(&mut self, _: &mut ValidatorContext<'a, S>, _: Spanning<&'a String>) {}
+    /// Called when leaving a variable value
     fn exit_variable_value(&mut self, _: &mut ValidatorContext<'a, S>, _: Spanning<&'a String>) {}
 
+    /// Called when entering a list value
     fn enter_list_value(
         &mut self,
         _: &mut ValidatorContext<'a, S>,
         _: Spanning<&'a Vec<Spanning<InputValue<S>>>>,
     ) {
     }
+    /// Called when leaving a list value
     fn exit_list_value(
         &mut self,
         _: &mut ValidatorContext<'a, S>,
@@ -128,12 +161,14 @@ where
     ) {
     }
 
+    /// Called when entering an object value
     fn enter_object_value(
         &mut self,
         _: &mut ValidatorContext<'a, S>,
         _: Spanning<&'a Vec<(Spanning<String>, Spanning<InputValue<S>>)>>,
     ) {
     }
+    /// Called when leaving an object value
     fn exit_object_value(
         &mut self,
         _: &mut ValidatorContext<'a, S>,
@@ -141,12 +176,14 @@ where
     ) {
     }
 
+    /// Called when entering an object field
     fn enter_object_field(
         &mut self,
         _: &mut ValidatorContext<'a, S>,
         _: &'a (Spanning<String>, Spanning<InputValue<S>>),
     ) {
     }
+    /// Called when leaving an object field
     fn exit_object_field(
         &mut self,
         _: &mut ValidatorContext<'a, S>,
@@ -7,18 +7,15 @@ use crate::{
     },
     parser::Spanning,
     schema::meta::Argument,
-    validation::{multi_visitor::MultiVisitorCons, ValidatorContext, Visitor},
+    validation::{ValidatorContext, Visitor},
     value::ScalarValue,
 };
 
-#[doc(hidden)]
-pub fn visit<'a, A, B, S>(
-    v: &mut MultiVisitorCons<A, B>,
-    ctx: &mut ValidatorContext<'a, S>,
-    d: &'a Document<S>,
-) where
+/// Visits all nodes of the document with the provided visitor
+pub fn visit<'a, V, S>(v: &mut V, ctx: &mut ValidatorContext<'a, S>, d: &'a Document<S>)
+where
     S: ScalarValue,
-    MultiVisitorCons<A, B>: Visitor<'a, S>,
+    V: Visitor<'a, S>,
 {
     v.enter_document(ctx, d);
     visit_definitions(v, ctx, d);
@@ -316,18 +313,24 @@ fn visit_input_value<'a, S, V>(
     match input_value.item {
         InputValue::Object(ref fields) => {
             for field in fields {
-                let inner_type = ctx
-                    .current_input_type_literal()
-                    .and_then(|t| match *t {
-                        Type::NonNullNamed(ref name) | Type::Named(ref name) => {
-                            ctx.schema.concrete_type_by_name(name)
-                        }
-                        _ => None,
-                    })
+                let object_type = ctx.current_input_type_literal().and_then(|t| match *t {
+                    Type::NonNullNamed(ref name) | Type::Named(ref name) => {
+                        ctx.schema.concrete_type_by_name(name)
+                    }
+                    _ => None,
+                });
+                let is_one_of = object_type.is_some_and(|ct| ct.is_one_of());
+                let inner_type = object_type
                     .and_then(|ct| ct.input_field_by_name(&field.0.item))
-                    .map(|f| &f.arg_type);
-
-                ctx.with_pushed_input_type(inner_type, |ctx| {
+                    .map(|f| match f.arg_type {
+                        // The field given to a OneOf input object must not be null, so neither
+                        // can the variables used as its value.
+                        Type::Named(ref name) if is_one_of => Type::NonNullNamed(name.clone()),
+                        Type::List(ref inner) if is_one_of => Type::NonNullList(inner.clone()),
+                        ref t => t.clone(),
+                    });
+
+                ctx.with_pushed_input_type(inner_type.as_ref(), |ctx| {
                     v.enter_object_field(ctx, field);
                     visit_input_value(v, ctx, &field.1);
                     v.exit_object_field(ctx, field);
@@ -40,45 +40,39 @@ Check the LICENSE file for details.
 #![deny(warnings)]
 #![doc(html_root_url = "https://docs.rs/juniper_actix/0.1.0")]
 
+use std::{collections::HashMap, sync::Arc};
+
+use actix_multipart::Multipart;
 use actix_web::{
     error::{ErrorBadRequest, ErrorMethodNotAllowed, ErrorUnsupportedMediaType},
-    http::{header::CONTENT_TYPE, Method},
+    http::{
+        header::{ACCEPT, CONTENT_TYPE},
+        Method,
+    },
     web, Error, FromRequest, HttpRequest, HttpResponse,
 };
+use futures::{StreamExt as _, TryStreamExt as _};
 use juniper::{
     http::{
-        graphiql::graphiql_source, playground::playground_source, GraphQLBatchRequest,
-        GraphQLRequest,
+        graphiql::graphiql_source,
+        incremental::{self, IncrementalBody},
+        multipart::UploadedFile,
+        playground::playground_source,
+        GraphQLBatchRequest, GraphQLRequest,
     },
-    ScalarValue,
+    GraphQLSubscriptionType, GraphQLTypeAsync, RootNode, ScalarValue,
 };
 use serde::Deserialize;
 
 #[derive(Deserialize, Clone, PartialEq, Debug)]
 #[serde(deny_unknown_fields)]
 struct GetGraphQLRequest {
+    #[serde(default)]
     query: String,
     #[serde(rename = "operationName")]
     operation_name: Option<String>,
     variables: Option<String>,
-}
-
-impl<S> From<GetGraphQLRequest> for GraphQLRequest<S>
-where
-    S: ScalarValue,
-{
-    fn from(get_req: GetGraphQLRequest) -> Self {
-        let GetGraphQLRequest {
-            query,
-            operation_name,
-            variables,
-        } = get_req;
-        let variables = match variables {
-            Some(variables) => Some(serde_json::from_str(&variables).unwrap()),
-            None => None,
-        };
-        Self::new(query, operation_name, variables)
-    }
+    extensions: Option<String>,
 }
 
 /// Actix Web GraphQL Handler for GET and POST requests
@@ -122,8 +116,8 @@ where
     CtxT: Sync,
     S: ScalarValue + Send + Sync,
 {
-    let get_req = web::Query::<GetGraphQLRequest>::from_query(req.query_string())?;
-    let req = GraphQLRequest::from(get_req.into_inner());
+    let mut req = get_request(&req)?;
+    req.load_persisted_query(schema);
     let gql_response = req.execute(schema, context).await;
     let body_response = serde_json::to_string(&gql_response)?;
     let mut response = match gql_response.is_ok() {
@@ -136,6 +130,9 @@ where
 }
 
 /// Actix GraphQL Handler for POST requests
+///
+/// Files can be uploaded with `multipart/form-data` requests, following the
+/// [`juniper::http::multipart`] spec.
 pub async fn post_graphql_handler<Query, Mutation, Subscription, CtxT, S>(
     schema: &juniper::RootNode<'static, Query, Mutation, Subscription, S>,
     context: &CtxT,
@@ -151,33 +148,189 @@ where
     Subscription::TypeInfo: Sync,
     CtxT: Sync,
     S: ScalarValue + Send + Sync,
+{
+    let mut req = post_request(&req, payload).await?;
+    req.load_persisted_queries(schema);
+    let gql_batch_response = req.execute(schema, context).await;
+    let gql_response = serde_json::to_string(&gql_batch_response)?;
+    let mut response = match gql_batch_response.is_ok() {
+        true => HttpResponse::Ok(),
+        false => HttpResponse::BadRequest(),
+    };
+    Ok(response.content_type("application/json").body(gql_response))
+}
+
+/// Actix GraphQL Handler for GET and POST requests, delivering the payloads of queries using the
+/// `@defer` and `@stream` directives as a `multipart/mixed` response to clients accepting it
+///
+/// The directives are only available once enabled with
+/// [`RootNode::with_incremental_delivery`](juniper::RootNode::with_incremental_delivery).
+pub async fn incremental_handler<Query, Mutation, Subscription, CtxT, S>(
+    req: HttpRequest,
+    payload: web::Payload,
+    root_node: Arc<RootNode<'static, Query, Mutation, Subscription, S>>,
+    context: CtxT,
+) -> Result<HttpResponse, Error>
+where
+    Query: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+    Query::TypeInfo: Send + Sync,
+    Mutation: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+    Mutation::TypeInfo: Send + Sync,
+    Subscription: GraphQLSubscriptionType<S, Context = CtxT> + Send + 'static,
+    Subscription::TypeInfo: Send + Sync,
+    CtxT: Send + Sync + 'static,
+    S: ScalarValue + Send + Sync + 'static,
+{
+    let accepts_multipart = req
+        .headers()
+        .get(ACCEPT)
+        .and_then(|hv| hv.to_str().ok())
+        .map_or(false, incremental::accepts_multipart);
+    let gql_req = match *req.method() {
+        Method::GET => GraphQLBatchRequest::Single(get_request(&req)?),
+        Method::POST => post_request(&req, payload).await?,
+        _ => {
+            return Err(ErrorMethodNotAllowed(
+                "GraphQL requests can only be sent with GET or POST",
+            ))
+        }
+    };
+
+    let (gql_response, is_ok) = match gql_req {
+        GraphQLBatchRequest::Single(mut gql_req) if accepts_multipart => {
+            gql_req.load_persisted_query(&*root_node);
+            let body =
+                incremental::execute_owned(gql_req, root_node, Arc::new(context), |payload| {
+                    serde_json::to_string(&payload).unwrap()
+                })
+                .await;
+            match body {
+                IncrementalBody::Json(json, is_ok) => (json, is_ok),
+                IncrementalBody::Multipart(chunks) => {
+                    return Ok(HttpResponse::Ok()
+                        .content_type(incremental::CONTENT_TYPE)
+                        .streaming(chunks.map(|chunk| Ok::<_, Error>(web::Bytes::from(chunk)))))
+                }
+            }
+        }
+        mut gql_req => {
+            gql_req.load_persisted_queries(&*root_node);
+            let gql_batch_response = gql_req.execute(&*root_node, &context).await;
+            (
+                serde_json::to_string(&gql_batch_response)?,
+                gql_batch_response.is_ok(),
+            )
+        }
+    };
+    let mut response = match is_ok {
+        true => HttpResponse::Ok(),
+        false => HttpResponse::BadRequest(),
+    };
+    Ok(response.content_type("application/json").body(gql_response))
+}
+
+fn get_request<S>(req: &HttpRequest) -> Result<GraphQLRequest<S>, Error>
+where
+    S: ScalarValue,
+{
+    let GetGraphQLRequest {
+        query,
+        operation_name,
+        variables,
+        extensions,
+    } = web::Query::<GetGraphQLRequest>::from_query(req.query_string())?.into_inner();
+    let variables = variables
+        .map(|variables| serde_json::from_str(&variables))
+        .transpose()
+        .map_err(ErrorBadRequest)?;
+    let req = GraphQLRequest::new(query, operation_name, variables);
+    Ok(match extensions {
+        Some(extensions) => {
+            req.with_extensions(serde_json::from_str(&extensions).map_err(ErrorBadRequest)?)
+        }
+        None => req,
+    })
+}
+
+async fn post_request<S>(
+    req: &HttpRequest,
+    payload: web::Payload,
+) -> Result<GraphQLBatchRequest<S>, Error>
+where
+    S: ScalarValue,
 {
     let content_type_header = req
         .headers()
         .get(CONTENT_TYPE)
         .and_then(|hv| hv.to_str().ok());
-    let req = match content_type_header {
+    match content_type_header {
         Some("application/json") => {
-            let body = String::from_request(&req, &mut payload.into_inner()).await?;
+            let body = String::from_request(req, &mut payload.into_inner()).await?;
             serde_json::from_str::<GraphQLBatchRequest<S>>(&body).map_err(ErrorBadRequest)
         }
         Some("application/graphql") => {
-            let body = String::from_request(&req, &mut payload.into_inner()).await?;
+            let body = String::from_request(req, &mut payload.into_inner()).await?;
             Ok(GraphQLBatchRequest::Single(GraphQLRequest::new(
                 body, None, None,
             )))
         }
+        Some(content_type) if content_type.starts_with("multipart/form-data") => {
+            multipart_request(req, payload).await
+        }
         _ => Err(ErrorUnsupportedMediaType(
-            "GraphQL requests should have content type `application/json` or `application/graphql`",
+            "GraphQL requests should have content type `application/json`, `application/graphql` \
+             or `multipart/form-data`",
         )),
-    }?;
-    let gql_batch_response = req.execute(schema, context).await;
-    let gql_response = serde_json::to_string(&gql_batch_response)?;
-    let mut response = match gql_batch_response.is_ok() {
-        true => HttpResponse::Ok(),
-        false => HttpResponse::BadRequest(),
-    };
-    Ok(response.content_type("application/json").body(gql_response))
+    }
+}
+
+async fn multipart_request<S>(
+    req: &HttpRequest,
+    payload: web::Payload,
+) -> Result<GraphQLBatchRequest<S>, Error>
+where
+    S: ScalarValue,
+{
+    let mut multipart = Multipart::new(req.headers(), payload.into_inner());
+    let mut operations = None;
+    let mut map = None;
+    let mut files = HashMap::new();
+
+    while let Some(mut field) = multipart.try_next().await? {
+        let disposition = field.content_disposition();
+        let name = match disposition.as_ref().and_then(|d| d.get_name()) {
+            Some(name) => name.to_owned(),
+            None => continue,
+        };
+        let filename = disposition
+            .as_ref()
+            .and_then(|d| d.get_filename())
+            .map(str::to_owned);
+        let content_type = field.content_type().to_string();
+        let mut content = Vec::new();
+        while let Some(chunk) = field.try_next().await? {
+            content.extend_from_slice(&chunk);
+        }
+
+        match name.as_str() {
+            "operations" => {
+                operations = Some(serde_json::from_slice(&content).map_err(ErrorBadRequest)?)
+            }
+            "map" => map = Some(serde_json::from_slice(&content).map_err(ErrorBadRequest)?),
+            _ => {
+                files.insert(
+                    name,
+                    UploadedFile::new(filename, Some(content_type), content),
+                );
+            }
+        }
+    }
+
+    let mut req: GraphQLBatchRequest<S> = operations
+        .ok_or_else(|| ErrorBadRequest("Missing `operations` field in multipart request"))?;
+    req.attach_uploads(map.unwrap_or_default(), files)
+        .map_err(ErrorBadRequest)?;
+    Ok(req)
 }
 
 /// Create a handler that replies with an HTML page containing GraphiQL. This does not handle routing, so you can mount it on any endpoint
@@ -222,12 +375,16 @@ pub async fn playground_handler(
 /// [1]: https://github.com/apollographql/subscriptions-transport-ws/blob/master/PROTOCOL.md
 #[cfg(feature = "subscriptions")]
 pub mod subscriptions {
-    use std::{fmt, sync::Arc};
+    use std::{convert::Infallible, fmt, sync::Arc};
 
     use actix::{prelude::*, Actor, StreamHandler};
     use actix_web::{
-        http::header::{HeaderName, HeaderValue},
-        web, HttpRequest, HttpResponse,
+        error::{ErrorBadRequest, ErrorMethodNotAllowed},
+        http::{
+            header::{HeaderName, HeaderValue},
+            Method,
+        },
+        web, FromRequest, HttpRequest, HttpResponse,
     };
     use actix_web_actors::ws;
 
@@ -236,13 +393,22 @@ pub mod subscriptions {
     use juniper::{
         futures::{
             stream::{SplitSink, SplitStream, StreamExt},
-            SinkExt,
+            Sink, SinkExt, Stream,
         },
+        http::GraphQLRequest,
         GraphQLSubscriptionType, GraphQLTypeAsync, RootNode, ScalarValue,
     };
-    use juniper_graphql_ws::{ArcSchema, ClientMessage, Connection, Init, ServerMessage};
+    use juniper_graphql_ws::{
+        graphql_transport_ws, ArcSchema, ClientMessage, Connection, Init, Protocol, ServerMessage,
+    };
+    use juniper_subscriptions::sse;
 
-    /// Serves the graphql-ws protocol over a WebSocket connection.
+    use super::get_request;
+
+    /// Serves the graphql-ws or the graphql-transport-ws protocol over a WebSocket connection.
+    ///
+    /// The protocol is picked from the `Sec-WebSocket-Protocol` header of the request, falling
+    /// back to graphql-ws if the header is missing or lists no supported protocol.
     ///
     /// The `init` argument is used to provide the context and additional configuration for
     /// connections. This can be a `juniper_graphql_ws::ConnectionConfig` if the context and
@@ -266,37 +432,40 @@ pub mod subscriptions {
         S: ScalarValue + Send + Sync + 'static,
         I: Init<S, CtxT> + Send,
     {
-        let (s_tx, s_rx) = Connection::new(ArcSchema(root_node), init).split::<Message>();
+        let protocol = Protocol::from_header(
+            req.headers()
+                .get("sec-websocket-protocol")
+                .and_then(|header| header.to_str().ok()),
+        );
 
-        let mut resp = ws::start(
-            SubscriptionActor {
-                graphql_tx: Arc::new(Mutex::new(s_tx)),
-                graphql_rx: Arc::new(Mutex::new(s_rx)),
-            },
-            &req,
-            stream,
-        )?;
+        let schema = ArcSchema(root_node);
+        let mut resp = match protocol {
+            Protocol::GraphQLWs => start(Connection::new(schema, init), &req, stream),
+            Protocol::GraphQLTransportWs => start(
+                graphql_transport_ws::Connection::new(schema, init),
+                &req,
+                stream,
+            ),
+        }?;
 
         resp.headers_mut().insert(
             HeaderName::from_static("sec-websocket-protocol"),
-            HeaderValue::from_static("graphql-ws"),
+            HeaderValue::from_static(protocol.as_str()),
         );
 
         Ok(resp)
     }
 
-    type ConnectionSplitSink<Query, Mutation, Subscription, CtxT, S, I> = Arc<
-        Mutex<SplitSink<Connection<ArcSchema<Query, Mutation, Subscription, CtxT, S>, I>, Message>>,
-    >;
-
-    type ConnectionSplitStream<Query, Mutation, Subscription, CtxT, S, I> =
-        Arc<Mutex<SplitStream<Connection<ArcSchema<Query, Mutation, Subscription, CtxT, S>, I>>>>;
-
-    /// Subscription Actor
-    /// coordinates messages between actix_web and juniper_graphql_ws
-    /// ws message -> actor -> juniper
-    /// juniper -> actor -> ws response
-    struct SubscriptionActor<Query, Mutation, Subscription, CtxT, S, I>
+    /// Serves the subscription of a GET or POST request over Server-Sent Events, for clients
+    /// which can't open WebSocket connections.
+    ///
+    /// Every response of the subscription is sent as an event of a `text/event-stream` response.
+    pub async fn sse_handler<Query, Mutation, Subscription, CtxT, S>(
+        req: HttpRequest,
+        payload: web::Payload,
+        root_node: Arc<RootNode<'static, Query, Mutation, Subscription, S>>,
+        context: CtxT,
+    ) -> Result<HttpResponse, actix_web::Error>
     where
         Query: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
         Query::TypeInfo: Send + Sync,
@@ -304,39 +473,92 @@ pub mod subscriptions {
         Mutation::TypeInfo: Send + Sync,
         Subscription: GraphQLSubscriptionType<S, Context = CtxT> + Send + 'static,
         Subscription::TypeInfo: Send + Sync,
-        CtxT: Unpin + Send + Sync + 'static,
+        CtxT: Send + Sync + 'static,
         S: ScalarValue + Send + Sync + 'static,
-        I: Init<S, CtxT> + Send,
     {
-        graphql_tx: ConnectionSplitSink<Query, Mutation, Subscription, CtxT, S, I>,
-        graphql_rx: ConnectionSplitStream<Query, Mutation, Subscription, CtxT, S, I>,
+        let mut gql_req = match *req.method() {
+            Method::GET => get_request(&req)?,
+            Method::POST => {
+                let body = String::from_request(&req, &mut payload.into_inner()).await?;
+                serde_json::from_str::<GraphQLRequest<S>>(&body).map_err(ErrorBadRequest)?
+            }
+            _ => {
+                return Err(ErrorMethodNotAllowed(
+                    "GraphQL subscriptions can only be sent with GET or POST",
+                ))
+            }
+        };
+        gql_req.load_persisted_query(&*root_node);
+
+        let events = sse::serve(gql_req, root_node, Arc::new(context), |response| {
+            serde_json::to_string(response).unwrap()
+        })
+        .await;
+
+        Ok(match events {
+            Ok(events) => HttpResponse::Ok()
+                .content_type(sse::CONTENT_TYPE)
+                .header("cache-control", "no-cache")
+                .streaming(events.map(|event| Ok::<_, actix_web::Error>(web::Bytes::from(event)))),
+            Err(json) => HttpResponse::BadRequest()
+                .content_type("application/json")
+                .body(json),
+        })
+    }
+
+    fn start<C>(
+        connection: C,
+        req: &HttpRequest,
+        stream: web::Payload,
+    ) -> Result<HttpResponse, actix_web::Error>
+    where
+        C: Sink<Message, Error = Infallible> + Stream + 'static,
+        C::Item: OutgoingMessage,
+    {
+        let (s_tx, s_rx) = connection.split::<Message>();
+
+        ws::start(
+            SubscriptionActor {
+                graphql_tx: Arc::new(Mutex::new(s_tx)),
+                graphql_rx: Arc::new(Mutex::new(s_rx)),
+            },
+            req,
+            stream,
+        )
     }
 
+    /// Subscription Actor
+    /// coordinates messages between actix_web and juniper_graphql_ws
     /// ws message -> actor -> juniper
-    impl<Query, Mutation, Subscription, CtxT, S, I>
-        StreamHandler<Result<ws::Message, ws::ProtocolError>>
-        for SubscriptionActor<Query, Mutation, Subscription, CtxT, S, I>
+    /// juniper -> actor -> ws response
+    struct SubscriptionActor<C>
     where
-        Query: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
-        Query::TypeInfo: Send + Sync,
-        Mutation: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
-        Mutation::TypeInfo: Send + Sync,
-        Subscription: GraphQLSubscriptionType<S, Context = CtxT> + Send + 'static,
-        Subscription::TypeInfo: Send + Sync,
-        CtxT: Unpin + Send + Sync + 'static,
-        S: ScalarValue + Send + Sync + 'static,
-        I: Init<S, CtxT> + Send,
+        C: Sink<Message, Error = Infallible> + Stream + 'static,
+        C::Item: OutgoingMessage,
     {
-        fn handle(&mut self, msg: Result<ws::Message, ws::ProtocolError>, ctx: &mut Self::Context) {
-            let msg = msg.map(|r| Message(r));
+        graphql_tx: Arc<Mutex<SplitSink<C, Message>>>,
+        graphql_rx: Arc<Mutex<SplitStream<C>>>,
+    }
 
+    /// ws message -> actor -> juniper
+    impl<C> StreamHandler<Result<ws::Message, ws::ProtocolError>> for SubscriptionActor<C>
+    where
+        C: Sink<Message, Error = Infallible> + Stream + 'static,
+        C::Item: OutgoingMessage,
+    {
+        fn handle(&mut self, msg: Result<ws::Message, ws::ProtocolError>, ctx: &mut Self::Context) {
             match msg {
+                Ok(ws::Message::Close(reason)) => {
+                    // Dropping the connection stops all of its operations.
+                    ctx.close(reason);
+                    ctx.stop();
+                }
                 Ok(msg) => {
                     let tx = self.graphql_tx.clone();
 
                     async move {
                         let mut tx = tx.lock().await;
-                        tx.send(msg)
+                        tx.send(Message(msg))
                             .await
                             .expect("Infallible: this should not happen");
                     }
@@ -352,18 +574,10 @@ pub mod subscriptions {
     }
 
     /// juniper -> actor
-    impl<Query, Mutation, Subscription, CtxT, S, I> Actor
-        for SubscriptionActor<Query, Mutation, Subscription, CtxT, S, I>
+    impl<C> Actor for SubscriptionActor<C>
     where
-        Query: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
-        Query::TypeInfo: Send + Sync,
-        Mutation: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
-        Mutation::TypeInfo: Send + Sync,
-        Subscription: GraphQLSubscriptionType<S, Context = CtxT> + Send + 'static,
-        Subscription::TypeInfo: Send + Sync,
-        CtxT: Unpin + Send + Sync + 'static,
-        S: ScalarValue + Send + Sync + 'static,
-        I: Init<S, CtxT> + Send,
+        C: Sink<Message, Error = Infallible> + Stream + 'static,
+        C::Item: OutgoingMessage,
     {
         type Context = ws::WebsocketContext<Self>;
 
@@ -390,40 +604,26 @@ pub mod subscriptions {
     }
 
     /// actor -> websocket response
-    impl<Query, Mutation, Subscription, CtxT, S, I> Handler<ServerMessageWrapper<S>>
-        for SubscriptionActor<Query, Mutation, Subscription, CtxT, S, I>
+    impl<C> Handler<ServerMessageWrapper<C::Item>> for SubscriptionActor<C>
     where
-        Query: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
-        Query::TypeInfo: Send + Sync,
-        Mutation: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
-        Mutation::TypeInfo: Send + Sync,
-        Subscription: GraphQLSubscriptionType<S, Context = CtxT> + Send + 'static,
-        Subscription::TypeInfo: Send + Sync,
-        CtxT: Unpin + Send + Sync + 'static,
-        S: ScalarValue + Send + Sync + 'static,
-        I: Init<S, CtxT> + Send,
+        C: Sink<Message, Error = Infallible> + Stream + 'static,
+        C::Item: OutgoingMessage,
     {
         type Result = ();
 
         fn handle(
             &mut self,
-            msg: ServerMessageWrapper<S>,
+            msg: ServerMessageWrapper<C::Item>,
             ctx: &mut ws::WebsocketContext<Self>,
         ) -> Self::Result {
-            let msg = serde_json::to_string(&msg.message);
-
-            match msg {
+            match msg.message.into_text() {
                 Ok(msg) => {
                     ctx.text(msg);
                 }
-                Err(e) => {
-                    let reason = ws::CloseReason {
-                        code: ws::CloseCode::Error,
-                        description: Some(format!("error serializing response: {}", e)),
-                    };
-
+                Err(reason) => {
                     // TODO: trace
                     ctx.close(Some(reason));
+                    ctx.stop();
                 }
             }
         }
@@ -431,11 +631,48 @@ pub mod subscriptions {
 
     #[derive(Message)]
     #[rtype(result = "()")]
-    struct ServerMessageWrapper<S>
+    struct ServerMessageWrapper<M>
+    where
+        M: OutgoingMessage,
+    {
+        message: M,
+    }
+
+    /// Messages sent to the client by the connection of a protocol
+    trait OutgoingMessage: Send + 'static {
+        /// Serializes the message, or returns the reason to close the WebSocket with instead.
+        fn into_text(self) -> Result<String, ws::CloseReason>;
+    }
+
+    impl<S> OutgoingMessage for ServerMessage<S>
     where
         S: ScalarValue + Send + Sync + 'static,
     {
-        message: ServerMessage<S>,
+        fn into_text(self) -> Result<String, ws::CloseReason> {
+            serde_json::to_string(&self).map_err(serialization_error)
+        }
+    }
+
+    impl<S> OutgoingMessage for graphql_transport_ws::Output<S>
+    where
+        S: ScalarValue + Send + Sync + 'static,
+    {
+        fn into_text(self) -> Result<String, ws::CloseReason> {
+            match self {
+                Self::Message(msg) => serde_json::to_string(&msg).map_err(serialization_error),
+                Self::Close { code, message } => Err(ws::CloseReason {
+                    code: code.into(),
+                    description: Some(message),
+                }),
+            }
+        }
+    }
+
+    fn serialization_error(e: serde_json::Error) -> ws::CloseReason {
+        ws::CloseReason {
+            code: ws::CloseCode::Error,
+            description: Some(format!("error serializing response: {}", e)),
+        }
     }
 
     #[derive(Debug)]
@@ -455,6 +692,19 @@ pub mod subscriptions {
         }
     }
 
+    impl<S: ScalarValue> std::convert::TryFrom<Message> for graphql_transport_ws::ClientMessage<S> {
+        type Error = Error;
+
+        fn try_from(msg: Message) -> Result<Self, Self::Error> {
+            match msg.0 {
+                ws::Message::Text(text) => {
+                    serde_json::from_slice(text.as_bytes()).map_err(|e| Error::Serde(e))
+                }
+                _ => Err(Error::UnexpectedClientMessage),
+            }
+        }
+    }
+
     /// Errors that can happen while handling client messages
     #[derive(Debug)]
     enum Error {
@@ -484,10 +734,14 @@ mod tests {
     use actix_web::{dev::ServiceResponse, http, http::header::CONTENT_TYPE, test, App};
     use juniper::{
         futures::stream::StreamExt,
-        http::tests::{run_http_test_suite, HttpIntegration, TestResponse},
+        http::{
+            persisted_query::InMemoryPersistedQueryStore,
+            tests::{run_http_test_suite, HttpIntegration, TestResponse},
+        },
         tests::fixtures::starwars::schema::{Database, Query},
         EmptyMutation, EmptySubscription, RootNode,
     };
+    use std::sync::Arc;
 
     use super::*;
 
@@ -661,6 +915,30 @@ mod tests {
         );
     }
 
+    #[actix_web::rt::test]
+    async fn graphql_get_rejects_malformed_parameters() {
+        let schema: Schema = RootNode::new(
+            Query,
+            EmptyMutation::<Database>::new(),
+            EmptySubscription::<Database>::new(),
+        );
+        let mut app =
+            test::init_service(App::new().data(schema).route("/", web::get().to(index))).await;
+
+        for parameter in &["variables", "extensions"] {
+            let req = test::TestRequest::get()
+                .uri(&format!(
+                    "/?query=%7B%20hero%20%7B%20name%20%7D%20%7D&{}=%7B",
+                    parameter
+                ))
+                .to_request();
+
+            let resp = test::call_service(&mut app, req).await;
+
+            assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+        }
+    }
+
     #[actix_web::rt::test]
     async fn batch_request_works() {
         use juniper::{
@@ -709,16 +987,26 @@ mod tests {
         assert!(result.is_err());
     }
 
-    pub struct TestActixWebIntegration;
+    pub struct TestActixWebIntegration {
+        persisted_queries: Arc<InMemoryPersistedQueryStore>,
+    }
 
     impl TestActixWebIntegration {
+        fn new() -> Self {
+            Self {
+                persisted_queries: Arc::new(InMemoryPersistedQueryStore::new(10)),
+            }
+        }
+
         fn make_request(&self, req: test::TestRequest) -> TestResponse {
+            let persisted_queries = self.persisted_queries.clone();
             actix_web::rt::System::new("request").block_on(async move {
                 let schema = Schema::new(
                     Query,
                     EmptyMutation::<Database>::new(),
                     EmptySubscription::<Database>::new(),
-                );
+                )
+                .with_persisted_queries(persisted_queries);
 
                 let mut app =
                     test::init_service(App::new().data(schema).route("/", web::to(index))).await;
@@ -766,7 +1054,7 @@ mod tests {
 
     #[test]
     fn test_actix_web_integration() {
-        run_http_test_suite(&TestActixWebIntegration);
+        run_http_test_suite(&TestActixWebIntegration::new());
     }
 }
 
@@ -88,12 +88,27 @@ pub fn impl_enum(ast: syn::DeriveInput, error: GraphQLScope) -> syn::Result<Toke
                 );
             }
 
+            if let Some(complexity) = field_attrs.complexity {
+                error.unsupported_attribute_within(
+                    complexity.span_ident(),
+                    UnsupportedAttribute::Complexity,
+                );
+            }
+
+            if let Some(guard) = field_attrs.guards.first() {
+                error.unsupported_attribute_within(guard.span_ident(), UnsupportedAttribute::Guard);
+            }
+
             Some(util::GraphQLTypeDefinitionField {
                 name,
                 _type,
                 args: Vec::new(),
                 description: field_attrs.description.map(SpanContainer::into_inner),
                 deprecation: field_attrs.deprecation.map(SpanContainer::into_inner),
+                directives: field_attrs.directives,
+                complexity: None,
+                guards: Vec::new(),
+                visibility: field_attrs.visibility.map(SpanContainer::into_inner),
                 resolver_code,
                 is_type_inferred: true,
                 is_async: false,
@@ -140,6 +155,8 @@ pub fn impl_enum(ast: syn::DeriveInput, error: GraphQLScope) -> syn::Result<Toke
         context: attrs.context.map(SpanContainer::into_inner),
         scalar: None,
         description: attrs.description.map(SpanContainer::into_inner),
+        directives: attrs.directives,
+        visibility: attrs.visibility.map(SpanContainer::into_inner),
         fields,
         // NOTICE: only unit variants allow -> no generics possible
         generics: syn::Generics::default(),
@@ -147,6 +164,7 @@ pub fn impl_enum(ast: syn::DeriveInput, error: GraphQLScope) -> syn::Result<Toke
         include_type_generics: true,
         generic_scalar: true,
         no_async: attrs.no_async.is_some(),
+        is_one_of: false,
     };
 
     Ok(definition.into_enum_tokens())
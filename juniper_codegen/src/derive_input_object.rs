@@ -9,16 +9,45 @@ use syn::{self, ext::IdentExt, spanned::Spanned, Data, Fields};
 
 pub fn impl_input_object(ast: syn::DeriveInput, error: GraphQLScope) -> syn::Result<TokenStream> {
     let ast_span = ast.span();
-    let fields = match ast.data {
+    let (fields, is_one_of) = match ast.data {
         Data::Struct(data) => match data.fields {
-            Fields::Named(named) => named.named,
+            Fields::Named(named) => (named.named.into_iter().collect::<Vec<_>>(), false),
             _ => {
                 return Err(
                     error.custom_error(ast_span, "all fields must be named, e.g., `test: String`")
                 )
             }
         },
-        _ => return Err(error.custom_error(ast_span, "can only be used on structs with fields")),
+        // Every variant of an enum is an input field of a OneOf input object, named after the
+        // variant, with the type the variant wraps.
+        Data::Enum(data) => {
+            let fields = data
+                .variants
+                .into_iter()
+                .filter_map(|variant| match variant.fields {
+                    Fields::Unnamed(unnamed) if unnamed.unnamed.len() == 1 => {
+                        let mut field = unnamed.unnamed.into_iter().next().unwrap();
+                        field.ident = Some(variant.ident);
+                        field.attrs = variant.attrs;
+                        Some(field)
+                    }
+                    fields => {
+                        error.emit_custom(
+                            fields.span(),
+                            "all variants must wrap exactly one input type, e.g., `Id(String)`",
+                        );
+                        None
+                    }
+                })
+                .collect::<Vec<_>>();
+            (fields, true)
+        }
+        _ => {
+            return Err(error.custom_error(
+                ast_span,
+                "can only be used on structs with fields or on enums",
+            ))
+        }
     };
 
     // Parse attributes.
@@ -50,10 +79,13 @@ pub fn impl_input_object(ast: syn::DeriveInput, error: GraphQLScope) -> syn::Res
             let field_ident = field.ident.as_ref().unwrap();
             let name = match field_attrs.name {
                 Some(ref name) => name.to_string(),
-                None => attrs
-                    .rename
-                    .unwrap_or(RenameRule::CamelCase)
-                    .apply(&field_ident.unraw().to_string()),
+                None => match attrs.rename.unwrap_or(RenameRule::CamelCase) {
+                    // Variants are in `PascalCase`, so they're split into words first.
+                    RenameRule::CamelCase if is_one_of => util::to_camel_case(
+                        &util::to_upper_snake_case(&field_ident.unraw().to_string()).to_lowercase(),
+                    ),
+                    rule => rule.apply(&field_ident.unraw().to_string()),
+                },
             };
 
             if let Some(span) = field_attrs.skip {
@@ -75,6 +107,33 @@ pub fn impl_input_object(ast: syn::DeriveInput, error: GraphQLScope) -> syn::Res
                 });
             }
 
+            if let Some(complexity) = field_attrs.complexity {
+                error.unsupported_attribute_within(
+                    complexity.span_ident(),
+                    UnsupportedAttribute::Complexity,
+                );
+            }
+
+            if let Some(guard) = field_attrs.guards.first() {
+                error.unsupported_attribute_within(guard.span_ident(), UnsupportedAttribute::Guard);
+            }
+
+            if let Some(visibility) = field_attrs.visibility {
+                error.unsupported_attribute_within(
+                    visibility.span_ident(),
+                    UnsupportedAttribute::Visibility,
+                );
+            }
+
+            if is_one_of {
+                if let Some(ref default) = field_attrs.default {
+                    error.unsupported_attribute_within(
+                        default.span_ident(),
+                        UnsupportedAttribute::Default,
+                    );
+                }
+            }
+
             let resolver_code = quote!(#field_ident);
 
             let default = field_attrs
@@ -90,6 +149,10 @@ pub fn impl_input_object(ast: syn::DeriveInput, error: GraphQLScope) -> syn::Res
                 args: Vec::new(),
                 description: field_attrs.description.map(SpanContainer::into_inner),
                 deprecation: None,
+                directives: field_attrs.directives,
+                complexity: None,
+                guards: Vec::new(),
+                visibility: None,
                 resolver_code,
                 is_type_inferred: true,
                 is_async: false,
@@ -139,12 +202,15 @@ pub fn impl_input_object(ast: syn::DeriveInput, error: GraphQLScope) -> syn::Res
         context: attrs.context.map(SpanContainer::into_inner),
         scalar: attrs.scalar.map(SpanContainer::into_inner),
         description: attrs.description.map(SpanContainer::into_inner),
+        directives: attrs.directives,
+        visibility: attrs.visibility.map(SpanContainer::into_inner),
         fields,
         generics: ast.generics,
         interfaces: vec![],
         include_type_generics: true,
         generic_scalar: true,
         no_async: attrs.no_async.is_some(),
+        is_one_of,
     };
 
     Ok(definition.into_input_object_tokens())
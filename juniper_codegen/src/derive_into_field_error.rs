@@ -0,0 +1,342 @@
+//! Code generation for `#[derive(IntoFieldError)]` macro.
+
+use std::collections::HashMap;
+
+use proc_macro2::{Group, Span, TokenStream, TokenTree};
+use quote::{format_ident, quote};
+use syn::{
+    ext::IdentExt as _,
+    parse::{Parse, ParseStream},
+    parse_quote,
+    spanned::Spanned as _,
+    token, Data, Fields,
+};
+
+use crate::{
+    common::parse::{
+        attr::{err, OptionExt as _},
+        ParseBufferExt as _,
+    },
+    result::GraphQLScope,
+    util::{filter_attrs, span_container::SpanContainer},
+};
+
+/// [`GraphQLScope`] of errors for `#[derive(IntoFieldError)]` macro.
+const ERR: GraphQLScope = GraphQLScope::DeriveIntoFieldError;
+
+/// Available metadata (arguments) behind `#[graphql]` attribute when generating code for a
+/// [GraphQL error][1].
+///
+/// [1]: https://spec.graphql.org/June2018/#sec-Errors
+#[derive(Debug, Default)]
+struct ErrorMeta {
+    /// Explicitly specified message of the error sent to clients.
+    ///
+    /// If absent, then the [`Display`](std::fmt::Display) implementation of the Rust type is used.
+    pub message: Option<SpanContainer<syn::LitStr>>,
+
+    /// Explicitly specified `code` of the error, put into its `extensions`.
+    pub code: Option<SpanContainer<syn::LitStr>>,
+
+    /// Explicitly specified `extensions` of the error, in the order of their declaration.
+    ///
+    /// Expressions may refer to the fields of the variant as `self.<field>`.
+    pub extensions: Vec<(SpanContainer<String>, syn::Expr)>,
+
+    /// Explicitly specified marker for the error not being meant for clients, so it's masked
+    /// by an `ErrorPolicy`.
+    pub internal: Option<SpanContainer<syn::Ident>>,
+}
+
+impl Parse for ErrorMeta {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut output = Self::default();
+
+        while !input.is_empty() {
+            let ident = input.parse_any_ident()?;
+            match ident.to_string().as_str() {
+                "message" => {
+                    input.parse::<token::Eq>()?;
+                    let msg = input.parse::<syn::LitStr>()?;
+                    output
+                        .message
+                        .replace(SpanContainer::new(ident.span(), Some(msg.span()), msg))
+                        .none_or_else(|_| err::dup_arg(&ident))?
+                }
+                "code" => {
+                    input.parse::<token::Eq>()?;
+                    let code = input.parse::<syn::LitStr>()?;
+                    output
+                        .code
+                        .replace(SpanContainer::new(ident.span(), Some(code.span()), code))
+                        .none_or_else(|_| err::dup_arg(&ident))?
+                }
+                "extensions" => {
+                    let content;
+                    syn::parenthesized!(content in input);
+                    while !content.is_empty() {
+                        let key = content.parse_any_ident()?;
+                        content.parse::<token::Eq>()?;
+                        let value = content.parse::<syn::Expr>()?;
+                        let name = key.unraw().to_string();
+                        if name == "code"
+                            || output.extensions.iter().any(|(k, _)| k.as_str() == name)
+                        {
+                            return Err(err::dup_arg(&key));
+                        }
+                        output.extensions.push((
+                            SpanContainer::new(key.span(), Some(value.span()), name),
+                            value,
+                        ));
+                        content.try_parse::<token::Comma>()?;
+                    }
+                }
+                "internal" => output
+                    .internal
+                    .replace(SpanContainer::new(ident.span(), None, ident.clone()))
+                    .none_or_else(|_| err::dup_arg(&ident))?,
+                name => {
+                    return Err(err::unknown_arg(&ident, name));
+                }
+            }
+            input.try_parse::<token::Comma>()?;
+        }
+
+        Ok(output)
+    }
+}
+
+impl ErrorMeta {
+    /// Tries to merge two [`ErrorMeta`]s into a single one, reporting about duplicates, if any.
+    fn try_merge(self, mut another: Self) -> syn::Result<Self> {
+        let mut extensions = self.extensions;
+        for (key, value) in another.extensions {
+            if extensions.iter().any(|(k, _)| k.as_str() == key.as_str()) {
+                return Err(err::dup_arg(key.span_ident()));
+            }
+            extensions.push((key, value));
+        }
+        Ok(Self {
+            message: try_merge_opt!(message: self, another),
+            code: try_merge_opt!(code: self, another),
+            extensions,
+            internal: try_merge_opt!(internal: self, another),
+        })
+    }
+
+    /// Parses [`ErrorMeta`] from the given multiple `name`d [`syn::Attribute`]s placed on a type
+    /// or a variant definition.
+    pub fn from_attrs(name: &str, attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        filter_attrs(name, attrs)
+            .map(|attr| attr.parse_args())
+            .try_fold(Self::default(), |prev, curr| prev.try_merge(curr?))
+    }
+}
+
+/// Expands `#[derive(IntoFieldError)]` macro into generated code.
+pub fn expand(input: TokenStream) -> syn::Result<TokenStream> {
+    let ast = syn::parse2::<syn::DeriveInput>(input)?;
+    let meta = ErrorMeta::from_attrs("graphql", &ast.attrs)?;
+
+    let arms = match &ast.data {
+        Data::Enum(data) => {
+            if let Some(message) = &meta.message {
+                return Err(ERR.custom_error(
+                    message.span_ident(),
+                    "message can only be specified on enum variants",
+                ));
+            }
+            if let Some((key, _)) = meta.extensions.first() {
+                return Err(ERR.custom_error(
+                    key.span_ident(),
+                    "extensions can only be specified on enum variants",
+                ));
+            }
+
+            data.variants
+                .iter()
+                .map(|var| {
+                    let var_meta = ErrorMeta::from_attrs("graphql", &var.attrs)?;
+                    let var_ident = &var.ident;
+                    expand_arm(quote! { Self::#var_ident }, &var.fields, var_meta, &meta)
+                })
+                .collect::<syn::Result<Vec<_>>>()?
+        }
+        Data::Struct(data) => {
+            vec![expand_arm(
+                quote! { Self },
+                &data.fields,
+                meta,
+                &ErrorMeta::default(),
+            )?]
+        }
+        Data::Union(_) => {
+            return Err(ERR.custom_error(ast.span(), "can only be derived for enums and structs"))
+        }
+    };
+
+    let ty = &ast.ident;
+    let (_, ty_generics, _) = ast.generics.split_for_impl();
+    let mut generics = ast.generics.clone();
+    generics.params.push(parse_quote! { __S });
+    generics
+        .make_where_clause()
+        .predicates
+        .push(parse_quote! { __S: ::juniper::ScalarValue });
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        #[automatically_derived]
+        impl#impl_generics ::juniper::IntoFieldError<__S> for #ty#ty_generics #where_clause {
+            fn into_field_error(self) -> ::juniper::FieldError<__S> {
+                match &self {
+                    #( #arms )*
+                }
+            }
+        }
+    })
+}
+
+/// Expands a match arm converting the variant matched by the `path` pattern into a
+/// `juniper::FieldError`.
+///
+/// The attributes of the variant are completed with the ones of the whole type, given as
+/// `defaults`.
+fn expand_arm(
+    path: TokenStream,
+    fields: &Fields,
+    meta: ErrorMeta,
+    defaults: &ErrorMeta,
+) -> syn::Result<TokenStream> {
+    let mut bindings = HashMap::new();
+    let pattern = match fields {
+        Fields::Unit => path,
+        Fields::Unnamed(fields) => {
+            let idents = (0..fields.unnamed.len()).map(|i| {
+                let binding = format_ident!("__self_{}", i);
+                bindings.insert(i.to_string(), binding.clone());
+                binding
+            });
+            let idents: Vec<_> = idents.collect();
+            quote! { #path(#( #idents ),*) }
+        }
+        Fields::Named(fields) => {
+            let fields = fields.named.iter().map(|f| {
+                let ident = f.ident.as_ref().unwrap();
+                let name = ident.unraw().to_string();
+                let binding = format_ident!("__self_{}", name);
+                bindings.insert(name, binding.clone());
+                quote! { #ident: #binding }
+            });
+            let fields: Vec<_> = fields.collect();
+            quote! { #path { #( #fields ),* } }
+        }
+    };
+
+    let message = match &meta.message {
+        Some(message) => quote! { #message },
+        None => quote! { &self },
+    };
+
+    let code = meta.code.as_ref().or(defaults.code.as_ref());
+    let extensions = if code.is_none() && meta.extensions.is_empty() {
+        quote! { ::juniper::Value::null() }
+    } else {
+        let capacity = meta.extensions.len() + code.map_or(0, |_| 1);
+        let code = code.map(|code| {
+            quote! {
+                extensions.add_field("code", ::juniper::Value::from(#code));
+            }
+        });
+        let fields = meta
+            .extensions
+            .iter()
+            .map(|(key, value)| {
+                let key = key.as_str();
+                let value = replace_self_fields(value.span(), quote! { #value }, &bindings)?;
+                Ok(quote! {
+                    extensions.add_field(
+                        #key,
+                        ::juniper::Value::from(::std::clone::Clone::clone(&(#value))),
+                    );
+                })
+            })
+            .collect::<syn::Result<Vec<_>>>()?;
+        quote! {{
+            let mut extensions = ::juniper::Object::<__S>::with_capacity(#capacity);
+            #code
+            #( #fields )*
+            ::juniper::Value::Object(extensions)
+        }}
+    };
+
+    let user_facing = if meta.internal.is_some() || defaults.internal.is_some() {
+        None
+    } else {
+        Some(quote! { .user_facing() })
+    };
+
+    Ok(quote! {
+        #pattern => ::juniper::FieldError::new(#message, #extensions)#user_facing,
+    })
+}
+
+/// Replaces the `self.<field>` accesses in the given `tokens` with the bindings of the matched
+/// variant's fields, so the fields can be used in the `extensions` of the error.
+///
+/// Method calls on `self` are left untouched.
+fn replace_self_fields(
+    span: Span,
+    tokens: TokenStream,
+    bindings: &HashMap<String, syn::Ident>,
+) -> syn::Result<TokenStream> {
+    let tokens: Vec<_> = tokens.into_iter().collect();
+    let mut output = TokenStream::new();
+
+    let mut i = 0;
+    while i < tokens.len() {
+        let is_self = matches!(&tokens[i], TokenTree::Ident(ident) if ident == "self");
+        let is_dot = matches!(tokens.get(i + 1), Some(TokenTree::Punct(p)) if p.as_char() == '.');
+        let member = match tokens.get(i + 2) {
+            Some(TokenTree::Ident(ident)) => Some(ident.unraw().to_string()),
+            // `self.0.1` is lexed as `self`, `.` and `0.1`.
+            Some(TokenTree::Literal(lit)) => Some(lit.to_string()),
+            _ => None,
+        };
+        let is_call = match tokens.get(i + 3) {
+            Some(TokenTree::Group(g)) => g.delimiter() == proc_macro2::Delimiter::Parenthesis,
+            Some(TokenTree::Punct(p)) => p.as_char() == ':',
+            _ => false,
+        };
+
+        match member {
+            Some(member) if is_self && is_dot && !is_call => {
+                let mut parts = member.splitn(2, '.');
+                let field = parts.next().unwrap();
+                let binding = bindings.get(field).ok_or_else(|| {
+                    ERR.custom_error(span, format!("has no field `{}` to refer to", field))
+                })?;
+                output.extend(quote! { (*#binding) });
+                if let Some(rest) = parts.next() {
+                    let index = syn::Index::from(rest.parse::<usize>().unwrap());
+                    output.extend(quote! { .#index });
+                }
+                i += 3;
+            }
+            _ => {
+                match &tokens[i] {
+                    TokenTree::Group(group) => {
+                        let stream = replace_self_fields(span, group.stream(), bindings)?;
+                        let mut replaced = Group::new(group.delimiter(), stream);
+                        replaced.set_span(group.span());
+                        output.extend(Some(TokenTree::Group(replaced)));
+                    }
+                    tt => output.extend(Some(tt.clone())),
+                }
+                i += 1;
+            }
+        }
+    }
+
+    Ok(output)
+}
@@ -72,6 +72,20 @@ pub fn build_derive_object(ast: syn::DeriveInput, error: GraphQLScope) -> syn::R
                 );
             }
 
+            let complexity = field_attrs.complexity.map(SpanContainer::into_inner);
+            if let Some(name) = complexity
+                .as_ref()
+                .and_then(|c| c.multiplier_argument.as_ref())
+            {
+                error.emit_custom(
+                    name.span_joined(),
+                    format!(
+                        "field has no argument `{}` to use as complexity multiplier",
+                        name.inner(),
+                    ),
+                );
+            }
+
             let resolver_code = quote!(
                 &self . #field_name
             );
@@ -82,6 +96,14 @@ pub fn build_derive_object(ast: syn::DeriveInput, error: GraphQLScope) -> syn::R
                 args: Vec::new(),
                 description: field_attrs.description.map(SpanContainer::into_inner),
                 deprecation: field_attrs.deprecation.map(SpanContainer::into_inner),
+                directives: field_attrs.directives,
+                complexity,
+                guards: field_attrs
+                    .guards
+                    .into_iter()
+                    .map(SpanContainer::into_inner)
+                    .collect(),
+                visibility: field_attrs.visibility.map(SpanContainer::into_inner),
                 resolver_code,
                 default: None,
                 is_type_inferred: true,
@@ -121,6 +143,8 @@ pub fn build_derive_object(ast: syn::DeriveInput, error: GraphQLScope) -> syn::R
         context: attrs.context.map(SpanContainer::into_inner),
         scalar: attrs.scalar.map(SpanContainer::into_inner),
         description: attrs.description.map(SpanContainer::into_inner),
+        directives: attrs.directives,
+        visibility: attrs.visibility.map(SpanContainer::into_inner),
         fields,
         generics: ast.generics,
         interfaces: attrs
@@ -131,6 +155,7 @@ pub fn build_derive_object(ast: syn::DeriveInput, error: GraphQLScope) -> syn::R
         include_type_generics: true,
         generic_scalar: true,
         no_async: attrs.no_async.is_some(),
+        is_one_of: false,
     };
 
     Ok(definition.into_tokens())
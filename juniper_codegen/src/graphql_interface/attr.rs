@@ -210,6 +210,11 @@ pub fn expand_on_trait(
 
         fields,
         implementers,
+        interfaces: meta
+            .interfaces
+            .into_iter()
+            .map(SpanContainer::into_inner)
+            .collect(),
     };
 
     // Attach the `juniper::AsDynGraphQLValue` on top of the trait if dynamic dispatch is used.
@@ -498,6 +503,7 @@ impl TraitMethod {
             ty,
             description,
             deprecated,
+            guards: meta.guards.iter().map(|g| g.as_ref().clone()).collect(),
             method: method_ident.clone(),
             arguments,
             is_async: method.sig.asyncness.is_some(),
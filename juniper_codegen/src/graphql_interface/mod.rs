@@ -73,6 +73,12 @@ struct TraitMeta {
     /// [2]: https://spec.graphql.org/June2018/#sec-Objects
     implementers: HashSet<SpanContainer<syn::Type>>,
 
+    /// Explicitly specified Rust types of [GraphQL interfaces][1] implemented by this
+    /// [GraphQL interface][1] type.
+    ///
+    /// [1]: https://spec.graphql.org/October2021/#sec-Interfaces.Interfaces-Implementing-Interfaces
+    interfaces: HashSet<SpanContainer<syn::Type>>,
+
     /// Explicitly specified type of [`Context`] to use for resolving this [GraphQL interface][1]
     /// type with.
     ///
@@ -180,6 +186,18 @@ impl Parse for TraitMeta {
                             .none_or_else(|_| err::dup_arg(impler_span))?;
                     }
                 }
+                "impl" | "implements" | "interfaces" => {
+                    input.parse::<token::Eq>()?;
+                    for iface in input.parse_maybe_wrapped_and_punctuated::<
+                        syn::Type, token::Bracket, token::Comma,
+                    >()? {
+                        let iface_span = iface.span();
+                        output
+                            .interfaces
+                            .replace(SpanContainer::new(ident.span(), Some(iface_span), iface))
+                            .none_or_else(|_| err::dup_arg(iface_span))?;
+                    }
+                }
                 "dyn" => {
                     input.parse::<token::Eq>()?;
                     let alias = input.parse::<syn::Ident>()?;
@@ -237,6 +255,7 @@ impl TraitMeta {
             context: try_merge_opt!(context: self, another),
             scalar: try_merge_opt!(scalar: self, another),
             implementers: try_merge_hashset!(implementers: self, another => span_joined),
+            interfaces: try_merge_hashset!(interfaces: self, another => span_joined),
             r#dyn: try_merge_opt!(r#dyn: self, another),
             r#enum: try_merge_opt!(r#enum: self, another),
             asyncness: try_merge_opt!(asyncness: self, another),
@@ -396,6 +415,13 @@ struct MethodMeta {
     /// [2]: https://spec.graphql.org/June2018/#sec-Deprecation
     deprecated: Option<SpanContainer<Option<syn::LitStr>>>,
 
+    /// Explicitly specified [`Guard`]s checked before this [GraphQL field][1] is resolved, in the
+    /// order of their declaration.
+    ///
+    /// [`Guard`]: juniper::Guard
+    /// [1]: https://spec.graphql.org/June2018/#sec-Language.Fields
+    guards: Vec<SpanContainer<syn::Expr>>,
+
     /// Explicitly specified marker indicating that this trait method should be omitted by code
     /// generation and not considered in the [GraphQL interface][1] type definition.
     ///
@@ -452,6 +478,14 @@ impl Parse for MethodMeta {
                         ))
                         .none_or_else(|_| err::dup_arg(&ident))?
                 }
+                "guard" => {
+                    input.parse::<token::Eq>()?;
+                    let lit = input.parse::<syn::LitStr>()?;
+                    let guard = lit.parse::<syn::Expr>()?;
+                    output
+                        .guards
+                        .push(SpanContainer::new(ident.span(), Some(lit.span()), guard));
+                }
                 "ignore" | "skip" => output
                     .ignore
                     .replace(SpanContainer::new(ident.span(), None, ident.clone()))
@@ -478,6 +512,7 @@ impl MethodMeta {
             name: try_merge_opt!(name: self, another),
             description: try_merge_opt!(description: self, another),
             deprecated: try_merge_opt!(deprecated: self, another),
+            guards: self.guards.into_iter().chain(another.guards).collect(),
             ignore: try_merge_opt!(ignore: self, another),
             downcast: try_merge_opt!(downcast: self, another),
         })
@@ -494,6 +529,7 @@ impl MethodMeta {
             if meta.name.is_some()
                 || meta.description.is_some()
                 || meta.deprecated.is_some()
+                || !meta.guards.is_empty()
                 || meta.downcast.is_some()
             {
                 return Err(syn::Error::new(
@@ -507,6 +543,7 @@ impl MethodMeta {
             if meta.name.is_some()
                 || meta.description.is_some()
                 || meta.deprecated.is_some()
+                || !meta.guards.is_empty()
                 || meta.ignore.is_some()
             {
                 return Err(syn::Error::new(
@@ -751,6 +788,11 @@ struct Definition {
     ///
     /// [1]: https://spec.graphql.org/June2018/#sec-Interfaces
     implementers: Vec<Implementer>,
+
+    /// Rust types of the [GraphQL interfaces][1] implemented by this [GraphQL interface][1].
+    ///
+    /// [1]: https://spec.graphql.org/October2021/#sec-Interfaces.Interfaces-Implementing-Interfaces
+    interfaces: Vec<syn::Type>,
 }
 
 impl Definition {
@@ -799,6 +841,22 @@ impl Definition {
 
         let fields_meta = self.fields.iter().map(Field::method_meta_tokens);
 
+        let interfaces = if !self.interfaces.is_empty() {
+            let mut iface_tys: Vec<_> = self.interfaces.iter().collect();
+            iface_tys.sort_unstable_by(|a, b| {
+                let (a, b) = (quote!(#a).to_string(), quote!(#b).to_string());
+                a.cmp(&b)
+            });
+
+            Some(quote! {
+                .interfaces(&[
+                    #( registry.get_type::<#iface_tys>(info), )*
+                ])
+            })
+        } else {
+            None
+        };
+
         quote! {
             #[automatically_derived]
             impl#impl_generics ::juniper::GraphQLType<#scalar> for #ty #where_clause
@@ -821,6 +879,7 @@ impl Definition {
                     ];
                     registry.build_interface_type::<#ty>(info, &fields)
                         #description
+                        #interfaces
                         .into_meta()
                 }
             }
@@ -845,7 +904,7 @@ impl Definition {
         let fields_resolvers = self
             .fields
             .iter()
-            .filter_map(|f| f.method_resolve_field_tokens(&trait_ty));
+            .filter_map(|f| f.method_resolve_field_tokens(scalar, &trait_ty));
         let async_fields_panic = {
             let names = self
                 .fields
@@ -932,6 +991,49 @@ impl Definition {
         }
     }
 
+    /// Returns generated code implementing [`InterfaceGuards`] trait for this
+    /// [GraphQL interface][1].
+    ///
+    /// [`InterfaceGuards`]: juniper::InterfaceGuards
+    /// [1]: https://spec.graphql.org/June2018/#sec-Interfaces
+    #[must_use]
+    fn impl_interface_guards_tokens(&self) -> TokenStream {
+        let scalar = &self.scalar;
+
+        let generics = self.ty.impl_generics();
+        let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+        let ty = self.ty.ty_tokens();
+
+        let iface_tys = &self.interfaces;
+        let fields_checks = self
+            .fields
+            .iter()
+            .filter_map(Field::method_check_field_guards_tokens);
+
+        quote! {
+            #[automatically_derived]
+            impl#impl_generics ::juniper::InterfaceGuards<#scalar> for #ty #where_clause
+            {
+                #[allow(unused_variables)]
+                fn check_field_guards(
+                    field_name: &str,
+                    context: &Self::Context,
+                    args: &::juniper::Arguments<#scalar>,
+                ) -> ::juniper::FieldResult<(), #scalar> {
+                    #( <#iface_tys as ::juniper::InterfaceGuards<#scalar>>::check_field_guards(
+                        field_name, context, args,
+                    )?; )*
+                    match field_name {
+                        #( #fields_checks )*
+                        _ => {}
+                    }
+                    Ok(())
+                }
+            }
+        }
+    }
+
     /// Returns generated code implementing [`GraphQLValueAsync`] trait for this
     /// [GraphQL interface][1].
     ///
@@ -959,7 +1061,7 @@ impl Definition {
         let fields_resolvers = self
             .fields
             .iter()
-            .map(|f| f.method_resolve_field_async_tokens(&trait_ty));
+            .map(|f| f.method_resolve_field_async_tokens(scalar, &trait_ty));
         let no_field_panic = self.panic_no_field_tokens();
 
         let custom_downcasts = self
@@ -1014,6 +1116,7 @@ impl Definition {
         let ty = self.ty.ty_tokens();
 
         let impler_tys: Vec<_> = self.implementers.iter().map(|impler| &impler.ty).collect();
+        let iface_tys = &self.interfaces;
 
         let all_implers_unique = if impler_tys.len() > 1 {
             Some(quote! { ::juniper::sa::assert_type_ne_all!(#( #impler_tys ),*); })
@@ -1029,6 +1132,7 @@ impl Definition {
                     #all_implers_unique
 
                     #( <#impler_tys as ::juniper::marker::GraphQLObjectType<#scalar>>::mark(); )*
+                    #( <#iface_tys as ::juniper::marker::GraphQLInterface<#scalar>>::mark(); )*
                 }
             }
         }
@@ -1091,6 +1195,7 @@ impl ToTokens for Definition {
             self.impl_graphql_type_tokens(),
             self.impl_graphql_value_tokens(),
             self.impl_graphql_value_async_tokens(),
+            self.impl_interface_guards_tokens(),
         ]);
     }
 }
@@ -1257,6 +1362,12 @@ struct Field {
     /// [2]: https://spec.graphql.org/June2018/#sec-Language.Fields
     deprecated: Option<Option<String>>,
 
+    /// [`Guard`]s checked before this [GraphQL field][2] is resolved.
+    ///
+    /// [`Guard`]: juniper::Guard
+    /// [2]: https://spec.graphql.org/June2018/#sec-Language.Fields
+    guards: Vec<syn::Expr>,
+
     /// Name of Rust trait method representing this [GraphQL field][2].
     ///
     /// [2]: https://spec.graphql.org/June2018/#sec-Language.Fields
@@ -1310,6 +1421,56 @@ impl Field {
         }
     }
 
+    /// Returns generated code checking the [`Guard`]s of this [`Field`] before it's resolved,
+    /// evaluating `on_error` with the [`FieldError`] of a rejecting [`Guard`] bound to `e`.
+    ///
+    /// The [`Guard`]s are checked through [`InterfaceGuards`], so the ones declared on the
+    /// [GraphQL interfaces][1] implemented by this one are checked as well.
+    ///
+    /// [`FieldError`]: juniper::FieldError
+    /// [`Guard`]: juniper::Guard
+    /// [`InterfaceGuards`]: juniper::InterfaceGuards
+    /// [1]: https://spec.graphql.org/June2018/#sec-Interfaces
+    #[must_use]
+    fn guards_tokens(&self, scalar: &ScalarValueType, on_error: &TokenStream) -> TokenStream {
+        let name = &self.name;
+
+        quote! {
+            match <Self as ::juniper::InterfaceGuards<#scalar>>::check_field_guards(
+                #name,
+                executor.context(),
+                args,
+            ) {
+                Ok(()) => {}
+                Err(e) => {
+                    #on_error
+                }
+            }
+        }
+    }
+
+    /// Returns generated code for the [`InterfaceGuards::check_field_guards`] method, checking
+    /// the [`Guard`]s of this [`Field`].
+    ///
+    /// Returns [`None`] if this [`Field`] has no [`Guard`]s.
+    ///
+    /// [`Guard`]: juniper::Guard
+    /// [`InterfaceGuards::check_field_guards`]: juniper::InterfaceGuards::check_field_guards
+    #[must_use]
+    fn method_check_field_guards_tokens(&self) -> Option<TokenStream> {
+        if self.guards.is_empty() {
+            return None;
+        }
+
+        let (name, guards) = (&self.name, &self.guards);
+
+        Some(quote! {
+            #name => {
+                #( ::juniper::Guard::check(&(#guards), context, args)?; )*
+            }
+        })
+    }
+
     /// Returns generated code for the [`GraphQLValue::resolve_field`] method, which resolves this
     /// [`Field`] synchronously.
     ///
@@ -1317,7 +1478,11 @@ impl Field {
     ///
     /// [`GraphQLValue::resolve_field`]: juniper::GraphQLValue::resolve_field
     #[must_use]
-    fn method_resolve_field_tokens(&self, trait_ty: &syn::Type) -> Option<TokenStream> {
+    fn method_resolve_field_tokens(
+        &self,
+        scalar: &ScalarValueType,
+        trait_ty: &syn::Type,
+    ) -> Option<TokenStream> {
         if self.is_async {
             return None;
         }
@@ -1329,10 +1494,13 @@ impl Field {
             .iter()
             .map(MethodArgument::method_resolve_field_tokens);
 
+        let guards = self.guards_tokens(scalar, &quote! { return Err(e); });
+
         let resolving_code = gen::sync_resolving_code();
 
         Some(quote! {
             #name => {
+                #guards
                 let res: #ty = <Self as #trait_ty>::#method(self #( , #arguments )*);
                 #resolving_code
             }
@@ -1344,7 +1512,11 @@ impl Field {
     ///
     /// [`GraphQLValueAsync::resolve_field_async`]: juniper::GraphQLValueAsync::resolve_field_async
     #[must_use]
-    fn method_resolve_field_async_tokens(&self, trait_ty: &syn::Type) -> TokenStream {
+    fn method_resolve_field_async_tokens(
+        &self,
+        scalar: &ScalarValueType,
+        trait_ty: &syn::Type,
+    ) -> TokenStream {
         let (name, ty, method) = (&self.name, &self.ty, &self.method);
 
         let arguments = self
@@ -1357,10 +1529,18 @@ impl Field {
             fut = quote! { ::juniper::futures::future::ready(#fut) };
         }
 
+        let guards = self.guards_tokens(
+            scalar,
+            &quote! {
+                return Box::pin(::juniper::futures::future::ready(Err(e)));
+            },
+        );
+
         let resolving_code = gen::async_resolving_code(Some(ty));
 
         quote! {
             #name => {
+                #guards
                 let fut = #fut;
                 #resolving_code
             }
@@ -46,13 +46,6 @@ fn create(
 
     let top_attrs = &_impl.attrs;
 
-    let scalar = _impl
-        .attrs
-        .scalar
-        .as_ref()
-        .map(|s| quote!( #s ))
-        .unwrap_or_else(|| quote!(::juniper::DefaultScalarValue));
-
     let fields = _impl
         .methods
         .iter()
@@ -109,16 +102,21 @@ fn create(
                         );
                     }
 
+                    // The argument is converted before the field is resolved, so an invalid value
+                    // is reported as an error of the field.
+                    let binding = quote::format_ident!("__arg_{}", final_name);
                     let resolver = quote!(
-                        let #mut_modifier #arg_ident = args
-                            .get::<#ty>(#final_name)
-                            .unwrap_or_else(::juniper::FromInputValue::<#scalar>::from_implicit_null);
+                        let #mut_modifier #arg_ident = #binding;
                     );
 
                     let field_type = util::GraphQLTypeDefinitionFieldArg {
                         description: attrs
                             .argument(&arg_name)
                             .and_then(|arg| arg.description.as_ref().map(|d| d.value())),
+                        directives: attrs
+                            .argument(&arg_name)
+                            .map(|arg| arg.directives.clone())
+                            .unwrap_or_default(),
                         default: attrs
                             .argument(&arg_name)
                             .and_then(|arg| arg.default.clone()),
@@ -169,12 +167,35 @@ fn create(
                 );
             }
 
+            let complexity = attrs.complexity.map(SpanContainer::into_inner);
+            if let Some(name) = complexity
+                .as_ref()
+                .and_then(|c| c.multiplier_argument.as_ref())
+                .filter(|name| !args.iter().any(|arg| &arg.name == name.as_ref()))
+            {
+                error.emit_custom(
+                    name.span_joined(),
+                    format!(
+                        "field has no argument `{}` to use as complexity multiplier",
+                        name.inner(),
+                    ),
+                );
+            }
+
             Some(util::GraphQLTypeDefinitionField {
                 name,
                 _type,
                 args,
                 description: attrs.description.map(SpanContainer::into_inner),
                 deprecation: attrs.deprecation.map(SpanContainer::into_inner),
+                directives: attrs.directives,
+                complexity,
+                guards: attrs
+                    .guards
+                    .into_iter()
+                    .map(SpanContainer::into_inner)
+                    .collect(),
+                visibility: attrs.visibility.map(SpanContainer::into_inner),
                 resolver_code,
                 is_type_inferred: false,
                 is_async,
@@ -214,6 +235,8 @@ fn create(
         scalar: _impl.attrs.scalar.map(SpanContainer::into_inner),
         context: _impl.attrs.context.map(SpanContainer::into_inner),
         description: _impl.description,
+        directives: _impl.attrs.directives,
+        visibility: _impl.attrs.visibility.map(SpanContainer::into_inner),
         fields,
         generics: _impl.generics.clone(),
         interfaces: _impl
@@ -225,6 +248,7 @@ fn create(
         include_type_generics: false,
         generic_scalar: true,
         no_async: _impl.attrs.no_async.is_some(),
+        is_one_of: false,
     };
 
     Ok(definition)
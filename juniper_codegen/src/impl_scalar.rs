@@ -239,6 +239,34 @@ pub fn build_scalar(
         _ => quote!(),
     };
 
+    // `from_input_value` may return a `Result` with an error telling why the conversion failed,
+    // which is then reported by `try_from_input_value`.
+    let from_input_value = if util::name_of_type(&from_input_value_result)
+        .is_some_and(|n| n == "Result")
+    {
+        quote!(
+            fn from_input_value(
+                value: &::juniper::InputValue<#generic_type>,
+            ) -> Option<Self> {
+                <Self as ::juniper::FromInputValue<#generic_type>>::try_from_input_value(value).ok()
+            }
+
+            fn try_from_input_value(
+                value: &::juniper::InputValue<#generic_type>,
+            ) -> Result<Self, ::juniper::InputValueError> {
+                let convert = |#from_input_value_arg: &::juniper::InputValue<#generic_type>|
+                    -> #from_input_value_result #from_input_value_body;
+                convert(value).map_err(::juniper::InputValueError::new)
+            }
+        )
+    } else {
+        quote!(
+            fn from_input_value(#from_input_value_arg: &::juniper::InputValue<#generic_type>) -> #from_input_value_result {
+                #from_input_value_body
+            }
+        )
+    };
+
     let _async = quote!(
         impl#async_generic_type_decl ::juniper::GraphQLValueAsync<#async_generic_type> for #impl_for_type
         where
@@ -321,9 +349,7 @@ pub fn build_scalar(
         impl#generic_type_decl ::juniper::FromInputValue<#generic_type> for #impl_for_type
         #generic_type_bound
         {
-            fn from_input_value(#from_input_value_arg: &::juniper::InputValue<#generic_type>) -> #from_input_value_result {
-                #from_input_value_body
-            }
+            #from_input_value
         }
 
         impl#generic_type_decl ::juniper::ParseScalarValue<#generic_type> for #impl_for_type
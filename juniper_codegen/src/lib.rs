@@ -108,6 +108,7 @@ macro_rules! try_merge_hashset {
 
 mod derive_enum;
 mod derive_input_object;
+mod derive_into_field_error;
 mod derive_object;
 mod derive_scalar_value;
 mod impl_object;
@@ -207,6 +208,76 @@ pub fn derive_scalar_value(input: TokenStream) -> TokenStream {
     }
 }
 
+/// `#[derive(IntoFieldError)]` macro for implementing [`IntoFieldError`] for application error
+/// types, so resolvers can return them directly.
+///
+/// Every variant of an enum is converted into a [`FieldError`] with the message and the
+/// `extensions` declared with its `#[graphql]` attribute:
+/// - `message = "..."` sets the message sent to clients. If absent, the [`Display`]
+///   implementation of the type is used;
+/// - `code = "..."` sets the `code` of the `extensions`. It may be specified on the enum as well,
+///   as a default for all of its variants;
+/// - `extensions(key = expr, ...)` adds entries to the `extensions`, where `expr` may refer to the
+///   fields of the variant as `self.<field>`. The values are converted with [`From`] into a
+///   [`Value`], so they have to be strings, `i32`s, `f64`s, `bool`s or [`Option`]s of them;
+/// - `internal` marks the variant as not meant for clients, so it's masked by an [`ErrorPolicy`].
+///   Other errors are marked with [`FieldError::user_facing`]. It may be specified on the enum
+///   as well.
+///
+/// ```
+/// use juniper::{graphql_object, graphql_value, FieldError, IntoFieldError};
+///
+/// #[derive(Debug, IntoFieldError)]
+/// #[graphql(code = "BAD_USER_INPUT")]
+/// enum UserError {
+///     #[graphql(message = "User not found", code = "NOT_FOUND", extensions(id = self.0))]
+///     NotFound(i32),
+///     #[graphql(message = "Name is too long", extensions(max = self.max as i32))]
+///     NameTooLong { max: usize },
+///     #[graphql(internal)]
+///     Database(String),
+/// }
+///
+/// impl std::fmt::Display for UserError {
+///     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+///         write!(f, "{:?}", self)
+///     }
+/// }
+///
+/// struct Query;
+///
+/// #[graphql_object]
+/// impl Query {
+///     fn user_name(id: i32) -> Result<String, UserError> {
+///         Err(UserError::NotFound(id))
+///     }
+/// }
+///
+/// let error: FieldError = UserError::NotFound(42).into_field_error();
+/// assert_eq!(error.message(), "User not found");
+/// assert_eq!(
+///     error.extensions(),
+///     &graphql_value!({ "code": "NOT_FOUND", "id": 42 }),
+/// );
+/// ```
+///
+/// Structs are converted the same way, with the `#[graphql]` attribute placed on the struct
+/// itself.
+///
+/// [`Display`]: std::fmt::Display
+/// [`ErrorPolicy`]: juniper::http::error_policy::ErrorPolicy
+/// [`FieldError`]: juniper::FieldError
+/// [`FieldError::user_facing`]: juniper::FieldError::user_facing
+/// [`IntoFieldError`]: juniper::IntoFieldError
+/// [`Value`]: juniper::Value
+#[proc_macro_error]
+#[proc_macro_derive(IntoFieldError, attributes(graphql))]
+pub fn derive_into_field_error(input: TokenStream) -> TokenStream {
+    derive_into_field_error::expand(input.into())
+        .unwrap_or_abort()
+        .into()
+}
+
 /**
 The `object` proc macro is the primary way of defining GraphQL resolvers
 that can not be implemented with the GraphQLObject derive.
@@ -346,6 +417,10 @@ impl Query {
 ```
 struct InternalQuery;
 
+fn is_admin<S>(_: &(), _: &juniper::Arguments<S>) -> juniper::FieldResult<(), S> {
+    Ok(())
+}
+
 // Doc comments can be used to specify graphql documentation.
 /// GRAPHQL DOCUMENTATION.
 /// More info for GraphQL users....
@@ -377,6 +452,32 @@ impl InternalQuery {
     )]
     fn deprecated_field_simple() -> bool { true }
 
+    // Type system directives can be applied to types, fields and arguments.
+    // They are emitted as-is when printing the schema language.
+    #[graphql(directive = r#"@tag(name: "internal")"#)]
+    fn tagged_field() -> bool { true }
+
+    // The Apollo Federation directives have shorthands: `external`,
+    // `requires = "..."` and `provides = "..."` on fields, and
+    // `key = "..."` and `extends` on types.
+    #[graphql(requires = "id")]
+    fn federated_field() -> bool { true }
+
+    // Complexity hints are used by `RootNode::with_max_complexity()`.
+    // The multiplier may also be the name of an integer argument.
+    #[graphql(complexity(cost = 2, multiplier = "first"))]
+    fn paginated_field(first: i32) -> Vec<i32> { vec![first] }
+
+    // Guards are checked with the context and the arguments before the
+    // field is resolved, and may reject it with an error.
+    // Any value implementing `juniper::Guard` can be used, as can functions.
+    #[graphql(guard = "is_admin")]
+    fn guarded_field() -> bool { true }
+
+    // Types, fields and enum values can be hidden from some requests with
+    // the visibility predicates registered by `RootNode::with_visibility()`.
+    #[graphql(visibility = "internal")]
+    fn internal_field() -> bool { true }
 
     // Customizing field arguments is a little awkward right now.
     // This will improve once [RFC 2564](https://github.com/rust-lang/rust/issues/60406)
@@ -617,6 +718,48 @@ pub fn graphql_subscription(args: TokenStream, input: TokenStream) -> TokenStrea
 /// }
 /// ```
 ///
+/// # Implementing other interfaces
+///
+/// A [GraphQL interface][1] may implement other interfaces with an `impl` attribute's argument,
+/// the same way objects do. Its implementers have to implement those interfaces too.
+///
+/// ```
+/// # use juniper::{graphql_interface, GraphQLObject};
+/// #
+/// #[graphql_interface(for = File)]
+/// trait Node {
+///     fn id(&self) -> &str;
+/// }
+///
+/// #[graphql_interface(impl = NodeValue, for = File)]
+/// trait Resource {
+///     fn id(&self) -> &str;
+///     fn url(&self) -> &str;
+/// }
+///
+/// #[derive(GraphQLObject)]
+/// #[graphql(impl = [NodeValue, ResourceValue])]
+/// struct File {
+///     id: String,
+///     url: String,
+/// }
+/// #[graphql_interface]
+/// impl Node for File {
+///     fn id(&self) -> &str {
+///         &self.id
+///     }
+/// }
+/// #[graphql_interface]
+/// impl Resource for File {
+///     fn id(&self) -> &str {
+///         &self.id
+///     }
+///     fn url(&self) -> &str {
+///         &self.url
+///     }
+/// }
+/// ```
+///
 /// # Custom name, description, deprecation and argument defaults
 ///
 /// The name of [GraphQL interface][1], its field, or a field argument may be overriden with a
@@ -17,6 +17,7 @@ pub enum GraphQLScope {
     DeriveInputObject,
     DeriveEnum,
     DeriveScalar,
+    DeriveIntoFieldError,
     ImplScalar,
     ImplObject,
 }
@@ -30,6 +31,7 @@ impl GraphQLScope {
             Self::DeriveInputObject => "#sec-Input-Objects",
             Self::DeriveEnum => "#sec-Enums",
             Self::DeriveScalar | Self::ImplScalar => "#sec-Scalars",
+            Self::DeriveIntoFieldError => "#sec-Errors",
         }
     }
 }
@@ -43,6 +45,7 @@ impl fmt::Display for GraphQLScope {
             Self::DeriveInputObject => "input object",
             Self::DeriveEnum => "enum",
             Self::DeriveScalar | Self::ImplScalar => "scalar",
+            Self::DeriveIntoFieldError => "field error",
         };
 
         write!(f, "GraphQL {}", name)
@@ -57,6 +60,9 @@ pub enum UnsupportedAttribute {
     Scalar,
     Deprecation,
     Default,
+    Complexity,
+    Guard,
+    Visibility,
 }
 
 impl GraphQLScope {
@@ -0,0 +1,269 @@
+//! Parsing of type system directives given as `#[graphql(directive = "...")]` attributes.
+
+use std::{iter::Peekable, str::Chars};
+
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+
+/// A directive applied to a type, field, argument or enum value.
+#[derive(Clone, Debug)]
+pub struct AppliedDirective {
+    name: String,
+    arguments: Vec<(String, DirectiveValue)>,
+}
+
+impl AppliedDirective {
+    /// Creates a directive without arguments, e.g. `@extends`.
+    pub fn new<N: Into<String>>(name: N) -> Self {
+        Self {
+            name: name.into(),
+            arguments: vec![],
+        }
+    }
+
+    /// Adds a `fields` argument, as taken by the federation directives like `@key`.
+    pub fn fields(mut self, fields: String) -> Self {
+        self.arguments
+            .push(("fields".into(), DirectiveValue::String(fields)));
+        self
+    }
+}
+
+impl ToTokens for AppliedDirective {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let name = &self.name;
+        let arguments = self
+            .arguments
+            .iter()
+            .map(|(name, value)| quote!( .argument(#name, #value) ));
+        quote!( ::juniper::meta::AppliedDirective::new(#name) #( #arguments )* ).to_tokens(tokens);
+    }
+}
+
+/// A constant GraphQL value used as a directive argument.
+#[derive(Clone, Debug)]
+enum DirectiveValue {
+    Null,
+    Int(i32),
+    Float(f64),
+    String(String),
+    Boolean(bool),
+    Enum(String),
+    List(Vec<DirectiveValue>),
+    Object(Vec<(String, DirectiveValue)>),
+}
+
+impl ToTokens for DirectiveValue {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let value = match self {
+            Self::Null => quote!(::juniper::InputValue::null()),
+            Self::Int(i) => quote!(::juniper::InputValue::scalar(#i)),
+            Self::Float(f) => quote!(::juniper::InputValue::scalar(#f)),
+            Self::String(s) => quote!(::juniper::InputValue::scalar(String::from(#s))),
+            Self::Boolean(b) => quote!(::juniper::InputValue::scalar(#b)),
+            Self::Enum(e) => quote!(::juniper::InputValue::enum_value(#e)),
+            Self::List(items) => quote!(::juniper::InputValue::list(vec![ #( #items ),* ])),
+            Self::Object(fields) => {
+                let fields = fields.iter().map(|(name, value)| {
+                    quote!((
+                        ::juniper::parser::Spanning::unlocated(String::from(#name)),
+                        ::juniper::parser::Spanning::unlocated(#value),
+                    ))
+                });
+                quote!(::juniper::InputValue::parsed_object(vec![ #( #fields ),* ]))
+            }
+        };
+        value.to_tokens(tokens);
+    }
+}
+
+/// Parses one or more directives, e.g. `@key(fields: "id") @shareable`.
+///
+/// Argument values must be constants, as there are no variables in a schema.
+pub fn parse_directives(lit: &syn::LitStr) -> syn::Result<Vec<AppliedDirective>> {
+    let source = lit.value();
+    let mut parser = Parser {
+        chars: source.chars().peekable(),
+    };
+
+    let mut directives = vec![];
+    parser.skip_ignored();
+    while parser.chars.peek().is_some() {
+        directives.push(
+            parser.directive().map_err(|msg| {
+                syn::Error::new(lit.span(), format!("invalid directive: {}", msg))
+            })?,
+        );
+        parser.skip_ignored();
+    }
+
+    if directives.is_empty() {
+        return Err(syn::Error::new(
+            lit.span(),
+            "expected at least one directive",
+        ));
+    }
+    Ok(directives)
+}
+
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl<'a> Parser<'a> {
+    fn skip_ignored(&mut self) {
+        while let Some(&c) = self.chars.peek() {
+            if c.is_whitespace() || c == ',' {
+                self.chars.next();
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        self.skip_ignored();
+        match self.chars.next() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => Err(format!("expected `{}`, found `{}`", expected, c)),
+            None => Err(format!("expected `{}`, found end of input", expected)),
+        }
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        self.skip_ignored();
+        if self.chars.peek() == Some(&expected) {
+            self.chars.next();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn name(&mut self) -> Result<String, String> {
+        self.skip_ignored();
+        let mut name = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if c == '_' || c.is_ascii_alphabetic() || (!name.is_empty() && c.is_ascii_digit()) {
+                name.push(c);
+                self.chars.next();
+            } else {
+                break;
+            }
+        }
+        if name.is_empty() {
+            Err(match self.chars.peek() {
+                Some(c) => format!("expected a name, found `{}`", c),
+                None => "expected a name, found end of input".into(),
+            })
+        } else {
+            Ok(name)
+        }
+    }
+
+    fn directive(&mut self) -> Result<AppliedDirective, String> {
+        self.expect('@')?;
+        let name = self.name()?;
+
+        let mut arguments = vec![];
+        if self.eat('(') {
+            while !self.eat(')') {
+                let arg_name = self.name()?;
+                self.expect(':')?;
+                arguments.push((arg_name, self.value()?));
+            }
+        }
+
+        Ok(AppliedDirective { name, arguments })
+    }
+
+    fn value(&mut self) -> Result<DirectiveValue, String> {
+        self.skip_ignored();
+        match self.chars.peek() {
+            Some('$') => Err("variables are not allowed in type system directives".into()),
+            Some('"') => self.string().map(DirectiveValue::String),
+            Some('[') => {
+                self.chars.next();
+                let mut items = vec![];
+                while !self.eat(']') {
+                    items.push(self.value()?);
+                }
+                Ok(DirectiveValue::List(items))
+            }
+            Some('{') => {
+                self.chars.next();
+                let mut fields = vec![];
+                while !self.eat('}') {
+                    let field_name = self.name()?;
+                    self.expect(':')?;
+                    fields.push((field_name, self.value()?));
+                }
+                Ok(DirectiveValue::Object(fields))
+            }
+            Some(&c) if c == '-' || c.is_ascii_digit() => self.number(),
+            Some(_) => Ok(match self.name()?.as_str() {
+                "null" => DirectiveValue::Null,
+                "true" => DirectiveValue::Boolean(true),
+                "false" => DirectiveValue::Boolean(false),
+                name => DirectiveValue::Enum(name.into()),
+            }),
+            None => Err("expected a value, found end of input".into()),
+        }
+    }
+
+    fn number(&mut self) -> Result<DirectiveValue, String> {
+        let mut number = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '+' || c == '.' {
+                number.push(c);
+                self.chars.next();
+            } else {
+                break;
+            }
+        }
+
+        if number.contains(&['.', 'e', 'E'][..]) {
+            number
+                .parse()
+                .map(DirectiveValue::Float)
+                .map_err(|_| format!("invalid float `{}`", number))
+        } else {
+            number
+                .parse()
+                .map(DirectiveValue::Int)
+                .map_err(|_| format!("invalid int `{}`", number))
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.chars.next();
+        let mut value = String::new();
+        loop {
+            match self.chars.next() {
+                Some('"') => return Ok(value),
+                Some('\\') => match self.chars.next() {
+                    Some('"') => value.push('"'),
+                    Some('\\') => value.push('\\'),
+                    Some('/') => value.push('/'),
+                    Some('b') => value.push('\u{0008}'),
+                    Some('f') => value.push('\u{000c}'),
+                    Some('n') => value.push('\n'),
+                    Some('r') => value.push('\r'),
+                    Some('t') => value.push('\t'),
+                    Some('u') => {
+                        let code = (0..4).filter_map(|_| self.chars.next()).collect::<String>();
+                        let c = u32::from_str_radix(&code, 16)
+                            .ok()
+                            .and_then(std::char::from_u32)
+                            .ok_or_else(|| format!("invalid unicode escape `\\u{}`", code))?;
+                        value.push(c);
+                    }
+                    Some(c) => return Err(format!("invalid escape sequence `\\{}`", c)),
+                    None => return Err("unterminated string".into()),
+                },
+                Some(c) => value.push(c),
+                None => return Err("unterminated string".into()),
+            }
+        }
+    }
+}
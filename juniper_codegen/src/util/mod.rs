@@ -1,11 +1,13 @@
 #![allow(clippy::single_match)]
 
+pub mod directive;
 pub mod duplicate;
 pub mod parse_impl;
 pub mod span_container;
 
 use std::{collections::HashMap, str::FromStr};
 
+use directive::AppliedDirective;
 use proc_macro2::{Span, TokenStream};
 use proc_macro_error::abort;
 use quote::quote;
@@ -80,6 +82,80 @@ pub struct DeprecationAttr {
     pub reason: Option<String>,
 }
 
+/// Complexity hints of a field, given as `complexity = 5` or
+/// `complexity(cost = 5, multiplier = 10)`.
+///
+/// The multiplier may also name an integer argument of the field, e.g. `multiplier = "first"`.
+#[derive(Debug)]
+pub struct ComplexityAttr {
+    pub cost: usize,
+    pub multiplier: Option<usize>,
+    pub multiplier_argument: Option<SpanContainer<String>>,
+}
+
+impl Parse for ComplexityAttr {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut output = ComplexityAttr {
+            cost: 1,
+            multiplier: None,
+            multiplier_argument: None,
+        };
+
+        if input.peek(token::Eq) {
+            input.parse::<token::Eq>()?;
+            output.cost = input.parse::<syn::LitInt>()?.base10_parse()?;
+            return Ok(output);
+        }
+
+        let content;
+        syn::parenthesized!(content in input);
+        while !content.is_empty() {
+            let ident = content.parse::<syn::Ident>()?;
+            content.parse::<token::Eq>()?;
+            match ident.to_string().as_str() {
+                "cost" => {
+                    output.cost = content.parse::<syn::LitInt>()?.base10_parse()?;
+                }
+                "multiplier" => {
+                    if content.peek(syn::LitStr) {
+                        let lit = content.parse::<syn::LitStr>()?;
+                        output.multiplier_argument = Some(SpanContainer::new(
+                            ident.span(),
+                            Some(lit.span()),
+                            lit.value(),
+                        ));
+                    } else {
+                        output.multiplier = Some(content.parse::<syn::LitInt>()?.base10_parse()?);
+                    }
+                }
+                _ => return Err(syn::Error::new(ident.span(), "unknown attribute")),
+            }
+            content.try_parse::<token::Comma>()?;
+        }
+
+        Ok(output)
+    }
+}
+
+impl quote::ToTokens for ComplexityAttr {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let cost = self.cost;
+        let multiplier = self
+            .multiplier
+            .map(|multiplier| quote!( .multiplier(#multiplier) ));
+        let multiplier_argument = self.multiplier_argument.as_ref().map(|name| {
+            let name = name.as_ref();
+            quote!( .multiplier_argument(#name) )
+        });
+        quote!(
+            ::juniper::meta::Complexity::new(#cost)
+                #multiplier
+                #multiplier_argument
+        )
+        .to_tokens(tokens);
+    }
+}
+
 pub fn find_graphql_attr(attrs: &[Attribute]) -> Option<&Attribute> {
     attrs
         .iter()
@@ -333,6 +409,8 @@ impl FromStr for RenameRule {
 pub struct ObjectAttributes {
     pub name: Option<SpanContainer<String>>,
     pub description: Option<SpanContainer<String>>,
+    pub directives: Vec<AppliedDirective>,
+    pub visibility: Option<SpanContainer<String>>,
     pub context: Option<SpanContainer<syn::Type>>,
     pub scalar: Option<SpanContainer<syn::Type>>,
     pub interfaces: Vec<SpanContainer<syn::Type>>,
@@ -366,6 +444,30 @@ impl Parse for ObjectAttributes {
                         val.value(),
                     ));
                 }
+                "directive" => {
+                    input.parse::<token::Eq>()?;
+                    let val = input.parse::<syn::LitStr>()?;
+                    output.directives.extend(directive::parse_directives(&val)?);
+                }
+                "key" => {
+                    input.parse::<token::Eq>()?;
+                    let val = input.parse::<syn::LitStr>()?;
+                    output
+                        .directives
+                        .push(AppliedDirective::new("key").fields(val.value()));
+                }
+                "extends" => {
+                    output.directives.push(AppliedDirective::new("extends"));
+                }
+                "visibility" => {
+                    input.parse::<token::Eq>()?;
+                    let val = input.parse::<syn::LitStr>()?;
+                    output.visibility = Some(SpanContainer::new(
+                        ident.span(),
+                        Some(val.span()),
+                        val.value(),
+                    ));
+                }
                 "context" | "Context" => {
                     input.parse::<token::Eq>()?;
                     // TODO: remove legacy support for string based Context.
@@ -446,6 +548,7 @@ pub struct FieldAttributeArgument {
     pub rename: Option<SpanContainer<syn::LitStr>>,
     pub default: Option<syn::Expr>,
     pub description: Option<syn::LitStr>,
+    pub directives: Vec<AppliedDirective>,
 }
 
 impl Parse for FieldAttributeArgument {
@@ -457,6 +560,7 @@ impl Parse for FieldAttributeArgument {
             rename: None,
             default: None,
             description: None,
+            directives: vec![],
         };
 
         let content;
@@ -476,6 +580,10 @@ impl Parse for FieldAttributeArgument {
                 "default" => {
                     arg.default = Some(content.parse()?);
                 }
+                "directive" => {
+                    let val: syn::LitStr = content.parse()?;
+                    arg.directives.extend(directive::parse_directives(&val)?);
+                }
                 _ => return Err(syn::Error::new(name.span(), "unknown attribute")),
             }
 
@@ -497,6 +605,10 @@ enum FieldAttribute {
     Name(SpanContainer<syn::LitStr>),
     Description(SpanContainer<syn::LitStr>),
     Deprecation(SpanContainer<DeprecationAttr>),
+    Directive(Vec<AppliedDirective>),
+    Complexity(SpanContainer<ComplexityAttr>),
+    Guard(SpanContainer<syn::Expr>),
+    Visibility(SpanContainer<syn::LitStr>),
     Skip(SpanContainer<syn::Ident>),
     Arguments(HashMap<String, FieldAttributeArgument>),
     Default(SpanContainer<Option<syn::Expr>>),
@@ -545,6 +657,51 @@ impl Parse for FieldAttribute {
                     },
                 )))
             }
+            "directive" => {
+                input.parse::<token::Eq>()?;
+                let lit = input.parse::<syn::LitStr>()?;
+                Ok(FieldAttribute::Directive(directive::parse_directives(
+                    &lit,
+                )?))
+            }
+            "external" => Ok(FieldAttribute::Directive(vec![AppliedDirective::new(
+                "external",
+            )])),
+            "requires" | "provides" => {
+                input.parse::<token::Eq>()?;
+                let lit = input.parse::<syn::LitStr>()?;
+                Ok(FieldAttribute::Directive(vec![AppliedDirective::new(
+                    ident.to_string(),
+                )
+                .fields(lit.value())]))
+            }
+            "complexity" => {
+                let complexity = input.parse::<ComplexityAttr>()?;
+                Ok(FieldAttribute::Complexity(SpanContainer::new(
+                    ident.span(),
+                    None,
+                    complexity,
+                )))
+            }
+            "guard" => {
+                input.parse::<token::Eq>()?;
+                let lit = input.parse::<syn::LitStr>()?;
+                let guard = lit.parse::<syn::Expr>()?;
+                Ok(FieldAttribute::Guard(SpanContainer::new(
+                    ident.span(),
+                    Some(lit.span()),
+                    guard,
+                )))
+            }
+            "visibility" => {
+                input.parse::<token::Eq>()?;
+                let lit = input.parse::<syn::LitStr>()?;
+                Ok(FieldAttribute::Visibility(SpanContainer::new(
+                    ident.span(),
+                    Some(lit.span()),
+                    lit,
+                )))
+            }
             "skip" => Ok(FieldAttribute::Skip(SpanContainer::new(
                 ident.span(),
                 None,
@@ -584,6 +741,13 @@ pub struct FieldAttributes {
     pub name: Option<SpanContainer<String>>,
     pub description: Option<SpanContainer<String>>,
     pub deprecation: Option<SpanContainer<DeprecationAttr>>,
+    pub directives: Vec<AppliedDirective>,
+    /// Only relevant for objects.
+    pub complexity: Option<SpanContainer<ComplexityAttr>>,
+    /// Only relevant for objects, checked in the order of declaration.
+    pub guards: Vec<SpanContainer<syn::Expr>>,
+    /// Only relevant for objects and enums.
+    pub visibility: Option<SpanContainer<String>>,
     // Only relevant for GraphQLObject derive.
     pub skip: Option<SpanContainer<syn::Ident>>,
     /// Only relevant for object macro.
@@ -609,6 +773,18 @@ impl Parse for FieldAttributes {
                 FieldAttribute::Deprecation(attr) => {
                     output.deprecation = Some(attr);
                 }
+                FieldAttribute::Directive(directives) => {
+                    output.directives.extend(directives);
+                }
+                FieldAttribute::Complexity(complexity) => {
+                    output.complexity = Some(complexity);
+                }
+                FieldAttribute::Guard(guard) => {
+                    output.guards.push(guard);
+                }
+                FieldAttribute::Visibility(visibility) => {
+                    output.visibility = Some(visibility.map(|val| val.value()));
+                }
                 FieldAttribute::Skip(ident) => {
                     output.skip = Some(ident);
                 }
@@ -664,6 +840,7 @@ impl FieldAttributes {
 pub struct GraphQLTypeDefinitionFieldArg {
     pub name: String,
     pub description: Option<String>,
+    pub directives: Vec<AppliedDirective>,
     pub default: Option<syn::Expr>,
     pub _type: Box<syn::Type>,
 }
@@ -674,6 +851,10 @@ pub struct GraphQLTypeDefinitionField {
     pub _type: syn::Type,
     pub description: Option<String>,
     pub deprecation: Option<DeprecationAttr>,
+    pub directives: Vec<AppliedDirective>,
+    pub complexity: Option<ComplexityAttr>,
+    pub guards: Vec<syn::Expr>,
+    pub visibility: Option<String>,
     pub args: Vec<GraphQLTypeDefinitionFieldArg>,
     pub resolver_code: TokenStream,
     pub is_type_inferred: bool,
@@ -682,6 +863,68 @@ pub struct GraphQLTypeDefinitionField {
     pub span: Span,
 }
 
+impl GraphQLTypeDefinitionField {
+    /// Returns the code run before this field is resolved, checking its guards and converting its
+    /// arguments into `__arg_<name>` bindings, evaluating `on_error` with the `FieldError` of a
+    /// rejecting guard or an invalid argument bound to `e`.
+    ///
+    /// The guards declared on the field of the same name of the implemented `interfaces` are
+    /// checked first, so they can't be bypassed by resolving the field on the object.
+    fn prelude_tokens(
+        &self,
+        interfaces: &[syn::Type],
+        scalar: &TokenStream,
+        on_error: &TokenStream,
+    ) -> TokenStream {
+        let name = &self.name;
+        let interface_guards = interfaces.iter().map(|iface| {
+            quote! {
+                match <#iface as ::juniper::InterfaceGuards<#scalar>>::check_field_guards(
+                    #name,
+                    executor.context(),
+                    &args,
+                ) {
+                    Ok(()) => {}
+                    Err(e) => {
+                        #on_error
+                    }
+                }
+            }
+        });
+        let guards = self.guards.iter().map(|guard| {
+            quote! {
+                match ::juniper::Guard::<_, #scalar>::check(&(#guard), executor.context(), &args) {
+                    Ok(()) => {}
+                    Err(e) => {
+                        #on_error
+                    }
+                }
+            }
+        });
+        let conversions = self.args.iter().map(|arg| {
+            let (name, ty) = (&arg.name, &arg._type);
+            let binding = quote::format_ident!("__arg_{}", name);
+            quote! {
+                let #binding = match args.try_get::<#ty>(#name) {
+                    Ok(Some(v)) => v,
+                    Ok(None) => <#ty as ::juniper::FromInputValue<#scalar>>::from_implicit_null(),
+                    Err(e) => {
+                        let e = ::juniper::FieldError::<#scalar>::from(
+                            format!("Invalid value for argument \"{}\": {}", #name, e),
+                        );
+                        #on_error
+                    }
+                };
+            }
+        });
+        quote! {
+            #( #interface_guards )*
+            #( #guards )*
+            #( #conversions )*
+        }
+    }
+}
+
 impl syn::spanned::Spanned for GraphQLTypeDefinitionField {
     fn span(&self) -> Span {
         self.span
@@ -704,6 +947,8 @@ pub struct GraphQLTypeDefiniton {
     pub context: Option<syn::Type>,
     pub scalar: Option<syn::Type>,
     pub description: Option<String>,
+    pub directives: Vec<AppliedDirective>,
+    pub visibility: Option<String>,
     pub fields: Vec<GraphQLTypeDefinitionField>,
     pub generics: syn::Generics,
     pub interfaces: Vec<syn::Type>,
@@ -721,6 +966,9 @@ pub struct GraphQLTypeDefiniton {
     pub generic_scalar: bool,
     // FIXME: make this redundant.
     pub no_async: bool,
+    // This flag indicates if the type is a OneOf input object, derived
+    // from an enum whose variants each wrap the type of an input field.
+    pub is_one_of: bool,
 }
 
 impl GraphQLTypeDefiniton {
@@ -747,6 +995,7 @@ impl GraphQLTypeDefiniton {
                     Some(value) => quote!( .description( #value ) ),
                     None => quote!(),
                 };
+                let directives = &arg.directives;
 
                 // Code.
                 match arg.default.as_ref() {
@@ -754,12 +1003,14 @@ impl GraphQLTypeDefiniton {
                         .argument(
                             registry.arg_with_default::<#arg_type>(#arg_name, &#value, info)
                                 #description
+                                #( .directive(#directives) )*
                         )
                     ),
                     None => quote!(
                         .argument(
                             registry.arg::<#arg_type>(#arg_name, info)
                                 #description
+                                #( .directive(#directives) )*
                         )
                     ),
                 }
@@ -781,6 +1032,18 @@ impl GraphQLTypeDefiniton {
                 None => quote!(),
             };
 
+            let directives = &field.directives;
+
+            let complexity = field
+                .complexity
+                .as_ref()
+                .map(|complexity| quote!( .complexity(#complexity) ));
+
+            let visibility = field
+                .visibility
+                .as_ref()
+                .map(|visibility| quote!( .visibility(#visibility) ));
+
             let field_name = &field.name;
 
             let _type = &field._type;
@@ -790,6 +1053,9 @@ impl GraphQLTypeDefiniton {
                     #(#args)*
                     #description
                     #deprecation
+                    #( .directive(#directives) )*
+                    #complexity
+                    #visibility
             }
         });
 
@@ -827,8 +1093,10 @@ impl GraphQLTypeDefiniton {
                     let _type = &field._type;
                     quote!(: #_type)
                 };
+                let prelude = field.prelude_tokens(&self.interfaces, &scalar, &quote!(return Err(e);));
                 quote!(
                     #name => {
+                        #prelude
                         let res #_type = (|| { #code })();
                         ::juniper::IntoResolvable::into(
                             res,
@@ -849,6 +1117,11 @@ impl GraphQLTypeDefiniton {
             .description
             .as_ref()
             .map(|description| quote!( .description(#description) ));
+        let directives = &self.directives;
+        let visibility = self
+            .visibility
+            .as_ref()
+            .map(|visibility| quote!( .visibility(#visibility) ));
 
         let interfaces = if !self.interfaces.is_empty() {
             let interfaces_ty = &self.interfaces;
@@ -897,9 +1170,20 @@ impl GraphQLTypeDefiniton {
                     quote!(: #_type)
                 };
 
+                let prelude = field.prelude_tokens(
+                    &self.interfaces,
+                    &scalar,
+                    &quote!(
+                        return ::juniper::futures::FutureExt::boxed(
+                            ::juniper::futures::future::ready(Err(e)),
+                        );
+                    ),
+                );
+
                 if field.is_async {
                     quote!(
                         #name => {
+                            #prelude
                             let f = async move {
                                 let res #_type = async move { #code }.await;
 
@@ -951,6 +1235,7 @@ impl GraphQLTypeDefiniton {
 
                     quote!(
                         #name => {
+                            #prelude
                             let res #_type = (||{ #code })();
                             let res2 = ::juniper::IntoResolvable::into(
                                 res,
@@ -1071,6 +1356,8 @@ impl GraphQLTypeDefiniton {
                     ];
                     let meta = registry.build_object_type::<#ty>(info, &fields)
                         #description
+                        #( .directive(#directives) )*
+                        #visibility
                         #interfaces;
                     meta.into_meta()
                 }
@@ -1150,18 +1437,21 @@ impl GraphQLTypeDefiniton {
                     Some(value) => quote!( .description( #value ) ),
                     None => quote!(),
                 };
+                let directives = &arg.directives;
 
                 match arg.default.as_ref() {
                     Some(value) => quote!(
                         .argument(
                             registry.arg_with_default::<#arg_type>(#arg_name, &#value, info)
                                 #description
+                                #( .directive(#directives) )*
                         )
                     ),
                     None => quote!(
                         .argument(
                             registry.arg::<#arg_type>(#arg_name, info)
                                 #description
+                                #( .directive(#directives) )*
                         )
                     ),
                 }
@@ -1183,6 +1473,18 @@ impl GraphQLTypeDefiniton {
                 None => quote!(),
             };
 
+            let directives = &field.directives;
+
+            let complexity = field
+                .complexity
+                .as_ref()
+                .map(|complexity| quote!( .complexity(#complexity) ));
+
+            let visibility = field
+                .visibility
+                .as_ref()
+                .map(|visibility| quote!( .visibility(#visibility) ));
+
             let field_name = &field.name;
 
             let type_name = &field._type;
@@ -1201,6 +1503,9 @@ impl GraphQLTypeDefiniton {
                     #(#args)*
                     #description
                     #deprecation
+                    #( .directive(#directives) )*
+                    #complexity
+                    #visibility
             }
         });
 
@@ -1208,6 +1513,11 @@ impl GraphQLTypeDefiniton {
             .description
             .as_ref()
             .map(|description| quote!( .description(#description) ));
+        let directives = &self.directives;
+        let visibility = self
+            .visibility
+            .as_ref()
+            .map(|visibility| quote!( .visibility(#visibility) ));
 
         let interfaces = if !self.interfaces.is_empty() {
             let interfaces_ty = &self.interfaces;
@@ -1268,9 +1578,12 @@ impl GraphQLTypeDefiniton {
                     let _type_name = &field._type;
                     _type = quote!(: #_type_name);
                 };
+                let prelude =
+                    field.prelude_tokens(&self.interfaces, &scalar, &quote!(return Err(e);));
                 quote!(
                     #name => {
                         ::juniper::futures::FutureExt::boxed(async move {
+                            #prelude
                             let res #_type = { #code };
                             let res = ::juniper::IntoFieldResult::<_, #scalar>::into_result(res)?;
                             let executor= executor.as_owned_executor();
@@ -1352,6 +1665,8 @@ impl GraphQLTypeDefiniton {
                         ];
                         let meta = registry.build_object_type::<#ty>(info, &fields)
                             #description
+                            #( .directive(#directives) )*
+                            #visibility
                             #interfaces;
                         meta.into_meta()
                     }
@@ -1460,9 +1775,15 @@ impl GraphQLTypeDefiniton {
             .description
             .as_ref()
             .map(|description| quote!( .description(#description) ));
+        let directives = &self.directives;
+        let visibility = self
+            .visibility
+            .as_ref()
+            .map(|visibility| quote!( .visibility(#visibility) ));
 
         let values = self.fields.iter().map(|variant| {
             let variant_name = &variant.name;
+            let variant_directives = &variant.directives;
 
             let descr = variant
                 .description
@@ -1479,11 +1800,19 @@ impl GraphQLTypeDefiniton {
                 })
                 .unwrap_or_else(|| quote!(::juniper::meta::DeprecationStatus::Current));
 
+            let visibility = variant
+                .visibility
+                .as_ref()
+                .map(|visibility| quote!(Some(#visibility.to_string())))
+                .unwrap_or_else(|| quote!(None));
+
             quote!(
                 ::juniper::meta::EnumValue {
                     name: #variant_name.to_string(),
                     description: #descr,
                     deprecation_status: #depr,
+                    directives: vec![ #( #variant_directives ),* ],
+                    visibility: #visibility,
                 },
             )
         });
@@ -1580,6 +1909,8 @@ impl GraphQLTypeDefiniton {
                         #( #values )*
                     ])
                     #description
+                    #( .directive(#directives) )*
+                    #visibility
                     .into_meta()
                 }
             }
@@ -1686,7 +2017,14 @@ impl GraphQLTypeDefiniton {
                     None => quote!(),
                 };
 
+                let directives = &field.directives;
+
                 let create_meta_field = match field.default {
+                    _ if self.is_one_of => {
+                        quote! {
+                            registry.arg::<Option<#field_ty>>(#field_name, &())
+                        }
+                    }
                     Some(ref def) => {
                         quote! {
                             registry.arg_with_default::<#field_ty>( #field_name, &#def, &())
@@ -1704,6 +2042,7 @@ impl GraphQLTypeDefiniton {
                         #create_meta_field
                         #description
                         #deprecation
+                        #( .directive(#directives) )*
                     },
                 )
             })
@@ -1715,6 +2054,7 @@ impl GraphQLTypeDefiniton {
             .map(|field| {
                 let field_ident = &field.resolver_code;
                 let field_name = &field.name;
+                let field_ty = &field._type;
 
                 // Build from_input clause.
                 let from_input_default = match field.default {
@@ -1728,11 +2068,20 @@ impl GraphQLTypeDefiniton {
 
                 quote!(
                     #field_ident: {
-                        // TODO: investigate the unwraps here, they seem dangerous!
                         match obj.get(#field_name) {
                             #from_input_default
-                            Some(ref v) => ::juniper::FromInputValue::from_input_value(v).unwrap(),
-                            None => ::juniper::FromInputValue::<#scalar>::from_implicit_null(),
+                            Some(ref v) => {
+                                <#field_ty as ::juniper::FromInputValue<#scalar>>::try_from_input_value(v)
+                                    .map_err(|e| e.in_field(#field_name))?
+                            }
+                            None => {
+                                // Required fields fail to convert from `null`.
+                                <#field_ty as ::juniper::FromInputValue<#scalar>>::try_from_input_value(
+                                    &::juniper::InputValue::Null,
+                                )
+                                .map_err(|_| ::juniper::InputValueError::missing_field(#field_name))?;
+                                <#field_ty as ::juniper::FromInputValue<#scalar>>::from_implicit_null()
+                            }
                         }
                     },
                 )
@@ -1752,10 +2101,100 @@ impl GraphQLTypeDefiniton {
             })
             .collect::<Vec<_>>();
 
+        let one_of_from_inputs = self
+            .fields
+            .iter()
+            .map(|field| {
+                let field_ident = &field.resolver_code;
+                let field_name = &field.name;
+                quote!(
+                    #field_name => ::juniper::FromInputValue::<#scalar>::try_from_input_value(v)
+                        .map(Self::#field_ident)
+                        .map_err(|e| e.in_field(#field_name)),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let one_of_to_inputs = self
+            .fields
+            .iter()
+            .map(|field| {
+                let field_ident = &field.resolver_code;
+                let field_name = &field.name;
+                quote!(
+                    Self::#field_ident(v) => (#field_name, ::juniper::ToInputValue::to_input_value(v)),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let one_of = if self.is_one_of {
+            Some(quote!( .one_of() ))
+        } else {
+            None
+        };
+
+        let from_input_body = if self.is_one_of {
+            // Exactly one field must be given, and it must not be `null`.
+            quote!(
+                let obj = value.to_object_value().ok_or_else(|| {
+                    ::juniper::InputValueError::new(
+                        format!("Expected input object \"{}\", found: {}", #name, value),
+                    )
+                })?;
+                if obj.len() != 1 {
+                    return Err(::juniper::InputValueError::new(format!(
+                        "Exactly one field of OneOf input object \"{}\" must be given",
+                        #name,
+                    )));
+                }
+                let (k, v) = obj.into_iter().next().unwrap();
+                if v.is_null() {
+                    return Err(::juniper::InputValueError::new("Must not be null").in_field(k));
+                }
+                match k {
+                    #( #one_of_from_inputs )*
+                    k => Err(::juniper::InputValueError::new("Unknown field").in_field(k)),
+                }
+            )
+        } else {
+            quote!(
+                let obj = value.to_object_value().ok_or_else(|| {
+                    ::juniper::InputValueError::new(
+                        format!("Expected input object \"{}\", found: {}", #name, value),
+                    )
+                })?;
+                Ok(#ty {
+                    #( #from_inputs )*
+                })
+            )
+        };
+
+        let to_input_body = if self.is_one_of {
+            quote!(
+                let field = match self {
+                    #( #one_of_to_inputs )*
+                };
+                ::juniper::InputValue::object(vec![field].into_iter().collect())
+            )
+        } else {
+            quote!(::juniper::InputValue::object(
+                vec![
+                    #( #to_inputs )*
+                ]
+                .into_iter()
+                .collect()
+            ))
+        };
+
         let description = self
             .description
             .as_ref()
             .map(|description| quote!( .description(#description) ));
+        let directives = &self.directives;
+        let visibility = self
+            .visibility
+            .as_ref()
+            .map(|visibility| quote!( .visibility(#visibility) ));
 
         // Preserve the original type_generics before modification,
         // since alteration makes them invalid if self.generic_scalar
@@ -1828,7 +2267,10 @@ impl GraphQLTypeDefiniton {
                         #( #meta_fields )*
                     ];
                     registry.build_input_object_type::<#ty>(&(), fields)
+                    #one_of
                     #description
+                    #( .directive(#directives) )*
+                    #visibility
                     .into_meta()
                 }
             }
@@ -1849,15 +2291,13 @@ impl GraphQLTypeDefiniton {
             {
                 fn from_input_value(value: &::juniper::InputValue<#scalar>) -> Option<Self>
                 {
-                    if let Some(obj) = value.to_object_value() {
-                        let item = #ty {
-                            #( #from_inputs )*
-                        };
-                        Some(item)
-                    }
-                    else {
-                        None
-                    }
+                    <Self as ::juniper::FromInputValue<#scalar>>::try_from_input_value(value).ok()
+                }
+
+                fn try_from_input_value(
+                    value: &::juniper::InputValue<#scalar>,
+                ) -> Result<Self, ::juniper::InputValueError> {
+                    #from_input_body
                 }
             }
 
@@ -1865,9 +2305,7 @@ impl GraphQLTypeDefiniton {
                 #where_clause
             {
                 fn to_input_value(&self) -> ::juniper::InputValue<#scalar> {
-                    ::juniper::InputValue::object(vec![
-                        #( #to_inputs )*
-                    ].into_iter().collect())
+                    #to_input_body
                 }
             }
         );
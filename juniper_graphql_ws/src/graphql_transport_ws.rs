@@ -0,0 +1,845 @@
+//! Implementation of the [graphql-transport-ws protocol][1], the successor of the graphql-ws
+//! protocol used by current releases of Apollo Client and urql.
+//!
+//! Compared to graphql-ws, operations are started with `subscribe` messages, their results are
+//! sent in `next` messages, and both sides can `ping` each other. Protocol violations close the
+//! connection, with the close codes of [`Output::Close`].
+//!
+//! [1]: https://github.com/enisdenjo/graphql-ws/blob/master/PROTOCOL.md
+
+use std::{
+    collections::HashMap,
+    convert::{Infallible, TryInto},
+    error::Error,
+    pin::Pin,
+    sync::Arc,
+    time::Duration,
+};
+
+use juniper::{
+    futures::{
+        channel::oneshot,
+        future::{self, BoxFuture, Either, Future, FutureExt},
+        stream::{self, BoxStream, SelectAll, StreamExt},
+        task::{Context, Poll, Waker},
+        Sink, Stream,
+    },
+    GraphQLError, RuleError, ScalarValue, Variables,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    execute_operation, utils::default_for_null, ConnectionConfig, DataPayload, ErrorPayload,
+    ExecutionParams, Init, OperationOutput, Schema, StartPayload,
+};
+
+/// ClientMessage defines the message types that clients can send.
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(bound(deserialize = "S: ScalarValue"))]
+#[serde(rename_all = "snake_case")]
+#[serde(tag = "type")]
+pub enum ClientMessage<S: ScalarValue> {
+    /// ConnectionInit is sent by the client upon connecting.
+    ConnectionInit {
+        /// Optional parameters of any type sent from the client. These are often used for
+        /// authentication.
+        #[serde(default, deserialize_with = "default_for_null")]
+        payload: Variables<S>,
+    },
+    /// Ping is used to check that the connection is alive. The server answers with a Pong.
+    Ping {
+        /// Optional parameters of any type sent from the client.
+        #[serde(default, deserialize_with = "default_for_null")]
+        payload: Variables<S>,
+    },
+    /// Pong is the answer to a Ping sent by the server.
+    Pong {
+        /// Optional parameters of any type sent from the client.
+        #[serde(default, deserialize_with = "default_for_null")]
+        payload: Variables<S>,
+    },
+    /// Subscribe messages are used to execute a GraphQL operation.
+    Subscribe {
+        /// The id of the operation. This can be anything, but must be unique. If there is another
+        /// in-flight operation with the same id, the connection is closed.
+        id: String,
+
+        /// The query, variables, and operation name.
+        payload: StartPayload<S>,
+    },
+    /// Complete messages are used to stop an operation, e.g. to unsubscribe from a subscription.
+    Complete {
+        /// The id of the operation to stop.
+        id: String,
+    },
+}
+
+/// ServerMessage defines the message types that servers can send.
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(bound(serialize = "S: ScalarValue"))]
+#[serde(rename_all = "snake_case")]
+#[serde(tag = "type")]
+pub enum ServerMessage<S: ScalarValue> {
+    /// ConnectionAck is sent in response to a client's ConnectionInit message if the server
+    /// accepted a connection.
+    ConnectionAck,
+    /// Ping is sent periodically after accepting a connection, to keep it alive.
+    Ping,
+    /// Pong is sent in response to a client's Ping message.
+    Pong,
+    /// Next contains the result of a query, mutation, or subscription event.
+    Next {
+        /// The id of the operation that the data is for.
+        id: String,
+
+        /// The data and errors that occurred during execution.
+        payload: DataPayload<S>,
+    },
+    /// Error contains an error that occurs before execution, such as validation errors. No more
+    /// messages are sent for the operation after it.
+    Error {
+        /// The id of the operation that triggered this error.
+        id: String,
+
+        /// The error(s).
+        payload: ErrorPayload,
+    },
+    /// Complete indicates that no more data will be sent for the given operation.
+    Complete {
+        /// The id of the operation that has completed.
+        id: String,
+    },
+}
+
+/// Output is the stream item type of a [`Connection`].
+#[derive(Debug, PartialEq)]
+pub enum Output<S: ScalarValue> {
+    /// A message to send to the client.
+    Message(ServerMessage<S>),
+    /// The connection must be closed with the given code and reason. This is the last item of the
+    /// stream. The codes used are:
+    ///
+    ///   * 4400 when a message can't be parsed.
+    ///   * 4401 when an operation is started before the connection is acknowledged.
+    ///   * 4403 when the initialization parameters are rejected.
+    ///   * 4409 when an operation is started with the id of an in-flight operation.
+    ///   * 4429 when the connection is initialized more than once.
+    Close {
+        /// The close code.
+        code: u16,
+        /// The close reason.
+        message: String,
+    },
+}
+
+enum Reaction<S: Schema> {
+    Output(Output<S::ScalarValue>),
+}
+
+impl<S: Schema> Reaction<S> {
+    /// Converts the reaction into a one-item stream.
+    fn into_stream(self) -> BoxStream<'static, Self> {
+        stream::once(future::ready(self)).boxed()
+    }
+
+    fn message(msg: ServerMessage<S::ScalarValue>) -> Self {
+        Reaction::Output(Output::Message(msg))
+    }
+
+    fn close(code: u16, message: impl Into<String>) -> Self {
+        Reaction::Output(Output::Close {
+            code,
+            message: message.into(),
+        })
+    }
+}
+
+enum ConnectionState<S: Schema, I: Init<S::ScalarValue, S::Context>> {
+    /// PreInit is the state before a ConnectionInit message has been accepted.
+    PreInit { init: I, schema: S },
+    /// Active is the state after a ConnectionInit message has been accepted.
+    Active {
+        config: Arc<ConnectionConfig<S::Context>>,
+        stoppers: HashMap<String, oneshot::Sender<()>>,
+        schema: S,
+    },
+    /// Terminated is the state after the connection has been closed.
+    Terminated,
+}
+
+impl<S: Schema, I: Init<S::ScalarValue, S::Context>> ConnectionState<S, I> {
+    // Each message we receive results in a stream of zero or more reactions. For example, a Ping
+    // message results in a one-item stream with a Pong message.
+    async fn handle_message(
+        self,
+        msg: ClientMessage<S::ScalarValue>,
+    ) -> (Self, BoxStream<'static, Reaction<S>>) {
+        match msg {
+            ClientMessage::Ping { .. } => {
+                return (self, Reaction::message(ServerMessage::Pong).into_stream());
+            }
+            ClientMessage::Pong { .. } => return (self, stream::empty().boxed()),
+            _ => {}
+        }
+
+        match self {
+            Self::PreInit { init, schema } => match msg {
+                ClientMessage::ConnectionInit { payload } => match init.init(payload).await {
+                    Ok(config) => {
+                        let keep_alive_interval = config.keep_alive_interval;
+
+                        let mut s = Reaction::message(ServerMessage::ConnectionAck).into_stream();
+
+                        if keep_alive_interval > Duration::from_secs(0) {
+                            s = s
+                                .chain(stream::unfold((), move |_| async move {
+                                    tokio::time::delay_for(keep_alive_interval).await;
+                                    Some((Reaction::message(ServerMessage::Ping), ()))
+                                }))
+                                .boxed();
+                        }
+
+                        (
+                            Self::Active {
+                                config: Arc::new(config),
+                                stoppers: HashMap::new(),
+                                schema,
+                            },
+                            s,
+                        )
+                    }
+                    Err(e) => (
+                        Self::Terminated,
+                        Reaction::close(4403, e.to_string()).into_stream(),
+                    ),
+                },
+                ClientMessage::Subscribe { .. } => (
+                    Self::Terminated,
+                    Reaction::close(4401, "Unauthorized").into_stream(),
+                ),
+                _ => (Self::PreInit { init, schema }, stream::empty().boxed()),
+            },
+            Self::Active {
+                config,
+                mut stoppers,
+                schema,
+            } => {
+                let reactions = match msg {
+                    ClientMessage::ConnectionInit { .. } => {
+                        return (
+                            Self::Terminated,
+                            Reaction::close(4429, "Too many initialisation requests").into_stream(),
+                        );
+                    }
+                    ClientMessage::Subscribe { id, payload } => {
+                        // Go ahead and prune canceled stoppers before looking for the id.
+                        stoppers.retain(|_, tx| !tx.is_canceled());
+
+                        if stoppers.contains_key(&id) {
+                            return (
+                                Self::Terminated,
+                                Reaction::close(
+                                    4409,
+                                    format!("Subscriber for {} already exists", id),
+                                )
+                                .into_stream(),
+                            );
+                        }
+
+                        if config.max_in_flight_operations > 0
+                            && stoppers.len() >= config.max_in_flight_operations
+                        {
+                            // Too many in-flight operations. Just send back a validation error.
+                            Reaction::message(ServerMessage::Error {
+                                id,
+                                payload: GraphQLError::ValidationError(vec![RuleError::new(
+                                    "Too many in-flight operations.",
+                                    &[],
+                                )])
+                                .into(),
+                            })
+                            .into_stream()
+                        } else {
+                            // Create a channel that we can use to cancel the operation.
+                            let (tx, rx) = oneshot::channel::<()>();
+                            stoppers.insert(id.clone(), tx);
+
+                            Self::start(
+                                id,
+                                ExecutionParams {
+                                    start_payload: payload,
+                                    config: config.clone(),
+                                    schema: schema.clone(),
+                                },
+                                rx,
+                            )
+                        }
+                    }
+                    ClientMessage::Complete { id } => {
+                        stoppers.remove(&id);
+                        stream::empty().boxed()
+                    }
+                    _ => stream::empty().boxed(),
+                };
+                (
+                    Self::Active {
+                        config,
+                        stoppers,
+                        schema,
+                    },
+                    reactions,
+                )
+            }
+            Self::Terminated => (self, stream::empty().boxed()),
+        }
+    }
+
+    /// Returns the stream of messages of an operation. The stream ends without a Complete message
+    /// if the operation fails with an error, or if the client stops it by firing `rx`.
+    fn start(
+        id: String,
+        params: ExecutionParams<S>,
+        rx: oneshot::Receiver<()>,
+    ) -> BoxStream<'static, Reaction<S>> {
+        let s = execute_operation(params).into_stream().flatten().boxed();
+
+        // Combine this with our oneshot channel so that the stream ends if the oneshot is ever
+        // fired, or after an error.
+        stream::unfold((Some(rx), s, id), |(rx, mut s, id)| async move {
+            let mut rx = rx?;
+            let output = match future::select(&mut rx, s.next()).await {
+                Either::Left(_) => return None,
+                Either::Right((output, _)) => output,
+            };
+            let (msg, rx) = match output {
+                Some(OperationOutput::Data(payload)) => (
+                    ServerMessage::Next {
+                        id: id.clone(),
+                        payload,
+                    },
+                    Some(rx),
+                ),
+                Some(OperationOutput::Error(payload)) => (
+                    ServerMessage::Error {
+                        id: id.clone(),
+                        payload,
+                    },
+                    None,
+                ),
+                None => (ServerMessage::Complete { id: id.clone() }, None),
+            };
+            Some((Reaction::message(msg), (rx, s, id)))
+        })
+        .boxed()
+    }
+}
+
+/// The state of a connection once a message is handled, with the reactions to the message.
+type HandledMessage<S, I> = (ConnectionState<S, I>, BoxStream<'static, Reaction<S>>);
+
+enum ConnectionSinkState<S: Schema, I: Init<S::ScalarValue, S::Context>> {
+    Ready {
+        state: ConnectionState<S, I>,
+    },
+    HandlingMessage {
+        result: BoxFuture<'static, HandledMessage<S, I>>,
+    },
+    Closed,
+}
+
+/// Implements the graphql-transport-ws protocol. This is a sink for `TryInto<ClientMessage>` and a
+/// stream of `Output`.
+pub struct Connection<S: Schema, I: Init<S::ScalarValue, S::Context>> {
+    reactions: SelectAll<BoxStream<'static, Reaction<S>>>,
+    stream_waker: Option<Waker>,
+    sink_state: ConnectionSinkState<S, I>,
+    closed: bool,
+}
+
+impl<S, I> Connection<S, I>
+where
+    S: Schema,
+    I: Init<S::ScalarValue, S::Context>,
+{
+    /// Creates a new connection, which is a sink for `TryInto<ClientMessage>` and a stream of
+    /// `Output`.
+    ///
+    /// The `schema` argument should typically be an `Arc<RootNode<...>>`.
+    ///
+    /// The `init` argument is used to provide the context and additional configuration for
+    /// connections, as for the graphql-ws [`crate::Connection`]. The keep-alive interval of the
+    /// configuration is used to send Ping messages.
+    pub fn new(schema: S, init: I) -> Self {
+        Self {
+            reactions: SelectAll::new(),
+            stream_waker: None,
+            sink_state: ConnectionSinkState::Ready {
+                state: ConnectionState::PreInit { init, schema },
+            },
+            closed: false,
+        }
+    }
+}
+
+impl<S, I, T> Sink<T> for Connection<S, I>
+where
+    T: TryInto<ClientMessage<S::ScalarValue>>,
+    T::Error: Error,
+    S: Schema,
+    I: Init<S::ScalarValue, S::Context> + Send,
+{
+    type Error = Infallible;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        match &mut self.sink_state {
+            ConnectionSinkState::Ready { .. } => Poll::Ready(Ok(())),
+            ConnectionSinkState::HandlingMessage { ref mut result } => {
+                match Pin::new(result).poll(cx) {
+                    Poll::Ready((state, reactions)) => {
+                        self.reactions.push(reactions);
+                        self.sink_state = ConnectionSinkState::Ready { state };
+                        if let Some(waker) = self.stream_waker.take() {
+                            waker.wake();
+                        }
+                        Poll::Ready(Ok(()))
+                    }
+                    Poll::Pending => Poll::Pending,
+                }
+            }
+            ConnectionSinkState::Closed => panic!("poll_ready called after close"),
+        }
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
+        let s = self.get_mut();
+        s.sink_state = match std::mem::replace(&mut s.sink_state, ConnectionSinkState::Closed) {
+            ConnectionSinkState::Ready { state } => match item.try_into() {
+                Ok(msg) => ConnectionSinkState::HandlingMessage {
+                    result: state.handle_message(msg).boxed(),
+                },
+                Err(e) => {
+                    // If we weren't able to parse the message, close the connection.
+                    s.reactions
+                        .push(Reaction::close(4400, e.to_string()).into_stream());
+                    if let Some(waker) = s.stream_waker.take() {
+                        waker.wake();
+                    }
+                    ConnectionSinkState::Ready {
+                        state: ConnectionState::Terminated,
+                    }
+                }
+            },
+            _ => panic!("start_send called when not ready"),
+        };
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        <Self as Sink<T>>::poll_ready(self, cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        self.sink_state = ConnectionSinkState::Closed;
+        if let Some(waker) = self.stream_waker.take() {
+            // Wake up the stream so it can close too.
+            waker.wake();
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<S, I> Stream for Connection<S, I>
+where
+    S: Schema,
+    I: Init<S::ScalarValue, S::Context>,
+{
+    type Item = Output<S::ScalarValue>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        self.stream_waker = Some(cx.waker().clone());
+
+        if self.closed {
+            return Poll::Ready(None);
+        }
+        if let ConnectionSinkState::Closed = self.sink_state {
+            return Poll::Ready(None);
+        }
+
+        // Poll the reactions for new outgoing messages.
+        if self.reactions.is_empty() {
+            return Poll::Pending;
+        }
+        match Pin::new(&mut self.reactions).poll_next(cx) {
+            Poll::Ready(Some(Reaction::Output(output))) => {
+                if let Output::Close { .. } = output {
+                    self.closed = true;
+                }
+                Poll::Ready(Some(output))
+            }
+            Poll::Ready(None) => {
+                // In rare cases, the reaction stream may terminate. For example, this will happen
+                // if the first message we receive does not require any reaction. Just recreate it
+                // in that case.
+                self.reactions = SelectAll::new();
+                Poll::Pending
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{convert::Infallible, io};
+
+    use juniper::{
+        futures::sink::SinkExt, graphql_object, graphql_subscription, DefaultScalarValue,
+        EmptyMutation, FieldResult, InputValue, RootNode, Value,
+    };
+
+    use super::*;
+
+    struct Context(i32);
+
+    struct Query;
+
+    #[graphql_object(context = Context)]
+    impl Query {
+        /// context just resolves to the current context.
+        async fn context(context: &Context) -> i32 {
+            context.0
+        }
+    }
+
+    struct Subscription;
+
+    #[graphql_subscription(context = Context)]
+    impl Subscription {
+        /// never never emits anything.
+        async fn never(context: &Context) -> BoxStream<'static, FieldResult<i32>> {
+            tokio::time::delay_for(Duration::from_secs(10000))
+                .map(|_| unreachable!())
+                .into_stream()
+                .boxed()
+        }
+
+        /// count emits the numbers from 1 to the current context.
+        async fn count(context: &Context) -> BoxStream<'static, FieldResult<i32>> {
+            stream::iter((1..=context.0).map(Ok)).boxed()
+        }
+    }
+
+    type ClientMessage = super::ClientMessage<DefaultScalarValue>;
+    type ServerMessage = super::ServerMessage<DefaultScalarValue>;
+    type Output = super::Output<DefaultScalarValue>;
+
+    type TestSchema = Arc<RootNode<'static, Query, EmptyMutation<Context>, Subscription>>;
+
+    fn new_test_schema() -> TestSchema {
+        Arc::new(RootNode::new(Query, EmptyMutation::new(), Subscription))
+    }
+
+    fn new_test_connection(context: i32) -> Connection<TestSchema, ConnectionConfig<Context>> {
+        Connection::new(
+            new_test_schema(),
+            ConnectionConfig::new(Context(context))
+                .with_keep_alive_interval(Duration::from_secs(0)),
+        )
+    }
+
+    fn subscribe(id: &str, query: &str) -> ClientMessage {
+        ClientMessage::Subscribe {
+            id: id.to_string(),
+            payload: StartPayload {
+                query: query.to_string(),
+                variables: Variables::default(),
+                operation_name: None,
+            },
+        }
+    }
+
+    fn message(msg: ServerMessage) -> Option<Output> {
+        Some(Output::Message(msg))
+    }
+
+    fn next(id: &str, field: &str, value: i32) -> Option<Output> {
+        message(ServerMessage::Next {
+            id: id.to_string(),
+            payload: DataPayload {
+                data: Value::Object([(field, Value::scalar(value))].iter().cloned().collect()),
+                errors: vec![],
+            },
+        })
+    }
+
+    fn close(code: u16, message: &str) -> Option<Output> {
+        Some(Output::Close {
+            code,
+            message: message.to_string(),
+        })
+    }
+
+    async fn init(
+        conn: &mut (impl Sink<ClientMessage, Error = Infallible> + Stream<Item = Output> + Unpin),
+    ) {
+        conn.send(ClientMessage::ConnectionInit {
+            payload: Variables::default(),
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(message(ServerMessage::ConnectionAck), conn.next().await);
+    }
+
+    #[test]
+    fn test_deserialization() {
+        assert_eq!(
+            ClientMessage::ConnectionInit {
+                payload: [("foo".to_string(), InputValue::scalar("bar"))]
+                    .iter()
+                    .cloned()
+                    .collect(),
+            },
+            serde_json::from_str(r##"{"type": "connection_init", "payload": {"foo": "bar"}}"##)
+                .unwrap(),
+        );
+
+        assert_eq!(
+            ClientMessage::Ping {
+                payload: Variables::default(),
+            },
+            serde_json::from_str(r##"{"type": "ping"}"##).unwrap(),
+        );
+
+        assert_eq!(
+            subscribe("foo", "query MyQuery { __typename }"),
+            serde_json::from_str(
+                r##"{"type": "subscribe", "id": "foo", "payload": {
+                "query": "query MyQuery { __typename }",
+                "extensions": {}
+            }}"##
+            )
+            .unwrap(),
+        );
+
+        assert_eq!(
+            ClientMessage::Complete {
+                id: "foo".to_string(),
+            },
+            serde_json::from_str(r##"{"type": "complete", "id": "foo"}"##).unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_serialization() {
+        assert_eq!(
+            serde_json::to_string(&ServerMessage::ConnectionAck).unwrap(),
+            r##"{"type":"connection_ack"}"##,
+        );
+
+        assert_eq!(
+            serde_json::to_string(&ServerMessage::Pong).unwrap(),
+            r##"{"type":"pong"}"##,
+        );
+
+        assert_eq!(
+            serde_json::to_string(&ServerMessage::Next {
+                id: "foo".to_string(),
+                payload: DataPayload {
+                    data: Value::null(),
+                    errors: vec![],
+                },
+            })
+            .unwrap(),
+            r##"{"type":"next","id":"foo","payload":{"data":null}}"##,
+        );
+
+        assert_eq!(
+            serde_json::to_string(&ServerMessage::Error {
+                id: "foo".to_string(),
+                payload: GraphQLError::UnknownOperationName.into(),
+            })
+            .unwrap(),
+            r##"{"type":"error","id":"foo","payload":[{"message":"Unknown operation","extensions":{"code":"BAD_USER_INPUT"}}]}"##,
+        );
+    }
+
+    #[tokio::test]
+    async fn test_query() {
+        let mut conn = new_test_connection(1);
+        init(&mut conn).await;
+
+        conn.send(subscribe("foo", "{context}")).await.unwrap();
+
+        assert_eq!(next("foo", "context", 1), conn.next().await);
+        assert_eq!(
+            message(ServerMessage::Complete {
+                id: "foo".to_string(),
+            }),
+            conn.next().await,
+        );
+    }
+
+    #[tokio::test]
+    async fn test_subscriptions() {
+        let mut conn = new_test_connection(2);
+        init(&mut conn).await;
+
+        conn.send(subscribe("foo", "subscription Foo {count}"))
+            .await
+            .unwrap();
+
+        assert_eq!(next("foo", "count", 1), conn.next().await);
+        assert_eq!(next("foo", "count", 2), conn.next().await);
+        assert_eq!(
+            message(ServerMessage::Complete {
+                id: "foo".to_string(),
+            }),
+            conn.next().await,
+        );
+    }
+
+    #[tokio::test]
+    async fn test_errors_are_not_completed() {
+        let mut conn = new_test_connection(1);
+        init(&mut conn).await;
+
+        conn.send(subscribe("foo", "asd")).await.unwrap();
+        match conn.next().await {
+            Some(Output::Message(ServerMessage::Error { id, .. })) => assert_eq!(id, "foo"),
+            output => panic!("expected error, got: {:?}", output),
+        }
+
+        conn.send(ClientMessage::Ping {
+            payload: Variables::default(),
+        })
+        .await
+        .unwrap();
+        assert_eq!(message(ServerMessage::Pong), conn.next().await);
+    }
+
+    #[tokio::test]
+    async fn test_ping() {
+        let mut conn = new_test_connection(1);
+
+        conn.send(ClientMessage::Ping {
+            payload: Variables::default(),
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(message(ServerMessage::Pong), conn.next().await);
+    }
+
+    #[tokio::test]
+    async fn test_keep_alives() {
+        let mut conn = Connection::new(
+            new_test_schema(),
+            ConnectionConfig::new(Context(1)).with_keep_alive_interval(Duration::from_millis(20)),
+        );
+        init(&mut conn).await;
+
+        for _ in 0..3 {
+            assert_eq!(message(ServerMessage::Ping), conn.next().await);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_before_init() {
+        let mut conn = new_test_connection(1);
+
+        conn.send(subscribe("foo", "{context}")).await.unwrap();
+
+        assert_eq!(close(4401, "Unauthorized"), conn.next().await);
+        assert_eq!(None, conn.next().await);
+    }
+
+    #[tokio::test]
+    async fn test_init_params_error() {
+        let mut conn = Connection::new(new_test_schema(), |_: Variables| async move {
+            Err(io::Error::other("init error"))
+        });
+
+        conn.send(ClientMessage::ConnectionInit {
+            payload: Variables::default(),
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(close(4403, "init error"), conn.next().await);
+    }
+
+    #[tokio::test]
+    async fn test_init_twice() {
+        let mut conn = Connection::new(new_test_schema(), |_: Variables| async move {
+            Ok(ConnectionConfig::new(Context(1))) as Result<_, Infallible>
+        });
+        init(&mut conn).await;
+
+        conn.send(ClientMessage::ConnectionInit {
+            payload: Variables::default(),
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            close(4429, "Too many initialisation requests"),
+            conn.next().await,
+        );
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_ids() {
+        let mut conn = new_test_connection(1);
+        init(&mut conn).await;
+
+        conn.send(subscribe("foo", "subscription Foo {never}"))
+            .await
+            .unwrap();
+        conn.send(subscribe("foo", "subscription Foo {never}"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            close(4409, "Subscriber for foo already exists"),
+            conn.next().await,
+        );
+    }
+
+    #[tokio::test]
+    async fn test_completed_ids_can_be_reused() {
+        let mut conn = new_test_connection(1);
+        init(&mut conn).await;
+
+        conn.send(subscribe("foo", "subscription Foo {never}"))
+            .await
+            .unwrap();
+        conn.send(ClientMessage::Complete {
+            id: "foo".to_string(),
+        })
+        .await
+        .unwrap();
+        conn.send(subscribe("foo", "{context}")).await.unwrap();
+
+        assert_eq!(next("foo", "context", 1), conn.next().await);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_message() {
+        struct Invalid;
+
+        impl std::convert::TryFrom<Invalid> for ClientMessage {
+            type Error = io::Error;
+
+            fn try_from(_: Invalid) -> Result<Self, Self::Error> {
+                Err(io::Error::other("invalid message"))
+            }
+        }
+
+        let mut conn = new_test_connection(1);
+
+        conn.send(Invalid).await.unwrap();
+
+        assert_eq!(close(4400, "invalid message"), conn.next().await);
+    }
+}
@@ -4,6 +4,8 @@
 
 This crate contains an implementation of the [graphql-ws protocol](https://github.com/apollographql/subscriptions-transport-ws/blob/263844b5c1a850c1e29814564eb62cb587e5eaaf/PROTOCOL.md), as used by Apollo.
 
+The newer [graphql-transport-ws protocol](https://github.com/enisdenjo/graphql-ws/blob/master/PROTOCOL.md) is implemented in the [`graphql_transport_ws`] module. Servers can pick the protocol requested by clients in the `Sec-WebSocket-Protocol` header with [`Protocol::from_header`].
+
 */
 
 #![deny(missing_docs)]
@@ -20,6 +22,8 @@ pub use schema::*;
 
 mod utils;
 
+pub mod graphql_transport_ws;
+
 use std::{
     collections::HashMap,
     convert::{Infallible, TryInto},
@@ -41,18 +45,53 @@ use juniper::{
     GraphQLError, RuleError, ScalarValue, Variables,
 };
 
-struct ExecutionParams<S: Schema> {
-    start_payload: StartPayload<S::ScalarValue>,
-    config: Arc<ConnectionConfig<S::Context>>,
-    schema: S,
+/// Protocol is a WebSocket subprotocol implemented by this crate.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Protocol {
+    /// The graphql-ws protocol of subscriptions-transport-ws, implemented by [`Connection`].
+    GraphQLWs,
+    /// The graphql-transport-ws protocol of graphql-ws, implemented by
+    /// [`graphql_transport_ws::Connection`].
+    GraphQLTransportWs,
+}
+
+impl Protocol {
+    /// Returns the name of the protocol, as sent in the `Sec-WebSocket-Protocol` header.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::GraphQLWs => "graphql-ws",
+            Self::GraphQLTransportWs => "graphql-transport-ws",
+        }
+    }
+
+    /// Picks the first supported protocol out of the comma-separated list of the
+    /// `Sec-WebSocket-Protocol` header of a request. Falls back to graphql-ws if there is no
+    /// header, or if none of its protocols are supported.
+    pub fn from_header(header: Option<&str>) -> Self {
+        header
+            .into_iter()
+            .flat_map(|header| header.split(','))
+            .find_map(|protocol| match protocol.trim() {
+                "graphql-ws" => Some(Self::GraphQLWs),
+                "graphql-transport-ws" => Some(Self::GraphQLTransportWs),
+                _ => None,
+            })
+            .unwrap_or(Self::GraphQLWs)
+    }
+}
+
+pub(crate) struct ExecutionParams<S: Schema> {
+    pub(crate) start_payload: StartPayload<S::ScalarValue>,
+    pub(crate) config: Arc<ConnectionConfig<S::Context>>,
+    pub(crate) schema: S,
 }
 
 /// ConnectionConfig is used to configure the connection once the client sends the ConnectionInit
 /// message.
 pub struct ConnectionConfig<CtxT> {
-    context: CtxT,
-    max_in_flight_operations: usize,
-    keep_alive_interval: Duration,
+    pub(crate) context: CtxT,
+    pub(crate) max_in_flight_operations: usize,
+    pub(crate) keep_alive_interval: Duration,
 }
 
 impl<CtxT> ConnectionConfig<CtxT> {
@@ -246,16 +285,30 @@ impl<S: Schema, I: Init<S::ScalarValue, S::Context>> ConnectionState<S, I> {
 
                                 // Create the operation stream. This stream will emit Data and Error
                                 // messages, but will not emit Complete – that part is up to us.
-                                let s = Self::start(
-                                    id.clone(),
-                                    ExecutionParams {
-                                        start_payload: payload,
-                                        config: config.clone(),
-                                        schema: schema.clone(),
-                                    },
-                                )
+                                let s = execute_operation(ExecutionParams {
+                                    start_payload: payload,
+                                    config: config.clone(),
+                                    schema: schema.clone(),
+                                })
                                 .into_stream()
-                                .flatten();
+                                .flatten()
+                                .map({
+                                    let id = id.clone();
+                                    move |output| {
+                                        Reaction::ServerMessage(match output {
+                                            OperationOutput::Data(payload) => ServerMessage::Data {
+                                                id: id.clone(),
+                                                payload,
+                                            },
+                                            OperationOutput::Error(payload) => {
+                                                ServerMessage::Error {
+                                                    id: id.clone(),
+                                                    payload,
+                                                }
+                                            }
+                                        })
+                                    }
+                                });
 
                                 // Combine this with our oneshot channel so that the stream ends if the
                                 // oneshot is ever fired.
@@ -295,49 +348,56 @@ impl<S: Schema, I: Init<S::ScalarValue, S::Context>> ConnectionState<S, I> {
             Self::Terminated => (self, stream::empty().boxed()),
         }
     }
+}
 
-    async fn start(id: String, params: ExecutionParams<S>) -> BoxStream<'static, Reaction<S>> {
-        // TODO: This could be made more efficient if juniper exposed functionality to allow us to
-        // parse and validate the query, determine whether it's a subscription, and then execute
-        // it. For now, the query gets parsed and validated twice.
-
-        let params = Arc::new(params);
-
-        // Try to execute this as a query or mutation.
-        match juniper::execute(
-            &params.start_payload.query,
-            params
-                .start_payload
-                .operation_name
-                .as_ref()
-                .map(|s| s.as_str()),
-            params.schema.root_node(),
-            &params.start_payload.variables,
-            &params.config.context,
-        )
-        .await
-        {
-            Ok((data, errors)) => {
-                return Reaction::ServerMessage(ServerMessage::Data {
-                    id: id.clone(),
-                    payload: DataPayload { data, errors },
-                })
-                .to_stream();
-            }
-            Err(GraphQLError::IsSubscription) => {}
-            Err(e) => {
-                return Reaction::ServerMessage(ServerMessage::Error {
-                    id: id.clone(),
-                    // e only references data owned by params. The new ErrorPayload will continue to keep that data alive.
-                    payload: unsafe { ErrorPayload::new_unchecked(Box::new(params.clone()), e) },
-                })
-                .to_stream();
-            }
-        }
+/// The result of an operation, sent to the client as the messages of the protocol
+pub(crate) enum OperationOutput<S> {
+    /// The result of a query or mutation, or an event of a subscription.
+    Data(DataPayload<S>),
+    /// An error which prevented the execution of the operation.
+    Error(ErrorPayload),
+}
 
-        // Try to execute as a subscription.
-        SubscriptionStart::new(id, params.clone()).boxed()
+/// Executes an operation, returning the stream of its results. The stream ends once the operation
+/// completes, or after it emits an error.
+pub(crate) async fn execute_operation<S: Schema>(
+    params: ExecutionParams<S>,
+) -> BoxStream<'static, OperationOutput<S::ScalarValue>> {
+    // TODO: This could be made more efficient if juniper exposed functionality to allow us to
+    // parse and validate the query, determine whether it's a subscription, and then execute
+    // it. For now, the query gets parsed and validated twice.
+
+    let params = Arc::new(params);
+
+    // Try to execute this as a query or mutation.
+    match juniper::execute(
+        &params.start_payload.query,
+        params.start_payload.operation_name.as_deref(),
+        params.schema.root_node(),
+        &params.start_payload.variables,
+        &params.config.context,
+    )
+    .await
+    {
+        Ok((data, errors)) => {
+            return stream::once(future::ready(OperationOutput::Data(DataPayload {
+                data,
+                errors,
+            })))
+            .boxed();
+        }
+        Err(GraphQLError::IsSubscription) => {}
+        Err(e) => {
+            return stream::once(future::ready(OperationOutput::Error(
+                // e only references data owned by params. The new ErrorPayload will continue to keep that data alive.
+                unsafe { ErrorPayload::new_unchecked(Box::new(params.clone()), e) },
+            )))
+            .boxed();
+        }
     }
+
+    // Try to execute as a subscription.
+    SubscriptionStart::new(params.clone()).boxed()
 }
 
 struct InterruptableStream<S> {
@@ -360,11 +420,10 @@ impl<S: Stream + Unpin> Stream for InterruptableStream<S> {
 /// SubscriptionStartState is the state for a subscription operation.
 enum SubscriptionStartState<S: Schema> {
     /// Init is the start before being polled for the first time.
-    Init { id: String },
+    Init,
     /// ResolvingIntoStream is the state after being polled for the first time. In this state,
     /// we're parsing, validating, and getting the actual event stream.
     ResolvingIntoStream {
-        id: String,
         future: BoxFuture<
             'static,
             Result<
@@ -376,7 +435,6 @@ enum SubscriptionStartState<S: Schema> {
     /// Streaming is the state after we've successfully obtained the event stream for the
     /// subscription. In this state, we're just forwarding events back to the client.
     Streaming {
-        id: String,
         stream: juniper_subscriptions::Connection<'static, S::ScalarValue>,
     },
     /// Terminated is the state once we're all done.
@@ -391,17 +449,17 @@ struct SubscriptionStart<S: Schema> {
 }
 
 impl<S: Schema> SubscriptionStart<S> {
-    fn new(id: String, params: Arc<ExecutionParams<S>>) -> Pin<Box<Self>> {
+    fn new(params: Arc<ExecutionParams<S>>) -> Pin<Box<Self>> {
         Box::pin(Self {
             params,
-            state: SubscriptionStartState::Init { id },
+            state: SubscriptionStartState::Init,
             _marker: PhantomPinned,
         })
     }
 }
 
 impl<S: Schema> Stream for SubscriptionStart<S> {
-    type Item = Reaction<S>;
+    type Item = OperationOutput<S::ScalarValue>;
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
         let (params, state) = unsafe {
@@ -413,13 +471,12 @@ impl<S: Schema> Stream for SubscriptionStart<S> {
 
         loop {
             match state {
-                SubscriptionStartState::Init { id } => {
+                SubscriptionStartState::Init => {
                     // XXX: resolve_into_stream returns a Future that references the execution
                     // parameters, and the returned stream also references them. We can guarantee
                     // that everything has the same lifetime in this self-referential struct.
                     let params = Arc::as_ptr(params);
                     *state = SubscriptionStartState::ResolvingIntoStream {
-                        id: id.clone(),
                         future: unsafe {
                             juniper::resolve_into_stream(
                                 &(*params).start_payload.query,
@@ -439,50 +496,38 @@ impl<S: Schema> Stream for SubscriptionStart<S> {
                         .boxed(),
                     };
                 }
-                SubscriptionStartState::ResolvingIntoStream {
-                    ref id,
-                    ref mut future,
-                } => match future.as_mut().poll(cx) {
-                    Poll::Ready(r) => match r {
-                        Ok(stream) => {
-                            *state = SubscriptionStartState::Streaming {
-                                id: id.clone(),
-                                stream,
-                            }
-                        }
-                        Err(e) => {
-                            return Poll::Ready(Some(Reaction::ServerMessage(
-                                ServerMessage::Error {
-                                    id: id.clone(),
+                SubscriptionStartState::ResolvingIntoStream { ref mut future } => {
+                    match future.as_mut().poll(cx) {
+                        Poll::Ready(r) => match r {
+                            Ok(stream) => *state = SubscriptionStartState::Streaming { stream },
+                            Err(e) => {
+                                *state = SubscriptionStartState::Terminated;
+                                return Poll::Ready(Some(OperationOutput::Error(
                                     // e only references data owned by params. The new ErrorPayload will continue to keep that data alive.
-                                    payload: unsafe {
+                                    unsafe {
                                         ErrorPayload::new_unchecked(Box::new(params.clone()), e)
                                     },
-                                },
-                            )));
-                        }
-                    },
-                    Poll::Pending => return Poll::Pending,
-                },
-                SubscriptionStartState::Streaming {
-                    ref id,
-                    ref mut stream,
-                } => match Pin::new(stream).poll_next(cx) {
-                    Poll::Ready(Some(output)) => {
-                        return Poll::Ready(Some(Reaction::ServerMessage(ServerMessage::Data {
-                            id: id.clone(),
-                            payload: DataPayload {
+                                )));
+                            }
+                        },
+                        Poll::Pending => return Poll::Pending,
+                    }
+                }
+                SubscriptionStartState::Streaming { ref mut stream } => {
+                    match Pin::new(stream).poll_next(cx) {
+                        Poll::Ready(Some(output)) => {
+                            return Poll::Ready(Some(OperationOutput::Data(DataPayload {
                                 data: output.data,
                                 errors: output.errors,
-                            },
-                        })));
-                    }
-                    Poll::Ready(None) => {
-                        *state = SubscriptionStartState::Terminated;
-                        return Poll::Ready(None);
+                            })));
+                        }
+                        Poll::Ready(None) => {
+                            *state = SubscriptionStartState::Terminated;
+                            return Poll::Ready(None);
+                        }
+                        Poll::Pending => return Poll::Pending,
                     }
-                    Poll::Pending => return Poll::Pending,
-                },
+                }
                 SubscriptionStartState::Terminated => return Poll::Ready(None),
             }
         }
@@ -1072,4 +1117,18 @@ mod test {
             msg @ _ => panic!("expected data, got: {:?}", msg),
         }
     }
+
+    #[test]
+    fn test_protocol_from_header() {
+        assert_eq!(Protocol::from_header(None), Protocol::GraphQLWs);
+        assert_eq!(
+            Protocol::from_header(Some("graphql-ws")),
+            Protocol::GraphQLWs
+        );
+        assert_eq!(
+            Protocol::from_header(Some("foo, graphql-transport-ws, graphql-ws")),
+            Protocol::GraphQLTransportWs,
+        );
+        assert_eq!(Protocol::from_header(Some("foo")), Protocol::GraphQLWs);
+    }
 }
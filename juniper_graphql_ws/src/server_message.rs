@@ -173,7 +173,7 @@ mod test {
                 payload: GraphQLError::UnknownOperationName.into(),
             })
             .unwrap(),
-            r##"{"type":"error","id":"foo","payload":[{"message":"Unknown operation"}]}"##,
+            r##"{"type":"error","id":"foo","payload":[{"message":"Unknown operation","extensions":{"code":"BAD_USER_INPUT"}}]}"##,
         );
 
         assert_eq!(
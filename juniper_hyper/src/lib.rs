@@ -1,15 +1,25 @@
 #![doc(html_root_url = "https://docs.rs/juniper_hyper/0.2.0")]
 
-use std::{error::Error, fmt, string::FromUtf8Error, sync::Arc};
+use std::{
+    collections::HashMap, convert::Infallible, error::Error, fmt, string::FromUtf8Error, sync::Arc,
+};
 
+use futures::StreamExt as _;
 use hyper::{
     header::{self, HeaderValue},
     Body, Method, Request, Response, StatusCode,
 };
 use juniper::{
-    http::{GraphQLBatchRequest, GraphQLRequest as JuniperGraphQLRequest, GraphQLRequest},
+    http::{
+        incremental::{self, IncrementalBody},
+        multipart::{MultipartError, UploadedFile},
+        GraphQLBatchRequest, GraphQLRequest as JuniperGraphQLRequest, GraphQLRequest,
+        GraphQLRequestExtensions,
+    },
     GraphQLSubscriptionType, GraphQLType, GraphQLTypeAsync, InputValue, RootNode, ScalarValue,
 };
+#[cfg(feature = "subscriptions")]
+use juniper_subscriptions::sse;
 use serde_json::error::Error as SerdeError;
 use url::form_urlencoded;
 
@@ -28,7 +38,7 @@ where
     CtxT: Sync,
     S: ScalarValue + Send + Sync,
 {
-    Ok(match parse_req(req).await {
+    Ok(match parse_req(req, &UploadLimits::default()).await {
         Ok(req) => execute_request_sync(root_node, context, req).await,
         Err(resp) => resp,
     })
@@ -49,14 +59,204 @@ where
     CtxT: Sync,
     S: ScalarValue + Send + Sync,
 {
-    Ok(match parse_req(req).await {
+    graphql_with_upload_limits(root_node, context, req, &UploadLimits::default()).await
+}
+
+/// Executes a request like [`graphql`], rejecting multipart requests exceeding the given limits
+/// instead of the default ones.
+pub async fn graphql_with_upload_limits<CtxT, QueryT, MutationT, SubscriptionT, S>(
+    root_node: Arc<RootNode<'static, QueryT, MutationT, SubscriptionT, S>>,
+    context: Arc<CtxT>,
+    req: Request<Body>,
+    limits: &UploadLimits,
+) -> Result<Response<Body>, hyper::Error>
+where
+    QueryT: GraphQLTypeAsync<S, Context = CtxT>,
+    QueryT::TypeInfo: Sync,
+    MutationT: GraphQLTypeAsync<S, Context = CtxT>,
+    MutationT::TypeInfo: Sync,
+    SubscriptionT: GraphQLSubscriptionType<S, Context = CtxT>,
+    SubscriptionT::TypeInfo: Sync,
+    CtxT: Sync,
+    S: ScalarValue + Send + Sync,
+{
+    Ok(match parse_req(req, limits).await {
+        Ok(req) => execute_request(root_node, context, req).await,
+        Err(resp) => resp,
+    })
+}
+
+/// The limits of the size of multipart requests uploading files
+///
+/// A request exceeding them is rejected while its body is being read.
+#[derive(Clone, Copy, Debug)]
+pub struct UploadLimits {
+    max_request_size: u64,
+    max_field_size: u64,
+}
+
+impl UploadLimits {
+    /// Constructs the default limits, of 50 MiB per request and 10 MiB per field
+    pub fn new() -> Self {
+        UploadLimits {
+            max_request_size: 50 * 1024 * 1024,
+            max_field_size: 10 * 1024 * 1024,
+        }
+    }
+
+    /// Sets the maximum size of the whole body of a request, in bytes
+    pub fn with_max_request_size(mut self, max_request_size: u64) -> Self {
+        self.max_request_size = max_request_size;
+        self
+    }
+
+    /// Sets the maximum size of a single field of a request, including files, in bytes
+    pub fn with_max_field_size(mut self, max_field_size: u64) -> Self {
+        self.max_field_size = max_field_size;
+        self
+    }
+
+    fn constraints(&self) -> multer::Constraints {
+        multer::Constraints::new().size_limit(
+            multer::SizeLimit::new()
+                .whole_stream(self.max_request_size)
+                .per_field(self.max_field_size),
+        )
+    }
+}
+
+impl Default for UploadLimits {
+    fn default() -> Self {
+        UploadLimits::new()
+    }
+}
+
+/// Executes a request like [`graphql`], delivering the payloads of queries using the `@defer`
+/// and `@stream` directives as a `multipart/mixed` response to clients accepting it.
+///
+/// The directives are only available once enabled with
+/// [`RootNode::with_incremental_delivery`](juniper::RootNode::with_incremental_delivery).
+pub async fn graphql_incremental<CtxT, QueryT, MutationT, SubscriptionT, S>(
+    root_node: Arc<RootNode<'static, QueryT, MutationT, SubscriptionT, S>>,
+    context: Arc<CtxT>,
+    req: Request<Body>,
+) -> Result<Response<Body>, hyper::Error>
+where
+    QueryT: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+    QueryT::TypeInfo: Send + Sync,
+    MutationT: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+    MutationT::TypeInfo: Send + Sync,
+    SubscriptionT: GraphQLSubscriptionType<S, Context = CtxT> + Send + 'static,
+    SubscriptionT::TypeInfo: Send + Sync,
+    CtxT: Send + Sync + 'static,
+    S: ScalarValue + Send + Sync,
+{
+    let accepts_multipart = req
+        .headers()
+        .get(header::ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .map_or(false, incremental::accepts_multipart);
+
+    Ok(match parse_req(req, &UploadLimits::default()).await {
+        Ok(GraphQLBatchRequest::Single(mut req)) if accepts_multipart => {
+            req.load_persisted_query(&*root_node);
+            let body = incremental::execute_owned(req, root_node, context, |payload| {
+                serde_json::to_string(&payload).unwrap()
+            })
+            .await;
+            match body {
+                IncrementalBody::Json(json, is_ok) => {
+                    let code = if is_ok {
+                        StatusCode::OK
+                    } else {
+                        StatusCode::BAD_REQUEST
+                    };
+                    let mut resp = new_response(code);
+                    resp.headers_mut().insert(
+                        header::CONTENT_TYPE,
+                        HeaderValue::from_static("application/json"),
+                    );
+                    *resp.body_mut() = Body::from(json);
+                    resp
+                }
+                IncrementalBody::Multipart(chunks) => {
+                    let mut resp = new_response(StatusCode::OK);
+                    resp.headers_mut().insert(
+                        header::CONTENT_TYPE,
+                        HeaderValue::from_static(incremental::CONTENT_TYPE),
+                    );
+                    *resp.body_mut() = Body::wrap_stream(chunks.map(Ok::<_, Infallible>));
+                    resp
+                }
+            }
+        }
         Ok(req) => execute_request(root_node, context, req).await,
         Err(resp) => resp,
     })
 }
 
+/// Serves the subscription of a GET or POST request over Server-Sent Events, sending every
+/// response of the subscription as an event of a `text/event-stream` response.
+///
+/// Clients which can't open WebSocket connections can use it instead of `juniper_graphql_ws`.
+#[cfg(feature = "subscriptions")]
+pub async fn graphql_sse<CtxT, QueryT, MutationT, SubscriptionT, S>(
+    root_node: Arc<RootNode<'static, QueryT, MutationT, SubscriptionT, S>>,
+    context: Arc<CtxT>,
+    req: Request<Body>,
+) -> Result<Response<Body>, hyper::Error>
+where
+    QueryT: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+    QueryT::TypeInfo: Send + Sync,
+    MutationT: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+    MutationT::TypeInfo: Send + Sync,
+    SubscriptionT: GraphQLSubscriptionType<S, Context = CtxT> + Send + 'static,
+    SubscriptionT::TypeInfo: Send + Sync,
+    CtxT: Send + Sync + 'static,
+    S: ScalarValue + Send + Sync + 'static,
+{
+    let mut req = match parse_req(req, &UploadLimits::default()).await {
+        Ok(GraphQLBatchRequest::Single(req)) => req,
+        Ok(GraphQLBatchRequest::Batch(_)) => {
+            return Ok(render_error(GraphQLRequestError::Invalid(
+                "batch requests can't be subscribed to".to_string(),
+            )))
+        }
+        Err(resp) => return Ok(resp),
+    };
+    req.load_persisted_query(&*root_node);
+
+    let events = sse::serve(req, root_node, context, |response| {
+        serde_json::to_string(response).unwrap()
+    })
+    .await;
+    Ok(match events {
+        Ok(events) => {
+            let mut resp = new_response(StatusCode::OK);
+            resp.headers_mut().insert(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static(sse::CONTENT_TYPE),
+            );
+            resp.headers_mut()
+                .insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+            *resp.body_mut() = Body::wrap_stream(events.map(Ok::<_, Infallible>));
+            resp
+        }
+        Err(json) => {
+            let mut resp = new_response(StatusCode::BAD_REQUEST);
+            resp.headers_mut().insert(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/json"),
+            );
+            *resp.body_mut() = Body::from(json);
+            resp
+        }
+    })
+}
+
 async fn parse_req<S: ScalarValue>(
     req: Request<Body>,
+    limits: &UploadLimits,
 ) -> Result<GraphQLBatchRequest<S>, Response<Body>> {
     match *req.method() {
         Method::GET => parse_get_req(req),
@@ -68,6 +268,14 @@ async fn parse_req<S: ScalarValue>(
             match content_type {
                 Some(Ok("application/json")) => parse_post_json_req(req.into_body()).await,
                 Some(Ok("application/graphql")) => parse_post_graphql_req(req.into_body()).await,
+                Some(Ok(content_type)) if content_type.starts_with("multipart/form-data") => {
+                    match multer::parse_boundary(content_type) {
+                        Ok(boundary) => {
+                            parse_post_multipart_req(boundary, req.into_body(), limits).await
+                        }
+                        Err(e) => Err(GraphQLRequestError::Multipart(e)),
+                    }
+                }
                 _ => return Err(new_response(StatusCode::BAD_REQUEST)),
             }
         }
@@ -118,6 +326,62 @@ async fn parse_post_graphql_req<S: ScalarValue>(
     )))
 }
 
+async fn parse_post_multipart_req<S: ScalarValue>(
+    boundary: String,
+    body: Body,
+    limits: &UploadLimits,
+) -> Result<GraphQLBatchRequest<S>, GraphQLRequestError> {
+    let mut multipart =
+        multer::Multipart::new_with_constraints(body, boundary, limits.constraints());
+    let mut operations = None;
+    let mut map = None;
+    let mut files = HashMap::new();
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(GraphQLRequestError::Multipart)?
+    {
+        let name = field.name().map(str::to_owned);
+        match name.as_deref() {
+            Some("operations") => {
+                let text = field.text().await.map_err(GraphQLRequestError::Multipart)?;
+                operations = Some(
+                    serde_json::from_str::<GraphQLBatchRequest<S>>(&text)
+                        .map_err(GraphQLRequestError::BodyJSONError)?,
+                );
+            }
+            Some("map") => {
+                let text = field.text().await.map_err(GraphQLRequestError::Multipart)?;
+                map = Some(
+                    serde_json::from_str::<HashMap<String, Vec<String>>>(&text)
+                        .map_err(GraphQLRequestError::Map)?,
+                );
+            }
+            Some(_) => {
+                let filename = field.file_name().map(str::to_owned);
+                let content_type = field.content_type().map(ToString::to_string);
+                let content = field
+                    .bytes()
+                    .await
+                    .map_err(GraphQLRequestError::Multipart)?;
+                files.insert(
+                    name.unwrap(),
+                    UploadedFile::new(filename, content_type, content.to_vec()),
+                );
+            }
+            None => continue,
+        }
+    }
+
+    let mut request = operations
+        .ok_or_else(|| GraphQLRequestError::Invalid("'operations' field is missing".to_string()))?;
+    request
+        .attach_uploads(map.unwrap_or_default(), files)
+        .map_err(GraphQLRequestError::Uploads)?;
+    Ok(request)
+}
+
 pub async fn graphiql(
     graphql_endpoint: &str,
     subscriptions_endpoint: Option<&str>,
@@ -153,7 +417,7 @@ fn render_error(err: GraphQLRequestError) -> Response<Body> {
 async fn execute_request_sync<CtxT, QueryT, MutationT, SubscriptionT, S>(
     root_node: Arc<RootNode<'static, QueryT, MutationT, SubscriptionT, S>>,
     context: Arc<CtxT>,
-    request: GraphQLBatchRequest<S>,
+    mut request: GraphQLBatchRequest<S>,
 ) -> Response<Body>
 where
     QueryT: GraphQLType<S, Context = CtxT>,
@@ -165,6 +429,7 @@ where
     CtxT: Sync,
     S: ScalarValue + Send + Sync,
 {
+    request.load_persisted_queries(&*root_node);
     let res = request.execute_sync(&*root_node, &context);
     let body = Body::from(serde_json::to_string_pretty(&res).unwrap());
     let code = if res.is_ok() {
@@ -184,7 +449,7 @@ where
 async fn execute_request<CtxT, QueryT, MutationT, SubscriptionT, S>(
     root_node: Arc<RootNode<'static, QueryT, MutationT, SubscriptionT, S>>,
     context: Arc<CtxT>,
-    request: GraphQLBatchRequest<S>,
+    mut request: GraphQLBatchRequest<S>,
 ) -> Response<Body>
 where
     QueryT: GraphQLTypeAsync<S, Context = CtxT>,
@@ -196,6 +461,7 @@ where
     CtxT: Sync,
     S: ScalarValue + Send + Sync,
 {
+    request.load_persisted_queries(&*root_node);
     let res = request.execute(&*root_node, &context).await;
     let body = Body::from(serde_json::to_string_pretty(&res).unwrap());
     let code = if res.is_ok() {
@@ -219,6 +485,7 @@ where
     let mut query = None;
     let operation_name = None;
     let mut variables = None;
+    let mut extensions = None;
     for (key, value) in form_urlencoded::parse(input.as_bytes()).into_owned() {
         match key.as_ref() {
             "query" => {
@@ -243,15 +510,30 @@ where
                     Err(e) => return Err(e),
                 }
             }
+            "extensions" => {
+                if extensions.is_some() {
+                    return Err(invalid_err("extensions"));
+                }
+                match serde_json::from_str::<GraphQLRequestExtensions>(&value)
+                    .map_err(GraphQLRequestError::Extensions)
+                {
+                    Ok(parsed_extensions) => extensions = Some(parsed_extensions),
+                    Err(e) => return Err(e),
+                }
+            }
             _ => continue,
         }
     }
-    match query {
-        Some(query) => Ok(JuniperGraphQLRequest::new(query, operation_name, variables)),
-        None => Err(GraphQLRequestError::Invalid(
+    if query.is_none() && extensions.is_none() {
+        return Err(GraphQLRequestError::Invalid(
             "'query' parameter is missing".to_string(),
-        )),
+        ));
     }
+    let request = JuniperGraphQLRequest::new(query.unwrap_or_default(), operation_name, variables);
+    Ok(match extensions {
+        Some(extensions) => request.with_extensions(extensions),
+        None => request,
+    })
 }
 
 fn invalid_err(parameter_name: &str) -> GraphQLRequestError {
@@ -282,6 +564,10 @@ enum GraphQLRequestError {
     BodyUtf8(FromUtf8Error),
     BodyJSONError(SerdeError),
     Variables(SerdeError),
+    Extensions(SerdeError),
+    Map(SerdeError),
+    Multipart(multer::Error),
+    Uploads(MultipartError),
     Invalid(String),
 }
 
@@ -292,6 +578,10 @@ impl fmt::Display for GraphQLRequestError {
             GraphQLRequestError::BodyUtf8(ref err) => fmt::Display::fmt(err, &mut f),
             GraphQLRequestError::BodyJSONError(ref err) => fmt::Display::fmt(err, &mut f),
             GraphQLRequestError::Variables(ref err) => fmt::Display::fmt(err, &mut f),
+            GraphQLRequestError::Extensions(ref err) => fmt::Display::fmt(err, &mut f),
+            GraphQLRequestError::Map(ref err) => fmt::Display::fmt(err, &mut f),
+            GraphQLRequestError::Multipart(ref err) => fmt::Display::fmt(err, &mut f),
+            GraphQLRequestError::Uploads(ref err) => fmt::Display::fmt(err, &mut f),
             GraphQLRequestError::Invalid(ref err) => fmt::Display::fmt(err, &mut f),
         }
     }
@@ -304,6 +594,10 @@ impl Error for GraphQLRequestError {
             GraphQLRequestError::BodyUtf8(ref err) => Some(err),
             GraphQLRequestError::BodyJSONError(ref err) => Some(err),
             GraphQLRequestError::Variables(ref err) => Some(err),
+            GraphQLRequestError::Extensions(ref err) => Some(err),
+            GraphQLRequestError::Map(ref err) => Some(err),
+            GraphQLRequestError::Multipart(ref err) => Some(err),
+            GraphQLRequestError::Uploads(ref err) => Some(err),
             GraphQLRequestError::Invalid(_) => None,
         }
     }
@@ -316,7 +610,7 @@ mod tests {
         Body, Method, Response, Server, StatusCode,
     };
     use juniper::{
-        http::tests as http_tests,
+        http::{persisted_query::InMemoryPersistedQueryStore, tests as http_tests},
         tests::fixtures::starwars::schema::{Database, Query},
         EmptyMutation, EmptySubscription, RootNode,
     };
@@ -380,11 +674,14 @@ mod tests {
         let addr: SocketAddr = ([127, 0, 0, 1], port).into();
 
         let db = Arc::new(Database::new());
-        let root_node = Arc::new(RootNode::new(
-            Query,
-            EmptyMutation::<Database>::new(),
-            EmptySubscription::<Database>::new(),
-        ));
+        let root_node = Arc::new(
+            RootNode::new(
+                Query,
+                EmptyMutation::<Database>::new(),
+                EmptySubscription::<Database>::new(),
+            )
+            .with_persisted_queries(InMemoryPersistedQueryStore::new(10)),
+        );
 
         let new_service = make_service_fn(move |_| {
             let root_node = root_node.clone();
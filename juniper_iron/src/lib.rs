@@ -117,8 +117,9 @@ use iron::{
     status,
 };
 use juniper::{
-    http, http::GraphQLBatchRequest, DefaultScalarValue, GraphQLType, InputValue, RootNode,
-    ScalarValue,
+    http,
+    http::{persisted_query::PersistedQueryStore, GraphQLBatchRequest, GraphQLRequestExtensions},
+    DefaultScalarValue, GraphQLType, InputValue, RootNode, ScalarValue,
 };
 use serde_json::error::Error as SerdeError;
 use urlencoded::{UrlDecodingError, UrlEncodedQuery};
@@ -196,6 +197,20 @@ where
     }
 }
 
+fn parse_extensions_param(
+    params: Option<Vec<String>>,
+) -> IronResult<Option<GraphQLRequestExtensions>> {
+    if let Some(values) = params {
+        Ok(
+            serde_json::from_str::<GraphQLRequestExtensions>(get_single_value(values)?.as_ref())
+                .map(Some)
+                .map_err(GraphQLIronError::Serde)?,
+        )
+    } else {
+        Ok(None)
+    }
+}
+
 impl<'a, CtxFactory, Query, Mutation, Subscription, CtxT, S>
     GraphQLHandler<'a, CtxFactory, Query, Mutation, Subscription, CtxT, S>
 where
@@ -224,21 +239,38 @@ where
         }
     }
 
+    /// Enables persisted queries, storing the queries sent by clients in `store`
+    ///
+    /// See the `juniper::http::persisted_query` module for more information.
+    pub fn with_persisted_queries<P>(mut self, store: P) -> Self
+    where
+        P: PersistedQueryStore + 'static,
+    {
+        self.root_node = self.root_node.with_persisted_queries(store);
+        self
+    }
+
     fn handle_get(&self, req: &mut Request) -> IronResult<GraphQLBatchRequest<S>> {
         let url_query = req
             .get_mut::<UrlEncodedQuery>()
             .map_err(GraphQLIronError::Url)?;
 
-        let query = parse_url_param(url_query.remove("query"))?
-            .ok_or_else(|| GraphQLIronError::InvalidData("No query provided"))?;
+        let query = parse_url_param(url_query.remove("query"))?;
         let operation_name = parse_url_param(url_query.remove("operationName"))?;
         let variables = parse_variable_param(url_query.remove("variables"))?;
+        let extensions = parse_extensions_param(url_query.remove("extensions"))?;
 
-        Ok(GraphQLBatchRequest::Single(http::GraphQLRequest::new(
-            query,
-            operation_name,
-            variables,
-        )))
+        // The query may be left out when the hash of a persisted query is sent instead.
+        if query.is_none() && extensions.is_none() {
+            return Err(GraphQLIronError::InvalidData("No query provided").into());
+        }
+        let request =
+            http::GraphQLRequest::new(query.unwrap_or_default(), operation_name, variables);
+
+        Ok(GraphQLBatchRequest::Single(match extensions {
+            Some(extensions) => request.with_extensions(extensions),
+            None => request,
+        }))
     }
 
     fn handle_post_json(&self, req: &mut Request) -> IronResult<GraphQLBatchRequest<S>> {
@@ -263,8 +295,9 @@ where
     fn execute_sync(
         &self,
         context: &CtxT,
-        request: GraphQLBatchRequest<S>,
+        mut request: GraphQLBatchRequest<S>,
     ) -> IronResult<Response> {
+        request.load_persisted_queries(&self.root_node);
         let response = request.execute_sync(&self.root_node, context);
         let content_type = "application/json".parse::<Mime>().unwrap();
         let json = serde_json::to_string_pretty(&response).unwrap();
@@ -410,10 +443,11 @@ mod tests {
     use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
 
     use juniper::{
-        http::tests as http_tests,
+        http::{persisted_query::InMemoryPersistedQueryStore, tests as http_tests},
         tests::fixtures::starwars::schema::{Database, Query},
         DefaultScalarValue, EmptyMutation, EmptySubscription,
     };
+    use std::sync::Arc;
 
     use super::GraphQLHandler;
 
@@ -437,11 +471,27 @@ mod tests {
         )
     }
 
-    struct TestIronIntegration;
+    struct TestIronIntegration {
+        persisted_queries: Arc<InMemoryPersistedQueryStore>,
+    }
+
+    impl TestIronIntegration {
+        fn make_handler(&self) -> Box<dyn Handler> {
+            Box::new(
+                <GraphQLHandler<_, _, _, _, _, DefaultScalarValue>>::new(
+                    context_factory,
+                    Query,
+                    EmptyMutation::<Database>::new(),
+                    EmptySubscription::<Database>::new(),
+                )
+                .with_persisted_queries(self.persisted_queries.clone()),
+            )
+        }
+    }
 
     impl http_tests::HttpIntegration for TestIronIntegration {
         fn get(&self, url: &str) -> http_tests::TestResponse {
-            request::get(&fixup_url(url), Headers::new(), &make_handler())
+            request::get(&fixup_url(url), Headers::new(), &self.make_handler())
                 .map(make_test_response)
                 .unwrap_or_else(make_test_error_response)
         }
@@ -449,7 +499,7 @@ mod tests {
         fn post_json(&self, url: &str, body: &str) -> http_tests::TestResponse {
             let mut headers = Headers::new();
             headers.set(ContentType::json());
-            request::post(&fixup_url(url), headers, body, &make_handler())
+            request::post(&fixup_url(url), headers, body, &self.make_handler())
                 .map(make_test_response)
                 .unwrap_or_else(make_test_error_response)
         }
@@ -461,7 +511,7 @@ mod tests {
                 SubLevel::Ext("graphql".into()),
                 vec![],
             )));
-            request::post(&fixup_url(url), headers, body, &make_handler())
+            request::post(&fixup_url(url), headers, body, &self.make_handler())
                 .map(make_test_response)
                 .unwrap_or_else(make_test_error_response)
         }
@@ -469,7 +519,9 @@ mod tests {
 
     #[test]
     fn test_iron_integration() {
-        let integration = TestIronIntegration;
+        let integration = TestIronIntegration {
+            persisted_queries: Arc::new(InMemoryPersistedQueryStore::new(10)),
+        };
 
         http_tests::run_http_test_suite(&integration);
     }
@@ -509,13 +561,4 @@ mod tests {
             content_type,
         }
     }
-
-    fn make_handler() -> Box<dyn Handler> {
-        Box::new(<GraphQLHandler<_, _, _, _, _, DefaultScalarValue>>::new(
-            context_factory,
-            Query,
-            EmptyMutation::<Database>::new(),
-            EmptySubscription::<Database>::new(),
-        ))
-    }
 }
@@ -99,7 +99,9 @@ where
         MutationT: GraphQLType<S, Context = CtxT>,
         SubscriptionT: GraphQLType<S, Context = CtxT>,
     {
-        let response = self.0.execute_sync(root_node, context);
+        let mut request = self.0.clone();
+        request.load_persisted_queries(root_node);
+        let response = request.execute_sync(root_node, context);
         let status = if response.is_ok() {
             Status::Ok
         } else {
@@ -126,7 +128,9 @@ where
         CtxT: Sync,
         S: Send + Sync,
     {
-        let response = self.0.execute(root_node, context).await;
+        let mut request = self.0.clone();
+        request.load_persisted_queries(root_node);
+        let response = request.execute(root_node, context).await;
         let status = if response.is_ok() {
             Status::Ok
         } else {
@@ -203,6 +207,7 @@ where
         let mut query = None;
         let mut operation_name = None;
         let mut variables = None;
+        let mut extensions = None;
 
         for form_item in form_items {
             let (key, value) = form_item.key_value();
@@ -246,6 +251,21 @@ where
                         );
                     }
                 }
+                "extensions" => {
+                    if extensions.is_some() {
+                        return Err("Extensions parameter must not occur more than once".to_owned());
+                    } else {
+                        let decoded;
+                        match value.url_decode() {
+                            Ok(v) => decoded = v,
+                            Err(e) => return Err(e.to_string()),
+                        }
+                        extensions = Some(
+                            serde_json::from_str::<http::GraphQLRequestExtensions>(&decoded)
+                                .map_err(|err| err.to_string())?,
+                        );
+                    }
+                }
                 _ => {
                     if strict {
                         return Err(format!("Prohibited extra field '{}'", key));
@@ -254,13 +274,18 @@ where
             }
         }
 
-        if let Some(query) = query {
-            Ok(GraphQLRequest(GraphQLBatchRequest::Single(
-                http::GraphQLRequest::new(query, operation_name, variables),
-            )))
-        } else {
-            Err("Query parameter missing".to_owned())
+        // The query may be left out when the hash of a persisted query is sent instead.
+        if query.is_none() && extensions.is_none() {
+            return Err("Query parameter missing".to_owned());
         }
+        let request =
+            http::GraphQLRequest::new(query.unwrap_or_default(), operation_name, variables);
+        Ok(GraphQLRequest(GraphQLBatchRequest::Single(
+            match extensions {
+                Some(extensions) => request.with_extensions(extensions),
+                None => request,
+            },
+        )))
     }
 }
 
@@ -450,7 +475,7 @@ mod tests {
     use futures;
 
     use juniper::{
-        http::tests as http_tests,
+        http::{persisted_query::InMemoryPersistedQueryStore, tests as http_tests},
         tests::fixtures::starwars::schema::{Database, Query},
         EmptyMutation, EmptySubscription, RootNode,
     };
@@ -552,11 +577,14 @@ mod tests {
     }
 
     fn make_rocket_without_routes() -> Rocket {
-        rocket::ignite().manage(Database::new()).manage(Schema::new(
-            Query,
-            EmptyMutation::<Database>::new(),
-            EmptySubscription::<Database>::new(),
-        ))
+        rocket::ignite().manage(Database::new()).manage(
+            Schema::new(
+                Query,
+                EmptyMutation::<Database>::new(),
+                EmptySubscription::<Database>::new(),
+            )
+            .with_persisted_queries(InMemoryPersistedQueryStore::new(10)),
+        )
     }
 
     async fn make_test_response(response: LocalResponse<'_>) -> http_tests::TestResponse {
@@ -4,6 +4,8 @@
 //!
 //! You need both this and `juniper` crate.
 //!
+//! Subscriptions can also be served over Server-Sent Events with the [`sse`] module.
+//!
 //! [`SubscriptionCoordinator`]: juniper::SubscriptionCoordinator
 //! [`SubscriptionConnection`]: juniper::SubscriptionConnection
 
@@ -11,6 +13,8 @@
 #![deny(warnings)]
 #![doc(html_root_url = "https://docs.rs/juniper_subscriptions/0.14.2")]
 
+pub mod sse;
+
 use std::{
     iter::FromIterator,
     pin::Pin,
@@ -0,0 +1,186 @@
+//! Serving subscriptions over [Server-Sent Events][1], for clients which can't open WebSocket
+//! connections.
+//!
+//! Every [`ExecutionOutput`] of the [`Connection`] of a subscription is sent as a `next` event,
+//! followed by a `complete` event once the subscription ends, as in the distinct connections mode
+//! of the [GraphQL over Server-Sent Events protocol][2]:
+//!
+//! ```text
+//! event: next
+//! data: {"data":{"users":{"id":"1"}}}
+//!
+//! event: complete
+//! data:
+//!
+//! ```
+//!
+//! [`ExecutionOutput`]: juniper::ExecutionOutput
+//! [1]: https://html.spec.whatwg.org/multipage/server-sent-events.html
+//! [2]: https://github.com/enisdenjo/graphql-sse/blob/master/PROTOCOL.md
+
+use std::sync::Arc;
+
+use futures::{
+    future,
+    stream::{self, BoxStream},
+    FutureExt as _, StreamExt as _,
+};
+use juniper::{
+    http::{GraphQLRequest, GraphQLResponse},
+    GraphQLSubscriptionType, GraphQLTypeAsync, RootNode, ScalarValue,
+};
+
+use crate::Connection;
+
+/// The `Content-Type` of Server-Sent Events responses
+pub const CONTENT_TYPE: &str = "text/event-stream";
+
+/// Subscribes to the subscription of `request`, returning the events of its [`Connection`] as the
+/// chunks of a `text/event-stream` body
+///
+/// Returns once the subscription is resolved, so the status of the response can be chosen: if
+/// the request can't be subscribed to, e.g. because it's invalid or isn't a subscription, the
+/// error response is returned instead, which should be sent with a `400 Bad Request` status.
+/// Responses are serialized to JSON with `serialize`, e.g. with `serde_json::to_string`.
+pub async fn serve<Query, Mutation, Subscription, CtxT, S, F>(
+    request: GraphQLRequest<S>,
+    root_node: Arc<RootNode<'static, Query, Mutation, Subscription, S>>,
+    context: Arc<CtxT>,
+    serialize: F,
+) -> Result<BoxStream<'static, String>, String>
+where
+    Query: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+    Query::TypeInfo: Send + Sync,
+    Mutation: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+    Mutation::TypeInfo: Send + Sync,
+    Subscription: GraphQLSubscriptionType<S, Context = CtxT> + Send + 'static,
+    Subscription::TypeInfo: Send + Sync,
+    CtxT: Send + Sync + 'static,
+    S: ScalarValue + Send + Sync + 'static,
+    F: Fn(&GraphQLResponse<'_, S>) -> String + Send + 'static,
+{
+    let owned = (request, root_node, context);
+    let (subscribed, events) =
+        juniper::http::resolve_owned_body(owned, move |(request, root_node, context)| {
+            async move {
+                let connection =
+                    match juniper::http::resolve_into_stream(request, &**root_node, &**context)
+                        .await
+                    {
+                        Ok((stream, errors)) => Connection::from_stream(stream, errors),
+                        Err(e) => {
+                            let response = GraphQLResponse::from_result(Err(e));
+                            return (Err(serialize(&response)), None);
+                        }
+                    };
+
+                let events = connection
+                    .map(move |output| {
+                        let response =
+                            GraphQLResponse::from_result(Ok((output.data, output.errors)));
+                        event("next", &serialize(&response))
+                    })
+                    .chain(stream::once(future::ready(event("complete", ""))));
+                (Ok(()), Some(events.boxed()))
+            }
+            .boxed()
+        })
+        .await;
+
+    subscribed.map(|()| events)
+}
+
+/// Formats an event of a `text/event-stream` body
+fn event(name: &str, data: &str) -> String {
+    let mut event = format!("event: {}\n", name);
+    for line in data.split('\n') {
+        event.push_str("data:");
+        if !line.is_empty() {
+            event.push(' ');
+            event.push_str(line);
+        }
+        event.push('\n');
+    }
+    event.push('\n');
+    event
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use futures::{
+        stream::{self, BoxStream},
+        StreamExt as _,
+    };
+    use juniper::{
+        graphql_object, graphql_subscription, http::GraphQLRequest, EmptyMutation, FieldResult,
+        RootNode,
+    };
+
+    use super::{event, serve};
+
+    struct Query;
+
+    #[graphql_object]
+    impl Query {
+        fn zero() -> i32 {
+            0
+        }
+    }
+
+    struct Subscription;
+
+    #[graphql_subscription]
+    impl Subscription {
+        async fn count() -> BoxStream<'static, FieldResult<i32>> {
+            stream::iter((1..=2).map(Ok)).boxed()
+        }
+    }
+
+    async fn serve_query(query: &str) -> Result<Vec<String>, String> {
+        let root_node = Arc::new(RootNode::new(
+            Query,
+            EmptyMutation::<()>::new(),
+            Subscription,
+        ));
+        let request = GraphQLRequest::new(query.into(), None, None);
+        let events = serve(request, root_node, Arc::new(()), |response| {
+            serde_json::to_string(response).unwrap()
+        })
+        .await?;
+        Ok(events.collect().await)
+    }
+
+    #[tokio::test]
+    async fn sends_responses_then_completes() {
+        assert_eq!(
+            serve_query("subscription { count }").await,
+            Ok(vec![
+                event("next", r#"{"data":{"count":1}}"#),
+                event("next", r#"{"data":{"count":2}}"#),
+                event("complete", ""),
+            ]),
+        );
+    }
+
+    #[tokio::test]
+    async fn returns_error_response_if_not_subscribed() {
+        let error = serve_query("subscription { unknown }").await.unwrap_err();
+
+        assert!(error.starts_with(r#"{"errors":[{"message":"Unknown field"#));
+    }
+
+    #[test]
+    fn formats_events() {
+        assert_eq!(
+            event("next", r#"{"data":{"a":1}}"#),
+            "event: next\ndata: {\"data\":{\"a\":1}}\n\n",
+        );
+        assert_eq!(
+            event("next", "{\n  \"data\": null\n}"),
+            "event: next\ndata: {\ndata:   \"data\": null\ndata: }\n\n",
+        );
+        assert_eq!(event("complete", ""), "event: complete\ndata:\n\n");
+    }
+}
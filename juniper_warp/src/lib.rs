@@ -41,15 +41,25 @@ Check the LICENSE file for details.
 #![doc(html_root_url = "https://docs.rs/juniper_warp/0.2.0")]
 
 use anyhow::anyhow;
-use bytes::Bytes;
-use futures::{FutureExt as _, TryFutureExt};
+use bytes::{Buf as _, Bytes};
+use futures::{future, FutureExt as _, StreamExt as _, TryFutureExt, TryStreamExt as _};
 use juniper::{
-    http::{GraphQLBatchRequest, GraphQLRequest},
-    ScalarValue,
+    http::{
+        incremental::{self, IncrementalBody},
+        multipart::UploadedFile,
+        GraphQLBatchRequest, GraphQLRequest, GraphQLRequestExtensions,
+    },
+    GraphQLSubscriptionType, GraphQLTypeAsync, RootNode, ScalarValue,
 };
-use std::{collections::HashMap, str, sync::Arc};
+use std::{collections::HashMap, convert::Infallible, str, sync::Arc};
 use tokio::task;
-use warp::{body, filters::BoxedFilter, http, query, Filter};
+use warp::{
+    body,
+    filters::BoxedFilter,
+    http,
+    multipart::{self, FormData},
+    query, Filter,
+};
 
 /// Make a filter for graphql queries/mutations.
 ///
@@ -59,6 +69,9 @@ use warp::{body, filters::BoxedFilter, http, query, Filter};
 ///
 /// In order to avoid blocking, this helper will use the `tokio_threadpool` threadpool created by hyper to resolve GraphQL requests.
 ///
+/// Files can be uploaded with `multipart/form-data` requests, following the
+/// [`juniper::http::multipart`] spec.
+///
 /// Example:
 ///
 /// ```
@@ -121,11 +134,13 @@ where
 {
     let schema = Arc::new(schema);
     let post_json_schema = schema.clone();
+    let post_multipart_schema = schema.clone();
     let post_graphql_schema = schema.clone();
 
-    let handle_post_json_request = move |context: CtxT, req: GraphQLBatchRequest<S>| {
+    let handle_post_json_request = move |context: CtxT, mut req: GraphQLBatchRequest<S>| {
         let schema = post_json_schema.clone();
         async move {
+            req.load_persisted_queries(&schema);
             let resp = req.execute(&schema, &context).await;
 
             Ok::<_, warp::Rejection>(build_response(
@@ -140,6 +155,22 @@ where
         .and(body::json())
         .and_then(handle_post_json_request);
 
+    let handle_post_multipart_request = move |context: CtxT, form: FormData| {
+        let schema = post_multipart_schema.clone();
+        async move {
+            let mut req = request_from_multipart(form).await?;
+            req.load_persisted_queries(&schema);
+            let resp = req.execute(&schema, &context).await;
+
+            Ok((serde_json::to_vec(&resp)?, resp.is_ok()))
+        }
+        .then(|res| async { Ok::<_, warp::Rejection>(build_response(res)) })
+    };
+    let post_multipart_filter = warp::post()
+        .and(context_extractor.clone())
+        .and(multipart::form())
+        .and_then(handle_post_multipart_request);
+
     let handle_post_graphql_request = move |context: CtxT, body: Bytes| {
         let schema = post_graphql_schema.clone();
         async move {
@@ -158,17 +189,11 @@ where
         .and(body::bytes())
         .and_then(handle_post_graphql_request);
 
-    let handle_get_request = move |context: CtxT, mut qry: HashMap<String, String>| {
+    let handle_get_request = move |context: CtxT, qry: HashMap<String, String>| {
         let schema = schema.clone();
         async move {
-            let req = GraphQLRequest::new(
-                qry.remove("query")
-                    .ok_or_else(|| anyhow!("Missing GraphQL query string in query parameters"))?,
-                qry.remove("operation_name"),
-                qry.remove("variables")
-                    .map(|vs| serde_json::from_str(&vs))
-                    .transpose()?,
-            );
+            let mut req = request_from_get(qry)?;
+            req.load_persisted_query(&schema);
 
             let resp = req.execute(&schema, &context).await;
 
@@ -184,6 +209,8 @@ where
     get_filter
         .or(post_json_filter)
         .unify()
+        .or(post_multipart_filter)
+        .unify()
         .or(post_graphql_filter)
         .unify()
         .boxed()
@@ -203,12 +230,14 @@ where
 {
     let schema = Arc::new(schema);
     let post_json_schema = schema.clone();
+    let post_multipart_schema = schema.clone();
     let post_graphql_schema = schema.clone();
 
-    let handle_post_json_request = move |context: CtxT, req: GraphQLBatchRequest<S>| {
+    let handle_post_json_request = move |context: CtxT, mut req: GraphQLBatchRequest<S>| {
         let schema = post_json_schema.clone();
         async move {
             let res = task::spawn_blocking(move || {
+                req.load_persisted_queries(&schema);
                 let resp = req.execute_sync(&schema, &context);
                 Ok((serde_json::to_vec(&resp)?, resp.is_ok()))
             })
@@ -223,6 +252,29 @@ where
         .and(body::json())
         .and_then(handle_post_json_request);
 
+    let handle_post_multipart_request = move |context: CtxT, form: FormData| {
+        let schema = post_multipart_schema.clone();
+        async move {
+            let mut req = match request_from_multipart(form).await {
+                Ok(req) => req,
+                Err(e) => return Ok(build_response(Err(e))),
+            };
+            let res = task::spawn_blocking(move || {
+                req.load_persisted_queries(&schema);
+                let resp = req.execute_sync(&schema, &context);
+                Ok((serde_json::to_vec(&resp)?, resp.is_ok()))
+            })
+            .await?;
+
+            Ok(build_response(res))
+        }
+        .map_err(|e: task::JoinError| warp::reject::custom(JoinError(e)))
+    };
+    let post_multipart_filter = warp::post()
+        .and(context_extractor.clone())
+        .and(multipart::form())
+        .and_then(handle_post_multipart_request);
+
     let handle_post_graphql_request = move |context: CtxT, body: Bytes| {
         let schema = post_graphql_schema.clone();
         async move {
@@ -245,19 +297,12 @@ where
         .and(body::bytes())
         .and_then(handle_post_graphql_request);
 
-    let handle_get_request = move |context: CtxT, mut qry: HashMap<String, String>| {
+    let handle_get_request = move |context: CtxT, qry: HashMap<String, String>| {
         let schema = schema.clone();
         async move {
             let res = task::spawn_blocking(move || {
-                let req = GraphQLRequest::new(
-                    qry.remove("query").ok_or_else(|| {
-                        anyhow!("Missing GraphQL query string in query parameters")
-                    })?,
-                    qry.remove("operation_name"),
-                    qry.remove("variables")
-                        .map(|vs| serde_json::from_str(&vs))
-                        .transpose()?,
-                );
+                let mut req = request_from_get(qry)?;
+                req.load_persisted_query(&schema);
 
                 let resp = req.execute_sync(&schema, &context);
                 Ok((serde_json::to_vec(&resp)?, resp.is_ok()))
@@ -276,11 +321,193 @@ where
     get_filter
         .or(post_json_filter)
         .unify()
+        .or(post_multipart_filter)
+        .unify()
         .or(post_graphql_filter)
         .unify()
         .boxed()
 }
 
+/// Makes a filter executing requests like [`make_graphql_filter`], delivering the payloads of
+/// queries using the `@defer` and `@stream` directives as a `multipart/mixed` response to clients
+/// accepting it.
+///
+/// The directives are only available once enabled with
+/// [`RootNode::with_incremental_delivery`](juniper::RootNode::with_incremental_delivery).
+///
+/// ```ignore
+/// let graphql_endpoint = warp::path("graphql")
+///     .and(make_incremental_filter(Arc::new(root_node), context_extractor));
+/// ```
+pub fn make_incremental_filter<Query, Mutation, Subscription, CtxT, S>(
+    root_node: Arc<RootNode<'static, Query, Mutation, Subscription, S>>,
+    context_extractor: BoxedFilter<(CtxT,)>,
+) -> BoxedFilter<(warp::reply::Response,)>
+where
+    Query: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+    Query::TypeInfo: Send + Sync,
+    Mutation: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+    Mutation::TypeInfo: Send + Sync,
+    Subscription: GraphQLSubscriptionType<S, Context = CtxT> + Send + 'static,
+    Subscription::TypeInfo: Send + Sync,
+    CtxT: Send + Sync + 'static,
+    S: ScalarValue + Send + Sync + 'static,
+{
+    let post_root_node = root_node.clone();
+
+    let handle_post_request = move |context: CtxT,
+                                    accept: Option<String>,
+                                    req: GraphQLBatchRequest<S>| {
+        let root_node = post_root_node.clone();
+        async move {
+            Ok::<_, warp::Rejection>(incremental_response(req, accept, root_node, context).await)
+        }
+    };
+    let post_filter = warp::post()
+        .and(context_extractor.clone())
+        .and(warp::header::optional::<String>("accept"))
+        .and(body::json())
+        .and_then(handle_post_request);
+
+    let handle_get_request =
+        move |context: CtxT, accept: Option<String>, qry: HashMap<String, String>| {
+            let root_node = root_node.clone();
+            async move {
+                Ok::<_, warp::Rejection>(match request_from_get(qry) {
+                    Ok(req) => {
+                        let req = GraphQLBatchRequest::Single(req);
+                        incremental_response(req, accept, root_node, context).await
+                    }
+                    Err(e) => http::Response::builder()
+                        .status(http::StatusCode::BAD_REQUEST)
+                        .body(e.to_string().into())
+                        .expect("response is valid"),
+                })
+            }
+        };
+    let get_filter = warp::get()
+        .and(context_extractor)
+        .and(warp::header::optional::<String>("accept"))
+        .and(query::query())
+        .and_then(handle_get_request);
+
+    get_filter.or(post_filter).unify().boxed()
+}
+
+async fn incremental_response<Query, Mutation, Subscription, CtxT, S>(
+    req: GraphQLBatchRequest<S>,
+    accept: Option<String>,
+    root_node: Arc<RootNode<'static, Query, Mutation, Subscription, S>>,
+    context: CtxT,
+) -> warp::reply::Response
+where
+    Query: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+    Query::TypeInfo: Send + Sync,
+    Mutation: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+    Mutation::TypeInfo: Send + Sync,
+    Subscription: GraphQLSubscriptionType<S, Context = CtxT> + Send + 'static,
+    Subscription::TypeInfo: Send + Sync,
+    CtxT: Send + Sync + 'static,
+    S: ScalarValue + Send + Sync + 'static,
+{
+    let accepts_multipart = accept
+        .as_deref()
+        .map_or(false, incremental::accepts_multipart);
+
+    let (json, is_ok) = match req {
+        GraphQLBatchRequest::Single(mut req) if accepts_multipart => {
+            req.load_persisted_query(&*root_node);
+            let body = incremental::execute_owned(req, root_node, Arc::new(context), |payload| {
+                serde_json::to_string(&payload).unwrap()
+            })
+            .await;
+            match body {
+                IncrementalBody::Json(json, is_ok) => (json, is_ok),
+                IncrementalBody::Multipart(chunks) => {
+                    return http::Response::builder()
+                        .header("content-type", incremental::CONTENT_TYPE)
+                        .body(hyper::Body::wrap_stream(chunks.map(Ok::<_, Infallible>)))
+                        .expect("response is valid")
+                }
+            }
+        }
+        mut req => {
+            req.load_persisted_queries(&*root_node);
+            let resp = req.execute(&*root_node, &context).await;
+            (serde_json::to_string(&resp).unwrap(), resp.is_ok())
+        }
+    };
+
+    http::Response::builder()
+        .status(if is_ok { 200 } else { 400 })
+        .header("content-type", "application/json")
+        .body(json.into())
+        .expect("response is valid")
+}
+
+fn request_from_get<S>(mut qry: HashMap<String, String>) -> anyhow::Result<GraphQLRequest<S>>
+where
+    S: ScalarValue,
+{
+    let extensions = qry
+        .remove("extensions")
+        .map(|ext| serde_json::from_str::<GraphQLRequestExtensions>(&ext))
+        .transpose()?;
+    // The query may be left out when the hash of a persisted query is sent instead.
+    let query = match (qry.remove("query"), &extensions) {
+        (Some(query), _) => query,
+        (None, Some(_)) => String::new(),
+        (None, None) => return Err(anyhow!("Missing GraphQL query string in query parameters")),
+    };
+    let req = GraphQLRequest::new(
+        query,
+        qry.remove("operation_name"),
+        qry.remove("variables")
+            .map(|vs| serde_json::from_str(&vs))
+            .transpose()?,
+    );
+
+    Ok(match extensions {
+        Some(extensions) => req.with_extensions(extensions),
+        None => req,
+    })
+}
+
+async fn request_from_multipart<S>(mut form: FormData) -> anyhow::Result<GraphQLBatchRequest<S>>
+where
+    S: ScalarValue,
+{
+    let mut operations = None;
+    let mut map = None;
+    let mut files = HashMap::new();
+
+    while let Some(part) = form.try_next().await? {
+        let name = part.name().to_owned();
+        let filename = part.filename().map(str::to_owned);
+        let content_type = part.content_type().map(str::to_owned);
+        let content = part
+            .stream()
+            .try_fold(Vec::new(), |mut content, mut data| {
+                content.extend_from_slice(&data.to_bytes());
+                future::ok(content)
+            })
+            .await?;
+
+        match name.as_str() {
+            "operations" => operations = Some(serde_json::from_slice(&content)?),
+            "map" => map = Some(serde_json::from_slice(&content)?),
+            _ => {
+                files.insert(name, UploadedFile::new(filename, content_type, content));
+            }
+        }
+    }
+
+    let mut req: GraphQLBatchRequest<S> =
+        operations.ok_or_else(|| anyhow!("Missing 'operations' field in multipart request"))?;
+    req.attach_uploads(map.unwrap_or_default(), files)?;
+    Ok(req)
+}
+
 /// Error raised by `tokio_threadpool` if the thread pool has been shutdown.
 ///
 /// Wrapper type is needed as inner type does not implement `warp::reject::Reject`.
@@ -387,10 +614,15 @@ pub mod subscriptions {
             sink::SinkExt,
             stream::StreamExt,
         },
+        http::GraphQLRequest,
         GraphQLSubscriptionType, GraphQLTypeAsync, RootNode, ScalarValue,
     };
-    use juniper_graphql_ws::{ArcSchema, ClientMessage, Connection, Init};
-    use std::{convert::Infallible, fmt, sync::Arc};
+    use juniper_graphql_ws::{
+        graphql_transport_ws, ArcSchema, ClientMessage, Connection, Init, Protocol,
+    };
+    use juniper_subscriptions::sse;
+    use std::{collections::HashMap, convert::Infallible, fmt, sync::Arc};
+    use warp::{body, filters::BoxedFilter, http, query, Filter};
 
     struct Message(warp::ws::Message);
 
@@ -402,6 +634,14 @@ pub mod subscriptions {
         }
     }
 
+    impl<S: ScalarValue> std::convert::TryFrom<Message> for graphql_transport_ws::ClientMessage<S> {
+        type Error = serde_json::Error;
+
+        fn try_from(msg: Message) -> serde_json::Result<Self> {
+            serde_json::from_slice(msg.0.as_bytes())
+        }
+    }
+
     /// Errors that can happen while serving a connection.
     #[derive(Debug)]
     pub enum Error {
@@ -479,6 +719,193 @@ pub mod subscriptions {
             Either::Right((r, _)) => r,
         }
     }
+
+    /// Serves the graphql-transport-ws protocol over a WebSocket connection.
+    ///
+    /// The `init` argument is used to provide the context and additional configuration for
+    /// connections, as for [`serve_graphql_ws`]. The connection is closed with the close code
+    /// defined by the protocol if the client violates it.
+    pub async fn serve_graphql_transport_ws<Query, Mutation, Subscription, CtxT, S, I>(
+        websocket: warp::ws::WebSocket,
+        root_node: Arc<RootNode<'static, Query, Mutation, Subscription, S>>,
+        init: I,
+    ) -> Result<(), Error>
+    where
+        Query: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+        Query::TypeInfo: Send + Sync,
+        Mutation: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+        Mutation::TypeInfo: Send + Sync,
+        Subscription: GraphQLSubscriptionType<S, Context = CtxT> + Send + 'static,
+        Subscription::TypeInfo: Send + Sync,
+        CtxT: Unpin + Send + Sync + 'static,
+        S: ScalarValue + Send + Sync + 'static,
+        I: Init<S, CtxT> + Send,
+    {
+        let (ws_tx, ws_rx) = websocket.split();
+        let (s_tx, s_rx) =
+            graphql_transport_ws::Connection::new(ArcSchema(root_node), init).split();
+
+        let ws_rx = ws_rx.map(|r| r.map(|msg| Message(msg)));
+        let s_rx = s_rx.map(|output| match output {
+            graphql_transport_ws::Output::Message(msg) => serde_json::to_string(&msg)
+                .map(|t| warp::ws::Message::text(t))
+                .map_err(|e| Error::Serde(e)),
+            graphql_transport_ws::Output::Close { code, message } => {
+                Ok(warp::ws::Message::close_with(code, message))
+            }
+        });
+
+        match future::select(
+            ws_rx.forward(s_tx.sink_err_into()),
+            s_rx.forward(ws_tx.sink_err_into()),
+        )
+        .await
+        {
+            Either::Left((r, _)) => r.map_err(|e| e.into()),
+            Either::Right((r, _)) => r,
+        }
+    }
+
+    /// Serves the given protocol over a WebSocket connection, by calling either
+    /// [`serve_graphql_ws`] or [`serve_graphql_transport_ws`].
+    ///
+    /// The protocol is usually picked with `juniper_graphql_ws::Protocol::from_header` from the
+    /// `Sec-WebSocket-Protocol` header of the upgrade request, which must then be sent back in the
+    /// response:
+    ///
+    /// ```ignore
+    /// warp::ws()
+    ///     .and(warp::header::optional::<String>("sec-websocket-protocol"))
+    ///     .map(move |ws: warp::ws::Ws, protocol: Option<String>| {
+    ///         let protocol = Protocol::from_header(protocol.as_deref());
+    ///         let root_node = root_node.clone();
+    ///         let reply = ws.on_upgrade(move |websocket| async move {
+    ///             serve(websocket, protocol, root_node, ConnectionConfig::new(Context {}))
+    ///                 .map(|r| {
+    ///                     if let Err(e) = r {
+    ///                         println!("Websocket error: {}", e);
+    ///                     }
+    ///                 })
+    ///                 .await
+    ///         });
+    ///         warp::reply::with_header(reply, "Sec-WebSocket-Protocol", protocol.as_str())
+    ///     })
+    /// ```
+    pub async fn serve<Query, Mutation, Subscription, CtxT, S, I>(
+        websocket: warp::ws::WebSocket,
+        protocol: Protocol,
+        root_node: Arc<RootNode<'static, Query, Mutation, Subscription, S>>,
+        init: I,
+    ) -> Result<(), Error>
+    where
+        Query: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+        Query::TypeInfo: Send + Sync,
+        Mutation: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+        Mutation::TypeInfo: Send + Sync,
+        Subscription: GraphQLSubscriptionType<S, Context = CtxT> + Send + 'static,
+        Subscription::TypeInfo: Send + Sync,
+        CtxT: Unpin + Send + Sync + 'static,
+        S: ScalarValue + Send + Sync + 'static,
+        I: Init<S, CtxT> + Send,
+    {
+        match protocol {
+            Protocol::GraphQLWs => serve_graphql_ws(websocket, root_node, init).await,
+            Protocol::GraphQLTransportWs => {
+                serve_graphql_transport_ws(websocket, root_node, init).await
+            }
+        }
+    }
+
+    /// Makes a filter serving subscriptions over Server-Sent Events, for clients which can't open
+    /// WebSocket connections.
+    ///
+    /// The subscription is read from the query parameters of GET requests or from the JSON body
+    /// of POST requests, and every response of the subscription is sent as an event of a
+    /// `text/event-stream` response.
+    ///
+    /// ```ignore
+    /// let sse_endpoint = warp::path("subscriptions")
+    ///     .and(make_sse_filter(root_node, context_extractor));
+    /// ```
+    pub fn make_sse_filter<Query, Mutation, Subscription, CtxT, S>(
+        root_node: Arc<RootNode<'static, Query, Mutation, Subscription, S>>,
+        context_extractor: BoxedFilter<(CtxT,)>,
+    ) -> BoxedFilter<(warp::reply::Response,)>
+    where
+        Query: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+        Query::TypeInfo: Send + Sync,
+        Mutation: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+        Mutation::TypeInfo: Send + Sync,
+        Subscription: GraphQLSubscriptionType<S, Context = CtxT> + Send + 'static,
+        Subscription::TypeInfo: Send + Sync,
+        CtxT: Send + Sync + 'static,
+        S: ScalarValue + Send + Sync + 'static,
+    {
+        let post_root_node = root_node.clone();
+
+        let handle_post_request = move |context: CtxT, req: GraphQLRequest<S>| {
+            let root_node = post_root_node.clone();
+            async move { Ok::<_, warp::Rejection>(sse_response(req, root_node, context).await) }
+        };
+        let post_filter = warp::post()
+            .and(context_extractor.clone())
+            .and(body::json())
+            .and_then(handle_post_request);
+
+        let handle_get_request = move |context: CtxT, qry: HashMap<String, String>| {
+            let root_node = root_node.clone();
+            async move {
+                Ok::<_, warp::Rejection>(match super::request_from_get(qry) {
+                    Ok(req) => sse_response(req, root_node, context).await,
+                    Err(e) => http::Response::builder()
+                        .status(http::StatusCode::BAD_REQUEST)
+                        .body(e.to_string().into())
+                        .expect("response is valid"),
+                })
+            }
+        };
+        let get_filter = warp::get()
+            .and(context_extractor)
+            .and(query::query())
+            .and_then(handle_get_request);
+
+        get_filter.or(post_filter).unify().boxed()
+    }
+
+    async fn sse_response<Query, Mutation, Subscription, CtxT, S>(
+        mut req: GraphQLRequest<S>,
+        root_node: Arc<RootNode<'static, Query, Mutation, Subscription, S>>,
+        context: CtxT,
+    ) -> warp::reply::Response
+    where
+        Query: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+        Query::TypeInfo: Send + Sync,
+        Mutation: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+        Mutation::TypeInfo: Send + Sync,
+        Subscription: GraphQLSubscriptionType<S, Context = CtxT> + Send + 'static,
+        Subscription::TypeInfo: Send + Sync,
+        CtxT: Send + Sync + 'static,
+        S: ScalarValue + Send + Sync + 'static,
+    {
+        req.load_persisted_query(&*root_node);
+        let events = sse::serve(req, root_node, Arc::new(context), |response| {
+            serde_json::to_string(response).unwrap()
+        })
+        .await;
+
+        match events {
+            Ok(events) => http::Response::builder()
+                .header("content-type", sse::CONTENT_TYPE)
+                .header("cache-control", "no-cache")
+                .body(hyper::Body::wrap_stream(events.map(Ok::<_, Infallible>)))
+                .expect("response is valid"),
+            Err(json) => http::Response::builder()
+                .status(http::StatusCode::BAD_REQUEST)
+                .header("content-type", "application/json")
+                .body(json.into())
+                .expect("response is valid"),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -682,7 +1109,10 @@ mod tests {
 #[cfg(test)]
 mod tests_http_harness {
     use juniper::{
-        http::tests::{run_http_test_suite, HttpIntegration, TestResponse},
+        http::{
+            persisted_query::InMemoryPersistedQueryStore,
+            tests::{run_http_test_suite, HttpIntegration, TestResponse},
+        },
         tests::fixtures::starwars::schema::{Database, Query},
         EmptyMutation, EmptySubscription, RootNode,
     };
@@ -703,7 +1133,8 @@ mod tests_http_harness {
                 Query,
                 EmptyMutation::<Database>::new(),
                 EmptySubscription::<Database>::new(),
-            );
+            )
+            .with_persisted_queries(InMemoryPersistedQueryStore::new(10));
             let state = warp::any().map(move || Database::new());
 
             let filter = path::end().and(if is_sync {